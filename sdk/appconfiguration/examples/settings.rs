@@ -0,0 +1,63 @@
+use azure_core::{ETag, MatchCondition};
+use azure_data_appconfiguration::{
+    ConfigurationClient, FeatureFlag, ListSettingsOptions, SetSettingOptions, WatchSettingOptions,
+};
+use azure_identity::DefaultAzureCredential;
+use futures::{StreamExt, TryStreamExt};
+use std::{env, sync::Arc, time::Duration};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let endpoint = env::var("AZURE_APPCONFIGURATION_URL")?;
+
+    let credential = Arc::new(DefaultAzureCredential::default());
+    let client = ConfigurationClient::new(endpoint, credential, None)?;
+
+    // Only add the setting if it does not already exist.
+    let mut options = SetSettingOptions::default();
+    options.match_condition = Some(MatchCondition::IfNoneMatch(ETag::any()));
+    match client.set_setting("app1/color", "red", Some(options)).await {
+        Ok(_) => println!("added app1/color"),
+        Err(error) if error.status() == Some(412) => println!("app1/color already exists"),
+        Err(error) => return Err(error.into()),
+    }
+
+    // Only replace the setting if it has not changed since it was read.
+    let setting = client
+        .get_setting("app1/color", None)
+        .await?
+        .into_model()
+        .await?;
+    let mut options = SetSettingOptions::default();
+    options.match_condition = setting.etag.clone().map(MatchCondition::IfMatch);
+    client
+        .set_setting("app1/color", "blue", Some(options))
+        .await?;
+
+    let mut flag = FeatureFlag::new("beta", true);
+    flag.description = Some("Enables beta features".to_string());
+    client.set_feature_flag(&flag, None).await?;
+
+    let mut options = ListSettingsOptions::default();
+    options.key_filter = Some("app1/*".to_string());
+    options.select = vec!["key".to_string()];
+    let keys: Vec<String> = client
+        .list_settings(Some(options))
+        .into_stream()
+        .map_ok(|setting| setting.key)
+        .try_collect()
+        .await?;
+    println!("keys: {keys:?}");
+
+    // Print the next two changes, including the change written above.
+    let mut options = WatchSettingOptions::default();
+    options.interval = Duration::from_secs(5);
+    let changes = client.watch_setting("app1/color", Some(options)).take(2);
+    futures::pin_mut!(changes);
+    while let Some(response) = changes.next().await {
+        let setting = response?.into_model().await?;
+        println!("{} = {:?}", setting.key, setting.value);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,422 @@
+#![doc = include_str!("../README.md")]
+
+mod models;
+mod sync_token;
+
+use azure_core::{
+    instrumented,
+    odata::QueryBuilder,
+    parse_endpoint,
+    policies::{BearerTokenSigner, Policy, SigningPolicy},
+    sleep::sleep,
+    ClientDiagnostics, ClientOptions, Context, ETag, Error, ErrorKind, Idempotent, MatchCondition,
+    Namespace, Options, Page, Pageable, Pipeline, Request, Response, Result, TokenCredential, Url,
+    ETAG,
+};
+use futures::{stream, Stream};
+pub use models::*;
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use sync_token::SyncTokenPolicy;
+
+const NAMESPACE: &str = "Microsoft.AppConfiguration";
+
+/// A client for the key-values and feature flags of an App Configuration store.
+#[derive(Debug, Clone)]
+pub struct ConfigurationClient {
+    endpoint: Url,
+    pipeline: Pipeline,
+    sync_tokens: Arc<SyncTokenPolicy>,
+}
+
+impl ConfigurationClient {
+    pub fn new(
+        endpoint: impl AsRef<str>,
+        credential: Arc<dyn TokenCredential>,
+        options: Option<ConfigurationClientOptions>,
+    ) -> Result<Self> {
+        let options = options.unwrap_or_default();
+        if options.api_version.is_empty() {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "api_version must not be empty",
+            ));
+        }
+        options.options.validate()?;
+
+        let mut endpoint = parse_endpoint(endpoint)?;
+        endpoint
+            .query_pairs_mut()
+            .clear()
+            .append_pair("api-version", &options.api_version);
+
+        let auth_policy: Arc<dyn Policy> = Arc::new(SigningPolicy::new(Arc::new(
+            BearerTokenSigner::new(credential, "https://azconfig.io/.default"),
+        )));
+        let sync_tokens = Arc::new(SyncTokenPolicy::default());
+
+        Ok(Self {
+            endpoint,
+            pipeline: Pipeline::new(
+                option_env!("CARGO_PKG_NAME"),
+                option_env!("CARGO_PKG_VERSION"),
+                &options.options,
+                vec![sync_tokens.clone()],
+                vec![auth_policy],
+            ),
+            sync_tokens,
+        })
+    }
+
+    pub fn endpoint(&self) -> &Url {
+        &self.endpoint
+    }
+
+    /// Gets the resolved configuration of the client without sending any requests.
+    pub fn diagnostics(&self) -> ClientDiagnostics {
+        self.pipeline.diagnostics(&self.endpoint)
+    }
+
+    /// Records the `syncToken` of a change notification, e.g., from Event Grid, so that subsequent
+    /// reads reflect that change even if they are served by a replica which has yet to receive it.
+    pub fn update_sync_token(&self, token: &str) {
+        self.sync_tokens.update(token);
+    }
+
+    #[instrumented("ConfigurationClient::get_setting")]
+    pub async fn get_setting<K>(
+        &self,
+        key: K,
+        options: Option<GetSettingOptions>,
+    ) -> Result<Response<ConfigurationSetting>>
+    where
+        K: Into<String>,
+    {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        let mut request = Request::new(
+            self.setting_url(&key.into(), options.label.as_deref()),
+            "GET",
+        );
+        request.insert_headers(&options.match_condition);
+
+        let response = self.pipeline.send(&mut ctx, &mut request).await?;
+        Ok(response.with_model())
+    }
+
+    /// Gets a setting like [`ConfigurationClient::get_setting`] but returns `None` if it has not
+    /// changed since `setting` was returned.
+    pub async fn get_setting_if_changed(
+        &self,
+        setting: &ConfigurationSetting,
+        options: Option<GetSettingOptions>,
+    ) -> Result<Option<Response<ConfigurationSetting>>> {
+        let mut options = options.unwrap_or_default();
+        options.label = setting.label.clone();
+        options.match_condition = setting.etag.clone().map(MatchCondition::IfNoneMatch);
+
+        match self.get_setting(setting.key.clone(), Some(options)).await {
+            Ok(response) => Ok(Some(response)),
+            Err(error) if error.status() == Some(304) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Sets a setting, or only replaces a setting which has not changed since it was read when
+    /// [`SetSettingOptions::match_condition`] is [`MatchCondition::IfMatch`] its [`ETag`].
+    #[instrumented("ConfigurationClient::set_setting")]
+    pub async fn set_setting<K, V>(
+        &self,
+        key: K,
+        value: V,
+        options: Option<SetSettingOptions>,
+    ) -> Result<Response<ConfigurationSetting>>
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        // Conditional writes can be safely retried since they fail if the first attempt succeeded.
+        ctx.insert(Idempotent::from(options.match_condition.is_some()));
+
+        let mut request = Request::new(
+            self.setting_url(&key.into(), options.label.as_deref()),
+            "PUT",
+        );
+        request.insert_headers(&options.match_condition);
+        request.set_json(&SetSettingRequest {
+            value: Some(value.into()),
+            content_type: options.content_type,
+            tags: options.tags,
+        })?;
+
+        let response = self.pipeline.send(&mut ctx, &mut request).await?;
+        Ok(response.with_model())
+    }
+
+    /// Sets a feature flag, replacing any [`SetSettingOptions::content_type`] with
+    /// [`FeatureFlag::CONTENT_TYPE`].
+    pub async fn set_feature_flag(
+        &self,
+        flag: &FeatureFlag,
+        options: Option<SetSettingOptions>,
+    ) -> Result<Response<ConfigurationSetting>> {
+        let mut options = options.unwrap_or_default();
+        options.content_type = Some(FeatureFlag::CONTENT_TYPE.to_string());
+
+        self.set_setting(flag.key(), serde_json::to_string(flag)?, Some(options))
+            .await
+    }
+
+    #[instrumented("ConfigurationClient::list_settings")]
+    pub fn list_settings(
+        &self,
+        options: Option<ListSettingsOptions>,
+    ) -> Pageable<ConfigurationSetting> {
+        let options = options.unwrap_or_default();
+        let client = self.clone();
+
+        Pageable::new(move |continuation_token: Option<String>| {
+            let client = client.clone();
+            let mut ctx = options.context.clone().unwrap_or_default();
+            let key_filter = options.key_filter.clone();
+            let label_filter = options.label_filter.clone();
+            let mut query = QueryBuilder::default();
+            query.with_select(options.select.iter().cloned());
+            async move {
+                ctx.insert(Namespace::from(NAMESPACE));
+                ctx.insert(Idempotent::from(true));
+
+                let url = match continuation_token {
+                    Some(next_link) => client.endpoint.join(&next_link)?,
+                    None => {
+                        let mut url = client.endpoint.clone();
+                        url.set_path("kv");
+                        if let Some(key_filter) = &key_filter {
+                            url.query_pairs_mut().append_pair("key", key_filter);
+                        }
+                        if let Some(label_filter) = &label_filter {
+                            url.query_pairs_mut().append_pair("label", label_filter);
+                        }
+                        query.append_to(&mut url);
+                        url
+                    }
+                };
+
+                let mut request = Request::new(url, "GET");
+                let response = client.pipeline.send(&mut ctx, &mut request).await?;
+                let headers = response.headers().clone();
+                let page: SettingsPage = response.json().await?;
+                Ok(Page::new(page.items, headers, page.next_link))
+            }
+        })
+    }
+
+    /// Gets a setting then polls for changes every [`WatchSettingOptions::interval`], yielding the
+    /// setting each time it changes.
+    ///
+    /// Changes written by this client, or recorded using [`ConfigurationClient::update_sync_token`],
+    /// are observed by the next poll.
+    pub fn watch_setting<K>(
+        &self,
+        key: K,
+        options: Option<WatchSettingOptions>,
+    ) -> impl Stream<Item = Result<Response<ConfigurationSetting>>> + Send
+    where
+        K: Into<String>,
+    {
+        let options = options.unwrap_or_default();
+        let setting = ConfigurationSetting {
+            key: key.into(),
+            label: options.label,
+            ..Default::default()
+        };
+
+        stream::unfold(
+            (self.clone(), setting, true),
+            move |(client, mut setting, first)| {
+                let get_options = GetSettingOptions {
+                    context: options.context.clone(),
+                    ..Default::default()
+                };
+                async move {
+                    let mut first = first;
+                    loop {
+                        if !first {
+                            sleep(options.interval).await;
+                        }
+                        first = false;
+
+                        match client
+                            .get_setting_if_changed(&setting, Some(get_options.clone()))
+                            .await
+                        {
+                            Ok(Some(response)) => {
+                                setting.etag = response
+                                    .headers()
+                                    .get_optional_string(&ETAG)
+                                    .map(ETag::from);
+                                return Some((Ok(response), (client, setting, false)));
+                            }
+                            Ok(None) => continue,
+                            Err(error) => return Some((Err(error), (client, setting, false))),
+                        }
+                    }
+                }
+            },
+        )
+    }
+
+    fn setting_url(&self, key: &str, label: Option<&str>) -> Url {
+        let mut url = self.endpoint.clone();
+        url.path_segments_mut()
+            .expect("endpoint is a base URL")
+            .pop_if_empty()
+            .push("kv")
+            .push(key);
+        if let Some(label) = label {
+            url.query_pairs_mut().append_pair("label", label);
+        }
+        url
+    }
+}
+
+#[derive(Debug, Clone, Options)]
+#[non_exhaustive]
+pub struct ConfigurationClientOptions {
+    #[options(default = "2023-10-01".to_string(), into)]
+    pub api_version: String,
+    pub options: ClientOptions,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct GetSettingOptions {
+    /// The label of the setting to get, or the setting without a label if not set.
+    #[options(into)]
+    pub label: Option<String>,
+    /// Gets the setting only if it does or does not match an ETag.
+    pub match_condition: Option<MatchCondition>,
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct SetSettingOptions {
+    #[options(into)]
+    pub label: Option<String>,
+    #[options(into)]
+    pub content_type: Option<String>,
+    pub tags: Option<HashMap<String, String>>,
+    /// Sets the setting only if it does or does not match an ETag, e.g., [`MatchCondition::IfNoneMatch`]
+    /// with [`ETag::any`] to only add a setting which does not exist.
+    pub match_condition: Option<MatchCondition>,
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct ListSettingsOptions {
+    /// Filters keys, e.g., `app1/*` for keys starting with `app1/`.
+    #[options(into)]
+    pub key_filter: Option<String>,
+    /// Filters labels, e.g., `\0` for settings without a label.
+    #[options(into)]
+    pub label_filter: Option<String>,
+    /// Gets only these fields of each setting, e.g., `key` and `value`, which must include `key`.
+    pub select: Vec<String>,
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct WatchSettingOptions {
+    #[options(into)]
+    pub label: Option<String>,
+    /// How long to wait between polls for changes.
+    #[options(default = Duration::from_secs(30))]
+    pub interval: Duration,
+    pub context: Option<Context>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_core::{
+        stream::BytesStream, transport::HttpClient, AccessToken, Headers, RetryOptions,
+        TokenRequestOptions, TransportOptions, AUTHORIZATION,
+    };
+    use std::{sync::Mutex, time::SystemTime};
+
+    #[derive(Debug)]
+    struct MockCredential;
+
+    #[async_trait::async_trait]
+    impl TokenCredential for MockCredential {
+        async fn get_token(
+            &self,
+            scopes: &[&str],
+            _options: &TokenRequestOptions,
+        ) -> Result<AccessToken> {
+            assert_eq!(scopes, ["https://azconfig.io/.default"]);
+            Ok(AccessToken::new(
+                "mock",
+                SystemTime::now() + Duration::from_secs(3600),
+            ))
+        }
+    }
+
+    /// Returns a setting, recording the `Authorization` header of each request.
+    #[derive(Debug, Default)]
+    struct SettingService {
+        authorizations: Mutex<Vec<Option<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClient for SettingService {
+        async fn execute_request(&self, request: &Request) -> Result<Response> {
+            self.authorizations
+                .lock()
+                .unwrap()
+                .push(request.headers().get_optional_string(&AUTHORIZATION));
+            Ok(Response::new(
+                200,
+                Headers::new(),
+                Box::pin(BytesStream::new(r#"{"key":"color","value":"blue"}"#)),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn sends_bearer_token() {
+        let service = Arc::new(SettingService::default());
+        let mut options = ConfigurationClientOptions::default();
+        options.options.retry = RetryOptions::none();
+        options.options.transport = TransportOptions::with_http_client(service.clone());
+        let client = ConfigurationClient::new(
+            "https://myconfig.azconfig.io",
+            Arc::new(MockCredential),
+            Some(options),
+        )
+        .unwrap();
+
+        let setting = client
+            .get_setting("color", None)
+            .await
+            .unwrap()
+            .into_model()
+            .await
+            .unwrap();
+        assert_eq!(setting.value.as_deref(), Some("blue"));
+        assert_eq!(
+            *service.authorizations.lock().unwrap(),
+            [Some("Bearer mock".to_string())]
+        );
+    }
+}
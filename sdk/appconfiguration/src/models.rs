@@ -0,0 +1,88 @@
+use azure_core::{json::from_json, ETag, Error, ErrorKind};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A key-value stored in App Configuration, optionally distinguished by a label.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[non_exhaustive]
+pub struct ConfigurationSetting {
+    pub key: String,
+    pub label: Option<String>,
+    pub value: Option<String>,
+    pub content_type: Option<String>,
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+    /// Identifies this version of the setting for conditional requests.
+    pub etag: Option<ETag>,
+    pub last_modified: Option<String>,
+    /// Whether the setting is read-only.
+    #[serde(default)]
+    pub locked: bool,
+}
+
+impl ConfigurationSetting {
+    /// Whether this setting stores a [`FeatureFlag`].
+    pub fn is_feature_flag(&self) -> bool {
+        self.key.starts_with(FeatureFlag::KEY_PREFIX)
+            && self.content_type.as_deref() == Some(FeatureFlag::CONTENT_TYPE)
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub(crate) struct SetSettingRequest {
+    pub value: Option<String>,
+    pub content_type: Option<String>,
+    pub tags: Option<HashMap<String, String>>,
+}
+
+/// A feature flag, which App Configuration stores as JSON in a setting whose key starts with
+/// [`FeatureFlag::KEY_PREFIX`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct FeatureFlag {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub enabled: bool,
+}
+
+impl FeatureFlag {
+    pub const KEY_PREFIX: &'static str = ".appconfig.featureflag/";
+    pub const CONTENT_TYPE: &'static str =
+        "application/vnd.microsoft.appconfig.ff+json;charset=utf-8";
+
+    pub fn new(id: impl Into<String>, enabled: bool) -> Self {
+        Self {
+            id: id.into(),
+            description: None,
+            enabled,
+        }
+    }
+
+    /// Gets the key of the setting which stores this feature flag.
+    pub fn key(&self) -> String {
+        format!("{}{}", Self::KEY_PREFIX, self.id)
+    }
+}
+
+impl TryFrom<&ConfigurationSetting> for FeatureFlag {
+    type Error = Error;
+
+    fn try_from(setting: &ConfigurationSetting) -> azure_core::Result<Self> {
+        if !setting.is_feature_flag() {
+            return Err(Error::with_message(ErrorKind::DataConversion, || {
+                format!("setting '{}' is not a feature flag", setting.key)
+            }));
+        }
+        from_json(setting.value.as_deref().unwrap_or_default())
+    }
+}
+
+/// A page of settings, where the next link is relative to the endpoint.
+#[derive(Deserialize)]
+pub(crate) struct SettingsPage {
+    #[serde(default)]
+    pub items: Vec<ConfigurationSetting>,
+    #[serde(rename = "@nextLink")]
+    pub next_link: Option<String>,
+}
@@ -0,0 +1,148 @@
+use azure_core::{
+    policies::{Policy, PolicyResult},
+    Context, HeaderName, Headers, HttpError, Request,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+const SYNC_TOKEN: HeaderName = HeaderName::from_static("sync-token");
+
+/// Sends the latest sync tokens so reads reflect every write this client has seen, including
+/// writes notified to [`ConfigurationClient::update_sync_token`](crate::ConfigurationClient::update_sync_token).
+///
+/// App Configuration returns tokens formatted as `<id>=<value>;sn=<sequence number>`, separated by
+/// commas, and accepts them as `<id>=<value>`. Only the token with the highest sequence number is
+/// kept for each ID.
+#[derive(Debug, Default)]
+pub(crate) struct SyncTokenPolicy {
+    tokens: Mutex<HashMap<String, SyncToken>>,
+}
+
+#[derive(Debug)]
+struct SyncToken {
+    value: String,
+    sequence_number: u64,
+}
+
+impl SyncTokenPolicy {
+    /// Records each valid token in a `Sync-Token` header value, ignoring any tokens not formatted
+    /// as App Configuration returns them.
+    pub(crate) fn update(&self, header: &str) {
+        let mut tokens = self.tokens.lock().unwrap();
+        for token in header.split(',') {
+            let Some((id, value, sequence_number)) = parse(token.trim()) else {
+                continue;
+            };
+            match tokens.get(id) {
+                Some(existing) if existing.sequence_number >= sequence_number => {}
+                _ => {
+                    tokens.insert(
+                        id.to_string(),
+                        SyncToken {
+                            value: value.to_string(),
+                            sequence_number,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    fn header(&self) -> Option<String> {
+        let tokens = self.tokens.lock().unwrap();
+        if tokens.is_empty() {
+            return None;
+        }
+        let mut header: Vec<_> = tokens
+            .iter()
+            .map(|(id, token)| format!("{id}={}", token.value))
+            .collect();
+        header.sort();
+        Some(header.join(","))
+    }
+}
+
+fn parse(token: &str) -> Option<(&str, &str, u64)> {
+    let (token, sequence_number) = token.split_once(";sn=")?;
+    let (id, value) = token.split_once('=')?;
+    Some((id, value, sequence_number.parse().ok()?))
+}
+
+#[async_trait::async_trait]
+impl Policy for SyncTokenPolicy {
+    async fn send(
+        &self,
+        ctx: &mut Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        if let Some(header) = self.header() {
+            request.insert_header(SYNC_TOKEN, header);
+        }
+        let result = next[0].send(ctx, request, &next[1..]).await;
+        // Failed writes may still have been applied, e.g., by an earlier attempt.
+        let headers: Option<&Headers> = match &result {
+            Ok(response) => Some(response.headers()),
+            Err(error) => error.downcast_ref::<HttpError>().map(HttpError::headers),
+        };
+        if let Some(header) = headers.and_then(|headers| headers.get_optional_str(&SYNC_TOKEN)) {
+            self.update(header);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_core::{
+        stream::BytesStream, transport::HttpClient, ClientOptions, Pipeline, Response,
+        RetryOptions, TransportOptions, Url,
+    };
+
+    /// Fails each request with a conflict, returning a newer sync token each time.
+    #[derive(Debug, Default)]
+    struct ConflictService {
+        sent: Mutex<Vec<Option<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClient for ConflictService {
+        async fn execute_request(&self, request: &Request) -> azure_core::Result<Response> {
+            let mut sent = self.sent.lock().unwrap();
+            sent.push(request.headers().get_optional_string(&SYNC_TOKEN));
+            let mut headers = Headers::new();
+            headers.insert(SYNC_TOKEN, format!("jtqGc1I4=v{0};sn={0}", sent.len()));
+            Ok(Response::new(409, headers, Box::pin(BytesStream::new(""))))
+        }
+    }
+
+    #[tokio::test]
+    async fn updates_from_errors() {
+        let service = Arc::new(ConflictService::default());
+        let policy = Arc::new(SyncTokenPolicy::default());
+        let mut options = ClientOptions::default();
+        options.retry = RetryOptions::none();
+        options.transport = TransportOptions::with_http_client(service.clone());
+        let pipeline = Pipeline::new(None, None, &options, vec![policy.clone()], Vec::new());
+
+        for _ in 0..2 {
+            let mut request = Request::new(
+                Url::parse("https://myconfig.azconfig.io/kv/color").unwrap(),
+                "PUT",
+            );
+            let error = pipeline
+                .send(&mut Context::default(), &mut request)
+                .await
+                .unwrap_err();
+            assert_eq!(error.status(), Some(409));
+        }
+        assert_eq!(
+            *service.sent.lock().unwrap(),
+            [None, Some("jtqGc1I4=v1".to_string())]
+        );
+        assert_eq!(policy.header().as_deref(), Some("jtqGc1I4=v2"));
+    }
+}
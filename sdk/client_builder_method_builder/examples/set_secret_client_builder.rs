@@ -1,17 +1,14 @@
-use azure_client_builder_method_builder::{Secret, SecretClient, SecretProperties};
+use azure_client_builder_method_builder::{SecretClient, SecretProperties};
 use azure_core::{ClientBuilder, Context, ExponentialRetryOptions, RetryOptions};
-use azure_identity::DefaultAzureCredential;
-use std::{env, sync::Arc};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = env::var("AZURE_KEYVAULT_URL")?;
-
-    let credential = Arc::new(DefaultAzureCredential::default());
-    let client = SecretClient::builder(endpoint, credential)? // Create a mutable builder.
+    // Reads AZURE_KEYVAULT_URL and authenticates using DefaultAzureCredential.
+    let client = SecretClient::builder() // Create a mutable builder.
+        .with_env()
         .with_api_version("7.4")
         .with_retry(RetryOptions::exponential(ExponentialRetryOptions::default()))
-        .build(); // Creates an immutable client.
+        .build()?; // Creates an immutable client.
 
     // Simple client method call.
     let response = client
@@ -19,7 +16,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .send()
         .await?;
 
-    let secret: Secret = response.json().await?;
+    let secret = response.into_model().await?;
     println!("set {} version {}", secret.name, secret.version);
 
     // More complex client method call.
@@ -29,11 +26,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let response = client
         .set_secret("secret-name", "rotated-value")
         .with_context(ctx)
-        .with_properties(SecretProperties { enabled: false })
+        .with_properties(SecretProperties::new(false))
         .send()
         .await?;
 
-    let secret: Secret = response.json().await?;
+    let secret = response.into_model().await?;
     println!("set {} version {}", secret.name, secret.version);
 
     // Concurrent client method calls with same options.
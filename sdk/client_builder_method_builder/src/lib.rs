@@ -1,32 +1,93 @@
 use azure_core::{
-    policies::{ApiKeyAuthenticationPolicy, Policy},
-    ClientBuilder, ClientOptions, Pipeline, Response, Result, TokenCredential, Url,
+    parse_endpoint,
+    policies::{ChallengeAuthenticationPolicy, Policy},
+    BuildError, ClientBuilder, ClientDiagnostics, ClientOptions, Idempotent, Namespace,
+    OperationName, Pipeline, Response, Result, SensitiveFields, ServiceAudiences, TokenCredential,
+    Url,
+};
+#[cfg(feature = "identity")]
+use azure_identity::DefaultAzureCredential;
+use azure_keyvault_common::{list_secrets_url, validate_secret_name, SoftDeleteConflictRetry};
+use futures::future::BoxFuture;
+#[cfg(feature = "identity")]
+use std::env;
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{self, Poll},
 };
-use std::sync::Arc;
 
 mod models;
 pub use models::*;
 
+const NAMESPACE: &str = "Microsoft.KeyVault";
+const AUDIENCES: ServiceAudiences = ServiceAudiences {
+    azure_public: "https://vault.azure.net",
+    azure_china: "https://vault.azure.cn",
+    azure_us_government: "https://vault.usgovcloudapi.net",
+};
+const SENSITIVE_FIELDS: &[&str] = &["value"];
+
 pub const DEFAULT_API_VERSION: &str = "7.5";
 
-#[derive(Clone, Debug)]
+/// The environment variable [`SecretClientBuilder::with_env`] reads the vault URL from.
+#[cfg(feature = "identity")]
+pub const ENDPOINT_ENV_VAR: &str = "AZURE_KEYVAULT_URL";
+
+#[derive(Clone, Debug, Default)]
 pub struct SecretClientBuilder {
-    endpoint: Url,
-    credential: Arc<dyn TokenCredential>,
+    endpoint: Option<String>,
+    /// The environment variable the endpoint was to be read from, if it was not set.
+    unset_endpoint_env_var: Option<String>,
+    credential: Option<Arc<dyn TokenCredential>>,
     api_version: Option<String>,
     scopes: Option<Vec<String>>,
+    retry_soft_delete_conflicts: bool,
+    anonymous: bool,
     options: ClientOptions,
 }
 
 impl SecretClientBuilder {
-    pub fn new(endpoint: impl AsRef<str>, credential: Arc<dyn TokenCredential>) -> Result<Self> {
-        Ok(Self {
-            endpoint: Url::parse(endpoint.as_ref())?,
-            credential: credential.clone(),
-            api_version: None,
-            scopes: None,
-            options: ClientOptions::default(),
-        })
+    /// Sets the vault URL, e.g., `https://my-vault.vault.azure.net`. Required.
+    pub fn with_endpoint(&mut self, endpoint: impl Into<String>) -> &mut Self {
+        self.endpoint = Some(endpoint.into());
+        self.unset_endpoint_env_var = None;
+        self
+    }
+
+    /// Sets the credential used to authenticate requests. Required.
+    pub fn with_credential(&mut self, credential: Arc<dyn TokenCredential>) -> &mut Self {
+        self.credential = Some(credential);
+        self
+    }
+
+    /// Sets the endpoint from the [`ENDPOINT_ENV_VAR`] environment variable and, unless already set,
+    /// the credential to a [`DefaultAzureCredential`]. Requires the `identity` feature.
+    #[cfg(feature = "identity")]
+    pub fn with_env(&mut self) -> &mut Self {
+        self.with_env_var(ENDPOINT_ENV_VAR)
+    }
+
+    /// Like [`with_env`](Self::with_env) but reads the vault URL from the environment variable
+    /// `endpoint`. If it is not set, [`build`](Self::build) fails with an invalid endpoint naming
+    /// the variable.
+    #[cfg(feature = "identity")]
+    pub fn with_env_var(&mut self, endpoint: &str) -> &mut Self {
+        match env::var(endpoint) {
+            Ok(endpoint) => {
+                self.with_endpoint(endpoint);
+            }
+            Err(_) => {
+                self.endpoint = None;
+                self.unset_endpoint_env_var = Some(endpoint.to_owned());
+            }
+        }
+        if self.credential.is_none() {
+            self.credential = Some(Arc::new(DefaultAzureCredential::default()));
+        }
+        self
     }
 
     pub fn with_api_version(&mut self, api_version: impl Into<String>) -> &mut Self {
@@ -39,34 +100,89 @@ impl SecretClientBuilder {
         self
     }
 
-    pub fn build(&self) -> SecretClient {
-        let mut endpoint = self.endpoint.clone();
-        endpoint.query_pairs_mut().clear().append_pair(
-            "api-version",
-            self.api_version
-                .as_ref()
-                .map(|version| version.as_ref())
-                .unwrap_or(DEFAULT_API_VERSION),
-        );
+    /// Retries the 409 conflicts returned when re-creating a deleted secret which is still being
+    /// purged, up to 5 times every 2 seconds.
+    pub fn with_retry_soft_delete_conflicts(&mut self, retry: bool) -> &mut Self {
+        self.retry_soft_delete_conflicts = retry;
+        self
+    }
 
-        let auth_policy: Arc<dyn Policy> = Arc::new(ApiKeyAuthenticationPolicy::new(
-            self.credential.clone(),
-            "https://vault.azure.net/.default".to_string(),
-        ));
+    /// Gets the resolved configuration of the client this would build.
+    pub fn diagnostics(&self) -> Result<ClientDiagnostics> {
+        Ok(self.build()?.diagnostics())
+    }
 
-        let mut per_retry_policies = vec![auth_policy];
+    /// Builds the client, returning an [`ErrorKind::InvalidInput`](azure_core::ErrorKind::InvalidInput)
+    /// error wrapping a [`BuildError`] which lists every setting that is missing or not valid.
+    pub fn build(&self) -> Result<SecretClient> {
+        let mut error = BuildError::default();
+        let endpoint = match self.endpoint.as_deref().map(parse_endpoint) {
+            Some(Ok(endpoint)) => Some(endpoint),
+            Some(Err(err)) => {
+                error.invalid("endpoint", err.to_string());
+                None
+            }
+            None => {
+                match &self.unset_endpoint_env_var {
+                    Some(name) => error.invalid(
+                        "endpoint",
+                        format!("environment variable {name} is not set"),
+                    ),
+                    None => error.missing("endpoint"),
+                };
+                None
+            }
+        };
+        match (&self.credential, self.anonymous) {
+            (None, false) => {
+                error.missing("credential");
+            }
+            (Some(_), true) => {
+                error.invalid("credential", "must not be set for an anonymous client");
+            }
+            _ => {}
+        }
+        let api_version = self.api_version.as_deref().unwrap_or(DEFAULT_API_VERSION);
+        if api_version.is_empty() {
+            error.invalid("api_version", "must not be empty");
+        }
+        if let Err(err) = self.options.validate() {
+            error.invalid("options", err.to_string());
+        }
+        error.into_result()?;
+        let Some(mut endpoint) = endpoint else {
+            unreachable!("missing settings were returned");
+        };
+
+        endpoint
+            .query_pairs_mut()
+            .clear()
+            .append_pair("api-version", api_version);
+
+        let mut per_retry_policies: Vec<Arc<dyn Policy>> = Vec::new();
+        if let Some(credential) = &self.credential {
+            per_retry_policies.push(Arc::new(ChallengeAuthenticationPolicy::new(
+                credential.clone(),
+                Some(AUDIENCES.scope(&endpoint, &self.options)),
+            )));
+        }
         per_retry_policies.extend_from_slice(&self.options.per_retry_policies);
 
-        SecretClient {
+        let options =
+            SoftDeleteConflictRetry::apply(&self.options, self.retry_soft_delete_conflicts);
+
+        let mut per_call_policies = self.options.per_call_policies.clone();
+        per_call_policies.push(Arc::new(SensitiveFields::from(SENSITIVE_FIELDS)));
+        Ok(SecretClient {
             endpoint,
             pipeline: Pipeline::new(
                 option_env!("CARGO_PKG_NAME"),
                 option_env!("CARGO_PKG_VERSION"),
-                &self.options,
-                self.options.per_call_policies.clone(),
+                &options,
+                per_call_policies,
                 per_retry_policies,
             ),
-        }
+        })
     }
 }
 
@@ -83,17 +199,54 @@ pub struct SecretClient {
 }
 
 impl SecretClient {
-    pub fn builder(
-        endpoint: impl AsRef<str>,
-        credential: Arc<dyn TokenCredential>,
-    ) -> Result<SecretClientBuilder> {
-        SecretClientBuilder::new(endpoint, credential)
+    pub fn builder() -> SecretClientBuilder {
+        SecretClientBuilder::default()
+    }
+
+    /// Gets a builder for a client which sends requests without authorization, e.g., to a local
+    /// emulator or when playing back recorded responses. Setting a credential is an error.
+    pub fn builder_anonymous() -> SecretClientBuilder {
+        SecretClientBuilder {
+            anonymous: true,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a client for the vault URL in the [`ENDPOINT_ENV_VAR`] environment variable which
+    /// authenticates using a [`DefaultAzureCredential`]. Requires the `identity` feature.
+    #[cfg(feature = "identity")]
+    pub fn from_env() -> Result<Self> {
+        Self::builder().with_env().build()
     }
 
     pub fn endpoint(&self) -> &Url {
         &self.endpoint
     }
 
+    /// Gets the resolved configuration of the client without sending any requests.
+    pub fn diagnostics(&self) -> ClientDiagnostics {
+        self.pipeline.diagnostics(&self.endpoint)
+    }
+
+    /// Creates a secret, or adds a new version of it if it already exists.
+    ///
+    /// ```
+    /// # use azure_client_builder_method_builder::SecretClient;
+    /// # use azure_core::{transport::InMemoryTransport, ClientBuilder, ResponseBuilder};
+    /// # #[tokio::main]
+    /// # async fn main() -> azure_core::Result<()> {
+    /// # let transport = InMemoryTransport::new(
+    /// #     ResponseBuilder::new(200).with_body(r#"{"name":"my-secret","version":"1","attributes":{"enabled":true}}"#),
+    /// # );
+    /// let client = SecretClient::builder_anonymous()
+    ///     .with_endpoint("https://my-vault.vault.azure.net")
+    ///     .with_transport(transport)
+    ///     .build()?;
+    /// let secret = client.set_secret("my-secret", "my-value").send().await?.into_model().await?;
+    /// assert_eq!(secret.version, "1");
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn set_secret<N, V>(&self, name: N, value: V) -> set_secret::SetSecretRequestBuilder
     where
         N: Into<String>,
@@ -108,12 +261,101 @@ impl SecretClient {
             context: None,
         }
     }
+
+    /// Gets the latest version of a secret, or the version set with `with_version`.
+    ///
+    /// ```
+    /// # use azure_client_builder_method_builder::SecretClient;
+    /// # use azure_core::{transport::InMemoryTransport, ClientBuilder, ResponseBuilder};
+    /// # #[tokio::main]
+    /// # async fn main() -> azure_core::Result<()> {
+    /// # let transport = InMemoryTransport::new(
+    /// #     ResponseBuilder::new(200).with_body(r#"{"name":"my-secret","version":"1","attributes":{"enabled":true}}"#),
+    /// # );
+    /// let client = SecretClient::builder_anonymous()
+    ///     .with_endpoint("https://my-vault.vault.azure.net")
+    ///     .with_transport(transport)
+    ///     .build()?;
+    /// let secret = client.get_secret("my-secret").send().await?.into_model().await?;
+    /// assert_eq!(secret.version, "1");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_secret<N>(&self, name: N) -> get_secret::GetSecretRequestBuilder
+    where
+        N: Into<String>,
+    {
+        get_secret::GetSecretRequestBuilder {
+            client: self.clone(),
+            name: name.into(),
+            version: None,
+            context: None,
+        }
+    }
+
+    pub fn begin_delete_secret<N>(&self, name: N) -> delete_secret::DeleteSecretRequestBuilder
+    where
+        N: Into<String>,
+    {
+        delete_secret::DeleteSecretRequestBuilder {
+            client: self.clone(),
+            name: name.into(),
+            context: None,
+        }
+    }
+
+    pub fn list_secrets(&self) -> list_secrets::ListSecretsRequestBuilder {
+        list_secrets::ListSecretsRequestBuilder {
+            client: self.clone(),
+            max_results: None,
+            include_managed: None,
+            context: None,
+        }
+    }
+}
+
+/// The future returned by `send()`, which names the client operation it calls.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct OperationFuture<T> {
+    operation_name: OperationName,
+    future: BoxFuture<'static, Result<T>>,
+}
+
+impl<T> OperationFuture<T> {
+    fn new(span: &'static str, future: impl Future<Output = Result<T>> + Send + 'static) -> Self {
+        Self {
+            operation_name: OperationName::from(span),
+            future: Box::pin(future),
+        }
+    }
+
+    /// Gets the name of the client operation, e.g., `SecretClient::set_secret`.
+    pub fn operation_name(&self) -> &OperationName {
+        &self.operation_name
+    }
+}
+
+impl<T> Future for OperationFuture<T> {
+    type Output = Result<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().future.as_mut().poll(cx)
+    }
+}
+
+impl<T> fmt::Debug for OperationFuture<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OperationFuture")
+            .field("operation_name", &self.operation_name)
+            .finish_non_exhaustive()
+    }
 }
 
 mod set_secret {
     use super::*;
-    use azure_core::{Context, Request, Span};
-    use futures::future::BoxFuture;
+    use azure_core::{instrumented, Context, Request};
+
+    const SPAN: &str = "SecretClient::set_secret";
 
     #[derive(Clone, Debug)]
     pub struct SetSecretRequestBuilder {
@@ -141,12 +383,16 @@ mod set_secret {
             self
         }
 
-        pub fn send(&self) -> BoxFuture<Result<Response>> {
-            Box::pin({
+        #[instrumented(SPAN)]
+        pub fn send(&self) -> OperationFuture<Response<Secret>> {
+            OperationFuture::new(SPAN, {
                 let this = self.clone();
                 async move {
                     let mut ctx = this.context.unwrap_or_default();
-                    ctx.insert(Span::from("SecretClient::set_secret"));
+                    ctx.insert(Namespace::from(NAMESPACE));
+                    ctx.insert(Idempotent::from(false));
+
+                    validate_secret_name(&this.name)?;
 
                     let mut url = this.client.endpoint.clone();
                     url.set_path(&format!("secrets/{}", this.name));
@@ -154,14 +400,229 @@ mod set_secret {
                     let body = SetSecretRequest {
                         value: this.value,
                         properties: this.properties,
+                        content_type: this.content_type,
                         ..Default::default()
                     };
-                    let mut request = Request::new(url, "GET");
+                    let mut request = Request::new(url, "PUT");
                     request.set_json(&body)?;
 
-                    this.client.pipeline.send(&mut ctx, &mut request).await
+                    let response = this.client.pipeline.send(&mut ctx, &mut request).await?;
+                    Ok(response.with_model())
                 }
             })
         }
     }
 }
+
+mod get_secret {
+    use super::*;
+    use azure_core::{instrumented, Context, Request};
+
+    const SPAN: &str = "SecretClient::get_secret";
+
+    #[derive(Clone, Debug)]
+    pub struct GetSecretRequestBuilder {
+        pub(crate) client: SecretClient,
+        pub(crate) name: String,
+        pub(crate) version: Option<String>,
+        pub(crate) context: Option<Context>,
+    }
+
+    impl GetSecretRequestBuilder {
+        /// Sets the version of the secret to get, or the latest version if not set.
+        pub fn with_version(&mut self, version: impl Into<String>) -> &mut Self {
+            self.version = Some(version.into());
+            self
+        }
+
+        pub fn with_context(&mut self, context: Context) -> &mut Self {
+            self.context = Some(context);
+            self
+        }
+
+        #[instrumented(SPAN)]
+        pub fn send(&self) -> OperationFuture<Response<Secret>> {
+            OperationFuture::new(SPAN, {
+                let this = self.clone();
+                async move {
+                    let mut ctx = this.context.unwrap_or_default();
+                    ctx.insert(Namespace::from(NAMESPACE));
+                    ctx.insert(Idempotent::from(true));
+
+                    validate_secret_name(&this.name)?;
+
+                    let mut url = this.client.endpoint.clone();
+                    url.set_path(&format!(
+                        "secrets/{}/{}",
+                        this.name,
+                        this.version.unwrap_or_default()
+                    ));
+
+                    let mut request = Request::new(url, "GET");
+                    let response = this.client.pipeline.send(&mut ctx, &mut request).await?;
+                    Ok(response.with_model())
+                }
+            })
+        }
+
+        /// Sends the request like [`GetSecretRequestBuilder::send`] but returns `None` if the secret
+        /// does not exist.
+        pub fn send_if_exists(&self) -> OperationFuture<Option<Response<Secret>>> {
+            let response = self.send();
+            OperationFuture::new(SPAN, async move {
+                match response.await {
+                    Ok(response) => Ok(Some(response)),
+                    Err(error) if error.is_not_found() => Ok(None),
+                    Err(error) => Err(error),
+                }
+            })
+        }
+    }
+}
+
+mod delete_secret {
+    use super::*;
+    use azure_core::{instrumented, Context, Poller};
+
+    const SPAN: &str = "SecretClient::begin_delete_secret";
+
+    #[derive(Clone, Debug)]
+    pub struct DeleteSecretRequestBuilder {
+        pub(crate) client: SecretClient,
+        pub(crate) name: String,
+        pub(crate) context: Option<Context>,
+    }
+
+    impl DeleteSecretRequestBuilder {
+        pub fn with_context(&mut self, context: Context) -> &mut Self {
+            self.context = Some(context);
+            self
+        }
+
+        #[instrumented(SPAN)]
+        pub fn send(&self) -> OperationFuture<Poller<DeletedSecret>> {
+            OperationFuture::new(SPAN, {
+                let this = self.clone();
+                async move {
+                    let mut ctx = this.context.clone().unwrap_or_default();
+                    ctx.insert(Namespace::from(NAMESPACE));
+                    ctx.insert(Idempotent::from(true));
+
+                    azure_keyvault_common::begin_delete_secret(
+                        &this.client.pipeline,
+                        &ctx,
+                        &this.client.endpoint,
+                        &this.name,
+                    )
+                    .await
+                }
+            })
+        }
+    }
+}
+
+mod list_secrets {
+    use super::*;
+    use azure_core::{instrumented, Context, Page, Pageable, Request};
+
+    const SPAN: &str = "SecretClient::list_secrets";
+
+    #[derive(Clone, Debug)]
+    pub struct ListSecretsRequestBuilder {
+        pub(crate) client: SecretClient,
+        pub(crate) max_results: Option<u32>,
+        pub(crate) include_managed: Option<bool>,
+        pub(crate) context: Option<Context>,
+    }
+
+    impl ListSecretsRequestBuilder {
+        /// Sets the maximum number of secrets to return per page, from 1 to 25.
+        pub fn with_max_results(&mut self, max_results: u32) -> &mut Self {
+            self.max_results = Some(max_results);
+            self
+        }
+
+        /// Sets whether to include secrets managed by Key Vault, such as those backing certificates.
+        pub fn with_include_managed(&mut self, include_managed: bool) -> &mut Self {
+            self.include_managed = Some(include_managed);
+            self
+        }
+
+        pub fn with_context(&mut self, context: Context) -> &mut Self {
+            self.context = Some(context);
+            self
+        }
+
+        #[instrumented(SPAN)]
+        pub fn send(&self) -> Result<Pageable<SecretItem>> {
+            let this = self.clone();
+            let mut ctx = this.context.unwrap_or_default();
+            ctx.insert(Namespace::from(NAMESPACE));
+            ctx.insert(Idempotent::from(true));
+
+            let first_page = list_secrets_url(
+                &this.client.endpoint,
+                this.max_results,
+                this.include_managed,
+            )?;
+            Ok(Pageable::new(move |continuation_token: Option<String>| {
+                let client = this.client.clone();
+                let first_page = first_page.clone();
+                let mut ctx = ctx.clone();
+                async move {
+                    let url = match continuation_token {
+                        Some(next_link) => Url::parse(&next_link)?,
+                        None => first_page,
+                    };
+
+                    let mut request = Request::new(url, "GET");
+                    let response = client.pipeline.send(&mut ctx, &mut request).await?;
+                    Page::from_response(response).await
+                }
+            }))
+        }
+    }
+}
+
+#[cfg(all(test, feature = "identity"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_endpoint_from_env_var() {
+        env::set_var(
+            "TEST_CLIENT_BUILDER_VAULT_URL",
+            "https://my-vault.vault.azure.net",
+        );
+        let client = SecretClient::builder()
+            .with_env_var("TEST_CLIENT_BUILDER_VAULT_URL")
+            .build()
+            .unwrap();
+        assert_eq!(
+            client.endpoint().as_str(),
+            "https://my-vault.vault.azure.net/?api-version=7.5"
+        );
+    }
+
+    #[test]
+    fn names_missing_env_var() {
+        let error = SecretClient::builder()
+            .with_env_var("TEST_CLIENT_BUILDER_UNSET_VAULT_URL")
+            .build()
+            .unwrap_err();
+        let build_error = error.downcast_ref::<BuildError>().unwrap();
+        assert!(build_error.missing_settings().is_empty());
+        assert_eq!(
+            build_error.to_string(),
+            "cannot build client: invalid endpoint: \
+             environment variable TEST_CLIENT_BUILDER_UNSET_VAULT_URL is not set"
+        );
+
+        // An endpoint set afterward is used instead.
+        let client = SecretClient::builder()
+            .with_env_var("TEST_CLIENT_BUILDER_UNSET_VAULT_URL")
+            .with_endpoint("https://my-vault.vault.azure.net")
+            .build();
+        assert!(client.is_ok());
+    }
+}
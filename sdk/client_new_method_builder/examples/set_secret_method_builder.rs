@@ -1,7 +1,5 @@
-use azure_client_new_method_builder::{
-    Secret, SecretClient, SecretClientOptions, SecretProperties,
-};
-use azure_core::{ClientOptions, Context, ExponentialRetryOptions, RetryOptions};
+use azure_client_new_method_builder::{SecretClient, SecretClientOptions, SecretProperties};
+use azure_core::{Context, ExponentialRetryOptions, RetryOptions};
 use azure_identity::DefaultAzureCredential;
 use std::{env, sync::Arc};
 
@@ -10,13 +8,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let endpoint = env::var("AZURE_KEYVAULT_URL")?;
 
     let credential = Arc::new(DefaultAzureCredential::default());
-    let options = SecretClientOptions {
-        api_version: "7.4".to_string(),
-        options: ClientOptions {
-            retry: RetryOptions::exponential(ExponentialRetryOptions {}),
-            ..Default::default()
-        },
-    };
+    let mut options = SecretClientOptions::default();
+    options.api_version = "7.4".to_string();
+    options.options.retry = RetryOptions::exponential(ExponentialRetryOptions::default());
     let client = SecretClient::new(endpoint, credential, Some(options))?;
 
     // Simple client method call.
@@ -25,7 +19,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .send()
         .await?;
 
-    let secret: Secret = response.json().await?;
+    let secret = response.into_model().await?;
     println!("set {} version {}", secret.name, secret.version);
 
     // More complex client method call.
@@ -35,11 +29,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let response = client
         .set_secret("secret-name", "rotated-value")
         .with_context(ctx)
-        .with_properties(SecretProperties { enabled: false })
+        .with_properties(SecretProperties::new(false))
         .send()
         .await?;
 
-    let secret: Secret = response.json().await?;
+    let secret = response.into_model().await?;
     println!("set {} version {}", secret.name, secret.version);
 
     // Concurrent client method calls with same options.
@@ -56,8 +50,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .set_secret("bar", "bar-value")
             .with_content_type("text/plain")
             .with_context(ctx.clone())
-            .send()
-        ,
+            .send(),
     );
 
     Ok(())
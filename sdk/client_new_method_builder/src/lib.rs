@@ -1,12 +1,23 @@
 use azure_core::{
-    policies::{ApiKeyAuthenticationPolicy, Policy},
-    ClientOptions, Pipeline, Response, Result, TokenCredential, Url,
+    parse_endpoint,
+    policies::{ChallengeAuthenticationPolicy, Policy},
+    ClientDiagnostics, ClientOptions, Error, ErrorKind, Idempotent, Namespace, Pipeline, Response,
+    Result, SensitiveFields, ServiceAudiences, TokenCredential, Url,
 };
+use azure_keyvault_common::{list_secrets_url, validate_secret_name, SoftDeleteConflictRetry};
 use std::sync::Arc;
 
 mod models;
 pub use models::*;
 
+const NAMESPACE: &str = "Microsoft.KeyVault";
+const AUDIENCES: ServiceAudiences = ServiceAudiences {
+    azure_public: "https://vault.azure.net",
+    azure_china: "https://vault.azure.cn",
+    azure_us_government: "https://vault.usgovcloudapi.net",
+};
+const SENSITIVE_FIELDS: &[&str] = &["value"];
+
 #[derive(Debug, Clone)]
 pub struct SecretClient {
     endpoint: Url,
@@ -20,15 +31,23 @@ impl SecretClient {
         options: Option<SecretClientOptions>,
     ) -> Result<Self> {
         let options = options.unwrap_or_default();
-        let mut endpoint = Url::parse(endpoint.as_ref())?;
+        if options.api_version.is_empty() {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "api_version must not be empty",
+            ));
+        }
+        options.options.validate()?;
+
+        let mut endpoint = parse_endpoint(endpoint)?;
         endpoint
             .query_pairs_mut()
             .clear()
             .append_pair("api-version", &options.api_version);
 
-        let auth_policy: Arc<dyn Policy> = Arc::new(ApiKeyAuthenticationPolicy::new(
+        let auth_policy: Arc<dyn Policy> = Arc::new(ChallengeAuthenticationPolicy::new(
             credential.clone(),
-            "https://vault.azure.net/.default".to_string(),
+            Some(AUDIENCES.scope(&endpoint, &options.options)),
         ));
         let per_retry_policies = vec![auth_policy];
 
@@ -37,8 +56,11 @@ impl SecretClient {
             pipeline: Pipeline::new(
                 option_env!("CARGO_PKG_NAME"),
                 option_env!("CARGO_PKG_VERSION"),
-                &options.options,
-                Vec::default(),
+                &SoftDeleteConflictRetry::apply(
+                    &options.options,
+                    options.retry_soft_delete_conflicts,
+                ),
+                vec![Arc::new(SensitiveFields::from(SENSITIVE_FIELDS))],
                 per_retry_policies,
             ),
         })
@@ -48,6 +70,31 @@ impl SecretClient {
         &self.endpoint
     }
 
+    /// Gets the resolved configuration of the client without sending any requests.
+    pub fn diagnostics(&self) -> ClientDiagnostics {
+        self.pipeline.diagnostics(&self.endpoint)
+    }
+
+    /// Creates a secret, or adds a new version of it if it already exists.
+    ///
+    /// ```
+    /// # use azure_client_new_method_builder::{SecretClient, SecretClientOptions};
+    /// # use azure_core::{test::FakeCredential, transport::InMemoryTransport, ResponseBuilder};
+    /// # use std::sync::Arc;
+    /// # #[tokio::main]
+    /// # async fn main() -> azure_core::Result<()> {
+    /// # let mut options = SecretClientOptions::default();
+    /// # options.options.transport = InMemoryTransport::new(
+    /// #     ResponseBuilder::new(200).with_body(r#"{"name":"my-secret","version":"1","attributes":{"enabled":true}}"#),
+    /// # )
+    /// # .into();
+    /// let credential = Arc::new(FakeCredential);
+    /// let client = SecretClient::new("https://my-vault.vault.azure.net", credential, Some(options))?;
+    /// let secret = client.set_secret("my-secret", "my-value").send().await?.into_model().await?;
+    /// assert_eq!(secret.version, "1");
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn set_secret<N, V>(&self, name: N, value: V) -> set_secret::SetSecretRequestBuilder
     where
         N: Into<String>,
@@ -62,11 +109,67 @@ impl SecretClient {
             context: None,
         }
     }
+
+    /// Gets the latest version of a secret, or the version set with `with_version`.
+    ///
+    /// ```
+    /// # use azure_client_new_method_builder::{SecretClient, SecretClientOptions};
+    /// # use azure_core::{test::FakeCredential, transport::InMemoryTransport, ResponseBuilder};
+    /// # use std::sync::Arc;
+    /// # #[tokio::main]
+    /// # async fn main() -> azure_core::Result<()> {
+    /// # let mut options = SecretClientOptions::default();
+    /// # options.options.transport = InMemoryTransport::new(
+    /// #     ResponseBuilder::new(200).with_body(r#"{"name":"my-secret","version":"1","attributes":{"enabled":true}}"#),
+    /// # )
+    /// # .into();
+    /// let credential = Arc::new(FakeCredential);
+    /// let client = SecretClient::new("https://my-vault.vault.azure.net", credential, Some(options))?;
+    /// let secret = client.get_secret("my-secret").send().await?.into_model().await?;
+    /// assert_eq!(secret.version, "1");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_secret<N>(&self, name: N) -> get_secret::GetSecretRequestBuilder
+    where
+        N: Into<String>,
+    {
+        get_secret::GetSecretRequestBuilder {
+            client: self.clone(),
+            name: name.into(),
+            version: None,
+            context: None,
+        }
+    }
+
+    pub fn begin_delete_secret<N>(&self, name: N) -> delete_secret::DeleteSecretRequestBuilder
+    where
+        N: Into<String>,
+    {
+        delete_secret::DeleteSecretRequestBuilder {
+            client: self.clone(),
+            name: name.into(),
+            context: None,
+        }
+    }
+
+    pub fn list_secrets(&self) -> list_secrets::ListSecretsRequestBuilder {
+        list_secrets::ListSecretsRequestBuilder {
+            client: self.clone(),
+            max_results: None,
+            include_managed: None,
+            context: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct SecretClientOptions {
     pub api_version: String,
+    /// Whether to retry the 409 conflicts returned when re-creating a deleted secret which is still
+    /// being purged, up to 5 times every 2 seconds.
+    pub retry_soft_delete_conflicts: bool,
     pub options: ClientOptions,
 }
 
@@ -74,6 +177,7 @@ impl Default for SecretClientOptions {
     fn default() -> Self {
         Self {
             api_version: "7.5".to_string(),
+            retry_soft_delete_conflicts: false,
             options: ClientOptions::default(),
         }
     }
@@ -81,7 +185,7 @@ impl Default for SecretClientOptions {
 
 mod set_secret {
     use super::*;
-    use azure_core::{Context, Request, Span};
+    use azure_core::{instrumented, Context, Request};
     use futures::future::BoxFuture;
 
     #[derive(Clone, Debug)]
@@ -110,12 +214,16 @@ mod set_secret {
             self
         }
 
-        pub fn send(&self) -> BoxFuture<'static, Result<Response>> {
+        #[instrumented("SecretClient::set_secret")]
+        pub fn send(&self) -> BoxFuture<'static, Result<Response<Secret>>> {
             Box::pin({
                 let this = self.clone();
                 async move {
                     let mut ctx = Context::default();
-                    ctx.insert(Span::from("SecretClient::set_secret"));
+                    ctx.insert(Namespace::from(NAMESPACE));
+                    ctx.insert(Idempotent::from(false));
+
+                    validate_secret_name(&this.name)?;
 
                     let mut url = this.client.endpoint.clone();
                     url.set_path(&format!("secrets/{}", this.name));
@@ -123,14 +231,182 @@ mod set_secret {
                     let body = SetSecretRequest {
                         value: this.value,
                         properties: this.properties,
+                        content_type: this.content_type,
                         ..Default::default()
                     };
-                    let mut request = Request::new(url, "GET");
+                    let mut request = Request::new(url, "PUT");
                     request.set_json(&body)?;
 
-                    this.client.pipeline.send(&mut ctx, &mut request).await
+                    let response = this.client.pipeline.send(&mut ctx, &mut request).await?;
+                    Ok(response.with_model())
+                }
+            })
+        }
+    }
+}
+
+mod get_secret {
+    use super::*;
+    use azure_core::{instrumented, Context, Request};
+    use futures::future::BoxFuture;
+
+    #[derive(Clone, Debug)]
+    pub struct GetSecretRequestBuilder {
+        pub(crate) client: SecretClient,
+        pub(crate) name: String,
+        pub(crate) version: Option<String>,
+        pub(crate) context: Option<Context>,
+    }
+
+    impl GetSecretRequestBuilder {
+        /// Sets the version of the secret to get, or the latest version if not set.
+        pub fn with_version(&mut self, version: impl Into<String>) -> &mut Self {
+            self.version = Some(version.into());
+            self
+        }
+
+        pub fn with_context(&mut self, context: Context) -> &mut Self {
+            self.context = Some(context);
+            self
+        }
+
+        #[instrumented("SecretClient::get_secret")]
+        pub fn send(&self) -> BoxFuture<'static, Result<Response<Secret>>> {
+            Box::pin({
+                let this = self.clone();
+                async move {
+                    let mut ctx = this.context.unwrap_or_default();
+                    ctx.insert(Namespace::from(NAMESPACE));
+                    ctx.insert(Idempotent::from(true));
+
+                    validate_secret_name(&this.name)?;
+
+                    let mut url = this.client.endpoint.clone();
+                    url.set_path(&format!(
+                        "secrets/{}/{}",
+                        this.name,
+                        this.version.unwrap_or_default()
+                    ));
+
+                    let mut request = Request::new(url, "GET");
+                    let response = this.client.pipeline.send(&mut ctx, &mut request).await?;
+                    Ok(response.with_model())
+                }
+            })
+        }
+
+        /// Sends the request like [`GetSecretRequestBuilder::send`] but returns `None` if the secret
+        /// does not exist.
+        pub fn send_if_exists(&self) -> BoxFuture<'static, Result<Option<Response<Secret>>>> {
+            let response = self.send();
+            Box::pin(async move {
+                match response.await {
+                    Ok(response) => Ok(Some(response)),
+                    Err(error) if error.is_not_found() => Ok(None),
+                    Err(error) => Err(error),
                 }
             })
         }
     }
 }
+
+mod delete_secret {
+    use super::*;
+    use azure_core::{instrumented, Context, Poller};
+    use futures::future::BoxFuture;
+
+    #[derive(Clone, Debug)]
+    pub struct DeleteSecretRequestBuilder {
+        pub(crate) client: SecretClient,
+        pub(crate) name: String,
+        pub(crate) context: Option<Context>,
+    }
+
+    impl DeleteSecretRequestBuilder {
+        pub fn with_context(&mut self, context: Context) -> &mut Self {
+            self.context = Some(context);
+            self
+        }
+
+        #[instrumented("SecretClient::begin_delete_secret")]
+        pub fn send(&self) -> BoxFuture<'static, Result<Poller<DeletedSecret>>> {
+            Box::pin({
+                let this = self.clone();
+                async move {
+                    let mut ctx = this.context.clone().unwrap_or_default();
+                    ctx.insert(Namespace::from(NAMESPACE));
+                    ctx.insert(Idempotent::from(true));
+
+                    azure_keyvault_common::begin_delete_secret(
+                        &this.client.pipeline,
+                        &ctx,
+                        &this.client.endpoint,
+                        &this.name,
+                    )
+                    .await
+                }
+            })
+        }
+    }
+}
+
+mod list_secrets {
+    use super::*;
+    use azure_core::{instrumented, Context, Page, Pageable, Request};
+
+    #[derive(Clone, Debug)]
+    pub struct ListSecretsRequestBuilder {
+        pub(crate) client: SecretClient,
+        pub(crate) max_results: Option<u32>,
+        pub(crate) include_managed: Option<bool>,
+        pub(crate) context: Option<Context>,
+    }
+
+    impl ListSecretsRequestBuilder {
+        /// Sets the maximum number of secrets to return per page, from 1 to 25.
+        pub fn with_max_results(&mut self, max_results: u32) -> &mut Self {
+            self.max_results = Some(max_results);
+            self
+        }
+
+        /// Sets whether to include secrets managed by Key Vault, such as those backing certificates.
+        pub fn with_include_managed(&mut self, include_managed: bool) -> &mut Self {
+            self.include_managed = Some(include_managed);
+            self
+        }
+
+        pub fn with_context(&mut self, context: Context) -> &mut Self {
+            self.context = Some(context);
+            self
+        }
+
+        #[instrumented("SecretClient::list_secrets")]
+        pub fn send(&self) -> Result<Pageable<SecretItem>> {
+            let this = self.clone();
+            let mut ctx = this.context.unwrap_or_default();
+            ctx.insert(Namespace::from(NAMESPACE));
+            ctx.insert(Idempotent::from(true));
+
+            let first_page = list_secrets_url(
+                &this.client.endpoint,
+                this.max_results,
+                this.include_managed,
+            )?;
+            Ok(Pageable::new(move |continuation_token: Option<String>| {
+                let client = this.client.clone();
+                let first_page = first_page.clone();
+                let mut ctx = ctx.clone();
+                async move {
+                    let url = match continuation_token {
+                        Some(next_link) => Url::parse(&next_link)?,
+                        None => first_page,
+                    };
+
+                    let mut request = Request::new(url, "GET");
+                    let response = client.pipeline.send(&mut ctx, &mut request).await?;
+                    Page::from_response(response).await
+                }
+            }))
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use azure_client_new_methods_params::{
-    Secret, SecretClient, SecretClientOptions, SecretProperties, SetSecretOptions,
+    SecretClient, SecretClientOptions, SecretProperties, SetSecretOptions,
 };
-use azure_core::{ClientOptions, Context, ExponentialRetryOptions, RetryOptions};
+use azure_core::{Context, ExponentialRetryOptions, RetryOptions};
 use azure_identity::DefaultAzureCredential;
 use std::{env, sync::Arc};
 
@@ -10,56 +10,56 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let endpoint = env::var("AZURE_KEYVAULT_URL")?;
 
     let credential = Arc::new(DefaultAzureCredential::default());
-    let options = SecretClientOptions {
-        api_version: "7.4".to_string(),
-        options: ClientOptions {
-            retry: RetryOptions::exponential(ExponentialRetryOptions {}),
-            ..Default::default()
-        },
-    };
+
+    // Log retries without having to implement a Policy.
+    let mut retry = RetryOptions::exponential(ExponentialRetryOptions::default());
+    retry.set_on_retry(|attempt| {
+        eprintln!(
+            "retry {} in {:?} after: {}",
+            attempt.attempt, attempt.delay, attempt.cause
+        );
+    });
+
+    let mut options = SecretClientOptions::default();
+    options.api_version = "7.4".to_string();
+    options.options.retry = retry;
 
     // Just showing that we can do both
     let client = SecretClient::new(endpoint.clone(), credential.clone(), Some(&options))?;
-    let client2 = SecretClient::new(endpoint, credential, Some(options))?;
+    let _client2 = SecretClient::new(endpoint, credential, Some(options))?;
 
     // Simple client method call.
     let response = client
         .set_secret("secret-name", "secret-value", None)
         .await?;
 
-    let secret: Secret = response.json().await?;
+    let secret = response.into_model().await?;
     println!("set {} version {}", secret.name, secret.version);
 
     // More complex client method call.
     let mut ctx = Context::default();
     ctx.insert("example".to_string());
 
+    let mut options = SetSecretOptions::default();
+    options.context = Some(ctx);
+    options.properties = Some(SecretProperties::new(false));
+
     let response = client
-        .set_secret(
-            "secret-name",
-            "rotated-value",
-            Some(SetSecretOptions {
-                context: Some(ctx),
-                properties: Some(SecretProperties { enabled: false }),
-                ..Default::default()
-            }),
-        )
+        .set_secret("secret-name", "rotated-value", Some(options))
         .await?;
 
-    // Option 2: Implement async TryFrom<Response> for models, which customers can also do. Options are not mutually exclusive.
-    // Note: async TryFrom<T> is still experimental but under consideration.
-    let secret: Secret = response.json().await?;
+    // Responses are typed with their model, so the body deserializes without naming the type.
+    // Note: this is used instead of TryFrom<Response> since the body is read asynchronously.
+    let secret = response.into_model().await?;
     println!("set {} version {}", secret.name, secret.version);
 
     // Concurrent client method calls with same options.
     let mut ctx = Context::default();
     ctx.insert("example".to_string());
 
-    let options = SetSecretOptions {
-        content_type: Some("text/plain".to_string()),
-        context: Some(ctx),
-        ..Default::default()
-    };
+    let mut options = SetSecretOptions::default();
+    options.content_type = Some("text/plain".to_string());
+    options.context = Some(ctx);
 
     let (_, _, _) = tokio::join!(
         client.set_secret("foo", "foo-value", Some(options.clone())),
@@ -3,12 +3,27 @@
 mod models;
 
 use azure_core::{
-    policies::{ApiKeyAuthenticationPolicy, Policy},
-    ClientOptions, Context, Pipeline, Request, Response, Result, Span, TokenCredential, Url,
+    instrumented, parse_endpoint,
+    policies::{ChallengeAuthenticationPolicy, Policy},
+    ClientDiagnostics, ClientOptions, Context, Error, ErrorKind, Idempotent, Namespace, Options,
+    Page, Pageable, Pipeline, Poller, Request, Response, Result, SensitiveFields, ServiceAudiences,
+    TokenCredential, Url,
+};
+use azure_keyvault_common::{list_secrets_url, validate_secret_name, SoftDeleteConflictRetry};
+use futures::{
+    future::{self, Either},
+    stream::{self, StreamExt},
 };
 pub use models::*;
-use std::{borrow::Cow, collections::HashMap, sync::Arc};
+use std::{borrow::Cow, collections::HashMap, pin::pin, sync::Arc, time::Duration};
 
+const NAMESPACE: &str = "Microsoft.KeyVault";
+const AUDIENCES: ServiceAudiences = ServiceAudiences {
+    azure_public: "https://vault.azure.net",
+    azure_china: "https://vault.azure.cn",
+    azure_us_government: "https://vault.usgovcloudapi.net",
+};
+const SENSITIVE_FIELDS: &[&str] = &["value"];
 
 #[derive(Debug, Clone)]
 pub struct SecretClient {
@@ -21,30 +36,60 @@ impl SecretClient {
         endpoint: impl AsRef<str>,
         credential: Arc<dyn TokenCredential>,
         options: Option<impl Into<Cow<'a, SecretClientOptions>>>,
+    ) -> Result<Self> {
+        Self::with_credential(endpoint, Some(credential), options)
+    }
+
+    /// Creates a client which sends requests without authorization, e.g., to a local emulator or
+    /// when playing back recorded responses.
+    pub fn new_anonymous<'a>(
+        endpoint: impl AsRef<str>,
+        options: Option<impl Into<Cow<'a, SecretClientOptions>>>,
+    ) -> Result<Self> {
+        Self::with_credential(endpoint, None, options)
+    }
+
+    fn with_credential<'a>(
+        endpoint: impl AsRef<str>,
+        credential: Option<Arc<dyn TokenCredential>>,
+        options: Option<impl Into<Cow<'a, SecretClientOptions>>>,
     ) -> Result<Self> {
         let options = options
             .map(|o| o.into())
             .unwrap_or_else(|| Cow::Owned(SecretClientOptions::default()));
 
-        let mut endpoint = Url::parse(endpoint.as_ref())?;
+        if options.api_version.is_empty() {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "api_version must not be empty",
+            ));
+        }
+        options.options.validate()?;
+
+        let mut endpoint = parse_endpoint(endpoint)?;
         endpoint
             .query_pairs_mut()
             .clear()
             .append_pair("api-version", &options.api_version);
 
-        let auth_policy: Arc<dyn Policy> = Arc::new(ApiKeyAuthenticationPolicy::new(
-            credential.clone(),
-            "https://vault.azure.net/.default".to_string(),
-        ));
-        let per_retry_policies = vec![auth_policy];
+        let mut per_retry_policies: Vec<Arc<dyn Policy>> = Vec::new();
+        if let Some(credential) = credential {
+            per_retry_policies.push(Arc::new(ChallengeAuthenticationPolicy::new(
+                credential,
+                Some(AUDIENCES.scope(&endpoint, &options.options)),
+            )));
+        }
 
         Ok(Self {
             endpoint,
             pipeline: Pipeline::new(
                 option_env!("CARGO_PKG_NAME"),
                 option_env!("CARGO_PKG_VERSION"),
-                &options.options,
-                Vec::default(),
+                &SoftDeleteConflictRetry::apply(
+                    &options.options,
+                    options.retry_soft_delete_conflicts,
+                ),
+                vec![Arc::new(SensitiveFields::from(SENSITIVE_FIELDS))],
                 per_retry_policies,
             ),
         })
@@ -54,13 +99,41 @@ impl SecretClient {
         &self.endpoint
     }
 
+    /// Gets the resolved configuration of the client without sending any requests.
+    pub fn diagnostics(&self) -> ClientDiagnostics {
+        self.pipeline.diagnostics(&self.endpoint)
+    }
+
+    /// Creates a secret, or adds a new version of it if it already exists.
+    ///
+    /// ```
+    /// # use azure_client_new_methods_params::{SecretClient, SecretClientOptions};
+    /// # use azure_core::{transport::InMemoryTransport, ResponseBuilder};
+    /// # #[tokio::main]
+    /// # async fn main() -> azure_core::Result<()> {
+    /// # let mut options = SecretClientOptions::default();
+    /// # options.options.transport = InMemoryTransport::new(
+    /// #     ResponseBuilder::new(200).with_body(r#"{"name":"my-secret","version":"1","attributes":{"enabled":true}}"#),
+    /// # )
+    /// # .into();
+    /// let client = SecretClient::new_anonymous("https://my-vault.vault.azure.net", Some(options))?;
+    /// let secret = client
+    ///     .set_secret("my-secret", "my-value", None)
+    ///     .await?
+    ///     .into_model()
+    ///     .await?;
+    /// assert_eq!(secret.version, "1");
+    /// # Ok(())
+    /// # }
+    /// ```
     #[allow(unused_variables)]
+    #[instrumented("SecretClient::set_secret")]
     pub async fn set_secret<N, V>(
         &self,
         name: N,
         value: V,
         options: Option<SetSecretOptions>,
-    ) -> azure_core::Result<Response>
+    ) -> azure_core::Result<Response<Secret>>
     where
         N: Into<String>,
         V: Into<String>,
@@ -68,28 +141,35 @@ impl SecretClient {
         let options = options.unwrap_or_default();
 
         let mut ctx = options.context.unwrap_or_default();
-        ctx.insert(Span::from("SecretClient::set_secret"));
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(false));
+
+        let name = name.into();
+        validate_secret_name(&name)?;
 
         let mut url = self.endpoint.clone();
-        url.set_path(&format!("secrets/{}", name.into()));
+        url.set_path(&format!("secrets/{}", name));
 
-        let mut request = Request::new(url, "GET");
+        let mut request = Request::new(url, "PUT");
         request.set_json(&SetSecretRequest {
             value: value.into(),
             properties: options.properties,
-            ..Default::default()
+            content_type: options.content_type,
+            tags: options.tags,
         })?;
 
-        self.pipeline.send(&mut ctx, &mut request).await
+        let response = self.pipeline.send(&mut ctx, &mut request).await?;
+        Ok(response.with_model())
     }
 
     #[allow(unused_variables)]
+    #[instrumented("SecretClient::set_secret")]
     pub async fn set_secret2<'a, N, V>(
         &self,
         name: N,
         value: V,
         options: Option<impl Into<Cow<'a, SetSecretOptions>>>,
-    ) -> azure_core::Result<Response>
+    ) -> azure_core::Result<Response<Secret>>
     where
         N: Into<String>,
         V: Into<String>,
@@ -99,67 +179,372 @@ impl SecretClient {
             .unwrap_or_else(|| Cow::Owned(SetSecretOptions::default()));
 
         let mut ctx = options.context.clone().unwrap_or_default();
-        ctx.insert(Span::from("SecretClient::set_secret"));
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(false));
+
+        let name = name.into();
+        validate_secret_name(&name)?;
 
         let mut url = self.endpoint.clone();
-        url.set_path(&format!("secrets/{}", name.into()));
+        url.set_path(&format!("secrets/{}", name));
 
-        let mut request = Request::new(url, "GET");
+        let mut request = Request::new(url, "PUT");
         request.set_json(&SetSecretRequest {
             value: value.into(),
             properties: options.properties.clone(),
-            ..Default::default()
+            content_type: options.content_type.clone(),
+            tags: options.tags.clone(),
         })?;
 
-        self.pipeline.send(&mut ctx, &mut request).await
+        let response = self.pipeline.send(&mut ctx, &mut request).await?;
+        Ok(response.with_model())
     }
-}
 
-#[derive(Debug, Clone)]
-pub struct SecretClientOptions {
-    pub api_version: String,
-    pub options: ClientOptions,
-}
+    /// Gets the latest version of a secret, or the version in `options`.
+    ///
+    /// ```
+    /// # use azure_client_new_methods_params::{SecretClient, SecretClientOptions};
+    /// # use azure_core::{transport::InMemoryTransport, ResponseBuilder};
+    /// # #[tokio::main]
+    /// # async fn main() -> azure_core::Result<()> {
+    /// # let mut options = SecretClientOptions::default();
+    /// # options.options.transport = InMemoryTransport::new(
+    /// #     ResponseBuilder::new(200).with_body(r#"{"name":"my-secret","version":"1","attributes":{"enabled":true}}"#),
+    /// # )
+    /// # .into();
+    /// let client = SecretClient::new_anonymous("https://my-vault.vault.azure.net", Some(options))?;
+    /// let secret = client.get_secret("my-secret", None).await?.into_model().await?;
+    /// assert_eq!(secret.version, "1");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrumented("SecretClient::get_secret")]
+    pub async fn get_secret<N>(
+        &self,
+        name: N,
+        options: Option<GetSecretOptions>,
+    ) -> azure_core::Result<Response<Secret>>
+    where
+        N: Into<String>,
+    {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        let name = name.into();
+        validate_secret_name(&name)?;
+
+        let mut url = self.endpoint.clone();
+        url.set_path(&format!(
+            "secrets/{}/{}",
+            name,
+            options.version.unwrap_or_default()
+        ));
+
+        let mut request = Request::new(url, "GET");
+        let response = self.pipeline.send(&mut ctx, &mut request).await?;
+        Ok(response.with_model())
+    }
 
-impl Default for SecretClientOptions {
-    fn default() -> Self {
-        Self {
-            api_version: "7.5".to_string(),
-            options: ClientOptions::default(),
+    /// Gets a secret like [`SecretClient::get_secret`] but returns `None` if the secret does not
+    /// exist.
+    pub async fn get_secret_if_exists<N>(
+        &self,
+        name: N,
+        options: Option<GetSecretOptions>,
+    ) -> azure_core::Result<Option<Response<Secret>>>
+    where
+        N: Into<String>,
+    {
+        match self.get_secret(name, options).await {
+            Ok(response) => Ok(Some(response)),
+            Err(error) if error.is_not_found() => Ok(None),
+            Err(error) => Err(error),
         }
     }
 }
 
-// Used to make options passing work for both T and &T
-impl<'a> From<&'a SecretClientOptions> for Cow<'a, SecretClientOptions> {
-    fn from(original: &'a SecretClientOptions) -> Self {
-        Cow::Borrowed(original)
+impl SecretClient {
+    #[instrumented("SecretClient::begin_delete_secret")]
+    pub async fn begin_delete_secret<N>(
+        &self,
+        name: N,
+        options: Option<DeleteSecretOptions>,
+    ) -> Result<Poller<DeletedSecret>>
+    where
+        N: Into<String>,
+    {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        let name = name.into();
+        azure_keyvault_common::begin_delete_secret(&self.pipeline, &ctx, &self.endpoint, &name)
+            .await
+    }
+
+    /// Deletes each secret and waits for it to be deleted, up to
+    /// [`DeleteSecretsOptions::max_concurrency`] at a time, e.g., to clean up after tests.
+    ///
+    /// Every secret is deleted even if some fail, and the report has the result for each name in
+    /// the order they were passed. A secret not deleted within
+    /// [`DeleteSecretsOptions::wait_timeout`] fails with a timeout.
+    ///
+    /// Returns an error without deleting any secrets if `max_concurrency` is 0.
+    pub async fn delete_secrets<I, N>(
+        &self,
+        names: I,
+        options: Option<DeleteSecretsOptions>,
+    ) -> Result<DeleteSecretsReport>
+    where
+        I: IntoIterator<Item = N>,
+        N: Into<String>,
+    {
+        let options = options.unwrap_or_default();
+        if options.max_concurrency == 0 {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "max_concurrency must be at least 1",
+            ));
+        }
+        let results = stream::iter(names.into_iter().map(Into::into))
+            .map(|name: String| {
+                let delete_options = DeleteSecretOptions {
+                    context: options.context.clone(),
+                };
+                async move {
+                    let result = match self
+                        .begin_delete_secret(name.clone(), Some(delete_options))
+                        .await
+                    {
+                        Ok(poller) => wait(poller, &name, options.wait_timeout).await,
+                        Err(error) => Err(error),
+                    };
+                    (name, result)
+                }
+            })
+            .buffered(options.max_concurrency)
+            .collect()
+            .await;
+        Ok(DeleteSecretsReport { results })
+    }
+
+    #[instrumented("SecretClient::list_secrets")]
+    pub fn list_secrets(
+        &self,
+        options: Option<ListSecretsOptions>,
+    ) -> Result<Pageable<SecretItem>> {
+        let options = options.unwrap_or_default();
+        let client = self.clone();
+
+        let first_page =
+            list_secrets_url(&self.endpoint, options.max_results, options.include_managed)?;
+        Ok(Pageable::new(move |continuation_token: Option<String>| {
+            let client = client.clone();
+            let first_page = first_page.clone();
+            let mut ctx = options.context.clone().unwrap_or_default();
+            async move {
+                ctx.insert(Namespace::from(NAMESPACE));
+                ctx.insert(Idempotent::from(true));
+
+                let url = match continuation_token {
+                    Some(next_link) => Url::parse(&next_link)?,
+                    None => first_page,
+                };
+
+                let mut request = Request::new(url, "GET");
+                let response = client.pipeline.send(&mut ctx, &mut request).await?;
+                Page::from_response(response).await
+            }
+        }))
     }
 }
 
-impl<'a> From<SecretClientOptions> for Cow<'a, SecretClientOptions> {
-    fn from(original: SecretClientOptions) -> Self {
-        Cow::Owned(original)
+/// Waits for `poller` to complete, failing if it takes longer than `timeout`.
+async fn wait(
+    poller: Poller<DeletedSecret>,
+    name: &str,
+    timeout: Option<Duration>,
+) -> Result<DeletedSecret> {
+    let Some(timeout) = timeout else {
+        return poller.wait_for_completion().await;
+    };
+    match future::select(
+        pin!(poller.wait_for_completion()),
+        pin!(azure_core::sleep::sleep(timeout)),
+    )
+    .await
+    {
+        Either::Left((result, _)) => result,
+        Either::Right(_) => Err(Error::with_message(ErrorKind::Other, || {
+            format!("secret '{name}' was not deleted within {timeout:?}")
+        })),
     }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Debug, Clone, Options)]
+#[non_exhaustive]
+pub struct SecretClientOptions {
+    #[options(default = "7.5".to_string(), into)]
+    pub api_version: String,
+    /// Whether to retry the 409 conflicts returned when re-creating a deleted secret which is still
+    /// being purged, up to 5 times every 2 seconds.
+    pub retry_soft_delete_conflicts: bool,
+    pub options: ClientOptions,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
 pub struct SetSecretOptions {
     pub properties: Option<SecretProperties>,
+    #[options(into)]
     pub content_type: Option<String>,
     pub tags: Option<HashMap<String, String>>,
     pub context: Option<Context>,
 }
 
-// Used to make options passing work for both T and &T
-impl<'a> From<&'a SetSecretOptions> for Cow<'a, SetSecretOptions> {
-    fn from(original: &'a SetSecretOptions) -> Self {
-        Cow::Borrowed(original)
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct GetSecretOptions {
+    /// The version of the secret to get, or the latest version if not set.
+    #[options(into)]
+    pub version: Option<String>,
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct ListSecretsOptions {
+    /// The maximum number of secrets to return per page, from 1 to 25.
+    pub max_results: Option<u32>,
+    /// Whether to include secrets managed by Key Vault, such as those backing certificates.
+    pub include_managed: Option<bool>,
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct DeleteSecretOptions {
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct DeleteSecretsOptions {
+    /// The maximum number of secrets to delete at once, which must be at least 1.
+    #[options(default = 8)]
+    pub max_concurrency: usize,
+    /// How long to wait for each secret to be deleted before reporting it failed, which is 1
+    /// minute by default. Set it to `None` to wait as long as each takes.
+    #[options(default = Some(Duration::from_secs(60)))]
+    pub wait_timeout: Option<Duration>,
+    pub context: Option<Context>,
+}
+
+/// The result of deleting each secret passed to [`SecretClient::delete_secrets`].
+#[derive(Debug)]
+pub struct DeleteSecretsReport {
+    results: Vec<(String, Result<DeletedSecret>)>,
+}
+
+impl DeleteSecretsReport {
+    /// Gets the name and result of each secret in the order they were passed.
+    pub fn results(&self) -> &[(String, Result<DeletedSecret>)] {
+        &self.results
+    }
+
+    pub fn deleted(&self) -> impl Iterator<Item = (&str, &DeletedSecret)> {
+        self.results.iter().filter_map(|(name, result)| {
+            result
+                .as_ref()
+                .ok()
+                .map(|deleted_secret| (name.as_str(), deleted_secret))
+        })
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = (&str, &Error)> {
+        self.results
+            .iter()
+            .filter_map(|(name, result)| result.as_ref().err().map(|error| (name.as_str(), error)))
+    }
+
+    /// Whether every secret was deleted.
+    pub fn is_success(&self) -> bool {
+        self.failed().next().is_none()
+    }
+
+    pub fn into_results(self) -> Vec<(String, Result<DeletedSecret>)> {
+        self.results
     }
 }
 
-impl<'a> From<SetSecretOptions> for Cow<'a, SetSecretOptions> {
-    fn from(original: SetSecretOptions) -> Self {
-        Cow::Owned(original)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_core::{transport::HttpClient, ResponseBuilder, RetryOptions, TransportOptions};
+
+    /// Deletes every secret, but only `gone` is ever found in the deleted secrets.
+    #[derive(Debug)]
+    struct VaultTransport;
+
+    #[async_trait::async_trait]
+    impl HttpClient for VaultTransport {
+        async fn execute_request(&self, request: &Request) -> Result<Response> {
+            let deleted = r#"{"id":"https://my-vault.vault.azure.net/secrets/gone","attributes":{"enabled":false}}"#;
+            Ok(match (request.method(), request.url().path()) {
+                ("DELETE", _) | ("GET", "/deletedsecrets/gone") => {
+                    ResponseBuilder::new(200).with_body(deleted).build()
+                }
+                _ => ResponseBuilder::new(404)
+                    .with_header(azure_core::RETRY_AFTER_MS, "10")
+                    .with_body(r#"{"error":{"code":"SecretNotFound"}}"#)
+                    .build(),
+            })
+        }
+    }
+
+    fn client() -> SecretClient {
+        let mut options = SecretClientOptions::default();
+        options.options.retry = RetryOptions::none();
+        options.options.transport = TransportOptions::with_http_client(Arc::new(VaultTransport));
+        SecretClient::new_anonymous("https://my-vault.vault.azure.net", Some(options)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn reports_secrets_not_deleted_in_time() {
+        let mut options = DeleteSecretsOptions::default();
+        options.with_wait_timeout(Duration::from_millis(100));
+        let report = client()
+            .delete_secrets(["gone", "stuck"], Some(options))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            report.deleted().map(|(name, _)| name).collect::<Vec<_>>(),
+            ["gone"]
+        );
+        let failed: Vec<_> = report.failed().collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, "stuck");
+        assert!(
+            failed[0].1.to_string().contains("not deleted within"),
+            "{}",
+            failed[0].1
+        );
+    }
+
+    #[tokio::test]
+    async fn requires_concurrency() {
+        let mut options = DeleteSecretsOptions::default();
+        options.with_max_concurrency(0);
+        let error = client()
+            .delete_secrets(["gone"], Some(options))
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::InvalidInput);
     }
 }
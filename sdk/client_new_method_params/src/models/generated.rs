@@ -0,0 +1,96 @@
+// Code generated from the Key Vault TypeSpec. Changes to this file will be lost when the code is regenerated.
+
+use super::serde_helpers;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, time::SystemTime};
+
+#[derive(Clone, Debug, Deserialize)]
+#[non_exhaustive]
+pub struct Secret {
+    pub name: String,
+    pub version: String,
+    #[serde(rename = "attributes")]
+    pub properties: SecretProperties,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub(crate) struct SetSecretRequest {
+    pub value: String,
+    #[serde(rename = "attributes")]
+    pub properties: Option<SecretProperties>,
+    #[serde(rename = "contentType")]
+    pub content_type: Option<String>,
+    pub tags: Option<HashMap<String, String>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[non_exhaustive]
+pub struct DeletedSecret {
+    pub id: String,
+    #[serde(rename = "recoveryId")]
+    pub recovery_id: Option<String>,
+    #[serde(rename = "deletedDate")]
+    pub deleted_date: Option<u64>,
+    #[serde(rename = "scheduledPurgeDate")]
+    pub scheduled_purge_date: Option<u64>,
+    #[serde(rename = "attributes")]
+    pub properties: SecretProperties,
+}
+
+/// The deleted secret item containing metadata about the deleted secret.
+#[derive(Clone, Debug, Deserialize)]
+#[non_exhaustive]
+pub struct DeletedSecretItem {
+    /// Secret identifier.
+    pub id: String,
+
+    /// The secret management attributes.
+    #[serde(rename = "attributes")]
+    pub properties: SecretProperties,
+
+    /// Type of the secret value such as a password.
+    #[serde(rename = "contentType")]
+    pub content_type: Option<String>,
+
+    /// True if the secret's lifetime is managed by Key Vault. If this is a key backing a certificate, then managed will be true.
+    pub managed: Option<bool>,
+
+    /// The url of the recovery object, used to identify and recover the deleted secret.
+    #[serde(rename = "recoveryId")]
+    pub recovery_id: Option<String>,
+
+    /// The time when the secret is scheduled to be purged, in UTC.
+    #[serde(
+        rename = "scheduledPurgeDate",
+        default,
+        deserialize_with = "serde_helpers::deserialize_unix_time_option"
+    )]
+    pub scheduled_purge_date: Option<SystemTime>,
+
+    /// The time when the secret was deleted, in UTC.
+    #[serde(
+        rename = "deletedDate",
+        default,
+        deserialize_with = "serde_helpers::deserialize_unix_time_option"
+    )]
+    pub deleted_date: Option<SystemTime>,
+
+    /// Application specific metadata in the form of key-value pairs.
+    pub tags: Option<HashMap<String, String>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[non_exhaustive]
+pub struct SecretItem {
+    pub id: String,
+    #[serde(rename = "attributes")]
+    pub properties: SecretProperties,
+    #[serde(rename = "contentType")]
+    pub content_type: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct SecretProperties {
+    pub enabled: bool,
+}
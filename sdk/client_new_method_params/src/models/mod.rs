@@ -0,0 +1,23 @@
+//! Models generated from the service specification, re-exported with handwritten conveniences.
+//!
+//! Generated models are in the `generated` module and must not be edited by hand. Constructors, trait implementations,
+//! and other conveniences are added here instead, and serde helpers which generated models refer to are in `serde_helpers`.
+
+mod generated;
+mod serde_helpers;
+
+use azure_core::{json::from_json, Headers, TerminalState};
+pub(crate) use generated::SetSecretRequest;
+pub use generated::*;
+
+impl SecretProperties {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+}
+
+impl TerminalState for DeletedSecret {
+    fn from_success(_headers: &Headers, body: &[u8]) -> azure_core::Result<Self> {
+        from_json(body)
+    }
+}
@@ -0,0 +1,15 @@
+//! Serde helpers for wire formats generated models refer to with `#[serde(deserialize_with = "...")]`.
+
+use serde::{Deserialize, Deserializer};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Deserializes a time from the number of seconds since the Unix epoch, which Key Vault uses for dates.
+pub(crate) fn deserialize_unix_time_option<'de, D>(
+    deserializer: D,
+) -> Result<Option<SystemTime>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let seconds = Option::<u64>::deserialize(deserializer)?;
+    Ok(seconds.map(|seconds| UNIX_EPOCH + Duration::from_secs(seconds)))
+}
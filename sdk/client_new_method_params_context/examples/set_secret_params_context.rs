@@ -1,7 +1,7 @@
 use azure_client_new_methods_params_context::{
-    Secret, SecretClient, SecretClientOptions, SecretProperties, SetSecretOptions,
+    SecretClient, SecretClientOptions, SecretProperties, SetSecretOptions,
 };
-use azure_core::{ClientOptions, Context, ExponentialRetryOptions, RetryOptions};
+use azure_core::{Context, ExponentialRetryOptions, RetryOptions};
 use azure_identity::DefaultAzureCredential;
 use std::{env, sync::Arc};
 
@@ -10,13 +10,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let endpoint = env::var("AZURE_KEYVAULT_URL")?;
 
     let credential = Arc::new(DefaultAzureCredential::default());
-    let options = SecretClientOptions {
-        api_version: "7.4".to_string(),
-        options: ClientOptions {
-            retry: RetryOptions::exponential(ExponentialRetryOptions {}),
-            ..Default::default()
-        },
-    };
+    let mut options = SecretClientOptions::default();
+    options.api_version = "7.4".to_string();
+    options.options.retry = RetryOptions::exponential(ExponentialRetryOptions::default());
     let client = SecretClient::new(endpoint, credential, Some(options))?;
 
     // Simple client method call.
@@ -24,38 +20,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .set_secret(&Context::default(), "secret-name", "secret-value", None)
         .await?;
 
-    let secret: Secret = response.json().await?;
+    let secret = response.into_model().await?;
     println!("set {} version {}", secret.name, secret.version);
 
     // More complex client method call.
     let mut ctx = Context::default();
     ctx.insert("example".to_string());
 
+    let mut options = SetSecretOptions::default();
+    options.properties = Some(SecretProperties::new(false));
+
     let response = client
-        .set_secret(
-            &ctx,
-            "secret-name",
-            "rotated-value",
-            Some(SetSecretOptions {
-                properties: Some(SecretProperties { enabled: false }),
-                ..Default::default()
-            }),
-        )
+        .set_secret(&ctx, "secret-name", "rotated-value", Some(options))
         .await?;
 
     // Option 2: Implement async TryFrom<Response> for models, which customers can also do. Options are not mutually exclusive.
     // Note: async TryFrom<T> is still experimental but under consideration.
-    let secret: Secret = response.json().await?;
+    let secret = response.into_model().await?;
     println!("set {} version {}", secret.name, secret.version);
 
     // Concurrent client method calls with same options.
     let mut ctx = Context::default();
     ctx.insert("example".to_string());
 
-    let options = SetSecretOptions {
-        content_type: Some("text/plain".to_string()),
-        ..Default::default()
-    };
+    let mut options = SetSecretOptions::default();
+    options.content_type = Some("text/plain".to_string());
 
     let (_, _) = tokio::join!(
         client.set_secret(&ctx, "foo", "foo-value", Some(options.clone())),
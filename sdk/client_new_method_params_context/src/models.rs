@@ -1,7 +1,9 @@
+use azure_core::{json::from_json, Headers, TerminalState};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 #[derive(Clone, Debug, Deserialize)]
+#[non_exhaustive]
 pub struct Secret {
     pub name: String,
     pub version: String,
@@ -19,7 +21,44 @@ pub(crate) struct SetSecretRequest {
     pub tags: Option<HashMap<String, String>>,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+#[non_exhaustive]
+pub struct DeletedSecret {
+    pub id: String,
+    #[serde(rename = "recoveryId")]
+    pub recovery_id: Option<String>,
+    #[serde(rename = "deletedDate")]
+    pub deleted_date: Option<u64>,
+    #[serde(rename = "scheduledPurgeDate")]
+    pub scheduled_purge_date: Option<u64>,
+    #[serde(rename = "attributes")]
+    pub properties: SecretProperties,
+}
+
+impl TerminalState for DeletedSecret {
+    fn from_success(_headers: &Headers, body: &[u8]) -> azure_core::Result<Self> {
+        from_json(body)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[non_exhaustive]
+pub struct SecretItem {
+    pub id: String,
+    #[serde(rename = "attributes")]
+    pub properties: SecretProperties,
+    #[serde(rename = "contentType")]
+    pub content_type: Option<String>,
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct SecretProperties {
     pub enabled: bool,
 }
+
+impl SecretProperties {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+}
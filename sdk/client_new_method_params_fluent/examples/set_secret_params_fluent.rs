@@ -0,0 +1,57 @@
+use azure_client_new_methods_params_fluent::{
+    SecretClient, SecretClientOptions, SecretProperties, SetSecretOptions,
+};
+use azure_core::Context;
+use azure_identity::DefaultAzureCredential;
+use std::{env, sync::Arc};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let endpoint = env::var("AZURE_KEYVAULT_URL")?;
+
+    let credential = Arc::new(DefaultAzureCredential::default());
+    let options = SecretClientOptions::default().with_api_version("7.4");
+    let client = SecretClient::new(endpoint, credential, Some(options))?;
+
+    // Simple client method call.
+    let response = client
+        .set_secret("secret-name", "secret-value", None)
+        .await?;
+
+    let secret = response.into_model().await?;
+    println!("set {} version {}", secret.name, secret.version);
+
+    // More complex client method call.
+    let mut ctx = Context::default();
+    ctx.insert("example".to_string());
+
+    let response = client
+        .set_secret(
+            "secret-name",
+            "rotated-value",
+            Some(
+                SetSecretOptions::default()
+                    .with_context(ctx)
+                    .with_properties(SecretProperties::new(false)),
+            ),
+        )
+        .await?;
+
+    let secret = response.into_model().await?;
+    println!("set {} version {}", secret.name, secret.version);
+
+    // Concurrent client method calls with same options.
+    let mut ctx = Context::default();
+    ctx.insert("example".to_string());
+
+    let options = SetSecretOptions::default()
+        .with_content_type("text/plain")
+        .with_context(ctx);
+
+    let (_, _) = tokio::join!(
+        client.set_secret("foo", "foo-value", Some(options.clone())),
+        client.set_secret("bar", "bar-value", Some(options)),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use azure_client_new_methods_params_ref::{
+    SecretClient, SecretClientOptions, SecretProperties, SetSecretOptions,
+};
+use azure_core::Context;
+use azure_identity::DefaultAzureCredential;
+use std::{env, sync::Arc};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let endpoint = env::var("AZURE_KEYVAULT_URL")?;
+
+    let credential = Arc::new(DefaultAzureCredential::default());
+
+    let mut options = SecretClientOptions::default();
+    options.api_version = "7.4".to_string();
+
+    // Options are only borrowed, so the same options can create multiple clients without cloning.
+    let client = SecretClient::new(&endpoint, credential.clone(), Some(&options))?;
+    let _client2 = SecretClient::new(&endpoint, credential, Some(&options))?;
+
+    // Simple client method call.
+    let response = client
+        .set_secret("secret-name", "secret-value", None)
+        .await?;
+
+    let secret = response.into_model().await?;
+    println!("set {} version {}", secret.name, secret.version);
+
+    // More complex client method call.
+    let mut ctx = Context::default();
+    ctx.insert("example".to_string());
+
+    let mut options = SetSecretOptions::default();
+    options.context = Some(ctx);
+    options.properties = Some(SecretProperties::new(false));
+
+    let response = client
+        .set_secret("secret-name", "rotated-value", Some(&options))
+        .await?;
+
+    let secret = response.into_model().await?;
+    println!("set {} version {}", secret.name, secret.version);
+
+    // Concurrent client method calls with same options.
+    let mut ctx = Context::default();
+    ctx.insert("example".to_string());
+
+    let mut options = SetSecretOptions::default();
+    options.content_type = Some("text/plain".to_string());
+    options.context = Some(ctx);
+
+    let (_, _) = tokio::join!(
+        client.set_secret("foo", "foo-value", Some(&options)),
+        client.set_secret("bar", "bar-value", Some(&options)),
+    );
+
+    Ok(())
+}
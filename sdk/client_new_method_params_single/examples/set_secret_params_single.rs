@@ -0,0 +1,60 @@
+use azure_client_new_methods_params_single::{
+    SecretClient, SecretClientOptions, SecretProperties, SetSecretOptions, SetSecretParams,
+};
+use azure_core::Context;
+use azure_identity::DefaultAzureCredential;
+use std::{env, sync::Arc};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let endpoint = env::var("AZURE_KEYVAULT_URL")?;
+
+    let credential = Arc::new(DefaultAzureCredential::default());
+    let mut options = SecretClientOptions::default();
+    options.api_version = "7.4".to_string();
+    let client = SecretClient::new(endpoint, credential, Some(options))?;
+
+    // Simple client method call.
+    let response = client
+        .set_secret(&SetSecretParams::new("secret-name", "secret-value"))
+        .await?;
+
+    let secret = response.into_model().await?;
+    println!("set {} version {}", secret.name, secret.version);
+
+    // More complex client method call.
+    let mut ctx = Context::default();
+    ctx.insert("example".to_string());
+
+    let response = client
+        .set_secret(
+            SetSecretParams::new("secret-name", "rotated-value")
+                .with_context(ctx)
+                .with_properties(SecretProperties::new(false)),
+        )
+        .await?;
+
+    let secret = response.into_model().await?;
+    println!("set {} version {}", secret.name, secret.version);
+
+    // Concurrent client method calls with same options.
+    let mut ctx = Context::default();
+    ctx.insert("example".to_string());
+
+    let mut options = SetSecretOptions::default();
+    options.content_type = Some("text/plain".to_string());
+    options.context = Some(ctx);
+
+    let mut params_foo = SetSecretParams::new("foo", "foo-value");
+    params_foo.with_options(options.clone());
+
+    let mut params_bar = SetSecretParams::new("bar", "bar-value");
+    params_bar.with_options(options);
+
+    let (_, _) = tokio::join!(
+        client.set_secret(&params_foo),
+        client.set_secret(&params_bar),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,412 @@
+#![doc = include_str!("../README.md")]
+
+mod models;
+
+use azure_core::{
+    instrumented, parse_endpoint,
+    policies::{ChallengeAuthenticationPolicy, Policy},
+    ClientDiagnostics, ClientOptions, Context, Error, ErrorKind, Idempotent, Namespace, Page,
+    Pageable, Pipeline, Poller, Request, Response, Result, SensitiveFields, ServiceAudiences,
+    TokenCredential, Url,
+};
+use azure_keyvault_common::{list_secrets_url, validate_secret_name, SoftDeleteConflictRetry};
+pub use models::*;
+use std::{collections::HashMap, sync::Arc};
+
+const NAMESPACE: &str = "Microsoft.KeyVault";
+const AUDIENCES: ServiceAudiences = ServiceAudiences {
+    azure_public: "https://vault.azure.net",
+    azure_china: "https://vault.azure.cn",
+    azure_us_government: "https://vault.usgovcloudapi.net",
+};
+const SENSITIVE_FIELDS: &[&str] = &["value"];
+
+#[derive(Debug, Clone)]
+pub struct SecretClient {
+    endpoint: Url,
+    pipeline: Pipeline,
+}
+
+impl SecretClient {
+    pub fn new(
+        endpoint: impl AsRef<str>,
+        credential: Arc<dyn TokenCredential>,
+        options: Option<SecretClientOptions>,
+    ) -> Result<Self> {
+        let options = options.unwrap_or_default();
+
+        if options.api_version.is_empty() {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "api_version must not be empty",
+            ));
+        }
+        options.options.validate()?;
+
+        let mut endpoint = parse_endpoint(endpoint)?;
+        endpoint
+            .query_pairs_mut()
+            .clear()
+            .append_pair("api-version", &options.api_version);
+
+        let auth_policy: Arc<dyn Policy> = Arc::new(ChallengeAuthenticationPolicy::new(
+            credential.clone(),
+            Some(AUDIENCES.scope(&endpoint, &options.options)),
+        ));
+        let per_retry_policies = vec![auth_policy];
+
+        Ok(Self {
+            endpoint,
+            pipeline: Pipeline::new(
+                option_env!("CARGO_PKG_NAME"),
+                option_env!("CARGO_PKG_VERSION"),
+                &SoftDeleteConflictRetry::apply(
+                    &options.options,
+                    options.retry_soft_delete_conflicts,
+                ),
+                vec![Arc::new(SensitiveFields::from(SENSITIVE_FIELDS))],
+                per_retry_policies,
+            ),
+        })
+    }
+
+    pub fn endpoint(&self) -> &Url {
+        &self.endpoint
+    }
+
+    /// Gets the resolved configuration of the client without sending any requests.
+    pub fn diagnostics(&self) -> ClientDiagnostics {
+        self.pipeline.diagnostics(&self.endpoint)
+    }
+
+    /// Creates a secret, or adds a new version of it if it already exists.
+    ///
+    /// ```
+    /// # use azure_client_new_methods_params_single::{GetSecretParams, SecretClient, SecretClientOptions, SetSecretParams};
+    /// # use azure_core::{test::FakeCredential, transport::InMemoryTransport, ResponseBuilder};
+    /// # use std::sync::Arc;
+    /// # #[tokio::main]
+    /// # async fn main() -> azure_core::Result<()> {
+    /// # let mut options = SecretClientOptions::default();
+    /// # options.options.transport = InMemoryTransport::new(
+    /// #     ResponseBuilder::new(200).with_body(r#"{"name":"my-secret","version":"1","attributes":{"enabled":true}}"#),
+    /// # )
+    /// # .into();
+    /// let credential = Arc::new(FakeCredential);
+    /// let client = SecretClient::new("https://my-vault.vault.azure.net", credential, Some(options))?;
+    /// let secret = client
+    ///     .set_secret(&SetSecretParams::new("my-secret", "my-value"))
+    ///     .await?
+    ///     .into_model()
+    ///     .await?;
+    /// assert_eq!(secret.version, "1");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrumented("SecretClient::set_secret")]
+    pub async fn set_secret(
+        &self,
+        params: &SetSecretParams,
+    ) -> azure_core::Result<Response<Secret>> {
+        let options = &params.options;
+
+        let mut ctx = options.context.clone().unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(false));
+
+        validate_secret_name(&params.name)?;
+
+        let mut url = self.endpoint.clone();
+        url.set_path(&format!("secrets/{}", params.name));
+
+        let mut request = Request::new(url, "PUT");
+        request.set_json(&SetSecretRequest {
+            value: params.value.clone(),
+            properties: options.properties.clone(),
+            content_type: options.content_type.clone(),
+            tags: options.tags.clone(),
+        })?;
+
+        let response = self.pipeline.send(&mut ctx, &mut request).await?;
+        Ok(response.with_model())
+    }
+
+    /// Gets the latest version of a secret, or the version in `params`.
+    ///
+    /// ```
+    /// # use azure_client_new_methods_params_single::{GetSecretParams, SecretClient, SecretClientOptions, SetSecretParams};
+    /// # use azure_core::{test::FakeCredential, transport::InMemoryTransport, ResponseBuilder};
+    /// # use std::sync::Arc;
+    /// # #[tokio::main]
+    /// # async fn main() -> azure_core::Result<()> {
+    /// # let mut options = SecretClientOptions::default();
+    /// # options.options.transport = InMemoryTransport::new(
+    /// #     ResponseBuilder::new(200).with_body(r#"{"name":"my-secret","version":"1","attributes":{"enabled":true}}"#),
+    /// # )
+    /// # .into();
+    /// let credential = Arc::new(FakeCredential);
+    /// let client = SecretClient::new("https://my-vault.vault.azure.net", credential, Some(options))?;
+    /// let secret = client
+    ///     .get_secret(&GetSecretParams::new("my-secret"))
+    ///     .await?
+    ///     .into_model()
+    ///     .await?;
+    /// assert_eq!(secret.version, "1");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrumented("SecretClient::get_secret")]
+    pub async fn get_secret(
+        &self,
+        params: &GetSecretParams,
+    ) -> azure_core::Result<Response<Secret>> {
+        let options = &params.options;
+
+        let mut ctx = options.context.clone().unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        validate_secret_name(&params.name)?;
+
+        let mut url = self.endpoint.clone();
+        url.set_path(&format!(
+            "secrets/{}/{}",
+            params.name,
+            options.version.as_deref().unwrap_or_default()
+        ));
+
+        let mut request = Request::new(url, "GET");
+        let response = self.pipeline.send(&mut ctx, &mut request).await?;
+        Ok(response.with_model())
+    }
+
+    /// Gets a secret like [`SecretClient::get_secret`] but returns `None` if the secret does not
+    /// exist.
+    pub async fn get_secret_if_exists(
+        &self,
+        params: &GetSecretParams,
+    ) -> azure_core::Result<Option<Response<Secret>>> {
+        match self.get_secret(params).await {
+            Ok(response) => Ok(Some(response)),
+            Err(error) if error.is_not_found() => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    #[instrumented("SecretClient::begin_delete_secret")]
+    pub async fn begin_delete_secret(
+        &self,
+        params: &DeleteSecretParams,
+    ) -> Result<Poller<DeletedSecret>> {
+        let mut ctx = params.options.context.clone().unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        azure_keyvault_common::begin_delete_secret(
+            &self.pipeline,
+            &ctx,
+            &self.endpoint,
+            &params.name,
+        )
+        .await
+    }
+
+    #[instrumented("SecretClient::list_secrets")]
+    pub fn list_secrets(&self, params: &ListSecretsParams) -> Result<Pageable<SecretItem>> {
+        let client = self.clone();
+        let options = &params.options;
+
+        let mut ctx = options.context.clone().unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        let first_page =
+            list_secrets_url(&self.endpoint, options.max_results, options.include_managed)?;
+        Ok(Pageable::new(move |continuation_token: Option<String>| {
+            let client = client.clone();
+            let first_page = first_page.clone();
+            let mut ctx = ctx.clone();
+            async move {
+                let url = match continuation_token {
+                    Some(next_link) => Url::parse(&next_link)?,
+                    None => first_page,
+                };
+
+                let mut request = Request::new(url, "GET");
+                let response = client.pipeline.send(&mut ctx, &mut request).await?;
+                Page::from_response(response).await
+            }
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct SecretClientOptions {
+    pub api_version: String,
+    /// Whether to retry the 409 conflicts returned when re-creating a deleted secret which is still
+    /// being purged, up to 5 times every 2 seconds.
+    pub retry_soft_delete_conflicts: bool,
+    pub options: ClientOptions,
+}
+
+impl Default for SecretClientOptions {
+    fn default() -> Self {
+        Self {
+            api_version: "7.5".to_string(),
+            retry_soft_delete_conflicts: false,
+            options: ClientOptions::default(),
+        }
+    }
+}
+
+/// The parameters of [`SecretClient::set_secret`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct SetSecretParams {
+    pub name: String,
+    pub value: String,
+    pub options: SetSecretOptions,
+}
+
+impl SetSecretParams {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            options: SetSecretOptions::default(),
+        }
+    }
+
+    pub fn with_properties(&mut self, properties: SecretProperties) -> &mut Self {
+        self.options.properties = Some(properties);
+        self
+    }
+
+    pub fn with_content_type(&mut self, content_type: impl Into<String>) -> &mut Self {
+        self.options.content_type = Some(content_type.into());
+        self
+    }
+
+    pub fn with_tags(&mut self, tags: HashMap<String, String>) -> &mut Self {
+        self.options.tags = Some(tags);
+        self
+    }
+
+    pub fn with_context(&mut self, context: Context) -> &mut Self {
+        self.options.context = Some(context);
+        self
+    }
+
+    /// Replaces all optional parameters, e.g., to share them across calls.
+    pub fn with_options(&mut self, options: SetSecretOptions) -> &mut Self {
+        self.options = options;
+        self
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct SetSecretOptions {
+    pub properties: Option<SecretProperties>,
+    pub content_type: Option<String>,
+    pub tags: Option<HashMap<String, String>>,
+    pub context: Option<Context>,
+}
+
+/// The parameters of [`SecretClient::get_secret`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct GetSecretParams {
+    pub name: String,
+    pub options: GetSecretOptions,
+}
+
+impl GetSecretParams {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            options: GetSecretOptions::default(),
+        }
+    }
+
+    pub fn with_version(&mut self, version: impl Into<String>) -> &mut Self {
+        self.options.version = Some(version.into());
+        self
+    }
+
+    pub fn with_context(&mut self, context: Context) -> &mut Self {
+        self.options.context = Some(context);
+        self
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct GetSecretOptions {
+    /// The version of the secret to get, or the latest version if not set.
+    pub version: Option<String>,
+    pub context: Option<Context>,
+}
+
+/// The parameters of [`SecretClient::list_secrets`], all of which are optional.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct ListSecretsParams {
+    pub options: ListSecretsOptions,
+}
+
+impl ListSecretsParams {
+    pub fn with_max_results(&mut self, max_results: u32) -> &mut Self {
+        self.options.max_results = Some(max_results);
+        self
+    }
+
+    pub fn with_include_managed(&mut self, include_managed: bool) -> &mut Self {
+        self.options.include_managed = Some(include_managed);
+        self
+    }
+
+    pub fn with_context(&mut self, context: Context) -> &mut Self {
+        self.options.context = Some(context);
+        self
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct ListSecretsOptions {
+    /// The maximum number of secrets to return per page, from 1 to 25.
+    pub max_results: Option<u32>,
+    /// Whether to include secrets managed by Key Vault, such as those backing certificates.
+    pub include_managed: Option<bool>,
+    pub context: Option<Context>,
+}
+
+/// The parameters of [`SecretClient::begin_delete_secret`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct DeleteSecretParams {
+    pub name: String,
+    pub options: DeleteSecretOptions,
+}
+
+impl DeleteSecretParams {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            options: DeleteSecretOptions::default(),
+        }
+    }
+
+    pub fn with_context(&mut self, context: Context) -> &mut Self {
+        self.options.context = Some(context);
+        self
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct DeleteSecretOptions {
+    pub context: Option<Context>,
+}
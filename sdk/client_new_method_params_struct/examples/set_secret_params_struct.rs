@@ -1,7 +1,7 @@
 use azure_client_new_methods_params_struct::{
-    Secret, SecretClient, SecretClientOptions, SecretProperties, SetSecretRequest,
+    SecretClient, SecretClientOptions, SecretProperties, SetSecretRequest,
 };
-use azure_core::{ClientOptions, Context, ExponentialRetryOptions, RetryOptions};
+use azure_core::{Context, ExponentialRetryOptions, RetryOptions};
 use azure_identity::DefaultAzureCredential;
 use std::{env, sync::Arc};
 
@@ -10,13 +10,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let endpoint = env::var("AZURE_KEYVAULT_URL")?;
 
     let credential = Arc::new(DefaultAzureCredential::default());
-    let options = SecretClientOptions {
-        api_version: "7.4".to_string(),
-        options: ClientOptions {
-            retry: RetryOptions::exponential(ExponentialRetryOptions {}),
-            ..Default::default()
-        },
-    };
+    let mut options = SecretClientOptions::default();
+    options.api_version = "7.4".to_string();
+    options.options.retry = RetryOptions::exponential(ExponentialRetryOptions::default());
     let client = SecretClient::new(endpoint, credential, Some(options))?;
 
     // Simple client method call.
@@ -24,7 +20,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .set_secret(&SetSecretRequest::new("secret-name", "secret-value"))
         .await?;
 
-    let secret: Secret = response.json().await?;
+    let secret = response.into_model().await?;
     println!("set {} version {}", secret.name, secret.version);
 
     // More complex client method call.
@@ -33,13 +29,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let mut request = SetSecretRequest::new("secret-name", "rotated-value");
     request.context = Some(ctx);
-    request.properties = Some(SecretProperties { enabled: false });
+    request.properties = Some(SecretProperties::new(false));
 
     let response = client.set_secret(&request).await?;
 
     // Option 2: Implement async TryFrom<Response> for models, which customers can also do. Options are not mutually exclusive.
     // Note: async TryFrom<T> is still experimental but under consideration.
-    let secret: Secret = response.json().await?;
+    let secret = response.into_model().await?;
     println!("set {} version {}", secret.name, secret.version);
 
     // Concurrent client method calls with same options.
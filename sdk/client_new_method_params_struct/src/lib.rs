@@ -3,13 +3,25 @@
 mod models;
 
 use azure_core::{
-    policies::{ApiKeyAuthenticationPolicy, Policy},
-    ClientOptions, Context, Pipeline, Request, Response, Result, Span, TokenCredential, Url,
+    instrumented, parse_endpoint,
+    policies::{ChallengeAuthenticationPolicy, Policy},
+    ClientDiagnostics, ClientOptions, Context, Error, ErrorKind, Idempotent, Namespace, Page,
+    Pageable, Pipeline, Poller, Request, Response, Result, SensitiveFields, ServiceAudiences,
+    TokenCredential, Url,
 };
+use azure_keyvault_common::{list_secrets_url, validate_secret_name, SoftDeleteConflictRetry};
 pub use models::*;
-use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
 
+const NAMESPACE: &str = "Microsoft.KeyVault";
+const AUDIENCES: ServiceAudiences = ServiceAudiences {
+    azure_public: "https://vault.azure.net",
+    azure_china: "https://vault.azure.cn",
+    azure_us_government: "https://vault.usgovcloudapi.net",
+};
+const SENSITIVE_FIELDS: &[&str] = &["value"];
+use serde::{Deserialize, Serialize};
+
 #[derive(Debug, Clone)]
 pub struct SecretClient {
     endpoint: Url,
@@ -23,15 +35,23 @@ impl SecretClient {
         options: Option<SecretClientOptions>,
     ) -> Result<Self> {
         let options = options.unwrap_or_default();
-        let mut endpoint = Url::parse(endpoint.as_ref())?;
+        if options.api_version.is_empty() {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "api_version must not be empty",
+            ));
+        }
+        options.options.validate()?;
+
+        let mut endpoint = parse_endpoint(endpoint)?;
         endpoint
             .query_pairs_mut()
             .clear()
             .append_pair("api-version", &options.api_version);
 
-        let auth_policy: Arc<dyn Policy> = Arc::new(ApiKeyAuthenticationPolicy::new(
+        let auth_policy: Arc<dyn Policy> = Arc::new(ChallengeAuthenticationPolicy::new(
             credential.clone(),
-            "https://vault.azure.net/.default".to_string(),
+            Some(AUDIENCES.scope(&endpoint, &options.options)),
         ));
         let per_retry_policies = vec![auth_policy];
 
@@ -40,8 +60,11 @@ impl SecretClient {
             pipeline: Pipeline::new(
                 option_env!("CARGO_PKG_NAME"),
                 option_env!("CARGO_PKG_VERSION"),
-                &options.options,
-                Vec::default(),
+                &SoftDeleteConflictRetry::apply(
+                    &options.options,
+                    options.retry_soft_delete_conflicts,
+                ),
+                vec![Arc::new(SensitiveFields::from(SENSITIVE_FIELDS))],
                 per_retry_policies,
             ),
         })
@@ -51,27 +74,181 @@ impl SecretClient {
         &self.endpoint
     }
 
+    /// Gets the resolved configuration of the client without sending any requests.
+    pub fn diagnostics(&self) -> ClientDiagnostics {
+        self.pipeline.diagnostics(&self.endpoint)
+    }
+
+    /// Creates a secret, or adds a new version of it if it already exists.
+    ///
+    /// ```
+    /// # use azure_client_new_methods_params_struct::{GetSecretRequest, SecretClient, SecretClientOptions, SetSecretRequest};
+    /// # use azure_core::{test::FakeCredential, transport::InMemoryTransport, ResponseBuilder};
+    /// # use std::sync::Arc;
+    /// # #[tokio::main]
+    /// # async fn main() -> azure_core::Result<()> {
+    /// # let mut options = SecretClientOptions::default();
+    /// # options.options.transport = InMemoryTransport::new(
+    /// #     ResponseBuilder::new(200).with_body(r#"{"name":"my-secret","version":"1","attributes":{"enabled":true}}"#),
+    /// # )
+    /// # .into();
+    /// let credential = Arc::new(FakeCredential);
+    /// let client = SecretClient::new("https://my-vault.vault.azure.net", credential, Some(options))?;
+    /// let secret = client
+    ///     .set_secret(&SetSecretRequest::new("my-secret", "my-value"))
+    ///     .await?
+    ///     .into_model()
+    ///     .await?;
+    /// assert_eq!(secret.version, "1");
+    /// # Ok(())
+    /// # }
+    /// ```
     #[allow(unused_variables)]
-    pub async fn set_secret(&self, request: &SetSecretRequest) -> azure_core::Result<Response> {
+    #[instrumented("SecretClient::set_secret")]
+    pub async fn set_secret(
+        &self,
+        request: &SetSecretRequest,
+    ) -> azure_core::Result<Response<Secret>> {
         let mut ctx = match request.context.as_ref() {
             Some(ctx) => Context::with_context(ctx),
             None => Context::default(),
         };
-        ctx.insert(Span::from("SecretClient::set_secret"));
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(false));
+
+        validate_secret_name(&request.name)?;
 
         let mut url = self.endpoint.clone();
         url.set_path(&format!("secrets/{}", request.name));
 
-        let mut req = Request::new(url, "GET");
+        let mut req = Request::new(url, "PUT");
         req.set_json(&request)?;
 
-        self.pipeline.send(&mut ctx, &mut req).await
+        let response = self.pipeline.send(&mut ctx, &mut req).await?;
+        Ok(response.with_model())
+    }
+
+    /// Gets the latest version of a secret, or the version in `request`.
+    ///
+    /// ```
+    /// # use azure_client_new_methods_params_struct::{GetSecretRequest, SecretClient, SecretClientOptions, SetSecretRequest};
+    /// # use azure_core::{test::FakeCredential, transport::InMemoryTransport, ResponseBuilder};
+    /// # use std::sync::Arc;
+    /// # #[tokio::main]
+    /// # async fn main() -> azure_core::Result<()> {
+    /// # let mut options = SecretClientOptions::default();
+    /// # options.options.transport = InMemoryTransport::new(
+    /// #     ResponseBuilder::new(200).with_body(r#"{"name":"my-secret","version":"1","attributes":{"enabled":true}}"#),
+    /// # )
+    /// # .into();
+    /// let credential = Arc::new(FakeCredential);
+    /// let client = SecretClient::new("https://my-vault.vault.azure.net", credential, Some(options))?;
+    /// let secret = client
+    ///     .get_secret(&GetSecretRequest::new("my-secret"))
+    ///     .await?
+    ///     .into_model()
+    ///     .await?;
+    /// assert_eq!(secret.version, "1");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrumented("SecretClient::get_secret")]
+    pub async fn get_secret(
+        &self,
+        request: &GetSecretRequest,
+    ) -> azure_core::Result<Response<Secret>> {
+        let mut ctx = match request.context.as_ref() {
+            Some(ctx) => Context::with_context(ctx),
+            None => Context::default(),
+        };
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        validate_secret_name(&request.name)?;
+
+        let mut url = self.endpoint.clone();
+        url.set_path(&format!(
+            "secrets/{}/{}",
+            request.name,
+            request.version.as_deref().unwrap_or_default()
+        ));
+
+        let mut req = Request::new(url, "GET");
+        let response = self.pipeline.send(&mut ctx, &mut req).await?;
+        Ok(response.with_model())
+    }
+
+    /// Gets a secret like [`SecretClient::get_secret`] but returns `None` if the secret does not
+    /// exist.
+    pub async fn get_secret_if_exists(
+        &self,
+        request: &GetSecretRequest,
+    ) -> azure_core::Result<Option<Response<Secret>>> {
+        match self.get_secret(request).await {
+            Ok(response) => Ok(Some(response)),
+            Err(error) if error.is_not_found() => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    #[instrumented("SecretClient::begin_delete_secret")]
+    pub async fn begin_delete_secret(
+        &self,
+        request: &DeleteSecretRequest,
+    ) -> Result<Poller<DeletedSecret>> {
+        let mut ctx = match request.context.as_ref() {
+            Some(ctx) => Context::with_context(ctx),
+            None => Context::default(),
+        };
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        azure_keyvault_common::begin_delete_secret(
+            &self.pipeline,
+            &ctx,
+            &self.endpoint,
+            &request.name,
+        )
+        .await
+    }
+
+    #[instrumented("SecretClient::list_secrets")]
+    pub fn list_secrets(&self, request: &ListSecretsRequest) -> Result<Pageable<SecretItem>> {
+        let client = self.clone();
+        let mut ctx = match request.context.as_ref() {
+            Some(ctx) => Context::with_context(ctx),
+            None => Context::default(),
+        };
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        let first_page =
+            list_secrets_url(&self.endpoint, request.max_results, request.include_managed)?;
+        Ok(Pageable::new(move |continuation_token: Option<String>| {
+            let client = client.clone();
+            let first_page = first_page.clone();
+            let mut ctx = ctx.clone();
+            async move {
+                let url = match continuation_token {
+                    Some(next_link) => Url::parse(&next_link)?,
+                    None => first_page,
+                };
+
+                let mut req = Request::new(url, "GET");
+                let response = client.pipeline.send(&mut ctx, &mut req).await?;
+                Page::from_response(response).await
+            }
+        }))
     }
 }
 
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct SecretClientOptions {
     pub api_version: String,
+    /// Whether to retry the 409 conflicts returned when re-creating a deleted secret which is still
+    /// being purged, up to 5 times every 2 seconds.
+    pub retry_soft_delete_conflicts: bool,
     pub options: ClientOptions,
 }
 
@@ -79,17 +256,21 @@ impl Default for SecretClientOptions {
     fn default() -> Self {
         Self {
             api_version: "7.5".to_string(),
+            retry_soft_delete_conflicts: false,
             options: ClientOptions::default(),
         }
     }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct SetSecretRequest {
     #[serde(skip_serializing)]
     name: String,
     value: String,
+    #[serde(rename = "attributes")]
     pub properties: Option<SecretProperties>,
+    #[serde(rename = "contentType")]
     pub content_type: Option<String>,
     pub tags: Option<HashMap<String, String>>,
 
@@ -110,3 +291,53 @@ impl SetSecretRequest {
         }
     }
 }
+
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct GetSecretRequest {
+    pub name: String,
+    /// The version of the secret to get, or the latest version if not set.
+    pub version: Option<String>,
+
+    // Ephemeral data used during the call.
+    pub context: Option<Context>,
+}
+
+impl GetSecretRequest {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct ListSecretsRequest {
+    /// The maximum number of secrets to return per page, from 1 to 25.
+    pub max_results: Option<u32>,
+    /// Whether to include secrets managed by Key Vault, such as those backing certificates.
+    pub include_managed: Option<bool>,
+
+    // Ephemeral data used during the call.
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct DeleteSecretRequest {
+    pub name: String,
+
+    // Ephemeral data used during the call.
+    pub context: Option<Context>,
+}
+
+impl DeleteSecretRequest {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use azure_client_new_method_subclients::{VaultClient, VaultClientOptions};
+use azure_identity::DefaultAzureCredential;
+use std::{env, sync::Arc};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let endpoint = env::var("AZURE_KEYVAULT_URL")?;
+
+    let credential = Arc::new(DefaultAzureCredential::default());
+    let mut options = VaultClientOptions::default();
+    options.api_version = "7.4".to_string();
+    let client = VaultClient::new(endpoint, credential, Some(options))?;
+
+    // Sub-clients are cheap to get since they share the pipeline of the vault client.
+    let secrets = client.secrets();
+    let response = secrets
+        .set_secret("secret-name", "secret-value", None)
+        .await?;
+    let secret = response.into_model().await?;
+    println!("set secret {} version {}", secret.name, secret.version);
+
+    let key = client
+        .keys()
+        .get_key("key-name", None)
+        .await?
+        .into_model()
+        .await?;
+    println!("got key {} ({})", key.key.kid, key.key.kty);
+
+    let certificate = client
+        .certificates()
+        .get_certificate("certificate-name", None)
+        .await?
+        .into_model()
+        .await?;
+    println!("got certificate {}", certificate.id);
+
+    Ok(())
+}
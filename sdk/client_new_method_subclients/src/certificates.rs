@@ -0,0 +1,73 @@
+use crate::{validate_name, NAMESPACE};
+use azure_core::{
+    instrumented, Context, Idempotent, Namespace, Pipeline, Request, Response, Result, Url,
+};
+use serde::Deserialize;
+
+/// A sub-client for certificate operations, created by
+/// [`VaultClient::certificates`](crate::VaultClient::certificates).
+#[derive(Debug, Clone)]
+pub struct CertificateClient {
+    endpoint: Url,
+    pipeline: Pipeline,
+}
+
+impl CertificateClient {
+    pub(crate) fn new(endpoint: Url, pipeline: Pipeline) -> Self {
+        Self { endpoint, pipeline }
+    }
+
+    #[instrumented("CertificateClient::get_certificate")]
+    pub async fn get_certificate<N>(
+        &self,
+        name: N,
+        options: Option<GetCertificateOptions>,
+    ) -> Result<Response<Certificate>>
+    where
+        N: Into<String>,
+    {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        let name = name.into();
+        validate_name(&name)?;
+
+        let mut url = self.endpoint.clone();
+        url.set_path(&format!(
+            "certificates/{}/{}",
+            name,
+            options.version.unwrap_or_default()
+        ));
+
+        let mut request = Request::new(url, "GET");
+        let response = self.pipeline.send(&mut ctx, &mut request).await?;
+        Ok(response.with_model())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct GetCertificateOptions {
+    /// The version of the certificate to get, or the latest version if not set.
+    pub version: Option<String>,
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[non_exhaustive]
+pub struct Certificate {
+    pub id: String,
+    /// The base64-encoded DER of the public certificate.
+    pub cer: Option<String>,
+    #[serde(rename = "attributes")]
+    pub properties: CertificateProperties,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[non_exhaustive]
+pub struct CertificateProperties {
+    pub enabled: bool,
+}
@@ -0,0 +1,75 @@
+use crate::{validate_name, NAMESPACE};
+use azure_core::{
+    instrumented, Context, Idempotent, Namespace, Pipeline, Request, Response, Result, Url,
+};
+use serde::Deserialize;
+
+/// A sub-client for key operations, created by [`VaultClient::keys`](crate::VaultClient::keys).
+#[derive(Debug, Clone)]
+pub struct KeyClient {
+    endpoint: Url,
+    pipeline: Pipeline,
+}
+
+impl KeyClient {
+    pub(crate) fn new(endpoint: Url, pipeline: Pipeline) -> Self {
+        Self { endpoint, pipeline }
+    }
+
+    #[instrumented("KeyClient::get_key")]
+    pub async fn get_key<N>(&self, name: N, options: Option<GetKeyOptions>) -> Result<Response<Key>>
+    where
+        N: Into<String>,
+    {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        let name = name.into();
+        validate_name(&name)?;
+
+        let mut url = self.endpoint.clone();
+        url.set_path(&format!(
+            "keys/{}/{}",
+            name,
+            options.version.unwrap_or_default()
+        ));
+
+        let mut request = Request::new(url, "GET");
+        let response = self.pipeline.send(&mut ctx, &mut request).await?;
+        Ok(response.with_model())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct GetKeyOptions {
+    /// The version of the key to get, or the latest version if not set.
+    pub version: Option<String>,
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[non_exhaustive]
+pub struct Key {
+    pub key: JsonWebKey,
+    #[serde(rename = "attributes")]
+    pub properties: KeyProperties,
+}
+
+/// The public part of a key; private key material is never returned.
+#[derive(Clone, Debug, Deserialize)]
+#[non_exhaustive]
+pub struct JsonWebKey {
+    pub kid: String,
+    /// The type of key, e.g., `RSA` or `EC`.
+    pub kty: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[non_exhaustive]
+pub struct KeyProperties {
+    pub enabled: bool,
+}
@@ -0,0 +1,122 @@
+#![doc = include_str!("../README.md")]
+
+mod certificates;
+mod keys;
+mod secrets;
+
+use azure_core::{
+    parse_endpoint,
+    policies::{ChallengeAuthenticationPolicy, Policy},
+    ClientDiagnostics, ClientOptions, Error, ErrorKind, Pipeline, Result, ServiceAudiences,
+    TokenCredential, Url,
+};
+pub use certificates::*;
+pub use keys::*;
+pub use secrets::*;
+use std::sync::Arc;
+
+const NAMESPACE: &str = "Microsoft.KeyVault";
+const AUDIENCES: ServiceAudiences = ServiceAudiences {
+    azure_public: "https://vault.azure.net",
+    azure_china: "https://vault.azure.cn",
+    azure_us_government: "https://vault.usgovcloudapi.net",
+};
+
+/// A client for a Key Vault which gets sub-clients for each group of operations, all sharing the same pipeline.
+#[derive(Debug, Clone)]
+pub struct VaultClient {
+    endpoint: Url,
+    pipeline: Pipeline,
+}
+
+impl VaultClient {
+    pub fn new(
+        endpoint: impl AsRef<str>,
+        credential: Arc<dyn TokenCredential>,
+        options: Option<VaultClientOptions>,
+    ) -> Result<Self> {
+        let options = options.unwrap_or_default();
+        if options.api_version.is_empty() {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "api_version must not be empty",
+            ));
+        }
+        options.options.validate()?;
+
+        let mut endpoint = parse_endpoint(endpoint)?;
+        endpoint
+            .query_pairs_mut()
+            .clear()
+            .append_pair("api-version", &options.api_version);
+
+        let auth_policy: Arc<dyn Policy> = Arc::new(ChallengeAuthenticationPolicy::new(
+            credential.clone(),
+            Some(AUDIENCES.scope(&endpoint, &options.options)),
+        ));
+        let per_retry_policies = vec![auth_policy];
+
+        Ok(Self {
+            endpoint,
+            pipeline: Pipeline::new(
+                option_env!("CARGO_PKG_NAME"),
+                option_env!("CARGO_PKG_VERSION"),
+                &options.options,
+                Vec::default(),
+                per_retry_policies,
+            ),
+        })
+    }
+
+    pub fn endpoint(&self) -> &Url {
+        &self.endpoint
+    }
+
+    /// Gets the resolved configuration of the client without sending any requests.
+    pub fn diagnostics(&self) -> ClientDiagnostics {
+        self.pipeline.diagnostics(&self.endpoint)
+    }
+
+    pub fn secrets(&self) -> SecretClient {
+        SecretClient::new(self.endpoint.clone(), self.pipeline.clone())
+    }
+
+    pub fn keys(&self) -> KeyClient {
+        KeyClient::new(self.endpoint.clone(), self.pipeline.clone())
+    }
+
+    pub fn certificates(&self) -> CertificateClient {
+        CertificateClient::new(self.endpoint.clone(), self.pipeline.clone())
+    }
+}
+
+/// Options for a [`VaultClient`] and all its sub-clients.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct VaultClientOptions {
+    pub api_version: String,
+    pub options: ClientOptions,
+}
+
+impl Default for VaultClientOptions {
+    fn default() -> Self {
+        Self {
+            api_version: "7.5".to_string(),
+            options: ClientOptions::default(),
+        }
+    }
+}
+
+/// Validates a secret, key, or certificate name is 1 to 127 alphanumeric characters or dashes, which Key Vault requires.
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || name.len() > 127
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return Err(Error::message(
+            ErrorKind::InvalidInput,
+            format!("name '{name}' must be 1 to 127 alphanumeric characters or dashes"),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,179 @@
+use crate::{validate_name, NAMESPACE};
+use azure_core::{
+    instrumented, Context, Idempotent, Namespace, Pipeline, Request, Response, Result,
+    SensitiveFields, Url,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const SENSITIVE_FIELDS: &[&str] = &["value"];
+
+/// A sub-client for secret operations, created by [`VaultClient::secrets`](crate::VaultClient::secrets).
+#[derive(Debug, Clone)]
+pub struct SecretClient {
+    endpoint: Url,
+    pipeline: Pipeline,
+}
+
+impl SecretClient {
+    pub(crate) fn new(endpoint: Url, pipeline: Pipeline) -> Self {
+        Self { endpoint, pipeline }
+    }
+
+    /// Creates a secret, or adds a new version of it if it already exists.
+    ///
+    /// ```
+    /// # use azure_client_new_method_subclients::{VaultClient, VaultClientOptions};
+    /// # use azure_core::{test::FakeCredential, transport::InMemoryTransport, ResponseBuilder};
+    /// # use std::sync::Arc;
+    /// # #[tokio::main]
+    /// # async fn main() -> azure_core::Result<()> {
+    /// # let mut options = VaultClientOptions::default();
+    /// # options.options.transport = InMemoryTransport::new(
+    /// #     ResponseBuilder::new(200).with_body(r#"{"name":"my-secret","version":"1","attributes":{"enabled":true}}"#),
+    /// # )
+    /// # .into();
+    /// let credential = Arc::new(FakeCredential);
+    /// let vault = VaultClient::new("https://my-vault.vault.azure.net", credential, Some(options))?;
+    /// let client = vault.secrets();
+    /// let secret = client.set_secret("my-secret", "my-value", None).await?.into_model().await?;
+    /// assert_eq!(secret.version, "1");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrumented("SecretClient::set_secret")]
+    pub async fn set_secret<N, V>(
+        &self,
+        name: N,
+        value: V,
+        options: Option<SetSecretOptions>,
+    ) -> Result<Response<Secret>>
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(false));
+        ctx.insert(SensitiveFields::from(SENSITIVE_FIELDS));
+
+        let name = name.into();
+        validate_name(&name)?;
+
+        let mut url = self.endpoint.clone();
+        url.set_path(&format!("secrets/{}", name));
+
+        let mut request = Request::new(url, "PUT");
+        request.set_json(&SetSecretRequest {
+            value: value.into(),
+            properties: options.properties,
+            content_type: options.content_type,
+            tags: options.tags,
+        })?;
+
+        let response = self.pipeline.send(&mut ctx, &mut request).await?;
+        Ok(response.with_model())
+    }
+
+    /// Gets the latest version of a secret, or the version in `options`.
+    ///
+    /// ```
+    /// # use azure_client_new_method_subclients::{VaultClient, VaultClientOptions};
+    /// # use azure_core::{test::FakeCredential, transport::InMemoryTransport, ResponseBuilder};
+    /// # use std::sync::Arc;
+    /// # #[tokio::main]
+    /// # async fn main() -> azure_core::Result<()> {
+    /// # let mut options = VaultClientOptions::default();
+    /// # options.options.transport = InMemoryTransport::new(
+    /// #     ResponseBuilder::new(200).with_body(r#"{"name":"my-secret","version":"1","attributes":{"enabled":true}}"#),
+    /// # )
+    /// # .into();
+    /// let credential = Arc::new(FakeCredential);
+    /// let vault = VaultClient::new("https://my-vault.vault.azure.net", credential, Some(options))?;
+    /// let client = vault.secrets();
+    /// let secret = client.get_secret("my-secret", None).await?.into_model().await?;
+    /// assert_eq!(secret.version, "1");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrumented("SecretClient::get_secret")]
+    pub async fn get_secret<N>(
+        &self,
+        name: N,
+        options: Option<GetSecretOptions>,
+    ) -> Result<Response<Secret>>
+    where
+        N: Into<String>,
+    {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+        ctx.insert(SensitiveFields::from(SENSITIVE_FIELDS));
+
+        let name = name.into();
+        validate_name(&name)?;
+
+        let mut url = self.endpoint.clone();
+        url.set_path(&format!(
+            "secrets/{}/{}",
+            name,
+            options.version.unwrap_or_default()
+        ));
+
+        let mut request = Request::new(url, "GET");
+        let response = self.pipeline.send(&mut ctx, &mut request).await?;
+        Ok(response.with_model())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct SetSecretOptions {
+    pub properties: Option<SecretProperties>,
+    pub content_type: Option<String>,
+    pub tags: Option<HashMap<String, String>>,
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct GetSecretOptions {
+    /// The version of the secret to get, or the latest version if not set.
+    pub version: Option<String>,
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[non_exhaustive]
+pub struct Secret {
+    pub name: String,
+    pub version: String,
+    #[serde(rename = "attributes")]
+    pub properties: SecretProperties,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+struct SetSecretRequest {
+    value: String,
+    #[serde(rename = "attributes")]
+    properties: Option<SecretProperties>,
+    #[serde(rename = "contentType")]
+    content_type: Option<String>,
+    tags: Option<HashMap<String, String>>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct SecretProperties {
+    pub enabled: bool,
+}
+
+impl SecretProperties {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+}
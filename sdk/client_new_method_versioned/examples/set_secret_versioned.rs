@@ -0,0 +1,28 @@
+use azure_client_new_method_versioned::{SecretClient, V7_4};
+use azure_identity::DefaultAzureCredential;
+use futures::TryStreamExt as _;
+use std::{env, pin::pin, sync::Arc};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let endpoint = env::var("AZURE_KEYVAULT_URL")?;
+    let credential = Arc::new(DefaultAzureCredential::default());
+
+    // The default version is used when the type is named without one.
+    let client: SecretClient = SecretClient::new(endpoint.clone(), credential.clone(), None)?;
+    client
+        .set_secret("secret-name", "secret-value", None)
+        .await?;
+
+    let mut secrets = pin!(client.list_secrets(None)?.into_stream());
+    while let Some(secret) = secrets.try_next().await? {
+        println!("{}", secret.id);
+    }
+
+    // Older versions must be named, and do not define operations added in later versions.
+    let client = SecretClient::<V7_4>::new(endpoint, credential, None)?;
+    client.get_secret("secret-name", None).await?;
+    // client.list_secrets(None); // error[E0599]: the method `list_secrets` exists but its trait bounds were not satisfied
+
+    Ok(())
+}
@@ -0,0 +1,256 @@
+#![doc = include_str!("../README.md")]
+
+mod models;
+mod versions;
+
+use azure_core::{
+    instrumented, parse_endpoint,
+    policies::{ChallengeAuthenticationPolicy, Policy},
+    ClientDiagnostics, ClientOptions, Context, Idempotent, Namespace, Page, Pageable, Pipeline,
+    Request, Response, Result, SensitiveFields, ServiceAudiences, TokenCredential, Url,
+};
+use azure_keyvault_common::{list_secrets_url, validate_secret_name};
+pub use models::*;
+use std::{collections::HashMap, marker::PhantomData, sync::Arc};
+pub use versions::*;
+
+const NAMESPACE: &str = "Microsoft.KeyVault";
+const AUDIENCES: ServiceAudiences = ServiceAudiences {
+    azure_public: "https://vault.azure.net",
+    azure_china: "https://vault.azure.cn",
+    azure_us_government: "https://vault.usgovcloudapi.net",
+};
+const SENSITIVE_FIELDS: &[&str] = &["value"];
+
+/// A client for the service version `V`, which determines the `api-version` sent and which operations are defined.
+#[derive(Debug)]
+pub struct SecretClient<V: ServiceVersion = V7_5> {
+    endpoint: Url,
+    pipeline: Pipeline,
+    version: PhantomData<V>,
+}
+
+// Derived `Clone` would require `V: Clone`.
+impl<V: ServiceVersion> Clone for SecretClient<V> {
+    fn clone(&self) -> Self {
+        Self {
+            endpoint: self.endpoint.clone(),
+            pipeline: self.pipeline.clone(),
+            version: PhantomData,
+        }
+    }
+}
+
+impl<V: ServiceVersion> SecretClient<V> {
+    pub fn new(
+        endpoint: impl AsRef<str>,
+        credential: Arc<dyn TokenCredential>,
+        options: Option<SecretClientOptions>,
+    ) -> Result<Self> {
+        let options = options.unwrap_or_default();
+        options.options.validate()?;
+
+        let mut endpoint = parse_endpoint(endpoint)?;
+        endpoint
+            .query_pairs_mut()
+            .clear()
+            .append_pair("api-version", V::API_VERSION);
+
+        let auth_policy: Arc<dyn Policy> = Arc::new(ChallengeAuthenticationPolicy::new(
+            credential.clone(),
+            Some(AUDIENCES.scope(&endpoint, &options.options)),
+        ));
+        let per_retry_policies = vec![auth_policy];
+
+        Ok(Self {
+            endpoint,
+            pipeline: Pipeline::new(
+                option_env!("CARGO_PKG_NAME"),
+                option_env!("CARGO_PKG_VERSION"),
+                &options.options,
+                vec![Arc::new(SensitiveFields::from(SENSITIVE_FIELDS))],
+                per_retry_policies,
+            ),
+            version: PhantomData,
+        })
+    }
+
+    pub fn endpoint(&self) -> &Url {
+        &self.endpoint
+    }
+
+    /// Gets the resolved configuration of the client without sending any requests.
+    pub fn diagnostics(&self) -> ClientDiagnostics {
+        self.pipeline.diagnostics(&self.endpoint)
+    }
+
+    /// Creates a secret, or adds a new version of it if it already exists.
+    ///
+    /// ```
+    /// # use azure_client_new_method_versioned::{SecretClient, SecretClientOptions};
+    /// # use azure_core::{test::FakeCredential, transport::InMemoryTransport, ResponseBuilder};
+    /// # use std::sync::Arc;
+    /// # #[tokio::main]
+    /// # async fn main() -> azure_core::Result<()> {
+    /// # let mut options = SecretClientOptions::default();
+    /// # options.options.transport = InMemoryTransport::new(
+    /// #     ResponseBuilder::new(200).with_body(r#"{"name":"my-secret","version":"1","attributes":{"enabled":true}}"#),
+    /// # )
+    /// # .into();
+    /// let credential = Arc::new(FakeCredential);
+    /// let client: SecretClient = SecretClient::new("https://my-vault.vault.azure.net", credential, Some(options))?;
+    /// let secret = client.set_secret("my-secret", "my-value", None).await?.into_model().await?;
+    /// assert_eq!(secret.version, "1");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrumented("SecretClient::set_secret")]
+    pub async fn set_secret<N, T>(
+        &self,
+        name: N,
+        value: T,
+        options: Option<SetSecretOptions>,
+    ) -> azure_core::Result<Response<Secret>>
+    where
+        N: Into<String>,
+        T: Into<String>,
+    {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(false));
+
+        let name = name.into();
+        validate_secret_name(&name)?;
+
+        let mut url = self.endpoint.clone();
+        url.set_path(&format!("secrets/{}", name));
+
+        let mut request = Request::new(url, "PUT");
+        request.set_json(&SetSecretRequest {
+            value: value.into(),
+            properties: options.properties,
+            content_type: options.content_type,
+            tags: options.tags,
+        })?;
+
+        let response = self.pipeline.send(&mut ctx, &mut request).await?;
+        Ok(response.with_model())
+    }
+
+    /// Gets the latest version of a secret, or the version in `options`.
+    ///
+    /// ```
+    /// # use azure_client_new_method_versioned::{SecretClient, SecretClientOptions};
+    /// # use azure_core::{test::FakeCredential, transport::InMemoryTransport, ResponseBuilder};
+    /// # use std::sync::Arc;
+    /// # #[tokio::main]
+    /// # async fn main() -> azure_core::Result<()> {
+    /// # let mut options = SecretClientOptions::default();
+    /// # options.options.transport = InMemoryTransport::new(
+    /// #     ResponseBuilder::new(200).with_body(r#"{"name":"my-secret","version":"1","attributes":{"enabled":true}}"#),
+    /// # )
+    /// # .into();
+    /// let credential = Arc::new(FakeCredential);
+    /// let client: SecretClient = SecretClient::new("https://my-vault.vault.azure.net", credential, Some(options))?;
+    /// let secret = client.get_secret("my-secret", None).await?.into_model().await?;
+    /// assert_eq!(secret.version, "1");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrumented("SecretClient::get_secret")]
+    pub async fn get_secret<N>(
+        &self,
+        name: N,
+        options: Option<GetSecretOptions>,
+    ) -> azure_core::Result<Response<Secret>>
+    where
+        N: Into<String>,
+    {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        let name = name.into();
+        validate_secret_name(&name)?;
+
+        let mut url = self.endpoint.clone();
+        url.set_path(&format!(
+            "secrets/{}/{}",
+            name,
+            options.version.unwrap_or_default()
+        ));
+
+        let mut request = Request::new(url, "GET");
+        let response = self.pipeline.send(&mut ctx, &mut request).await?;
+        Ok(response.with_model())
+    }
+}
+
+impl<V: Since7_5> SecretClient<V> {
+    /// Lists secrets including those managed by Key Vault, which is only defined for `7.5` and newer.
+    #[instrumented("SecretClient::list_secrets")]
+    pub fn list_secrets(
+        &self,
+        options: Option<ListSecretsOptions>,
+    ) -> Result<Pageable<SecretItem>> {
+        let options = options.unwrap_or_default();
+        let client = self.clone();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        let first_page = list_secrets_url(&self.endpoint, None, options.include_managed)?;
+        Ok(Pageable::new(move |continuation_token: Option<String>| {
+            let client = client.clone();
+            let first_page = first_page.clone();
+            let mut ctx = ctx.clone();
+            async move {
+                let url = match continuation_token {
+                    Some(next_link) => Url::parse(&next_link)?,
+                    None => first_page,
+                };
+
+                let mut request = Request::new(url, "GET");
+                let response = client.pipeline.send(&mut ctx, &mut request).await?;
+                Page::from_response(response).await
+            }
+        }))
+    }
+}
+
+/// Options for a [`SecretClient`], whose `api-version` is determined by its [`ServiceVersion`] instead.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct SecretClientOptions {
+    pub options: ClientOptions,
+}
+
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct SetSecretOptions {
+    pub properties: Option<SecretProperties>,
+    pub content_type: Option<String>,
+    pub tags: Option<HashMap<String, String>>,
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct GetSecretOptions {
+    /// The version of the secret to get, or the latest version if not set.
+    pub version: Option<String>,
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct ListSecretsOptions {
+    /// Whether to include secrets managed by Key Vault, such as those backing certificates.
+    pub include_managed: Option<bool>,
+    pub context: Option<Context>,
+}
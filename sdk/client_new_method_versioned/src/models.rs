@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 #[derive(Clone, Debug, Deserialize)]
+#[non_exhaustive]
 pub struct Secret {
     pub name: String,
     pub version: String,
@@ -19,7 +20,24 @@ pub(crate) struct SetSecretRequest {
     pub tags: Option<HashMap<String, String>>,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+#[non_exhaustive]
+pub struct SecretItem {
+    pub id: String,
+    #[serde(rename = "attributes")]
+    pub properties: SecretProperties,
+    #[serde(rename = "contentType")]
+    pub content_type: Option<String>,
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct SecretProperties {
     pub enabled: bool,
 }
+
+impl SecretProperties {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+}
@@ -0,0 +1,32 @@
+mod private {
+    pub trait Sealed {}
+}
+
+/// A version of the Key Vault service, which determines the `api-version` a [`SecretClient`](crate::SecretClient) sends.
+///
+/// This trait is sealed so that only versions the client was written for can be used.
+pub trait ServiceVersion: private::Sealed + Send + Sync + 'static {
+    const API_VERSION: &'static str;
+}
+
+/// Versions `7.5` and newer, which define [`SecretClient::list_secrets`](crate::SecretClient::list_secrets).
+pub trait Since7_5: ServiceVersion {}
+
+/// Key Vault `7.4`.
+#[derive(Clone, Copy, Debug)]
+pub struct V7_4;
+
+impl private::Sealed for V7_4 {}
+impl ServiceVersion for V7_4 {
+    const API_VERSION: &'static str = "7.4";
+}
+
+/// Key Vault `7.5`, which is the default.
+#[derive(Clone, Copy, Debug)]
+pub struct V7_5;
+
+impl private::Sealed for V7_5 {}
+impl ServiceVersion for V7_5 {
+    const API_VERSION: &'static str = "7.5";
+}
+impl Since7_5 for V7_5 {}
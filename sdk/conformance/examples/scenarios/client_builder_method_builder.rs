@@ -0,0 +1,51 @@
+use super::Outcome;
+use azure_client_builder_method_builder::SecretClient;
+use azure_conformance::{MockCredential, ENDPOINT};
+use azure_core::{ClientBuilder, ClientOptions, Result};
+use futures::TryStreamExt;
+use std::sync::Arc;
+
+pub const SOURCE: &str = include_str!("client_builder_method_builder.rs");
+
+pub async fn run(options: ClientOptions) -> Result<Outcome> {
+    // scenario:start
+    let credential = Arc::new(MockCredential);
+    let client = SecretClient::builder()
+        .with_endpoint(ENDPOINT)
+        .with_credential(credential)
+        .with_transport(options.transport)
+        .build()?;
+
+    let response = client
+        .set_secret("my-secret", "my-value")
+        .with_content_type("text/plain")
+        .send()
+        .await?;
+    let secret = response.into_model().await?;
+
+    let response = client.get_secret(&secret.name).send().await?;
+    let secret = response.into_model().await?;
+
+    let ids: Vec<String> = client
+        .list_secrets()
+        .with_max_results(25)
+        .send()?
+        .into_stream()
+        .map_ok(|secret| secret.id)
+        .try_collect()
+        .await?;
+
+    let deleted = client
+        .begin_delete_secret(&secret.name)
+        .send()
+        .await?
+        .wait_for_completion()
+        .await?;
+    // scenario:end
+
+    Ok(Outcome {
+        version: secret.version,
+        ids: Some(ids),
+        deleted_id: Some(deleted.id),
+    })
+}
@@ -0,0 +1,47 @@
+use super::Outcome;
+use azure_client_new_methods_params_fluent::{
+    ListSecretsOptions, SecretClient, SecretClientOptions, SetSecretOptions,
+};
+use azure_conformance::{MockCredential, ENDPOINT};
+use azure_core::{ClientOptions, Result};
+use futures::TryStreamExt;
+use std::sync::Arc;
+
+pub const SOURCE: &str = include_str!("client_new_method_params_fluent.rs");
+
+pub async fn run(options: ClientOptions) -> Result<Outcome> {
+    // scenario:start
+    let credential = Arc::new(MockCredential);
+    let client_options = SecretClientOptions::default().with_options(options);
+    let client = SecretClient::new(ENDPOINT, credential, Some(client_options))?;
+
+    let set_options = SetSecretOptions::default().with_content_type("text/plain");
+    let response = client
+        .set_secret("my-secret", "my-value", Some(set_options))
+        .await?;
+    let secret = response.into_model().await?;
+
+    let response = client.get_secret(&secret.name, None).await?;
+    let secret = response.into_model().await?;
+
+    let list_options = ListSecretsOptions::default().with_max_results(25);
+    let ids: Vec<String> = client
+        .list_secrets(Some(list_options))?
+        .into_stream()
+        .map_ok(|secret| secret.id)
+        .try_collect()
+        .await?;
+
+    let deleted = client
+        .begin_delete_secret(&secret.name, None)
+        .await?
+        .wait_for_completion()
+        .await?;
+    // scenario:end
+
+    Ok(Outcome {
+        version: secret.version,
+        ids: Some(ids),
+        deleted_id: Some(deleted.id),
+    })
+}
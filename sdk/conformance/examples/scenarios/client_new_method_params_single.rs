@@ -0,0 +1,49 @@
+use super::Outcome;
+use azure_client_new_methods_params_single::{
+    DeleteSecretParams, GetSecretParams, ListSecretsParams, SecretClient, SecretClientOptions,
+    SetSecretParams,
+};
+use azure_conformance::{MockCredential, ENDPOINT};
+use azure_core::{ClientOptions, Result};
+use futures::TryStreamExt;
+use std::sync::Arc;
+
+pub const SOURCE: &str = include_str!("client_new_method_params_single.rs");
+
+pub async fn run(options: ClientOptions) -> Result<Outcome> {
+    // scenario:start
+    let credential = Arc::new(MockCredential);
+    let mut client_options = SecretClientOptions::default();
+    client_options.options = options;
+    let client = SecretClient::new(ENDPOINT, credential, Some(client_options))?;
+
+    let response = client
+        .set_secret(SetSecretParams::new("my-secret", "my-value").with_content_type("text/plain"))
+        .await?;
+    let secret = response.into_model().await?;
+
+    let response = client
+        .get_secret(&GetSecretParams::new(&secret.name))
+        .await?;
+    let secret = response.into_model().await?;
+
+    let ids: Vec<String> = client
+        .list_secrets(ListSecretsParams::default().with_max_results(25))?
+        .into_stream()
+        .map_ok(|secret| secret.id)
+        .try_collect()
+        .await?;
+
+    let deleted = client
+        .begin_delete_secret(&DeleteSecretParams::new(&secret.name))
+        .await?
+        .wait_for_completion()
+        .await?;
+    // scenario:end
+
+    Ok(Outcome {
+        version: secret.version,
+        ids: Some(ids),
+        deleted_id: Some(deleted.id),
+    })
+}
@@ -0,0 +1,51 @@
+use super::Outcome;
+use azure_client_new_methods_params_struct::{
+    DeleteSecretRequest, GetSecretRequest, ListSecretsRequest, SecretClient, SecretClientOptions,
+    SetSecretRequest,
+};
+use azure_conformance::{MockCredential, ENDPOINT};
+use azure_core::{ClientOptions, Result};
+use futures::TryStreamExt;
+use std::sync::Arc;
+
+pub const SOURCE: &str = include_str!("client_new_method_params_struct.rs");
+
+pub async fn run(options: ClientOptions) -> Result<Outcome> {
+    // scenario:start
+    let credential = Arc::new(MockCredential);
+    let mut client_options = SecretClientOptions::default();
+    client_options.options = options;
+    let client = SecretClient::new(ENDPOINT, credential, Some(client_options))?;
+
+    let mut request = SetSecretRequest::new("my-secret", "my-value");
+    request.content_type = Some("text/plain".to_string());
+    let response = client.set_secret(&request).await?;
+    let secret = response.into_model().await?;
+
+    let response = client
+        .get_secret(&GetSecretRequest::new(&secret.name))
+        .await?;
+    let secret = response.into_model().await?;
+
+    let mut request = ListSecretsRequest::default();
+    request.max_results = Some(25);
+    let ids: Vec<String> = client
+        .list_secrets(&request)?
+        .into_stream()
+        .map_ok(|secret| secret.id)
+        .try_collect()
+        .await?;
+
+    let deleted = client
+        .begin_delete_secret(&DeleteSecretRequest::new(&secret.name))
+        .await?
+        .wait_for_completion()
+        .await?;
+    // scenario:end
+
+    Ok(Outcome {
+        version: secret.version,
+        ids: Some(ids),
+        deleted_id: Some(deleted.id),
+    })
+}
@@ -0,0 +1,33 @@
+use super::Outcome;
+use azure_client_new_method_subclients::{SetSecretOptions, VaultClient, VaultClientOptions};
+use azure_conformance::{MockCredential, ENDPOINT};
+use azure_core::{ClientOptions, Result};
+use std::sync::Arc;
+
+pub const SOURCE: &str = include_str!("client_new_method_subclients.rs");
+
+pub async fn run(options: ClientOptions) -> Result<Outcome> {
+    // scenario:start
+    let credential = Arc::new(MockCredential);
+    let mut client_options = VaultClientOptions::default();
+    client_options.options = options;
+    let client = VaultClient::new(ENDPOINT, credential, Some(client_options))?.secrets();
+
+    let mut set_options = SetSecretOptions::default();
+    set_options.content_type = Some("text/plain".to_string());
+    let response = client
+        .set_secret("my-secret", "my-value", Some(set_options))
+        .await?;
+    let secret = response.into_model().await?;
+
+    let response = client.get_secret(&secret.name, None).await?;
+    let secret = response.into_model().await?;
+    // scenario:end
+
+    // Sub-clients only set and get secrets.
+    Ok(Outcome {
+        version: secret.version,
+        ids: None,
+        deleted_id: None,
+    })
+}
@@ -0,0 +1,35 @@
+use super::Outcome;
+use azure_client_new_method_versioned::{
+    SecretClient, SecretClientOptions, SetSecretOptions, V7_5,
+};
+use azure_conformance::{MockCredential, ENDPOINT};
+use azure_core::{ClientOptions, Result};
+use std::sync::Arc;
+
+pub const SOURCE: &str = include_str!("client_new_method_versioned.rs");
+
+pub async fn run(options: ClientOptions) -> Result<Outcome> {
+    // scenario:start
+    let credential = Arc::new(MockCredential);
+    let mut client_options = SecretClientOptions::default();
+    client_options.options = options;
+    let client = SecretClient::<V7_5>::new(ENDPOINT, credential, Some(client_options))?;
+
+    let mut set_options = SetSecretOptions::default();
+    set_options.content_type = Some("text/plain".to_string());
+    let response = client
+        .set_secret("my-secret", "my-value", Some(set_options))
+        .await?;
+    let secret = response.into_model().await?;
+
+    let response = client.get_secret(&secret.name, None).await?;
+    let secret = response.into_model().await?;
+    // scenario:end
+
+    // Listing does not take a page size, and deleting is not defined.
+    Ok(Outcome {
+        version: secret.version,
+        ids: None,
+        deleted_id: None,
+    })
+}
@@ -0,0 +1,163 @@
+//! Runs the same scenario against each prototype and prints metrics of the code calling it.
+//!
+//! Only the code between the `// scenario:start` and `// scenario:end` comments of each module is
+//! measured, ignoring blank lines and comments.
+
+use azure_conformance::MockTransport;
+use azure_core::{transport::HttpClient, ClientOptions};
+use futures::future::BoxFuture;
+use std::sync::Arc;
+
+mod client_builder_method_builder;
+mod client_new_method_builder;
+mod client_new_method_params;
+mod client_new_method_params_context;
+mod client_new_method_params_fluent;
+mod client_new_method_params_ref;
+mod client_new_method_params_single;
+mod client_new_method_params_struct;
+mod client_new_method_subclients;
+mod client_new_method_versioned;
+
+/// What a scenario observed, which should be the same for every prototype.
+///
+/// Operations a prototype does not support are `None` and are not compared.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outcome {
+    pub version: String,
+    pub ids: Option<Vec<String>>,
+    pub deleted_id: Option<String>,
+}
+
+type Scenario = fn(ClientOptions) -> BoxFuture<'static, azure_core::Result<Outcome>>;
+
+const PROTOTYPES: &[(&str, &str, Scenario)] = &[
+    (
+        "client_builder_method_builder",
+        client_builder_method_builder::SOURCE,
+        |options| Box::pin(client_builder_method_builder::run(options)),
+    ),
+    (
+        "client_new_method_builder",
+        client_new_method_builder::SOURCE,
+        |options| Box::pin(client_new_method_builder::run(options)),
+    ),
+    (
+        "client_new_method_subclients",
+        client_new_method_subclients::SOURCE,
+        |options| Box::pin(client_new_method_subclients::run(options)),
+    ),
+    (
+        "client_new_method_versioned",
+        client_new_method_versioned::SOURCE,
+        |options| Box::pin(client_new_method_versioned::run(options)),
+    ),
+    (
+        "client_new_method_params",
+        client_new_method_params::SOURCE,
+        |options| Box::pin(client_new_method_params::run(options)),
+    ),
+    (
+        "client_new_method_params_context",
+        client_new_method_params_context::SOURCE,
+        |options| Box::pin(client_new_method_params_context::run(options)),
+    ),
+    (
+        "client_new_method_params_fluent",
+        client_new_method_params_fluent::SOURCE,
+        |options| Box::pin(client_new_method_params_fluent::run(options)),
+    ),
+    (
+        "client_new_method_params_ref",
+        client_new_method_params_ref::SOURCE,
+        |options| Box::pin(client_new_method_params_ref::run(options)),
+    ),
+    (
+        "client_new_method_params_single",
+        client_new_method_params_single::SOURCE,
+        |options| Box::pin(client_new_method_params_single::run(options)),
+    ),
+    (
+        "client_new_method_params_struct",
+        client_new_method_params_struct::SOURCE,
+        |options| Box::pin(client_new_method_params_struct::run(options)),
+    ),
+];
+
+#[derive(Debug, Default)]
+struct Metrics {
+    lines: usize,
+    characters: usize,
+    annotations: usize,
+}
+
+impl Metrics {
+    fn measure(source: &str) -> Self {
+        let mut metrics = Self::default();
+        for line in source
+            .lines()
+            .map(str::trim)
+            .skip_while(|line| *line != "// scenario:start")
+            .take_while(|line| *line != "// scenario:end")
+            .filter(|line| !line.is_empty() && !line.starts_with("//"))
+        {
+            metrics.lines += 1;
+            metrics.characters += line.chars().filter(|c| !c.is_whitespace()).count();
+            metrics.annotations += line.matches("::<").count();
+            if is_annotated_let(line) {
+                metrics.annotations += 1;
+            }
+        }
+        metrics
+    }
+}
+
+/// Whether a line binds a variable with an explicit type, e.g., `let secret: Secret = ...`.
+fn is_annotated_let(line: &str) -> bool {
+    line.strip_prefix("let ")
+        .and_then(|line| line.split('=').next())
+        .is_some_and(|pattern| pattern.replace("::", "").contains(':'))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!(
+        "{:<34} {:>6} {:>11} {:>12} {:>9}",
+        "prototype", "lines", "characters", "annotations", "requests"
+    );
+
+    let mut expected = None;
+    for (name, source, run) in PROTOTYPES {
+        let transport = Arc::new(MockTransport::default());
+        let mut options = ClientOptions::default();
+        options.transport = (transport.clone() as Arc<dyn HttpClient>).into();
+
+        let outcome = run(options).await?;
+        let requests = transport.requests();
+        let (expected_outcome, expected_requests) =
+            expected.get_or_insert_with(|| (outcome.clone(), requests.clone()));
+        assert_eq!(outcome.version, expected_outcome.version, "{name}");
+        if outcome.ids.is_some() {
+            assert_eq!(outcome.ids, expected_outcome.ids, "{name}");
+        }
+        if outcome.deleted_id.is_some() {
+            assert_eq!(outcome.deleted_id, expected_outcome.deleted_id, "{name}");
+            assert_eq!(&requests, expected_requests, "{name}");
+        } else {
+            // Prototypes which only set and get secrets send the first requests.
+            assert!(expected_requests.starts_with(&requests), "{name}");
+        }
+
+        let metrics = Metrics::measure(source);
+        println!(
+            "{:<34} {:>6} {:>11} {:>12} {:>9}",
+            name,
+            metrics.lines,
+            metrics.characters,
+            metrics.annotations,
+            requests.len()
+        );
+    }
+
+    Ok(())
+}
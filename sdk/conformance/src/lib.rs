@@ -0,0 +1,204 @@
+//! Runs the same scenarios against each prototype client over a [`MockTransport`] so any
+//! difference in what they send shows up as a test failure.
+
+use async_trait::async_trait;
+use azure_core::{
+    stream::BytesStream, transport::HttpClient, AccessToken, Body, ClientOptions, Error, ErrorKind,
+    Headers, LogVerbosity, Request, Response, Result, TokenCredential, TokenRequestOptions,
+    CONTENT_TYPE,
+};
+use serde_json::{json, Value};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+#[cfg(test)]
+mod tests;
+
+pub const ENDPOINT: &str = "https://my-vault.vault.azure.net";
+
+/// A request received by the [`MockTransport`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedRequest {
+    pub method: &'static str,
+    pub url: String,
+    /// Every header by name, including `authorization`.
+    pub headers: BTreeMap<String, String>,
+    /// The JSON body, if any.
+    pub body: Option<Value>,
+}
+
+/// Records every request and returns canned Key Vault responses, or 404 for anything else.
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+impl MockTransport {
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl HttpClient for MockTransport {
+    async fn execute_request(&self, request: &Request) -> Result<Response> {
+        let body = match request.body() {
+            Body::Bytes(bytes) if bytes.is_empty() => None,
+            Body::Bytes(bytes) => Some(serde_json::from_slice(bytes)?),
+            _ => {
+                return Err(Error::message(
+                    ErrorKind::InvalidInput,
+                    "only byte bodies are recorded",
+                ))
+            }
+        };
+        self.requests.lock().unwrap().push(RecordedRequest {
+            method: request.method(),
+            url: request.url().to_string(),
+            headers: request
+                .headers()
+                .iter()
+                .map(|(name, value)| (name.as_str().to_string(), value.as_str().to_string()))
+                .collect(),
+            body,
+        });
+
+        let segments: Vec<&str> = request
+            .url()
+            .path_segments()
+            .into_iter()
+            .flatten()
+            .collect();
+        let (status, body) = match (request.method(), segments.as_slice()) {
+            ("PUT", ["secrets", name]) | ("GET", ["secrets", name, _]) => (
+                200,
+                json!({
+                    "name": name,
+                    "value": "my-value",
+                    "version": "1",
+                    "attributes": { "enabled": true },
+                }),
+            ),
+            ("GET", ["secrets"]) => (
+                200,
+                json!({
+                    "value": [{
+                        "id": format!("{ENDPOINT}/secrets/my-secret"),
+                        "attributes": { "enabled": true },
+                        "contentType": "text/plain",
+                    }],
+                }),
+            ),
+            ("DELETE", ["secrets", _]) => (200, json!({})),
+            ("GET", ["deletedsecrets", name]) => (
+                200,
+                json!({
+                    "id": format!("{ENDPOINT}/secrets/{name}"),
+                    "recoveryId": format!("{ENDPOINT}/deletedsecrets/{name}"),
+                    "attributes": { "enabled": false },
+                }),
+            ),
+            _ => (
+                404,
+                json!({ "error": { "code": "NotFound", "message": "not found" } }),
+            ),
+        };
+        let mut headers = Headers::new();
+        headers.insert(CONTENT_TYPE, "application/json");
+        Ok(Response::new(
+            status,
+            headers,
+            Box::pin(BytesStream::new(serde_json::to_vec(&body)?)),
+        ))
+    }
+}
+
+/// Returns the same token valid for an hour for any scopes.
+#[derive(Debug, Default)]
+pub struct MockCredential;
+
+#[async_trait]
+impl TokenCredential for MockCredential {
+    async fn get_token(
+        &self,
+        _scopes: &[&str],
+        _options: &TokenRequestOptions,
+    ) -> Result<AccessToken> {
+        Ok(AccessToken::new(
+            "mock",
+            SystemTime::now() + Duration::from_secs(3600),
+        ))
+    }
+}
+
+/// Adapts a prototype's `SecretClient` to the operations used by the scenarios.
+#[async_trait]
+pub trait Prototype: Sized + Send + Sync {
+    /// Whether the prototype lists secrets with a page size; the scenarios skip listing if not.
+    const LISTS_SECRETS: bool = true;
+    /// Whether the prototype deletes secrets; the scenarios skip deleting if not.
+    const DELETES_SECRETS: bool = true;
+
+    fn new(
+        endpoint: &str,
+        credential: Arc<dyn TokenCredential>,
+        options: ClientOptions,
+    ) -> Result<Self>;
+
+    /// Sets a secret, returning the name of the secret in the response.
+    async fn set_secret(&self, name: &str, value: &str, content_type: &str) -> Result<String>;
+
+    /// Gets the latest version of a secret, returning its version.
+    async fn get_secret(&self, name: &str) -> Result<String>;
+
+    /// Lists every secret, returning their IDs.
+    async fn list_secrets(&self, _max_results: u32) -> Result<Vec<String>> {
+        Err(Error::message(ErrorKind::Other, "listing is not supported"))
+    }
+
+    /// Deletes a secret and waits for it to be deleted, returning its recovery ID.
+    async fn delete_secret(&self, _name: &str) -> Result<Option<String>> {
+        Err(Error::message(
+            ErrorKind::Other,
+            "deleting is not supported",
+        ))
+    }
+}
+
+/// Sets, gets, lists, and deletes a secret, as far as the prototype supports, returning the
+/// requests sent.
+///
+/// Bodies are logged, if logging is enabled, so tests can check secrets are redacted.
+pub async fn run_scenario<P: Prototype>() -> Result<Vec<RecordedRequest>> {
+    let transport = Arc::new(MockTransport::default());
+    let mut options = ClientOptions::default();
+    options.transport = (transport.clone() as Arc<dyn HttpClient>).into();
+    options.logging = LogVerbosity::Body.into();
+
+    let client = P::new(ENDPOINT, Arc::new(MockCredential), options)?;
+
+    let name = client
+        .set_secret("my-secret", "my-value", "text/plain")
+        .await?;
+    assert_eq!(name, "my-secret");
+
+    assert_eq!(client.get_secret("my-secret").await?, "1");
+
+    if P::LISTS_SECRETS {
+        let ids = client.list_secrets(25).await?;
+        assert_eq!(ids, [format!("{ENDPOINT}/secrets/my-secret")]);
+    }
+
+    if P::DELETES_SECRETS {
+        let recovery_id = client.delete_secret("my-secret").await?;
+        assert_eq!(
+            recovery_id,
+            Some(format!("{ENDPOINT}/deletedsecrets/my-secret"))
+        );
+    }
+
+    Ok(transport.requests())
+}
@@ -1 +1,89 @@
-pub trait TokenCredential: std::fmt::Debug + Send + Sync {}
+use crate::request::Request;
+use std::{
+    fmt,
+    time::{Duration, SystemTime},
+};
+
+/// Gets OAuth tokens, e.g., from Microsoft Entra ID, which a
+/// [`ChallengeAuthenticationPolicy`](crate::policies::ChallengeAuthenticationPolicy) sends as
+/// `Authorization: Bearer`.
+#[async_trait::async_trait]
+pub trait TokenCredential: std::fmt::Debug + Send + Sync {
+    /// Gets a token for `scopes`, e.g., `https://vault.azure.net/.default`.
+    async fn get_token(
+        &self,
+        scopes: &[&str],
+        options: &TokenRequestOptions,
+    ) -> crate::Result<AccessToken>;
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TokenRequestOptions {
+    /// The tenant to get the token from instead of the credential's default, e.g., from an
+    /// authentication challenge.
+    pub tenant_id: Option<String>,
+    /// Claims the token must have as JSON, e.g., decoded from an `insufficient_claims` challenge.
+    pub claims: Option<String>,
+    /// Whether the client can handle Continuous Access Evaluation (CAE) challenges, so the token
+    /// may be revoked before it expires.
+    pub enable_cae: bool,
+}
+
+/// Overrides the scopes or tenant of the token for a single operation, e.g., to call a
+/// pre-production instance of a service with a different audience or a resource in another tenant.
+///
+/// Insert it into the [`Context`](crate::Context) of an operation's options. A
+/// [`ChallengeAuthenticationPolicy`](crate::policies::ChallengeAuthenticationPolicy) uses it instead of the scopes or tenant of the client's
+/// challenge, and does not cache its token for other operations.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TokenOverride {
+    /// The scopes to get a token for, if not empty.
+    pub scopes: Vec<String>,
+    /// The tenant to get a token from, if set.
+    pub tenant_id: Option<String>,
+}
+
+/// A token and when it expires.
+#[derive(Clone, PartialEq, Eq)]
+pub struct AccessToken {
+    pub token: String,
+    pub expires_on: SystemTime,
+}
+
+impl AccessToken {
+    pub fn new(token: impl Into<String>, expires_on: SystemTime) -> Self {
+        Self {
+            token: token.into(),
+            expires_on,
+        }
+    }
+
+    /// Whether the token expires within `margin` of now, so it should be refreshed before use.
+    pub fn expires_within(&self, margin: Duration) -> bool {
+        self.expires_on <= SystemTime::now() + margin
+    }
+}
+
+impl fmt::Debug for AccessToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccessToken")
+            .field("expires_on", &self.expires_on)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Authorizes requests given their final method, URL, and headers, e.g., by signing them with a
+/// Storage account key or adding a shared access signature.
+///
+/// Wrap an implementation in a [`SigningPolicy`](crate::policies::SigningPolicy), like a
+/// [`BearerTokenSigner`](crate::policies::BearerTokenSigner) for tokens from a
+/// [`TokenCredential`], so that every kind of authorization is added at the same position.
+#[async_trait::async_trait]
+pub trait RequestSigner: Send + Sync + std::fmt::Debug {
+    /// Adds the headers or query parameters which authorize `request`, e.g., `Authorization`.
+    ///
+    /// Called again for each retry of `request`, so it must replace anything it previously added.
+    async fn sign(&self, request: &mut Request) -> crate::Result<()>;
+}
@@ -0,0 +1,462 @@
+use crate::{
+    error::{Error, ErrorKind},
+    headers::{Headers, CONTENT_TYPE},
+    request::{Body, Request},
+    response::Response,
+};
+use bytes::Bytes;
+use serde::de::DeserializeOwned;
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Requests sent together as the `multipart/mixed` body of a single request, e.g., for Blob
+/// `Submit Batch` or an OData `$batch` with changesets.
+///
+/// Sub-requests do not go through the pipeline, so they must have any headers the service
+/// requires, and only the batch request is retried and authenticated.
+#[derive(Clone, Debug)]
+pub struct BatchRequest {
+    boundary: String,
+    parts: Vec<Part>,
+}
+
+#[derive(Clone, Debug)]
+enum Part {
+    Request(Request),
+    /// Requests which succeed or fail together.
+    Changeset(String, Vec<Request>),
+}
+
+impl BatchRequest {
+    pub fn new() -> Self {
+        Self::with_boundary(boundary("batch"))
+    }
+
+    /// Creates a batch whose parts are separated by `boundary`, which must not occur in any part.
+    pub fn with_boundary(boundary: impl Into<String>) -> Self {
+        Self {
+            boundary: boundary.into(),
+            parts: Vec::new(),
+        }
+    }
+
+    /// Adds `request`.
+    ///
+    /// # Panics
+    ///
+    /// If `request` has a [`Body::Stream`], since the batch body is written all at once.
+    pub fn add_request(&mut self, request: Request) -> &mut Self {
+        assert_bytes_body(&request);
+        self.parts.push(Part::Request(request));
+        self
+    }
+
+    /// Adds `requests` as a changeset, which the service applies atomically.
+    ///
+    /// # Panics
+    ///
+    /// If any request has a [`Body::Stream`], since the batch body is written all at once.
+    pub fn add_changeset(&mut self, requests: impl IntoIterator<Item = Request>) -> &mut Self {
+        let requests: Vec<_> = requests.into_iter().collect();
+        requests.iter().for_each(assert_bytes_body);
+        self.parts
+            .push(Part::Changeset(boundary("changeset"), requests));
+        self
+    }
+
+    /// Gets the number of sub-requests, including those in changesets.
+    pub fn len(&self) -> usize {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Request(_) => 1,
+                Part::Changeset(_, requests) => requests.len(),
+            })
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the `Content-Type` of the batch, including its boundary.
+    pub fn content_type(&self) -> String {
+        format!("multipart/mixed; boundary={}", self.boundary)
+    }
+
+    pub fn to_bytes(&self) -> Bytes {
+        let mut body = Vec::new();
+        let mut content_id = 0;
+        for part in &self.parts {
+            body.extend_from_slice(format!("--{}\r\n", self.boundary).as_bytes());
+            match part {
+                Part::Request(request) => {
+                    write_request(&mut body, request, content_id);
+                    content_id += 1;
+                }
+                Part::Changeset(boundary, requests) => {
+                    body.extend_from_slice(
+                        format!("Content-Type: multipart/mixed; boundary={boundary}\r\n\r\n")
+                            .as_bytes(),
+                    );
+                    for request in requests {
+                        body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+                        write_request(&mut body, request, content_id);
+                        content_id += 1;
+                    }
+                    body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+                }
+            }
+        }
+        body.extend_from_slice(format!("--{}--\r\n", self.boundary).as_bytes());
+        body.into()
+    }
+
+    /// Sets the body and `Content-Type` of `request`, e.g., a `POST` to a `$batch` endpoint.
+    pub fn set_on(&self, request: &mut Request) {
+        request.insert_header(CONTENT_TYPE, self.content_type());
+        request.set_body(self.to_bytes());
+    }
+}
+
+impl Default for BatchRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn assert_bytes_body(request: &Request) {
+    assert!(
+        matches!(request.body(), Body::Bytes(_)),
+        "batch sub-requests must not have streamed bodies"
+    );
+}
+
+fn write_request(body: &mut Vec<u8>, request: &Request, content_id: usize) {
+    body.extend_from_slice(
+        format!(
+            "Content-Type: application/http\r\nContent-Transfer-Encoding: binary\r\nContent-ID: {content_id}\r\n\r\n"
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(
+        format!(
+            "{} {} HTTP/1.1\r\n",
+            request.method(),
+            request.path_and_query()
+        )
+        .as_bytes(),
+    );
+
+    // Sorted so batches are reproducible, e.g., for recorded tests.
+    let mut headers: Vec<_> = request.headers().iter().collect();
+    headers.sort_unstable_by_key(|(name, _)| *name);
+    for (name, value) in headers {
+        body.extend_from_slice(format!("{}: {}\r\n", name.as_str(), value.as_str()).as_bytes());
+    }
+
+    let Body::Bytes(bytes) = request.body() else {
+        unreachable!("streamed bodies are rejected when added");
+    };
+    if !bytes.is_empty() {
+        body.extend_from_slice(format!("Content-Length: {}\r\n", bytes.len()).as_bytes());
+    }
+    body.extend_from_slice(b"\r\n");
+    body.extend_from_slice(bytes);
+    body.extend_from_slice(b"\r\n");
+}
+
+/// Gets a boundary unlikely to occur in any part, without depending on a random number generator.
+fn boundary(prefix: &str) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("{prefix}_{nanos:x}{count:x}")
+}
+
+/// The responses to each sub-request of a [`BatchRequest`], in the order they were added with
+/// changesets flattened.
+#[derive(Clone, Debug)]
+pub struct BatchResponse {
+    responses: Vec<BatchPartResponse>,
+}
+
+impl BatchResponse {
+    /// Parses the `multipart/mixed` body of the response to a [`BatchRequest`].
+    pub async fn from_response(response: Response) -> crate::Result<Self> {
+        let content_type = response.headers().get_str(&CONTENT_TYPE)?.to_string();
+        let body = response.into_body().collect().await?;
+        Self::parse(&content_type, &body)
+    }
+
+    /// Parses a `multipart/mixed` body with the boundary in `content_type`.
+    pub fn parse(content_type: &str, body: &[u8]) -> crate::Result<Self> {
+        let mut responses = Vec::new();
+        parse_multipart(content_type, body, &mut responses)?;
+        Ok(Self { responses })
+    }
+
+    pub fn responses(&self) -> &[BatchPartResponse] {
+        &self.responses
+    }
+
+    pub fn into_responses(self) -> Vec<BatchPartResponse> {
+        self.responses
+    }
+}
+
+/// The response to a single sub-request of a [`BatchRequest`].
+#[derive(Clone, Debug)]
+pub struct BatchPartResponse {
+    status: u16,
+    headers: Headers,
+    body: Bytes,
+}
+
+impl BatchPartResponse {
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn headers(&self) -> &Headers {
+        &self.headers
+    }
+
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> crate::Result<T> {
+        crate::json::from_json(&self.body)
+    }
+}
+
+fn invalid(message: &'static str) -> Error {
+    Error::message(ErrorKind::DataConversion, message)
+}
+
+fn parse_multipart(
+    content_type: &str,
+    body: &[u8],
+    responses: &mut Vec<BatchPartResponse>,
+) -> crate::Result<()> {
+    let boundary = content_type
+        .split(';')
+        .find_map(|parameter| parameter.trim().strip_prefix("boundary="))
+        .map(|boundary| boundary.trim_matches('"'))
+        .ok_or_else(|| invalid("multipart content type has no boundary"))?;
+    let delimiter = format!("--{boundary}");
+
+    let mut parts = split(body, delimiter.as_bytes()).into_iter();
+    // Anything before the first delimiter is a preamble to ignore.
+    parts.next();
+    for part in parts {
+        if part.starts_with(b"--") {
+            return Ok(());
+        }
+        let (headers, content) = split_headers(trim_line_start(part))?;
+        let part_type = headers
+            .get_optional_str(&CONTENT_TYPE)
+            .unwrap_or_default()
+            .to_string();
+        if part_type.starts_with("multipart/mixed") {
+            parse_multipart(&part_type, content, responses)?;
+        } else {
+            responses.push(parse_response(content)?);
+        }
+    }
+    Err(invalid("multipart body has no closing delimiter"))
+}
+
+fn parse_response(part: &[u8]) -> crate::Result<BatchPartResponse> {
+    let (status_line, rest) = split_line(part);
+    let status = std::str::from_utf8(status_line)
+        .ok()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(|| invalid("batch part has no HTTP status line"))?;
+    let (headers, body) = split_headers(rest)?;
+    Ok(BatchPartResponse {
+        status,
+        headers,
+        body: Bytes::copy_from_slice(trim_line_end(body)),
+    })
+}
+
+/// Splits headers from the content following the first empty line.
+fn split_headers(mut part: &[u8]) -> crate::Result<(Headers, &[u8])> {
+    let mut headers = Headers::new();
+    loop {
+        if part.is_empty() {
+            return Ok((headers, part));
+        }
+        let (line, rest) = split_line(part);
+        part = rest;
+        if line.is_empty() {
+            return Ok((headers, part));
+        }
+        let line = std::str::from_utf8(line).map_err(|_| invalid("batch header is not UTF-8"))?;
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| invalid("batch header has no value"))?;
+        headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+    }
+}
+
+/// Splits the first line, without its `\r\n` or `\n`, from the rest.
+fn split_line(bytes: &[u8]) -> (&[u8], &[u8]) {
+    match bytes.iter().position(|&b| b == b'\n') {
+        Some(end) => (trim_line_end(&bytes[..end]), &bytes[end + 1..]),
+        None => (trim_line_end(bytes), &[]),
+    }
+}
+
+fn trim_line_start(bytes: &[u8]) -> &[u8] {
+    let bytes = bytes.strip_prefix(b"\r").unwrap_or(bytes);
+    bytes.strip_prefix(b"\n").unwrap_or(bytes)
+}
+
+fn trim_line_end(bytes: &[u8]) -> &[u8] {
+    let bytes = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+    bytes.strip_suffix(b"\r").unwrap_or(bytes)
+}
+
+fn split<'a>(bytes: &'a [u8], delimiter: &[u8]) -> Vec<&'a [u8]> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i + delimiter.len() <= bytes.len() {
+        if &bytes[i..i + delimiter.len()] == delimiter {
+            parts.push(&bytes[start..i]);
+            i += delimiter.len();
+            start = i;
+        } else {
+            i += 1;
+        }
+    }
+    parts.push(&bytes[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Url;
+
+    fn request(method: &'static str, path: &str, body: &'static str) -> Request {
+        let url = Url::parse("https://myaccount.table.core.windows.net")
+            .unwrap()
+            .join(path)
+            .unwrap();
+        let mut request = Request::new(url, method);
+        if !body.is_empty() {
+            request.insert_header(CONTENT_TYPE, "application/json");
+            request.set_body(body);
+        }
+        request
+    }
+
+    #[test]
+    fn to_bytes() {
+        let mut batch = BatchRequest::with_boundary("batch_1");
+        batch.add_request(request("GET", "/orders?$top=1", ""));
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch.content_type(), "multipart/mixed; boundary=batch_1");
+        assert_eq!(
+            batch.to_bytes(),
+            "--batch_1\r\n\
+            Content-Type: application/http\r\n\
+            Content-Transfer-Encoding: binary\r\n\
+            Content-ID: 0\r\n\
+            \r\n\
+            GET /orders?$top=1 HTTP/1.1\r\n\
+            \r\n\
+            \r\n\
+            --batch_1--\r\n"
+        );
+
+        batch.add_changeset([
+            request("POST", "/orders", "{}"),
+            request("DELETE", "/orders('1')", ""),
+        ]);
+        assert_eq!(batch.len(), 3);
+        let body = String::from_utf8(batch.to_bytes().to_vec()).unwrap();
+        let Part::Changeset(changeset, _) = &batch.parts[1] else {
+            panic!("expected changeset");
+        };
+        assert!(body.contains(&format!(
+            "--batch_1\r\nContent-Type: multipart/mixed; boundary={changeset}\r\n\r\n--{changeset}\r\n"
+        )));
+        assert!(body.contains(
+            "Content-ID: 1\r\n\r\nPOST /orders HTTP/1.1\r\ncontent-type: application/json\r\nContent-Length: 2\r\n\r\n{}\r\n"
+        ));
+        assert!(body.contains("Content-ID: 2\r\n\r\nDELETE /orders('1') HTTP/1.1\r\n"));
+        assert!(body.ends_with(&format!("--{changeset}--\r\n--batch_1--\r\n")));
+    }
+
+    #[test]
+    fn parse() {
+        let body = "--batchresponse_1\r\n\
+            Content-Type: application/http\r\n\
+            \r\n\
+            HTTP/1.1 200 OK\r\n\
+            Content-Type: application/json\r\n\
+            \r\n\
+            {\"value\":1}\r\n\
+            --batchresponse_1\r\n\
+            Content-Type: multipart/mixed; boundary=changesetresponse_2\r\n\
+            \r\n\
+            --changesetresponse_2\r\n\
+            Content-Type: application/http\r\n\
+            \r\n\
+            HTTP/1.1 204 No Content\r\n\
+            \r\n\
+            \r\n\
+            --changesetresponse_2\r\n\
+            Content-Type: application/http\r\n\
+            \r\n\
+            HTTP/1.1 409 Conflict\r\n\
+            x-ms-error-code: EntityAlreadyExists\r\n\
+            \r\n\
+            --changesetresponse_2--\r\n\
+            --batchresponse_1--\r\n";
+        let response =
+            BatchResponse::parse("multipart/mixed; boundary=batchresponse_1", body.as_bytes())
+                .unwrap();
+
+        let responses = response.responses();
+        assert_eq!(
+            responses.iter().map(|r| r.status()).collect::<Vec<_>>(),
+            [200, 204, 409]
+        );
+        assert_eq!(
+            responses[0].json::<serde_json::Value>().unwrap()["value"],
+            1
+        );
+        assert!(responses[1].is_success() && responses[1].body().is_empty());
+        assert!(!responses[2].is_success());
+        assert_eq!(
+            responses[2]
+                .headers()
+                .get_optional_str(&crate::headers::ERROR_CODE),
+            Some("EntityAlreadyExists")
+        );
+
+        let error = BatchResponse::parse("multipart/mixed", body.as_bytes()).unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::DataConversion);
+        assert!(BatchResponse::parse(
+            "multipart/mixed; boundary=batchresponse_1",
+            b"--batchresponse_1\r\n"
+        )
+        .is_err());
+    }
+}
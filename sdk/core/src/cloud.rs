@@ -0,0 +1,123 @@
+use crate::ClientOptions;
+use url::Url;
+
+/// An Azure cloud, which determines the audience of tokens for services whose endpoints do not
+/// identify it, e.g., `localhost`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Cloud {
+    #[default]
+    AzurePublic,
+    AzureChina,
+    AzureUsGovernment,
+}
+
+/// The audience of a service's tokens in each [`Cloud`], e.g., `https://vault.azure.net` for Key
+/// Vault in the public cloud.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ServiceAudiences {
+    pub azure_public: &'static str,
+    pub azure_china: &'static str,
+    pub azure_us_government: &'static str,
+}
+
+impl ServiceAudiences {
+    pub fn audience(&self, cloud: Cloud) -> &'static str {
+        match cloud {
+            Cloud::AzurePublic => self.azure_public,
+            Cloud::AzureChina => self.azure_china,
+            Cloud::AzureUsGovernment => self.azure_us_government,
+        }
+    }
+
+    /// Gets the scope of tokens for `endpoint`, e.g., `https://vault.usgovcloudapi.net/.default`
+    /// for `https://my-vault.vault.usgovcloudapi.net`.
+    ///
+    /// Uses [`ClientOptions::audience`] if set, then the audience whose host `endpoint` is in,
+    /// then the audience of [`ClientOptions::cloud`].
+    pub fn scope(&self, endpoint: &Url, options: &ClientOptions) -> String {
+        let audience = options.audience.as_deref().unwrap_or_else(|| {
+            let host = endpoint.host_str().unwrap_or_default();
+            [
+                Cloud::AzurePublic,
+                Cloud::AzureChina,
+                Cloud::AzureUsGovernment,
+            ]
+            .into_iter()
+            .map(|cloud| self.audience(cloud))
+            .find(|audience| {
+                let suffix = Url::parse(audience)
+                    .ok()
+                    .and_then(|url| url.host_str().map(str::to_string))
+                    .unwrap_or_default();
+                host.strip_suffix(&suffix)
+                    .is_some_and(|name| name.is_empty() || name.ends_with('.'))
+            })
+            .unwrap_or_else(|| self.audience(options.cloud))
+        });
+        format!("{}/.default", audience.trim_end_matches('/'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_VAULT: ServiceAudiences = ServiceAudiences {
+        azure_public: "https://vault.azure.net",
+        azure_china: "https://vault.azure.cn",
+        azure_us_government: "https://vault.usgovcloudapi.net",
+    };
+
+    #[test]
+    fn scope() {
+        let options = ClientOptions::default();
+        for (endpoint, scope) in [
+            (
+                "https://my-vault.vault.azure.net",
+                "https://vault.azure.net/.default",
+            ),
+            (
+                "https://my-vault.vault.azure.cn/",
+                "https://vault.azure.cn/.default",
+            ),
+            (
+                "https://my-vault.vault.usgovcloudapi.net",
+                "https://vault.usgovcloudapi.net/.default",
+            ),
+            (
+                "https://myvault.azure.cn",
+                "https://vault.azure.net/.default",
+            ),
+            ("http://localhost:8080", "https://vault.azure.net/.default"),
+        ] {
+            let endpoint = Url::parse(endpoint).unwrap();
+            assert_eq!(KEY_VAULT.scope(&endpoint, &options), scope, "{endpoint}");
+        }
+
+        let localhost = Url::parse("http://localhost:8080").unwrap();
+        let options = ClientOptions {
+            cloud: Cloud::AzureUsGovernment,
+            ..Default::default()
+        };
+        assert_eq!(
+            KEY_VAULT.scope(&localhost, &options),
+            "https://vault.usgovcloudapi.net/.default"
+        );
+        // The endpoint takes precedence over the cloud.
+        let public = Url::parse("https://my-vault.vault.azure.net").unwrap();
+        assert_eq!(
+            KEY_VAULT.scope(&public, &options),
+            "https://vault.azure.net/.default"
+        );
+
+        let options = ClientOptions {
+            audience: Some("https://custom.example.com/".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            KEY_VAULT.scope(&public, &options),
+            "https://custom.example.com/.default"
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use crate::error::{Error, ErrorKind};
+use std::fmt;
+
+/// The `name=value` settings of a connection string separated by `;`, e.g.,
+/// `Endpoint=sb://my-namespace.servicebus.windows.net/;SharedAccessKeyName=send;SharedAccessKey=...`.
+///
+/// Names are case-insensitive. Values may be quoted with `"` or `'` to include `;`, and are
+/// otherwise trimmed. Values are never included in errors or `Debug` output since they may be
+/// secrets.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct ConnectionString {
+    settings: Vec<(String, String)>,
+}
+
+impl ConnectionString {
+    /// Parses `connection_string`, returning an [`ErrorKind::InvalidInput`] error wrapping a
+    /// [`ConnectionStringError`] if a setting is malformed or set more than once.
+    pub fn parse(connection_string: &str) -> crate::Result<Self> {
+        let mut settings: Vec<(String, String)> = Vec::new();
+        let mut rest = connection_string;
+        let mut position = 0;
+        loop {
+            rest = rest.trim_start_matches(|c: char| c == ';' || c.is_whitespace());
+            if rest.is_empty() {
+                break;
+            }
+            position += 1;
+            let malformed = || ConnectionStringError::Malformed { position }.into_error();
+
+            let Some(separator) = rest
+                .find(['=', ';'])
+                .filter(|&i| rest[i..].starts_with('='))
+            else {
+                return Err(malformed());
+            };
+            let name = rest[..separator].trim();
+            if name.is_empty() {
+                return Err(malformed());
+            }
+            rest = rest[separator + 1..].trim_start();
+
+            let value = match rest.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let Some(end) = rest[1..].find(quote) else {
+                        return Err(malformed());
+                    };
+                    let value = &rest[1..=end];
+                    rest = rest[end + 2..].trim_start();
+                    if !rest.is_empty() && !rest.starts_with(';') {
+                        return Err(malformed());
+                    }
+                    value
+                }
+                _ => {
+                    let end = rest.find(';').unwrap_or(rest.len());
+                    let value = rest[..end].trim();
+                    rest = &rest[end..];
+                    value
+                }
+            };
+
+            if settings
+                .iter()
+                .any(|(existing, _)| existing.eq_ignore_ascii_case(name))
+            {
+                return Err(ConnectionStringError::Duplicate {
+                    name: name.to_string(),
+                }
+                .into_error());
+            }
+            settings.push((name.to_string(), value.to_string()));
+        }
+        Ok(Self { settings })
+    }
+
+    /// Gets the value of the setting `name`, ignoring case.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.settings
+            .iter()
+            .find(|(setting, _)| setting.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Gets the value of the setting `name`, returning an [`ErrorKind::InvalidInput`] error
+    /// wrapping [`ConnectionStringError::Missing`] if it is not set.
+    pub fn require(&self, name: &str) -> crate::Result<&str> {
+        self.get(name).ok_or_else(|| {
+            ConnectionStringError::Missing {
+                name: name.to_string(),
+            }
+            .into_error()
+        })
+    }
+
+    /// Gets the names of the settings in the order they were parsed.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.settings.iter().map(|(name, _)| name.as_str())
+    }
+}
+
+impl fmt::Debug for ConnectionString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConnectionString")
+            .field("settings", &self.names().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Why a [`ConnectionString`] could not be parsed or is missing a setting.
+///
+/// Returned as the source of an [`ErrorKind::InvalidInput`] error, so get it using
+/// [`Error::downcast_ref`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConnectionStringError {
+    /// The setting at the 1-based `position` is not of the form `name=value` or has an
+    /// unterminated quote.
+    Malformed { position: usize },
+    /// The setting `name` is set more than once.
+    Duplicate { name: String },
+    /// The required setting `name` is not set.
+    Missing { name: String },
+}
+
+impl ConnectionStringError {
+    /// Returns an [`ErrorKind::InvalidInput`] error wrapping this.
+    pub fn into_error(self) -> Error {
+        Error::new(ErrorKind::InvalidInput, self)
+    }
+}
+
+impl fmt::Display for ConnectionStringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionStringError::Malformed { position } => write!(
+                f,
+                "connection string setting {position} must be of the form name=value"
+            ),
+            ConnectionStringError::Duplicate { name } => {
+                write!(f, "connection string has more than one {name} setting")
+            }
+            ConnectionStringError::Missing { name } => {
+                write!(f, "connection string is missing the {name} setting")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConnectionStringError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let connection_string = ConnectionString::parse(
+            " AccountName = myaccount ;AccountKey=a2V5==;; SharedAccessSignature=\"sv=1;sig=a\" ;Empty=;",
+        )
+        .unwrap();
+        assert_eq!(
+            connection_string.names().collect::<Vec<_>>(),
+            [
+                "AccountName",
+                "AccountKey",
+                "SharedAccessSignature",
+                "Empty"
+            ]
+        );
+        assert_eq!(connection_string.get("accountname"), Some("myaccount"));
+        assert_eq!(connection_string.get("AccountKey"), Some("a2V5=="));
+        assert_eq!(
+            connection_string.get("SharedAccessSignature"),
+            Some("sv=1;sig=a")
+        );
+        assert_eq!(connection_string.get("Empty"), Some(""));
+        assert_eq!(connection_string.get("EndpointSuffix"), None);
+        assert_eq!(
+            format!("{connection_string:?}"),
+            r#"ConnectionString { settings: ["AccountName", "AccountKey", "SharedAccessSignature", "Empty"] }"#
+        );
+
+        let error = connection_string.require("EndpointSuffix").unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::InvalidInput);
+        assert_eq!(
+            error.downcast_ref::<ConnectionStringError>(),
+            Some(&ConnectionStringError::Missing {
+                name: "EndpointSuffix".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        for (connection_string, expected) in [
+            (
+                "AccountName",
+                ConnectionStringError::Malformed { position: 1 },
+            ),
+            (
+                "A=1;secret;B=2",
+                ConnectionStringError::Malformed { position: 2 },
+            ),
+            ("=secret", ConnectionStringError::Malformed { position: 1 }),
+            (
+                "A='secret",
+                ConnectionStringError::Malformed { position: 1 },
+            ),
+            (
+                "A='secret' x;B=2",
+                ConnectionStringError::Malformed { position: 1 },
+            ),
+            (
+                "A=1;a=2",
+                ConnectionStringError::Duplicate {
+                    name: "a".to_string(),
+                },
+            ),
+        ] {
+            let error = ConnectionString::parse(connection_string).unwrap_err();
+            assert_eq!(error.kind(), &ErrorKind::InvalidInput);
+            assert!(!error.to_string().contains("secret"), "{error}");
+            assert_eq!(
+                error.downcast_ref::<ConnectionStringError>(),
+                Some(&expected),
+                "{connection_string}"
+            );
+        }
+    }
+}
@@ -80,7 +80,88 @@ impl Default for Context {
     }
 }
 
-#[cfg(test)]
+/// Whether sending the request of an operation more than once has the same effect as sending it once.
+///
+/// Clients insert this into the [`Context`] for each operation so that policies can decide, e.g.,
+/// whether to resend a request which may have been received.
+#[derive(Clone, Copy, Debug)]
+pub struct Idempotent(bool);
+
+impl From<bool> for Idempotent {
+    fn from(idempotent: bool) -> Self {
+        Self(idempotent)
+    }
+}
+
+impl Idempotent {
+    pub fn is_idempotent(&self) -> bool {
+        self.0
+    }
+}
+
+/// The 1-based number of the current attempt to send a request, which the retry policy inserts
+/// into the [`Context`] before each attempt so later policies can report it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Attempt(u32);
+
+impl Attempt {
+    pub(crate) fn new(number: u32) -> Self {
+        Self(number)
+    }
+
+    pub fn number(&self) -> u32 {
+        self.0
+    }
+
+    /// Whether the request is being resent after an earlier attempt failed.
+    pub fn is_retry(&self) -> bool {
+        self.0 > 1
+    }
+}
+
+/// The key sent in the `idempotency-key` header of every request of an operation, including
+/// retries, so services and gateways which deduplicate on it apply the operation only once.
+///
+/// Insert one into the [`Context`] to resend an operation with the key of an earlier attempt,
+/// e.g., from [`Error::idempotency_key`](crate::Error::idempotency_key). Otherwise, unless the
+/// operation is [`Idempotent`], one is generated if
+/// [`ClientOptions::idempotency_keys`](crate::ClientOptions::idempotency_keys) is set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdempotencyKey(String);
+
+impl IdempotencyKey {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self(key.into())
+    }
+
+    /// Generates a random key formatted as a version 4 UUID.
+    pub fn generate() -> Self {
+        use std::hash::{BuildHasher, Hasher};
+
+        // Each `RandomState` is randomly seeded, which is random enough to avoid collisions.
+        let random = || {
+            std::collections::hash_map::RandomState::new()
+                .build_hasher()
+                .finish()
+        };
+        let high = (random() & !0xf000) | 0x4000;
+        let low = (random() & !(0xc << 60)) | (0x8 << 60);
+        Self(format!(
+            "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+            high >> 32,
+            (high >> 16) & 0xffff,
+            high & 0xffff,
+            low >> 48,
+            low & 0xffff_ffff_ffff
+        ))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(all(test, feature = "context"))]
 mod tests {
     use super::*;
 
@@ -0,0 +1,81 @@
+use crate::error::{Error, ErrorKind, ResultExt};
+use url::Url;
+
+/// Parses the endpoint of a service, e.g., `https://my-vault.vault.azure.net`.
+///
+/// Returns an [`ErrorKind::DataConversion`] error describing the problem if the endpoint
+/// is not an absolute `http` or `https` URL with a host.
+pub fn parse_endpoint(endpoint: impl AsRef<str>) -> crate::Result<Url> {
+    let endpoint = endpoint.as_ref().trim();
+    let url = Url::parse(endpoint).with_context(ErrorKind::DataConversion, || {
+        format!("invalid endpoint '{endpoint}': expected a URL like https://example.com")
+    })?;
+
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(Error::with_message(ErrorKind::DataConversion, || {
+            format!(
+                "invalid endpoint '{endpoint}': scheme must be http or https but was {}",
+                url.scheme()
+            )
+        }));
+    }
+    if url.host().is_none() {
+        return Err(Error::with_message(ErrorKind::DataConversion, || {
+            format!("invalid endpoint '{endpoint}': missing host")
+        }));
+    }
+
+    Ok(url)
+}
+
+/// Replaces the scheme, host, and port of `url` with those of `endpoint`, keeping its path and
+/// query.
+pub(crate) fn replace_origin(url: &mut Url, endpoint: &Url) {
+    // Both are http or https URLs with hosts, for which these cannot fail.
+    let _ = url.set_scheme(endpoint.scheme());
+    let _ = url.set_host(endpoint.host_str());
+    let _ = url.set_port(endpoint.port());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoint_shapes() {
+        for endpoint in [
+            "https://vault.azure.net",
+            "https://vault.azure.net/",
+            "https://vault.azure.net//",
+            "https://vault.azure.net:8443",
+            "https://vault.azure.net/path/",
+            "https://vault.azure.net?api-version=1",
+            "https://vault.azure.net#fragment",
+            " https://vault.azure.net ",
+            "http://localhost:8080",
+            "https://[::1]:8443",
+            "https://127.0.0.1",
+        ] {
+            assert!(parse_endpoint(endpoint).is_ok(), "{endpoint}");
+        }
+
+        for endpoint in [
+            "",
+            " ",
+            "vault.azure.net",
+            "vault.azure.net:443",
+            "//vault.azure.net",
+            "https://",
+            "https://vault.azure.net:99999",
+            "https://[::1",
+            "ftp://vault.azure.net",
+            "mailto:user@vault.azure.net",
+            "file:///etc/passwd",
+            "data:text/plain,vault",
+            "https://vault azure.net",
+        ] {
+            let error = parse_endpoint(endpoint).expect_err(endpoint);
+            assert_eq!(error.kind(), &ErrorKind::DataConversion, "{endpoint}");
+        }
+    }
+}
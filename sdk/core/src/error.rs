@@ -1,25 +1,36 @@
+use crate::{headers, http_error::HttpError};
 use std::{
     borrow::Cow,
     fmt::{Debug, Display},
+    time::Duration,
 };
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The class of an [`Error`], for callers to branch on.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum ErrorKind {
+    /// The service responded with a non-success status code and optional Azure error code.
     HttpResponse {
         status: u16,
         error_code: Option<String>,
     },
+    /// Sending the request or receiving the response failed.
     Io,
+    /// Data could not be serialized or deserialized.
     DataConversion,
+    /// A credential could not authenticate.
     Credential,
+    /// A parameter was invalid, so the request was not sent.
+    InvalidInput,
     Other,
 }
 
 impl ErrorKind {
     pub fn into_error(self) -> Error {
         Error {
+            request: None,
             context: Context::Simple(self),
         }
     }
@@ -38,6 +49,7 @@ impl Display for ErrorKind {
             }
             ErrorKind::Credential => write!(f, "Credential"),
             ErrorKind::DataConversion => write!(f, "DataConversion"),
+            ErrorKind::InvalidInput => write!(f, "InvalidInput"),
             ErrorKind::Io => write!(f, "Io"),
             ErrorKind::Other => write!(f, "Other"),
         }
@@ -46,15 +58,25 @@ impl Display for ErrorKind {
 
 #[derive(Debug)]
 pub struct Error {
+    request: Option<Box<RequestDetails>>,
     context: Context,
 }
 
+/// Identifies the operation and request which failed, for support.
+#[derive(Debug, Default)]
+struct RequestDetails {
+    operation: Option<&'static str>,
+    client_request_id: Option<String>,
+    idempotency_key: Option<String>,
+}
+
 impl Error {
     pub fn new<E>(kind: ErrorKind, error: E) -> Self
     where
         E: Into<Box<dyn std::error::Error + Send + Sync>>,
     {
         Self {
+            request: None,
             context: Context::Custom(Custom {
                 kind,
                 error: error.into(),
@@ -62,6 +84,25 @@ impl Error {
         }
     }
 
+    /// Creates an error with a message describing the error it wraps as its source.
+    #[must_use]
+    pub fn full<E, C>(kind: ErrorKind, error: E, message: C) -> Self
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+        C: Into<Cow<'static, str>>,
+    {
+        Self {
+            request: None,
+            context: Context::Full(
+                Custom {
+                    kind,
+                    error: error.into(),
+                },
+                message.into(),
+            ),
+        }
+    }
+
     pub fn kind(&self) -> &ErrorKind {
         match &self.context {
             Context::Simple(kind)
@@ -77,6 +118,7 @@ impl Error {
         C: Into<Cow<'static, str>>,
     {
         Self {
+            request: None,
             context: Context::Message {
                 kind,
                 message: message.into(),
@@ -92,6 +134,7 @@ impl Error {
         C: Into<Cow<'static, str>>,
     {
         Self {
+            request: None,
             context: Context::Message {
                 kind,
                 message: message().into(),
@@ -100,6 +143,133 @@ impl Error {
     }
 }
 
+impl Error {
+    /// Gets the HTTP status code if the service responded with an error.
+    pub fn status(&self) -> Option<u16> {
+        match self.kind() {
+            ErrorKind::HttpResponse { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// Gets the Azure error code, e.g., `SecretNotFound`, if the service responded with one.
+    pub fn error_code(&self) -> Option<&str> {
+        match self.kind() {
+            ErrorKind::HttpResponse { error_code, .. } => error_code.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Whether the service responded with 404 Not Found.
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(404)
+    }
+
+    /// Whether the service responded with 429 Too Many Requests.
+    pub fn is_throttled(&self) -> bool {
+        self.status() == Some(429)
+    }
+
+    /// Gets how long the service asked to wait before sending another request, e.g., when
+    /// [throttled](Error::is_throttled) after exhausting retries.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.downcast_ref::<HttpError>()?.retry_after()
+    }
+
+    /// Gets the name of the client operation which failed, e.g., `SecretClient::get_secret`.
+    pub fn operation(&self) -> Option<&'static str> {
+        self.request.as_ref()?.operation
+    }
+
+    /// Gets the `x-ms-client-request-id` sent with the failed request.
+    pub fn client_request_id(&self) -> Option<&str> {
+        self.request.as_ref()?.client_request_id.as_deref()
+    }
+
+    /// Gets the `idempotency-key` sent with the failed request, e.g., to resend the operation
+    /// with the same key so the service does not apply it twice.
+    pub fn idempotency_key(&self) -> Option<&str> {
+        self.request.as_ref()?.idempotency_key.as_deref()
+    }
+
+    /// Gets the `x-ms-request-id` the service responded with.
+    pub fn request_id(&self) -> Option<&str> {
+        self.downcast_ref::<HttpError>()?
+            .headers()
+            .get_optional_str(&headers::REQUEST_ID)
+    }
+
+    /// Records the operation and request which failed so they are displayed with the error.
+    pub(crate) fn set_request(
+        &mut self,
+        operation: Option<&'static str>,
+        client_request_id: Option<String>,
+        idempotency_key: Option<String>,
+    ) {
+        // Keep the details of the innermost operation, e.g., when getting a token fails.
+        if self.request.is_none()
+            && (operation.is_some() || client_request_id.is_some() || idempotency_key.is_some())
+        {
+            self.request = Some(Box::new(RequestDetails {
+                operation,
+                client_request_id,
+                idempotency_key,
+            }));
+        }
+    }
+
+    /// Appends `note` to the message displayed for the error, keeping its kind, request details,
+    /// and the error it wraps, e.g., so [`Error::downcast_ref`] still gets an [`HttpError`].
+    pub(crate) fn add_note(&mut self, note: impl Display) {
+        let context = std::mem::replace(&mut self.context, Context::Simple(ErrorKind::Other));
+        self.context = match context {
+            Context::Simple(kind) => Context::Message {
+                message: format!("{kind}: {note}").into(),
+                kind,
+            },
+            Context::Message { kind, message } => Context::Message {
+                kind,
+                message: format!("{message}: {note}").into(),
+            },
+            Context::Custom(custom) => {
+                let message = format!("{}: {note}", custom.error);
+                Context::Full(custom, message.into())
+            }
+            Context::Full(custom, message) => {
+                Context::Full(custom, format!("{message}: {note}").into())
+            }
+        };
+    }
+
+    /// Gets the wrapped error, if any.
+    pub fn get_ref(&self) -> Option<&(dyn std::error::Error + Send + Sync + 'static)> {
+        match &self.context {
+            Context::Custom(Custom { error, .. }) | Context::Full(Custom { error, .. }, _) => {
+                Some(&**error)
+            }
+            _ => None,
+        }
+    }
+
+    /// Gets the wrapped error if it is of type `T`.
+    pub fn downcast_ref<T>(&self) -> Option<&T>
+    where
+        T: std::error::Error + 'static,
+    {
+        self.get_ref()?.downcast_ref()
+    }
+
+    /// Consumes the error, returning the wrapped error or the original error if nothing is wrapped.
+    pub fn into_inner(self) -> std::result::Result<Box<dyn std::error::Error + Send + Sync>, Self> {
+        match self.context {
+            Context::Custom(Custom { error, .. }) | Context::Full(Custom { error, .. }, _) => {
+                Ok(error)
+            }
+            _ => Err(self),
+        }
+    }
+}
+
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self.context {
@@ -107,16 +277,29 @@ impl Display for Error {
             Context::Message { message, .. } => write!(f, "{message}"),
             Context::Custom(Custom { error, .. }) => write!(f, "{error}"),
             Context::Full(_, message) => write!(f, "{message}"),
+        }?;
+
+        let details: Vec<_> = [
+            ("operation", self.operation()),
+            ("client request ID", self.client_request_id()),
+            ("request ID", self.request_id()),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some(format!("{name}: {}", value?)))
+        .collect();
+        if !details.is_empty() {
+            write!(f, " ({})", details.join(", "))?;
         }
+        Ok(())
     }
 }
 
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match &self.context {
-            Context::Custom(Custom { error, .. }) | Context::Full(Custom { error, .. }, _) => {
-                Some(&**error)
-            }
+            // The wrapped error is only displayed as the source when a message replaces it.
+            Context::Custom(Custom { error, .. }) => error.source(),
+            Context::Full(Custom { error, .. }, _) => Some(&**error),
             _ => None,
         }
     }
@@ -125,11 +308,22 @@ impl std::error::Error for Error {
 impl From<ErrorKind> for Error {
     fn from(kind: ErrorKind) -> Self {
         Self {
+            request: None,
             context: Context::Simple(kind),
         }
     }
 }
 
+impl From<HttpError> for Error {
+    fn from(error: HttpError) -> Self {
+        let kind = ErrorKind::HttpResponse {
+            status: error.status(),
+            error_code: error.error_code().map(ToOwned::to_owned),
+        };
+        Self::new(kind, error)
+    }
+}
+
 impl From<std::io::Error> for Error {
     fn from(error: std::io::Error) -> Self {
         Self::new(ErrorKind::Io, error)
@@ -201,15 +395,7 @@ where
         Self: Sized,
         C: Into<Cow<'static, str>>,
     {
-        self.map_err(|e| Error {
-            context: Context::Full(
-                Custom {
-                    error: Box::new(e),
-                    kind,
-                },
-                message.into(),
-            ),
-        })
+        self.map_err(|e| Error::full(kind, e, message))
     }
 
     fn with_context<F, C>(self, kind: ErrorKind, f: F) -> Result<T>
@@ -221,3 +407,70 @@ where
         self.context(kind, f())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn source_chain() {
+        let io = std::io::Error::other("connection reset");
+        let error: Result<()> = Err(io).context(ErrorKind::Io, "failed to send request");
+        let error = error.unwrap_err();
+
+        assert_eq!(error.kind(), &ErrorKind::Io);
+        assert_eq!(error.to_string(), "failed to send request");
+        assert_eq!(error.source().unwrap().to_string(), "connection reset");
+        assert!(error.downcast_ref::<std::io::Error>().is_some());
+
+        let error = Error::new(ErrorKind::Other, error);
+        assert_eq!(error.source().unwrap().to_string(), "connection reset");
+        assert_eq!(error.status(), None);
+    }
+
+    #[test]
+    fn http_accessors() {
+        let error = Error::from(ErrorKind::HttpResponse {
+            status: 404,
+            error_code: Some("SecretNotFound".to_string()),
+        });
+        assert_eq!(error.status(), Some(404));
+        assert_eq!(error.error_code(), Some("SecretNotFound"));
+        assert!(error.is_not_found());
+        assert!(!error.is_throttled());
+    }
+
+    #[tokio::test]
+    async fn retry_after() {
+        let mut headers = crate::headers::Headers::new();
+        headers.insert(crate::headers::RETRY_AFTER, "5");
+        let response =
+            crate::Response::new(429, headers, Box::pin(crate::stream::BytesStream::new("")));
+        let error = Error::from(HttpError::new(response).await);
+        assert!(error.is_throttled());
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(5)));
+    }
+
+    #[tokio::test]
+    async fn request_details() {
+        let mut headers = crate::headers::Headers::new();
+        headers.insert(crate::headers::REQUEST_ID, "5678");
+        let response =
+            crate::Response::new(404, headers, Box::pin(crate::stream::BytesStream::new("")));
+        let mut error = Error::from(HttpError::new(response).await);
+        error.set_request(
+            Some("SecretClient::get_secret"),
+            Some("1234".to_string()),
+            None,
+        );
+
+        assert_eq!(error.operation(), Some("SecretClient::get_secret"));
+        assert_eq!(error.client_request_id(), Some("1234"));
+        assert_eq!(error.request_id(), Some("5678"));
+        assert_eq!(
+            error.to_string(),
+            "HTTP 404 (operation: SecretClient::get_secret, client request ID: 1234, request ID: 5678)"
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use crate::headers::{self, Header, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// An entity tag identifying a version of a resource, used for conditional requests.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct ETag(String);
+
+impl ETag {
+    /// Matches any version of a resource, e.g., to only create a resource if it does not exist
+    /// using [`MatchCondition::IfNoneMatch`].
+    pub fn any() -> Self {
+        Self("*".to_string())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for ETag {
+    fn from(etag: String) -> Self {
+        Self(etag)
+    }
+}
+
+impl From<&str> for ETag {
+    fn from(etag: &str) -> Self {
+        Self(etag.to_string())
+    }
+}
+
+impl fmt::Display for ETag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Sends a request only if the resource does or does not match an [`ETag`].
+///
+/// The service responds with 412 Precondition Failed for writes, or 304 Not Modified for reads
+/// using [`MatchCondition::IfNoneMatch`], when the condition is not met.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MatchCondition {
+    IfMatch(ETag),
+    IfNoneMatch(ETag),
+}
+
+impl Header for MatchCondition {
+    fn name(&self) -> HeaderName {
+        match self {
+            MatchCondition::IfMatch(_) => headers::IF_MATCH,
+            MatchCondition::IfNoneMatch(_) => headers::IF_NONE_MATCH,
+        }
+    }
+
+    fn value(&self) -> HeaderValue {
+        match self {
+            MatchCondition::IfMatch(etag) | MatchCondition::IfNoneMatch(etag) => {
+                etag.to_string().into()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headers::Headers;
+
+    #[test]
+    fn conditions() {
+        let mut headers = Headers::new();
+        headers.add(MatchCondition::IfMatch("\"1\"".into()));
+        headers.add(MatchCondition::IfNoneMatch(ETag::any()));
+        assert_eq!(headers.get_optional_str(&headers::IF_MATCH), Some("\"1\""));
+        assert_eq!(headers.get_optional_str(&headers::IF_NONE_MATCH), Some("*"));
+    }
+}
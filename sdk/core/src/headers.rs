@@ -1,5 +1,8 @@
 use crate::error::{Error, ErrorKind, ResultExt};
-use std::str::FromStr;
+use std::{
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 pub trait AsHeaders {
     type Iter: Iterator<Item = (HeaderName, HeaderValue)>;
@@ -236,16 +239,161 @@ impl From<&String> for HeaderValue {
     }
 }
 
+/// Gets how long to wait before sending another request from the `retry-after-ms`,
+/// `x-ms-retry-after-ms`, or `retry-after` headers.
+///
+/// A `retry-after` date is compared to the `date` of the response, if any, rather than the local
+/// clock, so the delay is correct even if the clocks of the client and service differ.
+pub(crate) fn retry_after(headers: &Headers) -> Option<Duration> {
+    [RETRY_AFTER_MS, X_MS_RETRY_AFTER_MS]
+        .iter()
+        .find_map(|name| headers.get_optional_str(name)?.parse().ok())
+        .map(Duration::from_millis)
+        .or_else(|| {
+            let retry_after = headers.get_optional_str(&RETRY_AFTER)?.trim();
+            if let Ok(seconds) = retry_after.parse() {
+                return Some(Duration::from_secs(seconds));
+            }
+            let retry_at = parse_http_date(retry_after)?;
+            let now = headers
+                .get_optional_str(&DATE)
+                .and_then(parse_http_date)
+                .unwrap_or_else(SystemTime::now);
+            Some(retry_at.duration_since(now).unwrap_or_default())
+        })
+}
+
+const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Formats `time` like `Sun, 06 Nov 1994 08:49:37 GMT` as HTTP headers require, e.g., for `x-ms-date`.
+pub fn format_http_date(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    // Days since 1970-01-01, which was a Thursday.
+    let days = seconds / 86_400;
+    let seconds = seconds % 86_400;
+
+    // Converts days since 1970-01-01 to a civil date, from Howard Hinnant's `civil_from_days`.
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+        DAYS[(days % 7) as usize],
+        day,
+        MONTHS[(month - 1) as usize],
+        year,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+    )
+}
+
+/// Parses a date like `Sun, 06 Nov 1994 08:49:37 GMT` as HTTP headers require.
+pub fn parse_http_date(date: &str) -> Option<SystemTime> {
+    let (_, date) = date.split_once(", ")?;
+    let [day, month, year, time, "GMT"] = date.split(' ').collect::<Vec<_>>()[..] else {
+        return None;
+    };
+    let [hour, minute, second] = time.split(':').collect::<Vec<_>>()[..] else {
+        return None;
+    };
+    let number = |value: &str, max: u64| value.parse::<u64>().ok().filter(|value| *value <= max);
+    let day = number(day, 31).filter(|day| *day > 0)?;
+    let month = MONTHS.iter().position(|name| *name == month)? as u64 + 1;
+    let year = number(year, 9999).filter(|year| *year >= 1970)?;
+    let seconds = number(hour, 23)? * 3600 + number(minute, 59)? * 60 + number(second, 60)?;
+
+    // Converts a civil date to days since 1970-01-01, from Howard Hinnant's `days_from_civil`.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = (era * 146_097 + day_of_era).checked_sub(719_468)?;
+    Some(UNIX_EPOCH + Duration::from_secs(days * 86_400 + seconds))
+}
+
 pub const ACCEPT: HeaderName = HeaderName::from_static("accept");
 pub const AUTHORIZATION: HeaderName = HeaderName::from_static("authorization");
+pub const AZURE_ASYNCOPERATION: HeaderName = HeaderName::from_static("azure-asyncoperation");
 pub const CLIENT_REQUEST_ID: HeaderName = HeaderName::from_static("x-ms-client-request-id");
 pub const CONTENT_ENCODING: HeaderName = HeaderName::from_static("content-encoding");
 pub const CONTENT_LENGTH: HeaderName = HeaderName::from_static("content-length");
+pub const CONTENT_TYPE: HeaderName = HeaderName::from_static("content-type");
+pub const DATE: HeaderName = HeaderName::from_static("date");
+pub const ERROR_CODE: HeaderName = HeaderName::from_static("x-ms-error-code");
 pub const ETAG: HeaderName = HeaderName::from_static("etag");
+pub const HOST: HeaderName = HeaderName::from_static("host");
+pub const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
 pub const IF_MATCH: HeaderName = HeaderName::from_static("if-match");
 pub const IF_MODIFIED_SINCE: HeaderName = HeaderName::from_static("if-modified-since");
 pub const IF_NONE_MATCH: HeaderName = HeaderName::from_static("if-none-match");
 pub const IF_UNMODIFIED_SINCE: HeaderName = HeaderName::from_static("if-unmodified-since");
+pub const LOCATION: HeaderName = HeaderName::from_static("location");
+pub const OPERATION_LOCATION: HeaderName = HeaderName::from_static("operation-location");
+pub const REQUEST_ID: HeaderName = HeaderName::from_static("x-ms-request-id");
+pub const RETRY_AFTER: HeaderName = HeaderName::from_static("retry-after");
+pub const RETRY_AFTER_MS: HeaderName = HeaderName::from_static("retry-after-ms");
 pub const TAGS: HeaderName = HeaderName::from_static("x-ms-tags");
 pub const USER_AGENT: HeaderName = HeaderName::from_static("user-agent");
 pub const WWW_AUTHENTICATE: HeaderName = HeaderName::from_static("www-authenticate");
+pub const X_MS_RETRY_AFTER_MS: HeaderName = HeaderName::from_static("x-ms-retry-after-ms");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn http_dates() {
+        let time = UNIX_EPOCH + Duration::from_secs(784_111_777);
+        assert_eq!(format_http_date(time), "Sun, 06 Nov 1994 08:49:37 GMT");
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"), Some(time));
+
+        let leap_day = UNIX_EPOCH + Duration::from_secs(951_782_400);
+        assert_eq!(format_http_date(leap_day), "Tue, 29 Feb 2000 00:00:00 GMT");
+        assert_eq!(parse_http_date(&format_http_date(leap_day)), Some(leap_day));
+
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37"), None);
+        assert_eq!(parse_http_date("Sun, 32 Nov 1994 08:49:37 GMT"), None);
+    }
+
+    #[test]
+    fn retry_after_headers() {
+        let mut headers = Headers::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, "2");
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(2)));
+
+        headers.insert(X_MS_RETRY_AFTER_MS, "250");
+        assert_eq!(retry_after(&headers), Some(Duration::from_millis(250)));
+
+        // Dates are relative to the date of the response, not the local clock.
+        let mut headers = Headers::new();
+        headers.insert(RETRY_AFTER, "Sun, 06 Nov 1994 08:49:37 GMT");
+        headers.insert(DATE, "Sun, 06 Nov 1994 08:49:07 GMT");
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(30)));
+
+        headers.insert(DATE, "Tue, 28 Feb 2023 23:59:59 GMT");
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+        headers.insert(RETRY_AFTER, "Wed, 01 Mar 2023 00:00:01 GMT");
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(2)));
+
+        headers.insert(RETRY_AFTER, "soon");
+        assert_eq!(retry_after(&headers), None);
+    }
+}
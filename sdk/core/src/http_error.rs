@@ -0,0 +1,228 @@
+use crate::{
+    headers::{self, Headers},
+    response::Response,
+};
+use bytes::Bytes;
+use serde::Deserialize;
+use std::{fmt, time::Duration};
+
+/// The most characters of a non-JSON body kept by [`body_snippet`].
+const MAX_SNIPPET_LENGTH: usize = 256;
+
+/// An HTTP error response returned by a service.
+#[derive(Clone)]
+pub struct HttpError {
+    status: u16,
+    headers: Headers,
+    error_code: Option<String>,
+    message: Option<String>,
+    snippet: Option<String>,
+    read_error: Option<String>,
+    body: Bytes,
+}
+
+impl HttpError {
+    /// Reads the entire response body to create an [`HttpError`].
+    pub async fn new(response: Response) -> Self {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let (body, read_error) = match response.into_body().collect().await {
+            Ok(body) => (body, None),
+            Err(error) => (Bytes::new(), Some(error.to_string())),
+        };
+
+        let details = serde_json::from_slice::<ErrorResponse>(&body)
+            .ok()
+            .map(|response| response.error);
+        let error_code = headers
+            .get_optional_string(&headers::ERROR_CODE)
+            .or_else(|| details.as_ref().and_then(|d| d.code.clone()));
+        let message = details.and_then(|d| d.message);
+        let snippet = body_snippet(&body);
+
+        Self {
+            status,
+            headers,
+            error_code,
+            message,
+            snippet,
+            read_error,
+            body,
+        }
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    pub fn headers(&self) -> &Headers {
+        &self.headers
+    }
+
+    pub fn error_code(&self) -> Option<&str> {
+        self.error_code.as_deref()
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// Gets the start of the body if it is not JSON, e.g., an HTML page returned by a gateway.
+    pub fn snippet(&self) -> Option<&str> {
+        self.snippet.as_deref()
+    }
+
+    /// Gets why the body could not be read, in which case [`HttpError::body`] is empty.
+    pub fn read_error(&self) -> Option<&str> {
+        self.read_error.as_deref()
+    }
+
+    /// Gets how long the service asked to wait before sending another request, e.g., when throttled.
+    pub fn retry_after(&self) -> Option<Duration> {
+        headers::retry_after(&self.headers)
+    }
+
+    /// Recreates the response this error was read from.
+    pub(crate) fn to_response(&self) -> Response {
+        Response::new(
+            self.status,
+            self.headers.clone(),
+            Box::pin(crate::stream::BytesStream::new(self.body.clone())),
+        )
+    }
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HTTP {}", self.status)?;
+        if let Some(error_code) = &self.error_code {
+            write!(f, " ({error_code})")?;
+        }
+        if let Some(message) = self.message.as_ref().or(self.snippet.as_ref()) {
+            write!(f, ": {message}")?;
+        }
+        if let Some(read_error) = &self.read_error {
+            write!(f, " (failed to read body: {read_error})")?;
+        }
+        Ok(())
+    }
+}
+
+// The headers and body are left out since they may contain secrets.
+impl fmt::Debug for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpError")
+            .field("status", &self.status)
+            .field("error_code", &self.error_code)
+            .field("message", &self.message)
+            .field("snippet", &self.snippet)
+            .field("read_error", &self.read_error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+/// Gets the start of a body which is not JSON with whitespace collapsed, or `None` if the body is
+/// empty or JSON. JSON bodies are skipped since they may contain secrets.
+pub(crate) fn body_snippet(body: &[u8]) -> Option<String> {
+    if serde_json::from_slice::<serde::de::IgnoredAny>(body).is_ok() {
+        return None;
+    }
+    let body = String::from_utf8_lossy(body);
+    let mut snippet = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    if snippet.is_empty() {
+        return None;
+    }
+    if let Some((index, _)) = snippet.char_indices().nth(MAX_SNIPPET_LENGTH) {
+        snippet.truncate(index);
+        snippet.push_str("...");
+    }
+    Some(snippet)
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: ErrorDetails,
+}
+
+#[derive(Deserialize)]
+struct ErrorDetails {
+    code: Option<String>,
+    message: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::BytesStream;
+
+    async fn http_error(status: u16, body: &'static str) -> HttpError {
+        let response = Response::new(status, Headers::new(), Box::pin(BytesStream::new(body)));
+        HttpError::new(response).await
+    }
+
+    #[tokio::test]
+    async fn non_json_bodies() {
+        let error = http_error(
+            502,
+            "<html>\n  <head><title>502 Bad Gateway</title></head>\n</html>",
+        )
+        .await;
+        assert_eq!(error.message(), None);
+        assert_eq!(
+            error.to_string(),
+            "HTTP 502: <html> <head><title>502 Bad Gateway</title></head> </html>"
+        );
+
+        assert_eq!(http_error(504, "").await.to_string(), "HTTP 504");
+        assert_eq!(http_error(504, " \r\n").await.to_string(), "HTTP 504");
+
+        let error = http_error(400, r#"{"error":{"code":"BadParameter","message":"bad"}}"#).await;
+        assert_eq!(error.snippet(), None);
+        assert_eq!(error.to_string(), "HTTP 400 (BadParameter): bad");
+
+        let error = http_error(500, r#"{"value":"secret"}"#).await;
+        assert_eq!(error.snippet(), None);
+        assert_eq!(error.to_string(), "HTTP 500");
+
+        let long = http_error(502, "x".repeat(1000).leak()).await;
+        assert_eq!(long.snippet().unwrap().len(), MAX_SNIPPET_LENGTH + 3);
+    }
+
+    #[tokio::test]
+    async fn records_body_read_errors() {
+        let chunks = vec![
+            Ok(Bytes::from_static(b"partial")),
+            Err(crate::Error::message(
+                crate::ErrorKind::Io,
+                "connection reset",
+            )),
+        ];
+        let response = Response::new(503, Headers::new(), Box::pin(futures::stream::iter(chunks)));
+        let error = HttpError::new(response).await;
+        assert_eq!(error.read_error(), Some("connection reset"));
+        assert!(error.body().is_empty());
+        assert_eq!(
+            error.to_string(),
+            "HTTP 503 (failed to read body: connection reset)"
+        );
+    }
+
+    #[tokio::test]
+    async fn debug_omits_headers_and_body() {
+        let mut headers = Headers::new();
+        headers.insert(headers::AUTHORIZATION, "Bearer secret");
+        let body = r#"{"error":{"code":"Conflict","message":"exists"},"key":"secret"}"#;
+        let response = Response::new(409, headers, Box::pin(BytesStream::new(body)));
+        let error = HttpError::new(response).await;
+        assert_eq!(
+            format!("{error:?}"),
+            r#"HttpError { status: 409, error_code: Some("Conflict"), message: Some("exists"), snippet: None, read_error: None, .. }"#
+        );
+    }
+}
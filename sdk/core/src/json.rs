@@ -1,5 +1,10 @@
+use crate::error::{Error, ErrorKind};
 use bytes::Bytes;
 use serde::{de::DeserializeOwned, Serialize};
+use std::fmt;
+
+/// The most bytes of a body kept by a [`DeserializeError`].
+const MAX_BODY_LENGTH: usize = 4096;
 
 pub fn to_json<T>(value: &T) -> crate::Result<Bytes>
 where
@@ -8,10 +13,128 @@ where
     Ok(Bytes::from(serde_json::to_vec(value)?))
 }
 
+/// Deserializes a JSON body, returning an [`ErrorKind::DataConversion`] error wrapping a
+/// [`DeserializeError`] if the body does not match `T`.
 pub fn from_json<S, T>(body: S) -> crate::Result<T>
 where
     S: AsRef<[u8]>,
     T: DeserializeOwned,
 {
-    serde_json::from_slice(body.as_ref()).map_err(Into::into)
+    let body = body.as_ref();
+    let mut deserializer = serde_json::Deserializer::from_slice(body);
+    serde_path_to_error::deserialize(&mut deserializer)
+        .map_err(|error| (error.path().to_string(), error.into_inner()))
+        .and_then(|value| {
+            // Fail on trailing characters like `serde_json::from_slice`.
+            deserializer
+                .end()
+                .map(|_| value)
+                .map_err(|error| (".".to_string(), error))
+        })
+        .map_err(|(path, error)| {
+            let error = DeserializeError {
+                path,
+                body: Bytes::copy_from_slice(&body[..body.len().min(MAX_BODY_LENGTH)]),
+                truncated: body.len() > MAX_BODY_LENGTH,
+                error,
+            };
+            Error::new(ErrorKind::DataConversion, error)
+        })
+}
+
+/// A JSON body which could not be deserialized, e.g., because a preview `api-version`
+/// returned properties the model does not expect.
+pub struct DeserializeError {
+    path: String,
+    body: Bytes,
+    truncated: bool,
+    error: serde_json::Error,
+}
+
+impl DeserializeError {
+    /// Gets the path to the value which could not be deserialized, e.g., `value[0].attributes`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Gets the raw body, truncated to 4096 bytes.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// Whether [`DeserializeError::body`] was truncated.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+// The body is not displayed since it may contain secrets.
+impl fmt::Debug for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeserializeError")
+            .field("path", &self.path)
+            .field("body_length", &self.body.len())
+            .field("truncated", &self.truncated)
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to deserialize JSON at `{}`: {}",
+            self.path, self.error
+        )
+    }
+}
+
+impl std::error::Error for DeserializeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    struct Item {
+        enabled: bool,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Items {
+        value: Vec<Item>,
+    }
+
+    #[test]
+    fn deserialize_error_path() {
+        let body = br#"{"value":[{"enabled":true},{"enabled":"yes"}]}"#;
+        let error = from_json::<_, Items>(body).unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::DataConversion);
+
+        let error = error.downcast_ref::<DeserializeError>().unwrap();
+        assert_eq!(error.path(), "value[1].enabled");
+        assert_eq!(error.body().as_ref(), body);
+        assert!(!error.is_truncated());
+
+        assert!(from_json::<_, Items>(r#"{"value":[]} trailing"#).is_err());
+    }
+
+    #[test]
+    fn deserialize_error_debug_omits_body() {
+        let body = br#"{"value":[{"password":"hunter2","enabled":"yes"}]}"#;
+        let error = from_json::<_, Items>(body).unwrap_err();
+        let debug = format!("{error:?}");
+        assert!(debug.contains("value[0].enabled"), "{debug}");
+        assert!(
+            debug.contains(&format!("body_length: {}", body.len())),
+            "{debug}"
+        );
+        assert!(!debug.contains("hunter2"), "{debug}");
+    }
 }
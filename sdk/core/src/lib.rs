@@ -1,25 +1,52 @@
 mod auth;
+mod batch;
+mod cloud;
+mod connection_string;
 mod context;
+mod endpoint;
 mod error;
+mod etag;
 mod headers;
+mod http_error;
 pub mod json;
+pub mod odata;
 mod options;
+mod paging;
 mod pipeline;
 pub mod policies;
+mod poller;
 mod request;
 mod response;
+pub mod sleep;
 pub mod stream;
+#[cfg(feature = "test")]
+pub mod test;
+#[cfg(feature = "tower")]
+pub mod tower;
 mod trace;
+pub mod transport;
+#[cfg(feature = "xml")]
+pub mod xml;
 
 pub use auth::*;
+pub use batch::*;
+pub use cloud::*;
+pub use connection_string::*;
 pub use context::*;
+pub use endpoint::*;
 pub use error::*;
+pub use etag::*;
 pub use headers::*;
+pub use http_error::*;
 pub use options::*;
+pub use paging::*;
 pub use pipeline::*;
+pub use poller::*;
 pub use request::*;
 pub use response::*;
 pub use trace::*;
 
 // Re-export common types.
 pub use url::Url;
+
+pub use azure_core_macros::{instrumented, Options};
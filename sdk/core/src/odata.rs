@@ -0,0 +1,243 @@
+//! Builds OData query options like `$filter` and `$select`, escaping literals so values from
+//! callers cannot change the meaning of a filter.
+
+use std::fmt;
+use url::Url;
+
+/// The `$filter`, `$select`, `$top`, and `$orderby` options of a list or query request.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryBuilder {
+    filter: Option<Filter>,
+    select: Vec<String>,
+    top: Option<u32>,
+    orderby: Vec<(String, Order)>,
+}
+
+impl QueryBuilder {
+    /// Sets the `$filter`, replacing any previously set.
+    pub fn with_filter(&mut self, filter: Filter) -> &mut Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Adds properties to `$select` so only they are returned.
+    pub fn with_select<I, S>(&mut self, properties: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.select.extend(properties.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_top(&mut self, top: u32) -> &mut Self {
+        self.top = Some(top);
+        self
+    }
+
+    /// Adds a property to `$orderby` after any already added.
+    pub fn with_orderby(&mut self, property: impl Into<String>, order: Order) -> &mut Self {
+        self.orderby.push((property.into(), order));
+        self
+    }
+
+    /// Appends the options which are set to the query of `url`, URL-encoding them.
+    pub fn append_to(&self, url: &mut Url) {
+        let mut options = Vec::new();
+        if let Some(filter) = &self.filter {
+            options.push(("$filter", filter.to_string()));
+        }
+        if !self.select.is_empty() {
+            options.push(("$select", self.select.join(",")));
+        }
+        if let Some(top) = self.top {
+            options.push(("$top", top.to_string()));
+        }
+        if !self.orderby.is_empty() {
+            let orderby: Vec<_> = self
+                .orderby
+                .iter()
+                .map(|(property, order)| match order {
+                    Order::Ascending => property.clone(),
+                    Order::Descending => format!("{property} desc"),
+                })
+                .collect();
+            options.push(("$orderby", orderby.join(",")));
+        }
+        // Only modify the query if there are options, so an empty `?` is not appended.
+        if !options.is_empty() {
+            url.query_pairs_mut().extend_pairs(options);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Order {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+/// A `$filter` expression, e.g., `Filter::eq("PartitionKey", "orders").and(Filter::gt("Total", 10))`.
+///
+/// Displays as the expression, with string literals quoted and escaped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Filter(String);
+
+impl Filter {
+    pub fn eq(property: &str, value: impl Into<Literal>) -> Self {
+        Self::compare(property, "eq", value.into())
+    }
+
+    pub fn ne(property: &str, value: impl Into<Literal>) -> Self {
+        Self::compare(property, "ne", value.into())
+    }
+
+    pub fn gt(property: &str, value: impl Into<Literal>) -> Self {
+        Self::compare(property, "gt", value.into())
+    }
+
+    pub fn ge(property: &str, value: impl Into<Literal>) -> Self {
+        Self::compare(property, "ge", value.into())
+    }
+
+    pub fn lt(property: &str, value: impl Into<Literal>) -> Self {
+        Self::compare(property, "lt", value.into())
+    }
+
+    pub fn le(property: &str, value: impl Into<Literal>) -> Self {
+        Self::compare(property, "le", value.into())
+    }
+
+    /// Creates a filter from an expression which is used as is, e.g., for functions like
+    /// `startswith(Name,'a')`. Literals in it must already be escaped.
+    pub fn raw(expression: impl Into<String>) -> Self {
+        Self(expression.into())
+    }
+
+    pub fn and(self, other: Filter) -> Self {
+        Self(format!("({}) and ({})", self.0, other.0))
+    }
+
+    pub fn or(self, other: Filter) -> Self {
+        Self(format!("({}) or ({})", self.0, other.0))
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        Self(format!("not ({})", self.0))
+    }
+
+    fn compare(property: &str, operator: &str, value: Literal) -> Self {
+        Self(format!("{property} {operator} {value}"))
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A literal value compared in a [`Filter`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Literal {
+    String(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Null,
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // Single quotes are escaped by doubling them.
+            Literal::String(value) => write!(f, "'{}'", value.replace('\'', "''")),
+            Literal::Int(value) => write!(f, "{value}"),
+            Literal::Float(value) => write!(f, "{value:?}"),
+            Literal::Bool(value) => write!(f, "{value}"),
+            Literal::Null => f.write_str("null"),
+        }
+    }
+}
+
+impl From<&str> for Literal {
+    fn from(value: &str) -> Self {
+        Literal::String(value.to_string())
+    }
+}
+
+impl From<String> for Literal {
+    fn from(value: String) -> Self {
+        Literal::String(value)
+    }
+}
+
+impl From<i32> for Literal {
+    fn from(value: i32) -> Self {
+        Literal::Int(value.into())
+    }
+}
+
+impl From<i64> for Literal {
+    fn from(value: i64) -> Self {
+        Literal::Int(value)
+    }
+}
+
+impl From<f64> for Literal {
+    fn from(value: f64) -> Self {
+        Literal::Float(value)
+    }
+}
+
+impl From<bool> for Literal {
+    fn from(value: bool) -> Self {
+        Literal::Bool(value)
+    }
+}
+
+impl<T: Into<Literal>> From<Option<T>> for Literal {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Literal::Null, Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_escapes_literals() {
+        let filter = Filter::eq("PartitionKey", "o'brien")
+            .and(Filter::gt("Total", 10).or(Filter::eq("Rush", true)))
+            .and(Filter::ne("Note", None::<&str>).not());
+        assert_eq!(
+            filter.to_string(),
+            "((PartitionKey eq 'o''brien') and ((Total gt 10) or (Rush eq true))) and (not (Note ne null))"
+        );
+        assert_eq!(Filter::le("Price", 2.0).to_string(), "Price le 2.0");
+    }
+
+    #[test]
+    fn append_to() {
+        let mut url = Url::parse("https://myaccount.table.core.windows.net/orders()").unwrap();
+        let mut query = QueryBuilder::default();
+        query.append_to(&mut url);
+        assert_eq!(url.query(), None);
+
+        query
+            .with_filter(Filter::eq("Status", "a&b"))
+            .with_select(["Status", "Total"])
+            .with_top(5)
+            .with_orderby("Total", Order::Descending)
+            .with_orderby("Status", Order::Ascending);
+        query.append_to(&mut url);
+        assert_eq!(
+            url.query(),
+            Some("%24filter=Status+eq+%27a%26b%27&%24select=Status%2CTotal&%24top=5&%24orderby=Total+desc%2CStatus")
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use crate::error::{Error, ErrorKind};
+use std::fmt;
+
+/// Lists every setting a [`ClientBuilder`](super::ClientBuilder) could not build a client from.
+///
+/// Returned as the source of an [`ErrorKind::InvalidInput`] error, so get it using
+/// [`Error::downcast_ref`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BuildError {
+    missing: Vec<&'static str>,
+    invalid: Vec<(&'static str, String)>,
+}
+
+impl BuildError {
+    /// Records a required setting which was not set.
+    pub fn missing(&mut self, name: &'static str) -> &mut Self {
+        self.missing.push(name);
+        self
+    }
+
+    /// Records a setting which was set but is not valid.
+    pub fn invalid(&mut self, name: &'static str, reason: impl Into<String>) -> &mut Self {
+        self.invalid.push((name, reason.into()));
+        self
+    }
+
+    /// Gets the names of required settings which were not set.
+    pub fn missing_settings(&self) -> &[&'static str] {
+        &self.missing
+    }
+
+    /// Gets the names of settings which are not valid and why.
+    pub fn invalid_settings(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        self.invalid
+            .iter()
+            .map(|(name, reason)| (*name, reason.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.invalid.is_empty()
+    }
+
+    /// Returns an [`ErrorKind::InvalidInput`] error wrapping this if any setting was recorded.
+    pub fn into_result(self) -> crate::Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        Err(Error::new(ErrorKind::InvalidInput, self))
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("cannot build client")?;
+        let mut separator = ": ";
+        if !self.missing.is_empty() {
+            write!(f, "{separator}missing {}", self.missing.join(", "))?;
+            separator = "; ";
+        }
+        for (name, reason) in &self.invalid {
+            write!(f, "{separator}invalid {name}: {reason}")?;
+            separator = "; ";
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_every_setting() {
+        assert!(BuildError::default().into_result().is_ok());
+
+        let mut error = BuildError::default();
+        error
+            .missing("endpoint")
+            .missing("credential")
+            .invalid("api_version", "must not be empty");
+        let error = error.into_result().unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::InvalidInput);
+        assert_eq!(
+            error.to_string(),
+            "cannot build client: missing endpoint, credential; invalid api_version: must not be empty"
+        );
+
+        let error = error.downcast_ref::<BuildError>().unwrap();
+        assert_eq!(error.missing_settings(), ["endpoint", "credential"]);
+        assert_eq!(
+            error.invalid_settings().collect::<Vec<_>>(),
+            [("api_version", "must not be empty")]
+        );
+    }
+}
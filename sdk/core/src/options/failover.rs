@@ -0,0 +1,32 @@
+use crate::endpoint::parse_endpoint;
+use std::time::Duration;
+use url::Url;
+
+/// Sends requests to a secondary endpoint, e.g., a geo-replicated read-only replica, after
+/// requests to the primary endpoint fail with I/O errors or 408, 500, 502, 503, or 504.
+///
+/// Requests to the secondary keep their path and query, replacing only the scheme, host, and
+/// port. Since the failed attempt is not resent, failover relies on retries being enabled.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct FailoverOptions {
+    pub secondary_endpoint: Url,
+    /// Whether only `GET` and `HEAD` requests fail over, e.g., because the secondary is
+    /// read-only. Defaults to `true`.
+    pub reads_only: bool,
+    /// How long to send requests to the secondary before sending the next one to the primary to
+    /// probe whether it has recovered, or `None` to never fail back. Defaults to one minute.
+    pub failback_after: Option<Duration>,
+}
+
+impl FailoverOptions {
+    /// Returns an [`ErrorKind::DataConversion`](crate::ErrorKind::DataConversion) error if
+    /// `secondary_endpoint` is not a valid endpoint.
+    pub fn new(secondary_endpoint: impl AsRef<str>) -> crate::Result<Self> {
+        Ok(Self {
+            secondary_endpoint: parse_endpoint(secondary_endpoint)?,
+            reads_only: true,
+            failback_after: Some(Duration::from_secs(60)),
+        })
+    }
+}
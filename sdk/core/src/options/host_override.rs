@@ -0,0 +1,28 @@
+use crate::endpoint::parse_endpoint;
+use url::Url;
+
+/// Sends requests to another endpoint than the client's, e.g., a local proxy or private endpoint
+/// in integration tests, after they are authorized for the client's endpoint.
+///
+/// Requests keep their path and query, replacing only the scheme, host, and port. TLS server
+/// names follow the new host, so to keep the original host for TLS, pin it to other addresses
+/// with a [`StaticResolver`](crate::transport::StaticResolver) instead.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct HostOverride {
+    pub endpoint: Url,
+    /// Whether to send the client's host in the `host` header instead of that of `endpoint`,
+    /// e.g., for proxies which route on it.
+    pub preserve_host: bool,
+}
+
+impl HostOverride {
+    /// Returns an [`ErrorKind::DataConversion`](crate::ErrorKind::DataConversion) error if
+    /// `endpoint` is not a valid endpoint.
+    pub fn new(endpoint: impl AsRef<str>) -> crate::Result<Self> {
+        Ok(Self {
+            endpoint: parse_endpoint(endpoint)?,
+            preserve_host: false,
+        })
+    }
+}
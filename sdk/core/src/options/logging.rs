@@ -0,0 +1,75 @@
+use crate::{
+    context::Context,
+    policies::{Policy, PolicyResult},
+    request::Request,
+};
+use std::sync::Arc;
+
+/// Options for logging requests sent by a client.
+///
+/// These apply in addition to any filter configured for the `log` or `tracing` subscriber,
+/// so a single client can be silenced without changing the global filter.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct LoggingOptions {
+    pub verbosity: LogVerbosity,
+}
+
+impl From<LogVerbosity> for LoggingOptions {
+    fn from(verbosity: LogVerbosity) -> Self {
+        Self { verbosity }
+    }
+}
+
+/// How much of each request and response is logged.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum LogVerbosity {
+    /// Nothing is logged.
+    None,
+    /// The method, URL, status code, and duration are logged.
+    Basic,
+    /// Redacted headers are logged in addition to [`LogVerbosity::Basic`].
+    #[default]
+    Headers,
+    /// Bodies are logged in addition to [`LogVerbosity::Headers`], truncated and with
+    /// [`SensitiveFields`] redacted.
+    ///
+    /// This behaves like [`LogVerbosity::Headers`] unless the `log-body` feature is enabled.
+    Body,
+}
+
+/// Names of JSON properties whose string values are redacted when bodies are logged.
+///
+/// Clients add this to the per-call policies of their pipeline so every request they send, and its
+/// response, is redacted, or insert it into the [`Context`] of operations whose models contain
+/// secrets. A value already in the `Context` takes precedence.
+#[derive(Clone, Copy, Debug)]
+pub struct SensitiveFields(&'static [&'static str]);
+
+impl From<&'static [&'static str]> for SensitiveFields {
+    fn from(fields: &'static [&'static str]) -> Self {
+        Self(fields)
+    }
+}
+
+impl SensitiveFields {
+    pub fn fields(&self) -> &'static [&'static str] {
+        self.0
+    }
+}
+
+#[async_trait::async_trait]
+impl Policy for SensitiveFields {
+    async fn send(
+        &self,
+        ctx: &mut Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        if ctx.value::<SensitiveFields>().is_none() {
+            ctx.insert(*self);
+        }
+        next[0].send(ctx, request, &next[1..]).await
+    }
+}
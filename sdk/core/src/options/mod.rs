@@ -1,20 +1,102 @@
+#[cfg(feature = "builder")]
+mod build_error;
+mod failover;
+mod host_override;
+mod logging;
 mod retry;
 mod transport;
 
+#[cfg(feature = "builder")]
+pub use build_error::*;
 #[cfg(feature = "builder")]
 pub use builder::*;
+pub use failover::*;
+pub use host_override::*;
+pub use logging::*;
 pub use retry::*;
 pub use transport::*;
 
-use crate::policies::Policy;
-use std::sync::Arc;
+use crate::{
+    cloud::Cloud,
+    error::{Error, ErrorKind},
+    policies::Policy,
+    trace::{Sampler, Tracer},
+};
+use std::{sync::Arc, time::Duration};
 
 #[derive(Clone, Debug, Default)]
+#[non_exhaustive]
 pub struct ClientOptions {
     pub retry: RetryOptions,
+    /// Sends requests after all other policies, e.g., using a custom or mock [`HttpClient`](crate::transport::HttpClient).
     pub transport: TransportOptions,
     pub per_call_policies: Vec<Arc<dyn Policy>>,
     pub per_retry_policies: Vec<Arc<dyn Policy>>,
+    pub logging: LoggingOptions,
+    /// Records a span for each attempt to send a request when set.
+    ///
+    /// Defaults to `TracingTracer` when the `tracing` feature is enabled.
+    pub tracer: Option<Arc<dyn Tracer>>,
+    /// Decides which operations are traced, e.g., to trace fewer high-volume operations.
+    pub sampler: Sampler,
+    /// The cloud whose audience is used for tokens when the endpoint does not identify one.
+    pub cloud: Cloud,
+    /// Overrides the audience of tokens, e.g., `https://vault.azure.net`, which clients otherwise
+    /// get from [`ServiceAudiences::scope`](crate::ServiceAudiences::scope).
+    pub audience: Option<String>,
+    /// Sends a generated [`IdempotencyKey`](crate::IdempotencyKey) with the requests of operations
+    /// which are not [`Idempotent`](crate::Idempotent), for services and gateways which
+    /// deduplicate on the `idempotency-key` header.
+    pub idempotency_keys: bool,
+    /// Limits how many requests a client and its clones send at once, e.g., so one component
+    /// cannot open hundreds of connections to a service. Requests wait until others complete.
+    pub max_concurrent_requests: Option<usize>,
+    /// Sends a second attempt of `GET` and `HEAD` requests which take longer than this and returns
+    /// whichever succeeds first, to reduce tail latency at the cost of more requests.
+    pub hedge_after: Option<Duration>,
+    /// Sends requests to a secondary endpoint while the primary is failing.
+    pub failover: Option<FailoverOptions>,
+    /// Sends requests to another endpoint than the client's, e.g., a local proxy in tests.
+    pub host_override: Option<HostOverride>,
+}
+
+impl ClientOptions {
+    /// Returns an [`ErrorKind::InvalidInput`] error describing the first option which is not valid,
+    /// so that clients fail when constructed instead of when sending their first request.
+    pub fn validate(&self) -> crate::Result<()> {
+        self.retry.validate()?;
+        self.transport.validate()?;
+        if self.max_concurrent_requests == Some(0) {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "max_concurrent_requests must be greater than zero",
+            ));
+        }
+        if self.hedge_after.is_some_and(|after| after.is_zero()) {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "hedge_after must be greater than zero",
+            ));
+        }
+        if self
+            .failover
+            .as_ref()
+            .is_some_and(|failover| failover.failback_after == Some(Duration::ZERO))
+        {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "failback_after must be greater than zero",
+            ));
+        }
+        if let Sampler::Ratio(ratio) = self.sampler {
+            if !(0.0..=1.0).contains(&ratio) {
+                return Err(Error::with_message(ErrorKind::InvalidInput, || {
+                    format!("sampler ratio must be between 0.0 and 1.0 but was {ratio}")
+                }));
+            }
+        }
+        Ok(())
+    }
 }
 
 mod builder {
@@ -32,5 +114,50 @@ mod builder {
             self.options().transport = transport.into();
             self
         }
+
+        fn with_logging(&mut self, logging: impl Into<LoggingOptions>) -> &mut Self {
+            self.options().logging = logging.into();
+            self
+        }
+
+        fn with_sampler(&mut self, sampler: Sampler) -> &mut Self {
+            self.options().sampler = sampler;
+            self
+        }
+
+        fn with_cloud(&mut self, cloud: Cloud) -> &mut Self {
+            self.options().cloud = cloud;
+            self
+        }
+
+        fn with_audience(&mut self, audience: impl Into<String>) -> &mut Self {
+            self.options().audience = Some(audience.into());
+            self
+        }
+
+        fn with_idempotency_keys(&mut self, idempotency_keys: bool) -> &mut Self {
+            self.options().idempotency_keys = idempotency_keys;
+            self
+        }
+
+        fn with_max_concurrent_requests(&mut self, max_concurrent_requests: usize) -> &mut Self {
+            self.options().max_concurrent_requests = Some(max_concurrent_requests);
+            self
+        }
+
+        fn with_hedge_after(&mut self, hedge_after: Duration) -> &mut Self {
+            self.options().hedge_after = Some(hedge_after);
+            self
+        }
+
+        fn with_failover(&mut self, failover: FailoverOptions) -> &mut Self {
+            self.options().failover = Some(failover);
+            self
+        }
+
+        fn with_host_override(&mut self, host_override: HostOverride) -> &mut Self {
+            self.options().host_override = Some(host_override);
+            self
+        }
     }
 }
@@ -1,33 +1,251 @@
+use crate::error::{Error, ErrorKind};
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+const RETRY_STATUSES: &[u16] = &[408, 429, 500, 502, 503, 504];
+
 #[derive(Clone, Debug, Default)]
 pub struct RetryOptions {
     mode: RetryMode,
+    on_retry: Option<OnRetry>,
+    /// The status codes which are retried, or `None` for [`RETRY_STATUSES`].
+    statuses: Option<Vec<u16>>,
+    no_retry_non_idempotent: bool,
 }
 
 impl RetryOptions {
+    /// Retries with a delay that doubles after each attempt, which is the default.
     pub fn exponential(options: ExponentialRetryOptions) -> Self {
         Self {
             mode: RetryMode::Exponential(options),
+            ..Default::default()
         }
     }
 
+    /// Retries with the same delay before each attempt.
     pub fn fixed(options: FixedRetryOptions) -> Self {
         Self {
             mode: RetryMode::Fixed(options),
+            ..Default::default()
         }
     }
 
+    /// Retries according to a [`RetryPolicy`], which replaces which errors are retried and how long to wait.
+    pub fn custom(policy: Arc<dyn RetryPolicy>) -> Self {
+        Self {
+            mode: RetryMode::Custom(policy),
+            ..Default::default()
+        }
+    }
+
+    /// Never retries, e.g., when the application implements its own retries.
+    ///
+    /// Responses with non-success status codes are still returned as errors.
     pub fn none() -> Self {
         Self {
             mode: RetryMode::None,
+            ..Default::default()
+        }
+    }
+
+    /// Replaces the retry mode with a [`RetryPolicy`] like [`RetryOptions::custom`] but keeps the callback
+    /// set with [`RetryOptions::set_on_retry`].
+    pub fn set_policy(&mut self, policy: Arc<dyn RetryPolicy>) {
+        self.mode = RetryMode::Custom(policy);
+    }
+
+    /// Sets a callback invoked before each retry is attempted.
+    ///
+    /// The callback runs after the delay has been computed but before sleeping,
+    /// so it must return quickly; it is intended for logging or emitting metrics.
+    pub fn set_on_retry<F>(&mut self, on_retry: F)
+    where
+        F: Fn(&RetryAttempt<'_>) + Send + Sync + 'static,
+    {
+        self.on_retry = Some(OnRetry(Arc::new(on_retry)));
+    }
+
+    /// Replaces the status codes which are retried, which are 408, 429, 500, 502, 503, and 504 by
+    /// default, e.g., to not retry 500 or to only retry throttling with 429.
+    ///
+    /// I/O errors are still retried. Status codes are ignored by a custom [`RetryPolicy`].
+    pub fn set_retry_statuses(&mut self, statuses: impl IntoIterator<Item = u16>) {
+        self.statuses = Some(statuses.into_iter().collect());
+    }
+
+    /// Gets the status codes which are retried unless a custom [`RetryPolicy`] was set.
+    pub fn retry_statuses(&self) -> &[u16] {
+        self.statuses.as_deref().unwrap_or(RETRY_STATUSES)
+    }
+
+    /// Sets whether operations which are not [`Idempotent`](crate::Idempotent) are retried, which
+    /// they are by default.
+    ///
+    /// When `false`, operations which clients do not mark as idempotent are not retried after
+    /// errors where the service may have applied the request, i.e., server errors like 500 or the
+    /// connection closing before a response is received. Throttling with 429, 408, and failing to
+    /// connect are still retried, since the service did not apply the request.
+    pub fn set_retry_non_idempotent(&mut self, retry: bool) {
+        self.no_retry_non_idempotent = !retry;
+    }
+
+    /// Gets whether operations which are not [`Idempotent`](crate::Idempotent) are retried.
+    pub fn retry_non_idempotent(&self) -> bool {
+        !self.no_retry_non_idempotent
+    }
+
+    /// Returns an [`ErrorKind::InvalidInput`] error if retries are enabled but could never be attempted.
+    pub fn validate(&self) -> crate::Result<()> {
+        let (max_retries, max_total_elapsed) = match &self.mode {
+            RetryMode::Exponential(options) => {
+                if options.initial_delay.is_zero() {
+                    return Err(invalid("initial_delay must be greater than zero"));
+                }
+                if options.max_delay < options.initial_delay {
+                    return Err(invalid("max_delay must not be less than initial_delay"));
+                }
+                (options.max_retries, options.max_total_elapsed)
+            }
+            RetryMode::Fixed(options) => (options.max_retries, options.max_total_elapsed),
+            RetryMode::Custom(_) | RetryMode::None => return Ok(()),
+        };
+        if max_retries == 0 {
+            return Err(invalid(
+                "max_retries must be greater than zero; use RetryOptions::none() to disable retries",
+            ));
+        }
+        if max_total_elapsed.is_zero() {
+            return Err(invalid("max_total_elapsed must be greater than zero"));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn max_retries(&self) -> u32 {
+        match &self.mode {
+            RetryMode::Exponential(options) => options.max_retries,
+            RetryMode::Fixed(options) => options.max_retries,
+            RetryMode::Custom(_) | RetryMode::None => 0,
+        }
+    }
+
+    pub(crate) fn max_total_elapsed(&self) -> Duration {
+        match &self.mode {
+            RetryMode::Exponential(options) => options.max_total_elapsed,
+            RetryMode::Fixed(options) => options.max_total_elapsed,
+            RetryMode::Custom(_) | RetryMode::None => Duration::ZERO,
+        }
+    }
+
+    /// Gets the delay before the given 1-based retry.
+    pub(crate) fn delay(&self, retry: u32) -> Duration {
+        match &self.mode {
+            RetryMode::Exponential(options) => {
+                let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+                options
+                    .initial_delay
+                    .saturating_mul(factor)
+                    .min(options.max_delay)
+            }
+            RetryMode::Fixed(options) => options.delay,
+            RetryMode::Custom(_) | RetryMode::None => Duration::ZERO,
+        }
+    }
+
+    pub(crate) fn notify(&self, attempt: &RetryAttempt<'_>) {
+        if let Some(OnRetry(on_retry)) = &self.on_retry {
+            on_retry(attempt);
+        }
+    }
+}
+
+impl RetryOptions {
+    /// Starts tracking attempts of an operation, e.g., for transports which do not send requests
+    /// through a [`Pipeline`](crate::Pipeline).
+    pub fn retrier(&self) -> Retrier<'_> {
+        Retrier {
+            options: self,
+            start: Instant::now(),
+            retries: 0,
+        }
+    }
+}
+
+/// Decides whether and when to retry each failed attempt of an operation according to [`RetryOptions`].
+#[derive(Debug)]
+pub struct Retrier<'a> {
+    options: &'a RetryOptions,
+    start: Instant,
+    retries: u32,
+}
+
+impl Retrier<'_> {
+    /// Gets the number of retries attempted so far.
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    /// Gets how long to wait before retrying after `error`, calling the callback set with
+    /// [`RetryOptions::set_on_retry`], or `None` if the error should be returned instead.
+    pub fn next_delay(&mut self, error: &Error) -> Option<Duration> {
+        let delay = self
+            .options
+            .retry_delay(error, self.retries + 1, self.start.elapsed())?;
+        self.retries += 1;
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            attempt = self.retries,
+            ?delay,
+            error.type = %error.kind(),
+            http.response.status_code = error.status(),
+            az.service_error_code = error.error_code(),
+            %error,
+            "retrying"
+        );
+
+        self.options.notify(&RetryAttempt {
+            attempt: self.retries,
+            delay,
+            cause: error,
+        });
+        Some(delay)
+    }
+}
+
+/// Retries I/O errors and transient status codes unless a custom [`RetryPolicy`] was set,
+/// so that other policies can fall back to these options.
+///
+/// Waits for the [`Error::retry_after`] of responses which have one instead of the delay of the
+/// retry mode, as long as it does not exceed the maximum total elapsed time.
+impl RetryPolicy for RetryOptions {
+    fn retry_delay(&self, error: &Error, retry: u32, elapsed: Duration) -> Option<Duration> {
+        if let RetryMode::Custom(policy) = &self.mode {
+            return policy.retry_delay(error, retry, elapsed);
         }
+
+        let retryable = error.kind() == &ErrorKind::Io
+            || error
+                .status()
+                .is_some_and(|status| self.retry_statuses().contains(&status));
+        // Wait as long as the service asked, e.g., when throttled, instead of backing off.
+        let delay = error.retry_after().unwrap_or_else(|| self.delay(retry));
+        (retryable && retry <= self.max_retries() && elapsed + delay <= self.max_total_elapsed())
+            .then_some(delay)
     }
 }
 
+fn invalid(message: &'static str) -> Error {
+    Error::message(ErrorKind::InvalidInput, message)
+}
+
 #[derive(Clone, Debug)]
 enum RetryMode {
     Exponential(ExponentialRetryOptions),
     Fixed(FixedRetryOptions),
-    // Custom(Arc<dyn Policy>),
+    Custom(Arc<dyn RetryPolicy>),
     None,
 }
 
@@ -37,8 +255,181 @@ impl Default for RetryMode {
     }
 }
 
-#[derive(Clone, Debug, Default)]
-pub struct ExponentialRetryOptions {}
+/// Decides which failed attempts are retried and how long to wait, set with [`RetryOptions::custom`].
+pub trait RetryPolicy: Send + Sync + fmt::Debug {
+    /// Gets how long to wait before the 1-based `retry`, or `None` to return the `error` instead.
+    ///
+    /// The `error` is an [`ErrorKind::HttpResponse`] for responses with non-success status codes,
+    /// and `elapsed` is the time since the first attempt was sent.
+    fn retry_delay(&self, error: &Error, retry: u32, elapsed: Duration) -> Option<Duration>;
+}
 
-#[derive(Clone, Debug, Default)]
-pub struct FixedRetryOptions {}
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ExponentialRetryOptions {
+    pub initial_delay: Duration,
+    pub max_retries: u32,
+    pub max_total_elapsed: Duration,
+    pub max_delay: Duration,
+}
+
+impl From<ExponentialRetryOptions> for RetryOptions {
+    fn from(options: ExponentialRetryOptions) -> Self {
+        Self::exponential(options)
+    }
+}
+
+impl Default for ExponentialRetryOptions {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(200),
+            max_retries: 8,
+            max_total_elapsed: Duration::from_secs(60),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct FixedRetryOptions {
+    pub delay: Duration,
+    pub max_retries: u32,
+    pub max_total_elapsed: Duration,
+}
+
+impl From<FixedRetryOptions> for RetryOptions {
+    fn from(options: FixedRetryOptions) -> Self {
+        Self::fixed(options)
+    }
+}
+
+impl Default for FixedRetryOptions {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_millis(200),
+            max_retries: 8,
+            max_total_elapsed: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Information about a retry passed to the callback set with [`RetryOptions::set_on_retry`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct RetryAttempt<'a> {
+    /// The 1-based number of the retry about to be attempted.
+    pub attempt: u32,
+    /// How long the pipeline will wait before retrying.
+    pub delay: Duration,
+    /// The error that caused the previous attempt to fail.
+    pub cause: &'a Error,
+}
+
+#[derive(Clone)]
+struct OnRetry(Arc<dyn Fn(&RetryAttempt<'_>) + Send + Sync>);
+
+impl fmt::Debug for OnRetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("OnRetry")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate() {
+        assert!(RetryOptions::default().validate().is_ok());
+        assert!(RetryOptions::none().validate().is_ok());
+        assert!(RetryOptions::fixed(FixedRetryOptions::default())
+            .validate()
+            .is_ok());
+
+        let fixed = FixedRetryOptions {
+            max_retries: 0,
+            ..Default::default()
+        };
+        let error = RetryOptions::fixed(fixed).validate().unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::InvalidInput);
+        assert!(error.to_string().contains("max_retries"));
+
+        let exponential = ExponentialRetryOptions {
+            initial_delay: Duration::ZERO,
+            ..Default::default()
+        };
+        let error = RetryOptions::exponential(exponential)
+            .validate()
+            .unwrap_err();
+        assert!(error.to_string().contains("initial_delay"));
+
+        let exponential = ExponentialRetryOptions {
+            max_delay: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let error = RetryOptions::exponential(exponential)
+            .validate()
+            .unwrap_err();
+        assert!(error.to_string().contains("max_delay"));
+
+        let exponential = ExponentialRetryOptions {
+            max_total_elapsed: Duration::ZERO,
+            ..Default::default()
+        };
+        let error = RetryOptions::exponential(exponential)
+            .validate()
+            .unwrap_err();
+        assert!(error.to_string().contains("max_total_elapsed"));
+    }
+
+    #[test]
+    fn retry_statuses() {
+        let mut options = RetryOptions::fixed(FixedRetryOptions {
+            delay: Duration::from_millis(1),
+            ..Default::default()
+        });
+        let status = |status| {
+            Error::from(ErrorKind::HttpResponse {
+                status,
+                error_code: None,
+            })
+        };
+        assert!(options.retrier().next_delay(&status(500)).is_some());
+        assert!(options.retrier().next_delay(&status(501)).is_none());
+
+        options.set_retry_statuses([429, 501]);
+        assert_eq!(options.retry_statuses(), [429, 501]);
+        assert!(options.retrier().next_delay(&status(500)).is_none());
+        assert!(options.retrier().next_delay(&status(501)).is_some());
+        assert!(options
+            .retrier()
+            .next_delay(&Error::from(ErrorKind::Io))
+            .is_some());
+    }
+
+    #[test]
+    fn retrier() {
+        let mut options = RetryOptions::fixed(FixedRetryOptions {
+            delay: Duration::from_millis(1),
+            max_retries: 2,
+            ..Default::default()
+        });
+        let attempts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        options.set_on_retry({
+            let attempts = attempts.clone();
+            move |attempt| attempts.lock().unwrap().push(attempt.attempt)
+        });
+
+        let io = Error::from(ErrorKind::Io);
+        let mut retrier = options.retrier();
+        assert_eq!(retrier.next_delay(&io), Some(Duration::from_millis(1)));
+        assert_eq!(retrier.next_delay(&io), Some(Duration::from_millis(1)));
+        assert_eq!(retrier.next_delay(&io), None);
+        assert_eq!(retrier.retries(), 2);
+        assert_eq!(*attempts.lock().unwrap(), [1, 2]);
+
+        let invalid = Error::from(ErrorKind::InvalidInput);
+        assert_eq!(options.retrier().next_delay(&invalid), None);
+    }
+}
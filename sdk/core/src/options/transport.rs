@@ -1,5 +1,10 @@
-use crate::{stream::BytesStream, Headers, Response};
+use crate::{
+    stream::BytesStream,
+    transport::{HttpClient, Resolver, SystemResolver},
+    Headers, Response,
+};
 use bytes::Bytes;
+use std::{net::IpAddr, sync::Arc, time::Duration};
 
 #[derive(Clone, Debug)]
 pub struct TransportOptions {
@@ -9,6 +14,11 @@ pub struct TransportOptions {
 #[derive(Clone, Debug)]
 enum TransportOptionsImpl {
     Bytes(bytes::Bytes),
+    Http(Arc<dyn HttpClient>),
+    #[cfg(feature = "reqwest")]
+    Default,
+    #[cfg(not(feature = "reqwest"))]
+    Unconfigured,
 }
 
 impl TransportOptions {
@@ -17,10 +27,35 @@ impl TransportOptions {
         Self { inner }
     }
 
+    /// Sends requests using the given [`HttpClient`].
+    pub fn with_http_client(http_client: Arc<dyn HttpClient>) -> Self {
+        let inner = TransportOptionsImpl::Http(http_client);
+        Self { inner }
+    }
+
+    /// Sends requests using a new default [`HttpClient`] configured with the given [`ConnectionOptions`].
+    ///
+    /// Returns an error if the client could not be created.
+    #[cfg(feature = "reqwest")]
+    pub fn with_connection_options(options: &ConnectionOptions) -> crate::Result<Self> {
+        crate::transport::reqwest::new_reqwest_client_with_options(options)
+            .map(Self::with_http_client)
+    }
+
+    /// Returns an [`ErrorKind::InvalidInput`](crate::ErrorKind::InvalidInput) error if no
+    /// transport is configured.
+    pub(crate) fn validate(&self) -> crate::Result<()> {
+        #[cfg(not(feature = "reqwest"))]
+        if let TransportOptionsImpl::Unconfigured = self.inner {
+            return Err(no_transport_error(crate::ErrorKind::InvalidInput));
+        }
+        Ok(())
+    }
+
     pub async fn send(
         &self,
         _ctx: &crate::Context,
-        _request: &mut crate::Request,
+        request: &mut crate::Request,
     ) -> crate::Result<crate::Response> {
         use TransportOptionsImpl as I;
         match &self.inner {
@@ -32,14 +67,206 @@ impl TransportOptions {
                 );
                 Ok(response)
             }
+            I::Http(http_client) => http_client.execute_request(request).await,
+            #[cfg(feature = "reqwest")]
+            I::Default => default_http_client()?.execute_request(request).await,
+            #[cfg(not(feature = "reqwest"))]
+            I::Unconfigured => Err(no_transport_error(crate::ErrorKind::Other)),
         }
     }
 }
 
+impl From<Arc<dyn HttpClient>> for TransportOptions {
+    fn from(http_client: Arc<dyn HttpClient>) -> Self {
+        Self::with_http_client(http_client)
+    }
+}
+
+#[cfg(not(feature = "reqwest"))]
+fn no_transport_error(kind: crate::ErrorKind) -> crate::Error {
+    crate::Error::message(
+        kind,
+        "no transport configured: enable the `reqwest` feature or call TransportOptions::with_http_client",
+    )
+}
+
+/// Gets the [`reqwest::Client`](::reqwest::Client) shared by all clients using default options,
+/// creating it on first use.
+#[cfg(feature = "reqwest")]
+fn default_http_client() -> crate::Result<Arc<dyn HttpClient>> {
+    static DEFAULT_HTTP_CLIENT: std::sync::OnceLock<Arc<dyn HttpClient>> =
+        std::sync::OnceLock::new();
+    if let Some(http_client) = DEFAULT_HTTP_CLIENT.get() {
+        return Ok(http_client.clone());
+    }
+    // A failure is returned from each request rather than cached, so a later request may succeed.
+    let http_client = crate::transport::reqwest::new_reqwest_client()?;
+    Ok(DEFAULT_HTTP_CLIENT.get_or_init(|| http_client).clone())
+}
+
 impl Default for TransportOptions {
+    /// Uses a [`reqwest::Client`](::reqwest::Client) shared by all clients using default options.
+    ///
+    /// The client is created when the first request is sent, which returns an error if it
+    /// could not be created.
+    #[cfg(feature = "reqwest")]
+    fn default() -> Self {
+        Self {
+            inner: TransportOptionsImpl::Default,
+        }
+    }
+
+    /// Without the `reqwest` feature there is no default transport and one must be passed to
+    /// [`TransportOptions::with_http_client`].
+    #[cfg(not(feature = "reqwest"))]
     fn default() -> Self {
         Self {
-            inner: TransportOptionsImpl::Bytes(Bytes::new()),
+            inner: TransportOptionsImpl::Unconfigured,
         }
     }
 }
+
+/// Options used to configure connections made by an [`HttpClient`].
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct ConnectionOptions {
+    /// Resolves host names instead of the operating system resolver.
+    pub resolver: Option<Arc<dyn Resolver>>,
+
+    /// Which IP address families to connect to and in what order.
+    pub ip_preference: IpPreference,
+
+    /// Whether to race connection attempts across address families.
+    ///
+    /// Only honored by `HyperClient`; the `reqwest` transport always starts the fallback attempt after 300ms.
+    /// Use [`IpPreference::Ipv4Only`] or [`IpPreference::Ipv6Only`] to avoid racing with any transport.
+    pub happy_eyeballs: HappyEyeballs,
+}
+
+impl ConnectionOptions {
+    /// Gets the resolver which also applies the [`IpPreference`], or `None` if the transport default should be used.
+    pub(crate) fn effective_resolver(&self) -> Option<Arc<dyn Resolver>> {
+        if self.resolver.is_none() && self.ip_preference == IpPreference::Any {
+            return None;
+        }
+
+        Some(Arc::new(PreferenceResolver {
+            resolver: self
+                .resolver
+                .clone()
+                .unwrap_or_else(|| Arc::new(SystemResolver)),
+            preference: self.ip_preference,
+        }))
+    }
+}
+
+/// Which IP address families to connect to and in what order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IpPreference {
+    /// Connect to addresses in the order they were resolved.
+    #[default]
+    Any,
+    /// Connect only to IPv4 addresses.
+    Ipv4Only,
+    /// Connect only to IPv6 addresses.
+    Ipv6Only,
+    /// Connect to IPv4 addresses before IPv6 addresses.
+    PreferIpv4,
+    /// Connect to IPv6 addresses before IPv4 addresses.
+    PreferIpv6,
+}
+
+impl IpPreference {
+    fn apply(self, mut addrs: Vec<IpAddr>) -> Vec<IpAddr> {
+        match self {
+            IpPreference::Any => {}
+            IpPreference::Ipv4Only => addrs.retain(IpAddr::is_ipv4),
+            IpPreference::Ipv6Only => addrs.retain(IpAddr::is_ipv6),
+            // Stable sorts preserve the resolved order within each family.
+            IpPreference::PreferIpv4 => addrs.sort_by_key(IpAddr::is_ipv6),
+            IpPreference::PreferIpv6 => addrs.sort_by_key(IpAddr::is_ipv4),
+        }
+        addrs
+    }
+}
+
+/// Whether to race connection attempts across address families as described in RFC 6555.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HappyEyeballs {
+    /// Use the transport default.
+    #[default]
+    Default,
+    /// Try each address in order without racing.
+    Disabled,
+    /// Start a connection attempt to the next address family after the given timeout.
+    Timeout(Duration),
+}
+
+#[derive(Debug)]
+struct PreferenceResolver {
+    resolver: Arc<dyn Resolver>,
+    preference: IpPreference,
+}
+
+#[async_trait::async_trait]
+impl Resolver for PreferenceResolver {
+    async fn resolve(&self, host: &str) -> crate::Result<Vec<IpAddr>> {
+        let addrs = self.resolver.resolve(host).await?;
+        Ok(self.preference.apply(addrs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    #[test]
+    fn ip_preference() {
+        let v4 = |n| IpAddr::V4(Ipv4Addr::new(10, 0, 0, n));
+        let v6 = |n| IpAddr::V6(Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, n));
+        let addrs = vec![v6(1), v4(1), v6(2), v4(2)];
+
+        assert_eq!(IpPreference::Any.apply(addrs.clone()), addrs);
+        assert_eq!(
+            IpPreference::Ipv4Only.apply(addrs.clone()),
+            vec![v4(1), v4(2)]
+        );
+        assert_eq!(
+            IpPreference::Ipv6Only.apply(addrs.clone()),
+            vec![v6(1), v6(2)]
+        );
+        assert_eq!(
+            IpPreference::PreferIpv4.apply(addrs.clone()),
+            vec![v4(1), v4(2), v6(1), v6(2)]
+        );
+        assert_eq!(
+            IpPreference::PreferIpv6.apply(addrs.clone()),
+            vec![v6(1), v6(2), v4(1), v4(2)]
+        );
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[test]
+    fn with_connection_options() {
+        let options = ConnectionOptions {
+            ip_preference: IpPreference::PreferIpv4,
+            ..Default::default()
+        };
+        assert!(TransportOptions::with_connection_options(&options).is_ok());
+    }
+
+    #[cfg(not(feature = "reqwest"))]
+    #[test]
+    fn requires_a_transport_without_reqwest() {
+        let mut options = crate::ClientOptions::default();
+        let error = options.validate().unwrap_err();
+        assert_eq!(error.kind(), &crate::ErrorKind::InvalidInput);
+
+        options.transport =
+            crate::transport::InMemoryTransport::new(&crate::ResponseBuilder::new(200)).into();
+        assert!(options.validate().is_ok());
+    }
+}
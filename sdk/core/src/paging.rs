@@ -0,0 +1,307 @@
+use crate::{
+    error::{Error, ErrorKind},
+    headers::Headers,
+    response::Response,
+};
+use futures::{
+    channel::oneshot, future::BoxFuture, stream, Future, Stream, StreamExt, TryStreamExt,
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+type PageStream<T> = Pin<Box<dyn Stream<Item = crate::Result<Page<T>>> + Send>>;
+
+/// A single page of items returned by a list operation.
+#[derive(Clone, Debug)]
+pub struct Page<T> {
+    items: Vec<T>,
+    headers: Headers,
+    continuation_token: Option<String>,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, headers: Headers, continuation_token: Option<String>) -> Self {
+        Self {
+            items,
+            headers,
+            continuation_token,
+        }
+    }
+
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn into_items(self) -> Vec<T> {
+        self.items
+    }
+
+    /// Gets the headers of the response which returned this page.
+    pub fn headers(&self) -> &Headers {
+        &self.headers
+    }
+
+    /// Gets the token used to request the next page, or `None` if this is the last page.
+    pub fn continuation_token(&self) -> Option<&str> {
+        self.continuation_token.as_deref()
+    }
+}
+
+impl<T: DeserializeOwned> Page<T> {
+    /// Deserializes a page from a response body following Azure REST API guidelines,
+    /// with items in a `value` array and an optional `nextLink` to the next page.
+    pub async fn from_response(response: Response) -> crate::Result<Self> {
+        let headers = response.headers().clone();
+        let body: PagedBody<T> = response.json().await?;
+        Ok(Self::new(body.value, headers, body.next_link))
+    }
+}
+
+#[derive(Deserialize)]
+struct PagedBody<T> {
+    #[serde(default = "Vec::new")]
+    value: Vec<T>,
+    #[serde(rename = "nextLink")]
+    next_link: Option<String>,
+}
+
+/// An asynchronous sequence of pages returned by a list operation.
+///
+/// Iterate over all items across pages with [`Pageable::into_stream`], or over each [`Page`]
+/// with [`Pageable::by_page`] to access response headers and continuation tokens.
+pub struct Pageable<T> {
+    pages: PageStream<T>,
+}
+
+impl<T> Pageable<T>
+where
+    T: Send + 'static,
+{
+    /// Creates a `Pageable` which calls `get_page` with `None` to get the first page and with
+    /// each page's continuation token to get subsequent pages until a page has no continuation token.
+    pub fn new<F, Fut>(get_page: F) -> Self
+    where
+        F: Fn(Option<String>) -> Fut + Send + 'static,
+        Fut: Future<Output = crate::Result<Page<T>>> + Send + 'static,
+    {
+        Self::with_continuation_token(None, get_page)
+    }
+
+    /// Creates a `Pageable` which resumes from a continuation token returned by a previous [`Page`].
+    pub fn with_continuation_token<F, Fut>(continuation_token: Option<String>, get_page: F) -> Self
+    where
+        F: Fn(Option<String>) -> Fut + Send + 'static,
+        Fut: Future<Output = crate::Result<Page<T>>> + Send + 'static,
+    {
+        // Requests for later pages are children of the span current when the pageable was created.
+        #[cfg(feature = "tracing")]
+        let get_page = {
+            use tracing::Instrument;
+
+            let parent = tracing::Span::current();
+            move |continuation_token| get_page(continuation_token).instrument(parent.clone())
+        };
+
+        let pages = stream::unfold(
+            (State::Start(continuation_token), get_page),
+            |(state, get_page)| async move {
+                let continuation_token = match state {
+                    State::Start(continuation_token) => continuation_token,
+                    State::More(continuation_token) => Some(continuation_token),
+                    State::Done => return None,
+                };
+
+                match get_page(continuation_token).await {
+                    Ok(page) => {
+                        let state = match &page.continuation_token {
+                            Some(continuation_token) => State::More(continuation_token.clone()),
+                            None => State::Done,
+                        };
+                        Some((Ok(page), (state, get_page)))
+                    }
+                    Err(error) => Some((Err(error), (State::Done, get_page))),
+                }
+            },
+        );
+
+        Self {
+            pages: Box::pin(pages),
+        }
+    }
+
+    /// Fetches the next page while the caller processes the current one.
+    ///
+    /// The request for the next page is started as soon as a page is returned, so at most one
+    /// request is outstanding. Requests are sent from tasks started with `spawn` so that they
+    /// make progress even while the stream is not polled, e.g., `|task| { tokio::spawn(task); }`.
+    pub fn with_prefetch<S>(self, spawn: S) -> Self
+    where
+        S: Fn(BoxFuture<'static, ()>) + Send + 'static,
+    {
+        Self {
+            pages: Box::pin(Prefetch {
+                state: PrefetchState::Idle(self.pages),
+                spawn: Box::new(spawn),
+            }),
+        }
+    }
+
+    /// Gets a stream of each [`Page`] including its headers and continuation token.
+    pub fn by_page(self) -> impl Stream<Item = crate::Result<Page<T>>> + Send {
+        self.pages
+    }
+
+    /// Gets a stream of all items across all pages.
+    pub fn into_stream(self) -> impl Stream<Item = crate::Result<T>> + Send {
+        self.pages
+            .map_ok(|page| stream::iter(page.items.into_iter().map(Ok)))
+            .try_flatten()
+    }
+}
+
+impl<T> std::fmt::Debug for Pageable<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pageable").finish_non_exhaustive()
+    }
+}
+
+type Fetched<T> = (Option<crate::Result<Page<T>>>, PageStream<T>);
+
+struct Prefetch<T> {
+    state: PrefetchState<T>,
+    spawn: Box<dyn Fn(BoxFuture<'static, ()>) + Send>,
+}
+
+enum PrefetchState<T> {
+    Idle(PageStream<T>),
+    Fetching(oneshot::Receiver<Fetched<T>>),
+    Done,
+}
+
+impl<T: Send + 'static> Prefetch<T> {
+    fn fetch(&self, mut pages: PageStream<T>) -> PrefetchState<T> {
+        let (sender, receiver) = oneshot::channel();
+        (self.spawn)(Box::pin(async move {
+            let page = pages.next().await;
+            let _ = sender.send((page, pages));
+        }));
+        PrefetchState::Fetching(receiver)
+    }
+}
+
+// The page stream is already pinned on the heap and the receiver is `Unpin`.
+impl<T> Unpin for Prefetch<T> {}
+
+impl<T: Send + 'static> Stream for Prefetch<T> {
+    type Item = crate::Result<Page<T>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match std::mem::replace(&mut self.state, PrefetchState::Done) {
+                PrefetchState::Idle(pages) => self.state = self.fetch(pages),
+                PrefetchState::Fetching(mut receiver) => {
+                    let (page, pages) = match Pin::new(&mut receiver).poll(cx) {
+                        Poll::Ready(Ok(fetched)) => fetched,
+                        Poll::Ready(Err(_)) => {
+                            return Poll::Ready(Some(Err(Error::message(
+                                ErrorKind::Other,
+                                "the task fetching the next page was dropped",
+                            ))))
+                        }
+                        Poll::Pending => {
+                            self.state = PrefetchState::Fetching(receiver);
+                            return Poll::Pending;
+                        }
+                    };
+
+                    // Start fetching the following page before returning this one.
+                    if let Some(Ok(_)) = page {
+                        self.state = self.fetch(pages);
+                    }
+                    return Poll::Ready(page);
+                }
+                PrefetchState::Done => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+enum State {
+    Start(Option<String>),
+    More(String),
+    Done,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    fn pageable() -> Pageable<u32> {
+        counted_pageable(Arc::new(AtomicUsize::new(0)))
+    }
+
+    fn counted_pageable(requests: Arc<AtomicUsize>) -> Pageable<u32> {
+        Pageable::new(move |continuation_token: Option<String>| {
+            let requests = requests.clone();
+            async move {
+                // Only count requests which make progress after first being polled.
+                tokio::task::yield_now().await;
+                requests.fetch_add(1, Ordering::SeqCst);
+                let page = continuation_token.map_or(0, |token| token.parse().unwrap());
+                let next = (page < 2).then(|| (page + 1).to_string());
+                Ok(Page::new(
+                    vec![page * 10, page * 10 + 1],
+                    Headers::new(),
+                    next,
+                ))
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn into_stream() {
+        let items: Vec<u32> = pageable().into_stream().try_collect().await.unwrap();
+        assert_eq!(items, vec![0, 1, 10, 11, 20, 21]);
+    }
+
+    #[tokio::test]
+    async fn by_page() {
+        let pages: Vec<Page<u32>> = pageable().by_page().try_collect().await.unwrap();
+        let tokens: Vec<Option<&str>> = pages.iter().map(Page::continuation_token).collect();
+        assert_eq!(tokens, vec![Some("1"), Some("2"), None]);
+    }
+
+    fn spawn(task: BoxFuture<'static, ()>) {
+        tokio::spawn(task);
+    }
+
+    #[tokio::test]
+    async fn with_prefetch() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let mut pages = Box::pin(
+            counted_pageable(requests.clone())
+                .with_prefetch(spawn)
+                .by_page(),
+        );
+
+        pages.next().await.unwrap().unwrap();
+        // The second page is requested while the first is processed, without polling the stream.
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        let items: Vec<u32> = counted_pageable(requests.clone())
+            .with_prefetch(spawn)
+            .into_stream()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(items, vec![0, 1, 10, 11, 20, 21]);
+    }
+}
@@ -1,19 +1,47 @@
 use std::sync::Arc;
 
+#[cfg(feature = "tracing")]
+use crate::trace::{Namespace, ParentSpan};
 use crate::{
-    context::Context,
-    options::ClientOptions,
-    policies::{Policy, TransportPolicy},
+    context::{Context, IdempotencyKey, Idempotent},
+    headers,
+    options::{ClientOptions, RetryOptions},
+    policies::{
+        ConcurrencyPolicy, FailoverPolicy, HedgingPolicy, HostOverridePolicy, Policy,
+        RequestInstrumentationPolicy, RetryPolicy, TransportPolicy,
+    },
     request::Request,
     response::Response,
+    trace::{OperationName, Sampled, Sampler},
 };
+use url::Url;
 
 #[derive(Clone, Debug)]
 pub struct Pipeline {
     pipeline: Vec<Arc<dyn Policy>>,
+    retry: RetryOptions,
+    sampler: Sampler,
+    idempotency_keys: bool,
+}
+
+/// The resolved configuration of a client, e.g., to log when an application starts.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ClientDiagnostics {
+    /// The endpoint without its query.
+    pub endpoint: Url,
+    /// The `api-version` sent with each request, if any.
+    pub api_version: Option<String>,
+    pub retry: RetryOptions,
+    /// The names of the policies in the order they are called.
+    pub policies: Vec<&'static str>,
 }
 
 impl Pipeline {
+    /// Creates a pipeline sending requests through the policies configured by `options`.
+    ///
+    /// `options` are not validated; clients should call [`ClientOptions::validate`] first. A
+    /// `max_concurrent_requests` of zero allows one request at a time so requests cannot wait forever.
     pub fn new(
         _crate_name: Option<&'static str>,
         _crate_version: Option<&'static str>,
@@ -26,27 +54,325 @@ impl Pipeline {
                 + options.per_call_policies.len()
                 + per_retry_policies.len()
                 + options.per_retry_policies.len()
-                + 1,
+                + 9,
         );
 
         pipeline.extend_from_slice(&per_call_policies);
         pipeline.extend_from_slice(&options.per_call_policies);
 
         // TODO: Telemetry, custom headers, etc. policies.
-        // TODO: Retry policy.
+
+        let retry: Arc<dyn Policy> = Arc::new(RetryPolicy::new(options.retry.clone()));
+        pipeline.push(retry);
+
+        // Choose the endpoint of each attempt before other policies, e.g., authorization, see it.
+        if let Some(failover) = &options.failover {
+            pipeline.push(Arc::new(FailoverPolicy::new(failover.clone())));
+        }
 
         pipeline.extend_from_slice(&per_retry_policies);
         pipeline.extend_from_slice(&options.per_retry_policies);
 
+        // Rewrite the host after authorization so tokens are for the client's endpoint.
+        if let Some(host_override) = &options.host_override {
+            pipeline.push(Arc::new(HostOverridePolicy::new(host_override.clone())));
+        }
+
+        if let Some(hedge_after) = options.hedge_after {
+            pipeline.push(Arc::new(HedgingPolicy::new(hedge_after)));
+        }
+
+        // Limit requests after authorization so that waiting for tokens does not hold a permit.
+        if let Some(max_concurrent_requests) = options.max_concurrent_requests {
+            pipeline.push(Arc::new(ConcurrencyPolicy::new(
+                max_concurrent_requests.max(1),
+            )));
+        }
+
+        #[cfg(feature = "tracing")]
+        let tracer = options
+            .tracer
+            .clone()
+            .or_else(|| Some(Arc::new(crate::trace::TracingTracer)));
+        #[cfg(not(feature = "tracing"))]
+        let tracer = options.tracer.clone();
+
+        if let Some(tracer) = tracer {
+            let instrumentation: Arc<dyn Policy> =
+                Arc::new(RequestInstrumentationPolicy::new(tracer));
+            pipeline.push(instrumentation);
+        }
+
+        #[cfg(feature = "opentelemetry")]
+        pipeline.push(Arc::new(crate::policies::MetricsPolicy::new()));
+
+        #[cfg(any(feature = "log", feature = "tracing"))]
+        if options.logging.verbosity != crate::options::LogVerbosity::None {
+            let logging: Arc<dyn Policy> =
+                Arc::new(crate::policies::LoggingPolicy::new(options.logging.clone()));
+            pipeline.push(logging);
+        }
+
         let transport: Arc<dyn Policy> = Arc::new(TransportPolicy::new(options.transport.clone()));
         pipeline.push(transport);
 
-        Self { pipeline }
+        Self {
+            pipeline,
+            retry: options.retry.clone(),
+            sampler: options.sampler,
+            idempotency_keys: options.idempotency_keys,
+        }
+    }
+
+    /// Gets the resolved configuration of a client sending requests to `endpoint` through this pipeline.
+    pub fn diagnostics(&self, endpoint: &Url) -> ClientDiagnostics {
+        let api_version = endpoint
+            .query_pairs()
+            .find(|(name, _)| name == "api-version")
+            .map(|(_, value)| value.into_owned());
+        let mut endpoint = endpoint.clone();
+        endpoint.set_query(None);
+
+        ClientDiagnostics {
+            endpoint,
+            api_version,
+            retry: self.retry.clone(),
+            policies: self.pipeline.iter().map(|policy| policy.name()).collect(),
+        }
     }
 
     pub async fn send(&self, ctx: &mut Context, request: &mut Request) -> crate::Result<Response> {
-        self.pipeline[0]
-            .send(ctx, request, &self.pipeline[1..])
-            .await
+        // Set the key before the retry policy so that every attempt sends the same one.
+        let idempotency_key = match ctx.value::<IdempotencyKey>() {
+            Some(key) => Some(key.clone()),
+            None if self.idempotency_keys
+                && !ctx
+                    .value::<Idempotent>()
+                    .is_some_and(Idempotent::is_idempotent) =>
+            {
+                Some(IdempotencyKey::generate())
+            }
+            None => None,
+        };
+        if let Some(key) = &idempotency_key {
+            request.insert_header(headers::IDEMPOTENCY_KEY, key.as_str().to_string());
+        }
+        let idempotency_key = idempotency_key.map(|key| key.as_str().to_string());
+
+        match self.send_sampled(ctx, request).await {
+            Ok(mut response) => {
+                response.set_idempotency_key(idempotency_key);
+                Ok(response)
+            }
+            Err(mut error) => {
+                error.set_request(
+                    ctx.value::<OperationName>().map(OperationName::name),
+                    request
+                        .headers()
+                        .get_optional_string(&headers::CLIENT_REQUEST_ID),
+                    idempotency_key,
+                );
+                Err(error)
+            }
+        }
+    }
+
+    async fn send_sampled(
+        &self,
+        ctx: &mut Context,
+        request: &mut Request,
+    ) -> crate::Result<Response> {
+        #[cfg(feature = "tracing")]
+        {
+            use tracing::Instrument;
+
+            // Spans are children of the current span unless the caller set a parent.
+            let parent = ctx
+                .value::<ParentSpan>()
+                .map_or_else(tracing::Span::current, |parent| parent.span().clone());
+            let parent_sampled = match ctx.value::<Sampled>() {
+                Some(sampled) => Some(sampled.is_sampled()),
+                None if ctx.value::<ParentSpan>().is_some() => Some(!parent.is_disabled()),
+                None => None,
+            };
+            let sampled = self.sample(ctx, parent_sampled);
+
+            let span = match ctx.value::<OperationName>() {
+                _ if !sampled => tracing::Span::none(),
+                Some(operation) => tracing::info_span!(
+                    target: "azure_core::operation",
+                    parent: &parent,
+                    "operation",
+                    otel.name = operation.name(),
+                    az.namespace = ctx.value::<Namespace>().map(Namespace::as_str),
+                ),
+                None => parent,
+            };
+
+            self.pipeline[0]
+                .send(ctx, request, &self.pipeline[1..])
+                .instrument(span)
+                .await
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        {
+            let parent_sampled = ctx.value::<Sampled>().map(Sampled::is_sampled);
+            self.sample(ctx, parent_sampled);
+
+            self.pipeline[0]
+                .send(ctx, request, &self.pipeline[1..])
+                .await
+        }
+    }
+
+    /// Decides whether to trace the operation and records the decision in the [`Context`]
+    /// so that policies do not start spans for operations which are not sampled.
+    fn sample(&self, ctx: &mut Context, parent_sampled: Option<bool>) -> bool {
+        let sampled = self.sampler.should_sample(parent_sampled);
+        ctx.insert(Sampled::from(sampled));
+        sampled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{headers::Headers, stream::BytesStream, transport::HttpClient};
+
+    #[derive(Debug)]
+    struct MockClient;
+
+    #[async_trait::async_trait]
+    impl HttpClient for MockClient {
+        async fn execute_request(&self, request: &Request) -> crate::Result<Response> {
+            Ok(Response::new(
+                200,
+                Headers::new(),
+                Box::pin(BytesStream::new(request.url().path().to_string())),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_transport() {
+        let http_client: Arc<dyn HttpClient> = Arc::new(MockClient);
+        let options = ClientOptions {
+            transport: http_client.into(),
+            ..Default::default()
+        };
+        let pipeline = Pipeline::new(None, None, &options, Vec::new(), Vec::new());
+
+        let mut ctx = Context::default();
+        let mut request = Request::new(
+            Url::parse("https://vault.azure.net/secrets").unwrap(),
+            "GET",
+        );
+        let response = pipeline.send(&mut ctx, &mut request).await.unwrap();
+        assert_eq!(response.into_body().collect().await.unwrap(), "/secrets");
+    }
+
+    #[tokio::test]
+    async fn sends_with_zero_concurrent_requests() {
+        let http_client: Arc<dyn HttpClient> = Arc::new(MockClient);
+        let options = ClientOptions {
+            transport: http_client.into(),
+            max_concurrent_requests: Some(0),
+            ..Default::default()
+        };
+        let pipeline = Pipeline::new(None, None, &options, Vec::new(), Vec::new());
+
+        let mut ctx = Context::default();
+        let mut request = Request::new(Url::parse("https://vault.azure.net/").unwrap(), "GET");
+        let response = tokio::time::timeout(
+            std::time::Duration::from_secs(10),
+            pipeline.send(&mut ctx, &mut request),
+        )
+        .await
+        .expect("request was sent");
+        assert_eq!(response.unwrap().status(), 200);
+    }
+
+    #[derive(Debug, Default)]
+    struct FlakyClient {
+        keys: std::sync::Mutex<Vec<Option<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClient for FlakyClient {
+        async fn execute_request(&self, request: &Request) -> crate::Result<Response> {
+            let mut keys = self.keys.lock().unwrap();
+            keys.push(
+                request
+                    .headers()
+                    .get_optional_string(&headers::IDEMPOTENCY_KEY),
+            );
+            let status = if keys.len() == 1 { 503 } else { 200 };
+            Ok(crate::ResponseBuilder::new(status).build())
+        }
+    }
+
+    #[tokio::test]
+    async fn idempotency_keys_are_constant_across_retries() {
+        let client = Arc::new(FlakyClient::default());
+        let http_client: Arc<dyn HttpClient> = client.clone();
+        let options = ClientOptions {
+            transport: http_client.into(),
+            retry: RetryOptions::fixed(crate::FixedRetryOptions {
+                delay: std::time::Duration::from_millis(1),
+                ..Default::default()
+            }),
+            idempotency_keys: true,
+            ..Default::default()
+        };
+        let pipeline = Pipeline::new(None, None, &options, Vec::new(), Vec::new());
+        let url = Url::parse("https://vault.azure.net/keys").unwrap();
+
+        let mut ctx = Context::default();
+        let mut request = Request::new(url.clone(), "POST");
+        let response = pipeline.send(&mut ctx, &mut request).await.unwrap();
+        let key = response
+            .idempotency_key()
+            .expect("generated key")
+            .to_string();
+        assert_eq!(key.len(), 36);
+        assert_eq!(
+            *client.keys.lock().unwrap(),
+            [Some(key.clone()), Some(key.clone())]
+        );
+
+        // Another operation gets another key, unless the caller supplies one.
+        let mut request = Request::new(url.clone(), "POST");
+        let response = pipeline.send(&mut ctx, &mut request).await.unwrap();
+        assert_ne!(response.idempotency_key(), Some(key.as_str()));
+
+        ctx.insert(IdempotencyKey::new("1234"));
+        let mut request = Request::new(url.clone(), "POST");
+        let response = pipeline.send(&mut ctx, &mut request).await.unwrap();
+        assert_eq!(response.idempotency_key(), Some("1234"));
+
+        let mut ctx = Context::default();
+        ctx.insert(Idempotent::from(true));
+        let mut request = Request::new(url, "PUT");
+        let response = pipeline.send(&mut ctx, &mut request).await.unwrap();
+        assert_eq!(response.idempotency_key(), None);
+    }
+
+    #[test]
+    fn diagnostics() {
+        let pipeline = Pipeline::new(
+            None,
+            None,
+            &ClientOptions::default(),
+            Vec::new(),
+            Vec::new(),
+        );
+        let endpoint = Url::parse("https://vault.azure.net?api-version=7.5").unwrap();
+        let diagnostics = pipeline.diagnostics(&endpoint);
+
+        assert_eq!(diagnostics.endpoint.as_str(), "https://vault.azure.net/");
+        assert_eq!(diagnostics.api_version.as_deref(), Some("7.5"));
+        assert_eq!(diagnostics.policies.first(), Some(&"RetryPolicy"));
+        assert_eq!(diagnostics.policies.last(), Some(&"TransportPolicy"));
     }
 }
@@ -1,10 +1,25 @@
 use crate::{
-    auth::TokenCredential,
+    auth::{AccessToken, RequestSigner, TokenCredential, TokenOverride, TokenRequestOptions},
     context::Context,
+    error::{Error, ErrorKind},
+    headers::{AUTHORIZATION, WWW_AUTHENTICATE},
+    http_error::HttpError,
     policies::{Policy, PolicyResult},
-    request::Request,
+    request::{Body, Request},
+    response::Response,
 };
-use std::sync::Arc;
+use base64::{
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+    Engine as _,
+};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use url::Url;
+
+/// Tokens are refreshed when they expire within this long, so they do not expire in flight.
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(300);
 
 #[derive(Clone, Debug)]
 pub struct ApiKeyAuthenticationPolicy {
@@ -29,3 +44,716 @@ impl Policy for ApiKeyAuthenticationPolicy {
         next[0].send(ctx, request, &next[1..]).await
     }
 }
+
+/// Authorizes requests with a bearer token for the scope and tenant a service returns in a
+/// `WWW-Authenticate` challenge, e.g., Key Vault's
+/// `Bearer authorization="https://login.microsoftonline.com/{tenant}", resource="https://vault.azure.net"`.
+///
+/// Requests are sent with a token for the initial scope, if any, until the first `401` with a
+/// challenge, which is then resent with a token for the challenge's scope and tenant. The
+/// challenge is kept for later requests, and its resource must be in the domain of the request
+/// so a malicious endpoint cannot get tokens for other services.
+#[derive(Debug)]
+pub struct ChallengeAuthenticationPolicy {
+    credential: Arc<dyn TokenCredential>,
+    state: Mutex<ChallengeState>,
+}
+
+#[derive(Debug, Default)]
+struct ChallengeState {
+    challenge: Option<Challenge>,
+    token: Option<AccessToken>,
+}
+
+impl ChallengeAuthenticationPolicy {
+    /// Creates a policy which authorizes requests for `scope` until challenged, or sends them
+    /// without authorization to discover the scope if `None`.
+    pub fn new(credential: Arc<dyn TokenCredential>, scope: Option<String>) -> Self {
+        Self {
+            credential,
+            state: Mutex::new(ChallengeState {
+                challenge: scope.map(|scope| Challenge {
+                    scopes: vec![scope],
+                    tenant_id: None,
+                }),
+                token: None,
+            }),
+        }
+    }
+
+    /// Sets a token for `challenge` on `request`, caching it unless `overridden` by a
+    /// [`TokenOverride`] so other requests are not sent with it.
+    ///
+    /// Always gets a new token if the token must have `claims`, since the cached token lacks them.
+    async fn authorize(
+        &self,
+        request: &mut Request,
+        challenge: &Challenge,
+        overridden: bool,
+        claims: Option<String>,
+    ) -> crate::Result<()> {
+        let cached = if overridden || claims.is_some() {
+            None
+        } else {
+            let state = self.state.lock().unwrap();
+            state
+                .token
+                .clone()
+                .filter(|token| !token.expires_within(TOKEN_REFRESH_MARGIN))
+                .filter(|_| state.challenge.as_ref() == Some(challenge))
+        };
+        let token = match cached {
+            Some(token) => token,
+            None => {
+                let options = TokenRequestOptions {
+                    tenant_id: challenge.tenant_id.clone(),
+                    claims,
+                    // Claims challenges for revoked tokens are handled by `send`.
+                    enable_cae: true,
+                };
+                let scopes: Vec<&str> = challenge.scopes.iter().map(String::as_str).collect();
+                let token = self.credential.get_token(&scopes, &options).await?;
+                if !overridden {
+                    let mut state = self.state.lock().unwrap();
+                    state.challenge = Some(challenge.clone());
+                    state.token = Some(token.clone());
+                }
+                token
+            }
+        };
+        request.insert_header(AUTHORIZATION, format!("Bearer {}", token.token));
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Policy for ChallengeAuthenticationPolicy {
+    async fn send(
+        &self,
+        ctx: &mut Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        let overrides = ctx.value::<TokenOverride>().cloned();
+        let current = self.state.lock().unwrap().challenge.clone();
+        let current = Challenge::apply(current, overrides.as_ref());
+        if let Some(challenge) = &current {
+            self.authorize(request, challenge, overrides.is_some(), None)
+                .await?;
+        }
+
+        let response = next[0].send(ctx, request, &next[1..]).await?;
+        if response.status() != 401 {
+            return Ok(response);
+        }
+        let header = response.headers().get_optional_str(&WWW_AUTHENTICATE);
+        let claims = header.and_then(parse_claims);
+        let challenge = header.and_then(Challenge::parse);
+
+        // A Continuous Access Evaluation challenge, e.g., because the token was revoked, is
+        // resent once with a token for the claims.
+        if let Some(claims) = claims {
+            let Some(current) = &current else {
+                return Ok(response);
+            };
+            self.authorize(request, current, overrides.is_some(), Some(claims))
+                .await?;
+            rewind(request, response).await?;
+            return next[0].send(ctx, request, &next[1..]).await;
+        }
+
+        let Some(challenge) = challenge else {
+            return Ok(response);
+        };
+        challenge.verify(request.url())?;
+        let challenge = Challenge::apply(Some(challenge), overrides.as_ref());
+        // A token for the same challenge was already rejected, so another would be too.
+        if challenge == current {
+            return Ok(response);
+        }
+
+        if let Some(challenge) = &challenge {
+            self.authorize(request, challenge, overrides.is_some(), None)
+                .await?;
+        }
+        rewind(request, response).await?;
+        next[0].send(ctx, request, &next[1..]).await
+    }
+}
+
+/// Rewinds a streamed body read by the challenged request so it is resent in full, or returns
+/// the error of the challenge `response` if the body cannot be rewound.
+async fn rewind(request: &Request, response: Response) -> crate::Result<()> {
+    let Body::Stream(body) = request.body() else {
+        return Ok(());
+    };
+    if let Err(rewind_error) = body.rewind().await {
+        let mut error = Error::from(HttpError::new(response).await);
+        error.add_note(format!("the request was not resent: {rewind_error}"));
+        return Err(error);
+    }
+    Ok(())
+}
+
+/// The scopes and tenant of a `Bearer` challenge.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Challenge {
+    scopes: Vec<String>,
+    tenant_id: Option<String>,
+}
+
+impl Challenge {
+    /// Parses a `Bearer` challenge with an `authorization`, or `authorization_uri`, and a
+    /// `resource` or `scope`. Returns `None` for other schemes.
+    fn parse(header: &str) -> Option<Self> {
+        let mut authorization = None;
+        let mut resource = None;
+        let mut scope = None;
+        for (name, value) in bearer_parameters(header)? {
+            match name {
+                "authorization" | "authorization_uri" => authorization = Some(value),
+                "resource" => resource = Some(value),
+                "scope" => scope = Some(value),
+                _ => {}
+            }
+        }
+
+        let scopes = match (scope, resource) {
+            (Some(scope), _) => scope.split(' ').map(str::to_string).collect(),
+            (None, Some(resource)) => {
+                vec![format!("{}/.default", resource.trim_end_matches('/'))]
+            }
+            (None, None) => return None,
+        };
+        // The tenant is the first segment of the authority, e.g., `https://login.microsoftonline.com/{tenant}`.
+        let tenant_id = authorization
+            .and_then(|authorization| Url::parse(authorization).ok())
+            .and_then(|url| {
+                url.path_segments()?
+                    .next()
+                    .filter(|tenant| !tenant.is_empty())
+                    .map(str::to_string)
+            });
+        Some(Self { scopes, tenant_id })
+    }
+
+    /// Applies `overrides` to `challenge`, returning `None` if there is still no scope.
+    fn apply(challenge: Option<Challenge>, overrides: Option<&TokenOverride>) -> Option<Self> {
+        let Some(overrides) = overrides else {
+            return challenge;
+        };
+        let (scopes, tenant_id) = match challenge {
+            Some(challenge) => (challenge.scopes, challenge.tenant_id),
+            None => (Vec::new(), None),
+        };
+        let scopes = match overrides.scopes.as_slice() {
+            [] => scopes,
+            overrides => overrides.to_vec(),
+        };
+        if scopes.is_empty() {
+            return None;
+        }
+        Some(Self {
+            scopes,
+            tenant_id: overrides.tenant_id.clone().or(tenant_id),
+        })
+    }
+
+    /// Verifies the host of each of the challenge's scopes is the host of `url` or a parent
+    /// domain, e.g., `vault.azure.net` for `my-vault.vault.azure.net`.
+    fn verify(&self, url: &Url) -> crate::Result<()> {
+        self.scopes
+            .iter()
+            .try_for_each(|scope| verify_scope(scope, url))
+    }
+}
+
+/// Gets the `name="value"` parameters of a `Bearer` challenge, or `None` for other schemes.
+fn bearer_parameters(header: &str) -> Option<Vec<(&str, &str)>> {
+    let (scheme, parameters) = header.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+    Some(
+        parameters
+            .split(',')
+            .filter_map(|parameter| parameter.split_once('='))
+            .map(|(name, value)| (name.trim(), value.trim().trim_matches('"')))
+            .collect(),
+    )
+}
+
+/// Decodes the `claims` of a `Bearer` challenge with `error="insufficient_claims"`.
+fn parse_claims(header: &str) -> Option<String> {
+    let parameters = bearer_parameters(header)?;
+    let value = |name| {
+        parameters
+            .iter()
+            .find(|(parameter, _)| *parameter == name)
+            .map(|(_, value)| *value)
+    };
+    if value("error") != Some("insufficient_claims") {
+        return None;
+    }
+    let claims = value("claims")?;
+    let claims = STANDARD
+        .decode(claims)
+        .or_else(|_| URL_SAFE_NO_PAD.decode(claims.trim_end_matches('=')))
+        .ok()?;
+    String::from_utf8(claims).ok()
+}
+
+fn verify_scope(scope: &str, url: &Url) -> crate::Result<()> {
+    let resource = Url::parse(scope).ok();
+    let resource_host = resource
+        .as_ref()
+        .and_then(Url::host_str)
+        .unwrap_or_default();
+    let host = url.host_str().unwrap_or_default();
+    let in_domain = !resource_host.is_empty()
+        && host
+            .strip_suffix(resource_host)
+            .is_some_and(|name| name.is_empty() || name.ends_with('.'));
+    if !in_domain {
+        return Err(Error::with_message(ErrorKind::Credential, || {
+            format!("challenge scope '{scope}' is not in the domain of '{host}'")
+        }));
+    }
+    Ok(())
+}
+
+/// Signs each attempt of a request using a [`RequestSigner`].
+///
+/// Signatures cover headers set by earlier policies, so add this after every other per-retry
+/// policy, e.g., by pushing it onto a copy of `ClientOptions::per_retry_policies`.
+#[derive(Clone, Debug)]
+pub struct SigningPolicy {
+    signer: Arc<dyn RequestSigner>,
+}
+
+impl SigningPolicy {
+    pub fn new(signer: Arc<dyn RequestSigner>) -> Self {
+        Self { signer }
+    }
+}
+
+#[async_trait::async_trait]
+impl Policy for SigningPolicy {
+    async fn send(
+        &self,
+        ctx: &mut Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        self.signer.sign(request).await?;
+        next[0].send(ctx, request, &next[1..]).await
+    }
+}
+
+/// Authorizes requests with a bearer token for a fixed scope from a [`TokenCredential`], as a
+/// [`RequestSigner`] so that clients which also support signatures, e.g., Storage Shared Key or
+/// shared access signatures, add every kind of authorization with a [`SigningPolicy`].
+///
+/// Tokens are cached until they are about to expire. Unlike [`ChallengeAuthenticationPolicy`],
+/// challenges from the service are not handled.
+#[derive(Debug)]
+pub struct BearerTokenSigner {
+    credential: Arc<dyn TokenCredential>,
+    scope: String,
+    token: Mutex<Option<AccessToken>>,
+}
+
+impl BearerTokenSigner {
+    pub fn new(credential: Arc<dyn TokenCredential>, scope: impl Into<String>) -> Self {
+        Self {
+            credential,
+            scope: scope.into(),
+            token: Mutex::default(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl RequestSigner for BearerTokenSigner {
+    async fn sign(&self, request: &mut Request) -> crate::Result<()> {
+        let cached = self
+            .token
+            .lock()
+            .unwrap()
+            .clone()
+            .filter(|token| !token.expires_within(TOKEN_REFRESH_MARGIN));
+        let token = match cached {
+            Some(token) => token,
+            None => {
+                let token = self
+                    .credential
+                    .get_token(&[&self.scope], &TokenRequestOptions::default())
+                    .await?;
+                *self.token.lock().unwrap() = Some(token.clone());
+                token
+            }
+        };
+        request.insert_header(AUTHORIZATION, format!("Bearer {}", token.token));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        policies::tests::{MockPolicy, OnceStream},
+        stream::{BytesStream, StreamBody},
+        ResponseBuilder, Url,
+    };
+    use bytes::Bytes;
+    use futures::TryStreamExt;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[derive(Debug, Default)]
+    struct CountingSigner {
+        count: AtomicU32,
+    }
+
+    #[async_trait::async_trait]
+    impl RequestSigner for CountingSigner {
+        async fn sign(&self, request: &mut Request) -> crate::Result<()> {
+            let count = self.count.fetch_add(1, Ordering::SeqCst) + 1;
+            request.insert_header(AUTHORIZATION, format!("Signed {count}"));
+            Ok(())
+        }
+    }
+
+    /// Responds with the `authorization` header of the request.
+    fn echo() -> Arc<MockPolicy> {
+        Arc::new(MockPolicy::respond(|request, _| {
+            let mut response = ResponseBuilder::new(200);
+            if let Some(authorization) = request.headers().get_optional_string(&AUTHORIZATION) {
+                response.with_header(AUTHORIZATION, authorization);
+            }
+            response
+        }))
+    }
+
+    #[derive(Debug, Default)]
+    struct RecordingCredential {
+        requests: Mutex<Vec<(String, Option<String>)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl TokenCredential for RecordingCredential {
+        async fn get_token(
+            &self,
+            scopes: &[&str],
+            options: &TokenRequestOptions,
+        ) -> crate::Result<AccessToken> {
+            let scope = scopes.join(" ");
+            let mut token = format!("{scope}@{}", options.tenant_id.as_deref().unwrap_or("none"));
+            if let Some(claims) = &options.claims {
+                token.push_str(&format!(" with {claims}"));
+            }
+            self.requests
+                .lock()
+                .unwrap()
+                .push((scope, options.tenant_id.clone()));
+            Ok(AccessToken::new(
+                token,
+                std::time::SystemTime::now() + Duration::from_secs(3600),
+            ))
+        }
+    }
+
+    /// Challenges any request without a token for the Key Vault scope in `tenant`.
+    fn challenger() -> MockPolicy {
+        MockPolicy::respond(|request, _| {
+            let authorized = request.headers().get_optional_str(&AUTHORIZATION)
+                == Some("Bearer https://vault.azure.net/.default@tenant");
+            if authorized {
+                return ResponseBuilder::new(200);
+            }
+            let mut response = ResponseBuilder::new(401);
+            response.with_header(
+                WWW_AUTHENTICATE,
+                r#"Bearer authorization="https://login.microsoftonline.com/tenant", resource="https://vault.azure.net""#,
+            );
+            response
+        })
+    }
+
+    #[tokio::test]
+    async fn authorizes_challenges() {
+        let credential: Arc<RecordingCredential> = Arc::default();
+        let policy = ChallengeAuthenticationPolicy::new(
+            credential.clone(),
+            Some("https://vault.azure.net/.default".to_string()),
+        );
+
+        let mut ctx = Context::default();
+        let next: Vec<Arc<dyn Policy>> = vec![Arc::new(challenger())];
+        for _ in 0..2 {
+            let mut request = Request::new(
+                Url::parse("https://my-vault.vault.azure.net/secrets").unwrap(),
+                "GET",
+            );
+            let response = policy.send(&mut ctx, &mut request, &next).await.unwrap();
+            assert_eq!(response.status(), 200);
+        }
+        // The initial scope was challenged for a tenant, which is cached for the second request.
+        assert_eq!(
+            *credential.requests.lock().unwrap(),
+            [
+                ("https://vault.azure.net/.default".to_string(), None),
+                (
+                    "https://vault.azure.net/.default".to_string(),
+                    Some("tenant".to_string())
+                ),
+            ]
+        );
+
+        let policy = ChallengeAuthenticationPolicy::new(credential.clone(), None);
+        let mut request = Request::new(
+            Url::parse("https://my-vault.vault.contoso.com/secrets").unwrap(),
+            "GET",
+        );
+        let error = policy
+            .send(&mut ctx, &mut request, &next)
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::Credential);
+        assert_eq!(credential.requests.lock().unwrap().len(), 2);
+    }
+
+    /// Reads the whole body, like a transport, before challenging the request.
+    #[derive(Debug)]
+    struct ReadingChallenger(MockPolicy, Mutex<Vec<Bytes>>);
+
+    #[async_trait::async_trait]
+    impl Policy for ReadingChallenger {
+        async fn send(
+            &self,
+            ctx: &mut Context,
+            request: &mut Request,
+            next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            if let Body::Stream(body) = request.body() {
+                let chunks: Vec<Bytes> = body.reader().try_collect().await?;
+                self.1.lock().unwrap().push(chunks.concat().into());
+            }
+            self.0.send(ctx, request, next).await
+        }
+    }
+
+    async fn send_stream(body: StreamBody) -> (PolicyResult, Vec<Bytes>) {
+        let policy = ChallengeAuthenticationPolicy::new(
+            Arc::new(RecordingCredential::default()),
+            Some("https://vault.azure.net/.default".to_string()),
+        );
+        let transport = Arc::new(ReadingChallenger(challenger(), Mutex::default()));
+        let next: Vec<Arc<dyn Policy>> = vec![transport.clone()];
+        let mut request = Request::new(
+            Url::parse("https://my-vault.vault.azure.net/secrets/my-secret").unwrap(),
+            "PUT",
+        );
+        request.set_body(body);
+        let result = policy
+            .send(&mut Context::default(), &mut request, &next)
+            .await;
+        let bodies = transport.1.lock().unwrap().clone();
+        (result, bodies)
+    }
+
+    #[tokio::test]
+    async fn resends_streamed_bodies_in_full() {
+        let (result, bodies) = send_stream(StreamBody::new(BytesStream::new("data"))).await;
+        assert_eq!(result.unwrap().status(), 200);
+        assert_eq!(bodies, ["data", "data"]);
+
+        let (result, bodies) =
+            send_stream(StreamBody::new(OnceStream(BytesStream::new("data")))).await;
+        let error = result.unwrap_err();
+        assert_eq!(error.status(), Some(401));
+        assert!(error.to_string().contains("not resent"), "{error}");
+        assert!(error.downcast_ref::<HttpError>().is_some());
+        assert_eq!(bodies, ["data"]);
+    }
+
+    #[tokio::test]
+    async fn overrides_scope_and_tenant() {
+        let credential: Arc<RecordingCredential> = Arc::default();
+        let policy = ChallengeAuthenticationPolicy::new(
+            credential.clone(),
+            Some("https://vault.azure.net/.default".to_string()),
+        );
+        let next: Vec<Arc<dyn Policy>> = vec![Arc::new(challenger())];
+
+        let mut ctx = Context::default();
+        ctx.insert(TokenOverride {
+            tenant_id: Some("other".to_string()),
+            ..Default::default()
+        });
+        let mut request = Request::new(
+            Url::parse("https://my-vault.vault.azure.net/secrets").unwrap(),
+            "GET",
+        );
+        // The challenged tenant does not replace the overridden one, so the token is rejected.
+        let response = policy.send(&mut ctx, &mut request, &next).await.unwrap();
+        assert_eq!(response.status(), 401);
+
+        // Overridden tokens are not cached for other requests.
+        let mut ctx = Context::default();
+        let response = policy.send(&mut ctx, &mut request, &next).await.unwrap();
+        assert_eq!(response.status(), 200);
+
+        ctx.insert(TokenOverride {
+            scopes: vec!["https://vault.azure.net/.default".to_string()],
+            tenant_id: Some("tenant".to_string()),
+        });
+        let response = policy.send(&mut ctx, &mut request, &next).await.unwrap();
+        assert_eq!(response.status(), 200);
+
+        let tenants: Vec<_> = credential
+            .requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, tenant)| tenant.clone())
+            .collect();
+        assert_eq!(
+            tenants,
+            [
+                Some("other".to_string()),
+                None,
+                Some("tenant".to_string()),
+                Some("tenant".to_string()),
+            ]
+        );
+    }
+
+    const CLAIMS: &str = r#"{"access_token":{"nbf":{"essential":true,"value":"1726077595"}}}"#;
+
+    /// Challenges any request without a token for the claims in [`CLAIMS`].
+    fn revoker() -> MockPolicy {
+        MockPolicy::respond(|request, _| {
+            let authorized = request
+                .headers()
+                .get_optional_str(&AUTHORIZATION)
+                .is_some_and(|authorization| authorization.ends_with(CLAIMS));
+            if authorized {
+                return ResponseBuilder::new(200);
+            }
+            let mut response = ResponseBuilder::new(401);
+            response.with_header(
+                WWW_AUTHENTICATE,
+                format!(
+                    r#"Bearer realm="", authorization_uri="https://login.microsoftonline.com/common/oauth2/authorize", error="insufficient_claims", claims="{}""#,
+                    STANDARD.encode(CLAIMS)
+                ),
+            );
+            response
+        })
+    }
+
+    #[tokio::test]
+    async fn reauthorizes_claims_challenges() {
+        let credential: Arc<RecordingCredential> = Arc::default();
+        let policy = ChallengeAuthenticationPolicy::new(
+            credential.clone(),
+            Some("https://vault.azure.net/.default".to_string()),
+        );
+        let next: Vec<Arc<dyn Policy>> = vec![Arc::new(revoker())];
+
+        let mut ctx = Context::default();
+        for _ in 0..2 {
+            let mut request = Request::new(
+                Url::parse("https://my-vault.vault.azure.net/secrets").unwrap(),
+                "GET",
+            );
+            let response = policy.send(&mut ctx, &mut request, &next).await.unwrap();
+            assert_eq!(response.status(), 200);
+        }
+        // The token for the claims is cached for the second request.
+        assert_eq!(credential.requests.lock().unwrap().len(), 2);
+
+        // Claims challenges without a token to replace are returned.
+        let policy = ChallengeAuthenticationPolicy::new(credential.clone(), None);
+        let mut request = Request::new(
+            Url::parse("https://my-vault.vault.azure.net/secrets").unwrap(),
+            "GET",
+        );
+        let response = policy.send(&mut ctx, &mut request, &next).await.unwrap();
+        assert_eq!(response.status(), 401);
+    }
+
+    #[test]
+    fn parse_claims_challenge() {
+        let header = format!(
+            r#"Bearer error="insufficient_claims", claims="{}""#,
+            URL_SAFE_NO_PAD.encode(CLAIMS)
+        );
+        assert_eq!(parse_claims(&header).as_deref(), Some(CLAIMS));
+        assert_eq!(
+            parse_claims(r#"Bearer error="invalid_token", claims="e30=""#),
+            None
+        );
+        assert_eq!(
+            parse_claims(r#"Bearer error="insufficient_claims", claims="!""#),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_challenge() {
+        assert_eq!(
+            Challenge::parse(
+                r#"Bearer authorization_uri="https://login.microsoftonline.com/tenant/", scope="https://storage.azure.com/.default""#
+            ),
+            Some(Challenge {
+                scopes: vec!["https://storage.azure.com/.default".to_string()],
+                tenant_id: Some("tenant".to_string()),
+            })
+        );
+        assert_eq!(Challenge::parse(r#"Basic realm="vault""#), None);
+        assert_eq!(Challenge::parse("Bearer"), None);
+    }
+
+    #[tokio::test]
+    async fn signs_with_cached_bearer_tokens() {
+        let credential: Arc<RecordingCredential> = Arc::default();
+        let policy = SigningPolicy::new(Arc::new(BearerTokenSigner::new(
+            credential.clone(),
+            "https://storage.azure.com/.default",
+        )));
+
+        let mut ctx = Context::default();
+        let next: Vec<Arc<dyn Policy>> = vec![echo()];
+        for _ in 0..2 {
+            let mut request = Request::new(Url::parse("https://localhost").unwrap(), "GET");
+            let response = policy.send(&mut ctx, &mut request, &next).await.unwrap();
+            assert_eq!(
+                response.headers().get_optional_str(&AUTHORIZATION),
+                Some("Bearer https://storage.azure.com/.default@none")
+            );
+        }
+        assert_eq!(credential.requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn signs_each_attempt() {
+        let signer: Arc<CountingSigner> = Arc::default();
+        let policy = SigningPolicy::new(signer.clone());
+
+        let mut ctx = Context::default();
+        let mut request = Request::new(Url::parse("https://localhost").unwrap(), "GET");
+        let next: Vec<Arc<dyn Policy>> = vec![echo()];
+        for expected in ["Signed 1", "Signed 2"] {
+            let response = policy
+                .send(&mut ctx, &mut request, &next)
+                .await
+                .expect("expected response");
+            assert_eq!(
+                response.headers().get_optional_str(&AUTHORIZATION),
+                Some(expected)
+            );
+        }
+    }
+}
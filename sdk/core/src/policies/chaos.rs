@@ -0,0 +1,188 @@
+use crate::{
+    context::Context,
+    headers,
+    policies::{Policy, PolicyResult},
+    request::Request,
+    response::ResponseBuilder,
+    sleep::sleep,
+};
+use std::{
+    hash::{BuildHasher, Hasher},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+    time::Duration,
+};
+
+const INJECTED_ERROR_CODE: &str = "InjectedFault";
+
+/// Injects latency and errors into a fraction of requests, e.g., for resilience game days in a
+/// staging environment. It should not be added in production.
+///
+/// Add it to `ClientOptions::per_retry_policies` so each attempt may be affected, and enable,
+/// disable, or reconfigure it at runtime through its [`ChaosHandle`]. Injected errors are
+/// responses with [`ChaosOptions::error_status`] and an `InjectedFault` error code, which are
+/// retried and returned like errors from the service.
+#[derive(Clone, Debug)]
+pub struct ChaosPolicy {
+    handle: ChaosHandle,
+}
+
+impl ChaosPolicy {
+    /// Creates a policy which is enabled with `options`.
+    pub fn new(options: ChaosOptions) -> Self {
+        Self {
+            handle: ChaosHandle {
+                enabled: Arc::new(AtomicBool::new(true)),
+                options: Arc::new(RwLock::new(options)),
+            },
+        }
+    }
+
+    /// Gets a handle which changes this policy and every clone of it.
+    pub fn handle(&self) -> ChaosHandle {
+        self.handle.clone()
+    }
+}
+
+/// Enables, disables, or reconfigures a [`ChaosPolicy`] shared by clients.
+#[derive(Clone, Debug)]
+pub struct ChaosHandle {
+    enabled: Arc<AtomicBool>,
+    options: Arc<RwLock<ChaosOptions>>,
+}
+
+impl ChaosHandle {
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_options(&self, options: ChaosOptions) {
+        *self.options.write().unwrap() = options;
+    }
+
+    pub fn options(&self) -> ChaosOptions {
+        self.options.read().unwrap().clone()
+    }
+}
+
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ChaosOptions {
+    /// The fraction of requests, from 0 to 1, to delay by `latency` before sending them.
+    pub latency_rate: f64,
+    pub latency: Duration,
+    /// The fraction of requests, from 0 to 1, to fail with `error_status` instead of sending them.
+    pub error_rate: f64,
+    /// The status of injected errors, which is `503 Service Unavailable` by default.
+    pub error_status: u16,
+}
+
+impl ChaosOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Default for ChaosOptions {
+    fn default() -> Self {
+        Self {
+            latency_rate: 0.0,
+            latency: Duration::ZERO,
+            error_rate: 0.0,
+            error_status: 503,
+        }
+    }
+}
+
+/// Returns whether a random number from 0 to 1 is less than `rate`.
+fn sample(rate: f64) -> bool {
+    // Each `RandomState` is randomly seeded, which is random enough for injecting faults.
+    let random = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish();
+    ((random >> 11) as f64 / (1u64 << 53) as f64) < rate
+}
+
+#[async_trait::async_trait]
+impl Policy for ChaosPolicy {
+    async fn send(
+        &self,
+        ctx: &mut Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        if !self.handle.is_enabled() {
+            return next[0].send(ctx, request, &next[1..]).await;
+        }
+
+        let options = self.handle.options();
+        if sample(options.latency_rate) {
+            sleep(options.latency).await;
+        }
+        if sample(options.error_rate) {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                status = options.error_status,
+                url = %request.url(),
+                "injecting an error"
+            );
+            return Ok(ResponseBuilder::new(options.error_status)
+                .with_header(headers::ERROR_CODE, INJECTED_ERROR_CODE)
+                .with_json(&serde_json::json!({
+                    "error": {
+                        "code": INJECTED_ERROR_CODE,
+                        "message": "the error was injected by a ChaosPolicy",
+                    },
+                }))?
+                .build());
+        }
+        next[0].send(ctx, request, &next[1..]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policies::tests::{self, MockPolicy};
+    use std::time::Instant;
+
+    async fn send(policy: &ChaosPolicy) -> PolicyResult {
+        let next: Vec<Arc<dyn Policy>> = vec![Arc::new(MockPolicy::new(200))];
+        tests::send(policy, "GET", "https://localhost", &next).await
+    }
+
+    #[tokio::test]
+    async fn injects_faults_until_disabled() {
+        let mut options = ChaosOptions::new();
+        options.error_rate = 1.0;
+        let policy = ChaosPolicy::new(options);
+        let handle = policy.handle();
+
+        let response = send(&policy).await.unwrap();
+        assert_eq!(response.status(), 503);
+        assert_eq!(
+            response.headers().get_optional_str(&headers::ERROR_CODE),
+            Some(INJECTED_ERROR_CODE)
+        );
+
+        let mut options = ChaosOptions::new();
+        options.latency_rate = 1.0;
+        options.latency = Duration::from_millis(20);
+        handle.set_options(options);
+        let start = Instant::now();
+        assert_eq!(send(&policy).await.unwrap().status(), 200);
+        assert!(start.elapsed() >= Duration::from_millis(20));
+
+        let mut options = ChaosOptions::new();
+        options.error_rate = 1.0;
+        handle.set_options(options);
+        handle.set_enabled(false);
+        assert_eq!(send(&policy).await.unwrap().status(), 200);
+    }
+}
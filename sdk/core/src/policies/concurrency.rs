@@ -0,0 +1,81 @@
+use crate::{
+    context::Context,
+    policies::{Policy, PolicyResult},
+    request::Request,
+};
+use futures::{
+    channel::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    lock::Mutex,
+    StreamExt,
+};
+use std::sync::Arc;
+
+/// Limits how many requests a client sends at once, set with
+/// [`ClientOptions::max_concurrent_requests`](crate::ClientOptions::max_concurrent_requests).
+///
+/// Each attempt waits for one of the permits, which it holds until its response headers are
+/// received, so waiting between retries does not hold one.
+#[derive(Debug)]
+pub(crate) struct ConcurrencyPolicy {
+    /// Returns permits to `permits` when requests complete.
+    release: UnboundedSender<()>,
+    /// Waiters lock the receiver in turn to take the next available permit.
+    permits: Mutex<UnboundedReceiver<()>>,
+}
+
+impl ConcurrencyPolicy {
+    pub fn new(max_concurrent_requests: usize) -> Self {
+        let (release, permits) = mpsc::unbounded();
+        for _ in 0..max_concurrent_requests {
+            release.unbounded_send(()).expect("receiver is open");
+        }
+        Self {
+            release,
+            permits: Mutex::new(permits),
+        }
+    }
+}
+
+/// Returns the permit when dropped, even if the request is canceled.
+struct Permit<'a>(&'a UnboundedSender<()>);
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        let _ = self.0.unbounded_send(());
+    }
+}
+
+#[async_trait::async_trait]
+impl Policy for ConcurrencyPolicy {
+    async fn send(
+        &self,
+        ctx: &mut Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        // A permit stays in the channel until received, so none are lost if this is canceled.
+        self.permits.lock().await.next().await;
+        let _permit = Permit(&self.release);
+        next[0].send(ctx, request, &next[1..]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policies::tests::{self, MockPolicy};
+
+    #[tokio::test]
+    async fn limits_requests_in_flight() {
+        let policy = ConcurrencyPolicy::new(2);
+        let transport = Arc::new(MockPolicy::new(200).with_delays(&[10]));
+        let next: Vec<Arc<dyn Policy>> = vec![transport.clone()];
+
+        let sends = (0..5).map(|_| tests::send(&policy, "GET", "https://localhost", &next));
+        for result in futures::future::join_all(sends).await {
+            assert_eq!(result.expect("expected response").status(), 200);
+        }
+
+        assert_eq!(transport.max_in_flight(), 2);
+    }
+}
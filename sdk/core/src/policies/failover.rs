@@ -0,0 +1,140 @@
+use crate::{
+    context::Context,
+    endpoint::replace_origin,
+    error::ErrorKind,
+    options::FailoverOptions,
+    policies::{Policy, PolicyResult},
+    request::Request,
+};
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+const FAILOVER_STATUSES: &[u16] = &[408, 500, 502, 503, 504];
+
+/// Sends requests to the secondary endpoint of [`FailoverOptions`] while the primary is failing.
+#[derive(Debug)]
+pub(crate) struct FailoverPolicy {
+    options: FailoverOptions,
+    /// When the primary last failed, if requests are being sent to the secondary.
+    failed_over_at: Mutex<Option<Instant>>,
+}
+
+impl FailoverPolicy {
+    pub fn new(options: FailoverOptions) -> Self {
+        Self {
+            options,
+            failed_over_at: Mutex::default(),
+        }
+    }
+
+    fn use_secondary(&self) -> bool {
+        self.failed_over_at
+            .lock()
+            .unwrap()
+            .is_some_and(|failed_over_at| {
+                self.options
+                    .failback_after
+                    .is_none_or(|after| failed_over_at.elapsed() < after)
+            })
+    }
+}
+
+#[async_trait::async_trait]
+impl Policy for FailoverPolicy {
+    async fn send(
+        &self,
+        ctx: &mut Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        if self.options.reads_only && !matches!(request.method(), "GET" | "HEAD") {
+            return next[0].send(ctx, request, &next[1..]).await;
+        }
+
+        if self.use_secondary() {
+            replace_origin(request.url_mut(), &self.options.secondary_endpoint);
+            return next[0].send(ctx, request, &next[1..]).await;
+        }
+
+        let result = next[0].send(ctx, request, &next[1..]).await;
+        let failed = match &result {
+            Ok(response) => FAILOVER_STATUSES.contains(&response.status()),
+            Err(error) => error.kind() == &ErrorKind::Io,
+        };
+        let mut failed_over_at = self.failed_over_at.lock().unwrap();
+        if failed {
+            #[cfg(feature = "tracing")]
+            if failed_over_at.is_none() {
+                tracing::warn!(
+                    secondary_endpoint = %self.options.secondary_endpoint,
+                    "primary endpoint failed; failing over"
+                );
+            }
+            *failed_over_at = Some(Instant::now());
+        } else {
+            // The primary has recovered, or it was probed after `failback_after`.
+            *failed_over_at = None;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        policies::tests::{self, MockPolicy},
+        ResponseBuilder,
+    };
+    use std::{
+        sync::atomic::{AtomicBool, Ordering},
+        time::Duration,
+    };
+
+    #[tokio::test]
+    async fn fails_over_and_back() {
+        let mut options = FailoverOptions::new("https://secondary").unwrap();
+        options.failback_after = Some(Duration::from_millis(20));
+        let policy = FailoverPolicy::new(options);
+        // Fails requests to the primary endpoint until it recovers.
+        let recovered = Arc::new(AtomicBool::new(false));
+        let transport = Arc::new(MockPolicy::respond({
+            let recovered = recovered.clone();
+            move |request, _| match request.url().host_str() {
+                Some("primary") if !recovered.load(Ordering::SeqCst) => ResponseBuilder::new(503),
+                _ => ResponseBuilder::new(200),
+            }
+        }));
+        let next: Vec<Arc<dyn Policy>> = vec![transport.clone()];
+        let send = |method| {
+            let policy = &policy;
+            let next = &next;
+            async move {
+                let url = "https://primary/secrets/name?api-version=7.5";
+                tests::send(policy, method, url, next).await.unwrap()
+            }
+        };
+
+        assert_eq!(send("GET").await.status(), 503);
+        assert_eq!(send("GET").await.status(), 200);
+        // Writes are not sent to a read-only secondary.
+        assert_eq!(send("PUT").await.status(), 503);
+
+        recovered.store(true, Ordering::SeqCst);
+        crate::sleep::sleep(Duration::from_millis(20)).await;
+        assert_eq!(send("GET").await.status(), 200);
+        assert_eq!(send("GET").await.status(), 200);
+
+        let hosts: Vec<_> = transport
+            .requests()
+            .iter()
+            .map(|request| request.url().host_str().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            hosts,
+            ["primary", "secondary", "primary", "primary", "primary"]
+        );
+    }
+}
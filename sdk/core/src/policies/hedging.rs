@@ -0,0 +1,101 @@
+use crate::{
+    context::Context,
+    policies::{Policy, PolicyResult},
+    request::Request,
+    sleep::sleep,
+};
+use futures::future::{self, Either};
+use std::{sync::Arc, time::Duration};
+
+/// Sends a second attempt of `GET` and `HEAD` requests which have not completed within
+/// [`ClientOptions::hedge_after`](crate::ClientOptions::hedge_after), returning whichever
+/// succeeds first and canceling the other.
+#[derive(Clone, Debug)]
+pub(crate) struct HedgingPolicy {
+    after: Duration,
+}
+
+impl HedgingPolicy {
+    pub fn new(after: Duration) -> Self {
+        Self { after }
+    }
+}
+
+#[async_trait::async_trait]
+impl Policy for HedgingPolicy {
+    async fn send(
+        &self,
+        ctx: &mut Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        // Only reads can be sent twice without their effects being applied twice.
+        if !matches!(request.method(), "GET" | "HEAD") {
+            return next[0].send(ctx, request, &next[1..]).await;
+        }
+
+        let mut hedge_ctx = ctx.clone();
+        let mut hedge_request = request.clone();
+        let first = next[0].send(ctx, request, &next[1..]);
+        let delay = sleep(self.after);
+        let first = match future::select(first, delay).await {
+            Either::Left((result, _)) => return result,
+            Either::Right(((), first)) => first,
+        };
+
+        let hedge = next[0].send(&mut hedge_ctx, &mut hedge_request, &next[1..]);
+        // Bound so that the canceled attempt is dropped before the hedge's context and request.
+        let result = match future::select(first, hedge).await {
+            Either::Left((Ok(response), _)) | Either::Right((Ok(response), _)) => Ok(response),
+            // Wait for the other attempt, which may still succeed.
+            Either::Left((Err(_), hedge)) => hedge.await,
+            Either::Right((Err(_), first)) => first.await,
+        };
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        policies::tests::{self, MockPolicy},
+        ResponseBuilder,
+    };
+
+    /// Responds after the delay of each call, with 200 plus the 0-based number of the call as its status.
+    fn delayed(delays: &[u64]) -> Arc<MockPolicy> {
+        Arc::new(
+            MockPolicy::respond(|_, call| ResponseBuilder::new(200 + call as u16))
+                .with_delays(delays),
+        )
+    }
+
+    async fn send(method: &'static str, transport: Arc<MockPolicy>) -> PolicyResult {
+        let policy = HedgingPolicy::new(Duration::from_millis(20));
+        tests::send(&policy, method, "https://localhost", &[transport]).await
+    }
+
+    #[tokio::test]
+    async fn hedges_slow_reads() {
+        let transport = delayed(&[1000, 1]);
+        let response = send("GET", transport.clone()).await.unwrap();
+        assert_eq!(response.status(), 201);
+        assert_eq!(transport.calls(), 2);
+        // The slow attempt was canceled.
+        assert_eq!(transport.completed(), 1);
+
+        let transport = delayed(&[1]);
+        let response = send("GET", transport.clone()).await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(transport.calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn does_not_hedge_writes() {
+        let transport = delayed(&[50]);
+        let response = send("PUT", transport.clone()).await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(transport.calls(), 1);
+    }
+}
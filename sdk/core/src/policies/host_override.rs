@@ -0,0 +1,82 @@
+use crate::{
+    context::Context,
+    endpoint::replace_origin,
+    headers,
+    options::HostOverride,
+    policies::{Policy, PolicyResult},
+    request::Request,
+};
+use std::sync::Arc;
+
+/// Sends requests to the endpoint of a [`HostOverride`].
+#[derive(Clone, Debug)]
+pub(crate) struct HostOverridePolicy {
+    host_override: HostOverride,
+}
+
+impl HostOverridePolicy {
+    pub fn new(host_override: HostOverride) -> Self {
+        Self { host_override }
+    }
+}
+
+#[async_trait::async_trait]
+impl Policy for HostOverridePolicy {
+    async fn send(
+        &self,
+        ctx: &mut Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        if self.host_override.preserve_host {
+            let url = request.url();
+            let host = match (url.host_str(), url.port()) {
+                (Some(host), Some(port)) => format!("{host}:{port}"),
+                (Some(host), None) => host.to_string(),
+                (None, _) => String::new(),
+            };
+            request.insert_header(headers::HOST, host);
+        }
+        replace_origin(request.url_mut(), &self.host_override.endpoint);
+        next[0].send(ctx, request, &next[1..]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policies::tests::{self, MockPolicy};
+
+    #[tokio::test]
+    async fn rewrites_host() {
+        let transport = Arc::new(MockPolicy::new(200));
+        let next: Vec<Arc<dyn Policy>> = vec![transport.clone()];
+        let mut host_override = HostOverride::new("http://localhost:8080").unwrap();
+
+        for preserve_host in [false, true] {
+            host_override.preserve_host = preserve_host;
+            let policy = HostOverridePolicy::new(host_override.clone());
+            let url = "https://vault.azure.net/secrets/name?api-version=7.5";
+            tests::send(&policy, "GET", url, &next).await.unwrap();
+        }
+
+        let requests: Vec<_> = transport
+            .requests()
+            .iter()
+            .map(|request| {
+                (
+                    request.url().to_string(),
+                    request.headers().get_optional_string(&headers::HOST),
+                )
+            })
+            .collect();
+        let url = "http://localhost:8080/secrets/name?api-version=7.5".to_string();
+        assert_eq!(
+            requests,
+            [
+                (url.clone(), None),
+                (url, Some("vault.azure.net".to_string()))
+            ]
+        );
+    }
+}
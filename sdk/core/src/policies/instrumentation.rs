@@ -0,0 +1,320 @@
+use crate::{
+    context::{Attempt, Context},
+    headers,
+    policies::{Policy, PolicyResult},
+    request::Request,
+    trace::{sanitize_url, Attribute, Namespace, Sampled, Tracer},
+};
+use std::sync::Arc;
+
+/// Starts a span for each attempt to send a request with attributes following
+/// the OpenTelemetry conventions for Azure SDKs.
+#[derive(Clone, Debug)]
+pub(crate) struct RequestInstrumentationPolicy {
+    tracer: Arc<dyn Tracer>,
+}
+
+impl RequestInstrumentationPolicy {
+    pub fn new(tracer: Arc<dyn Tracer>) -> Self {
+        Self { tracer }
+    }
+}
+
+#[async_trait::async_trait]
+impl Policy for RequestInstrumentationPolicy {
+    async fn send(
+        &self,
+        ctx: &mut Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        if ctx
+            .value::<Sampled>()
+            .is_some_and(|sampled| !sampled.is_sampled())
+        {
+            return next[0].send(ctx, request, &next[1..]).await;
+        }
+
+        let url = request.url();
+        let mut attributes = vec![
+            Attribute::new("http.request.method", request.method()),
+            Attribute::new("url.full", sanitize_url(url)),
+        ];
+        if let Some(host) = url.host_str() {
+            attributes.push(Attribute::new("server.address", host));
+        }
+        if let Some(port) = url.port_or_known_default() {
+            attributes.push(Attribute::new("server.port", i64::from(port)));
+        }
+        if let Some(namespace) = ctx.value::<Namespace>() {
+            attributes.push(Attribute::new("az.namespace", namespace.as_str()));
+        }
+        if let Some(client_request_id) = request
+            .headers()
+            .get_optional_str(&headers::CLIENT_REQUEST_ID)
+        {
+            attributes.push(Attribute::new("az.client_request_id", client_request_id));
+        }
+        if let Some(attempt) = ctx.value::<Attempt>().filter(|attempt| attempt.is_retry()) {
+            attributes.push(Attribute::new(
+                "http.request.resend_count",
+                i64::from(attempt.number() - 1),
+            ));
+        }
+
+        let mut span = self.tracer.start_span(request.method(), attributes);
+        let send = next[0].send(ctx, request, &next[1..]);
+        #[cfg(feature = "tracing")]
+        let result = match span.tracing_span() {
+            Some(tracing_span) => tracing::Instrument::instrument(send, tracing_span).await,
+            None => send.await,
+        };
+        #[cfg(not(feature = "tracing"))]
+        let result = send.await;
+        match &result {
+            Ok(response) => {
+                let status = response.status();
+                span.set_attribute(Attribute::new(
+                    "http.response.status_code",
+                    i64::from(status),
+                ));
+                if status >= 400 {
+                    span.set_attribute(Attribute::new("error.type", status.to_string()));
+                    if let Some(error_code) =
+                        response.headers().get_optional_str(&headers::ERROR_CODE)
+                    {
+                        span.set_attribute(Attribute::new("az.service_error_code", error_code));
+                    }
+                }
+                if let Some(request_id) = response.headers().get_optional_str(&headers::REQUEST_ID)
+                {
+                    span.set_attribute(Attribute::new("az.service_request_id", request_id));
+                }
+            }
+            Err(error) => {
+                if let Some(status) = error.status() {
+                    span.set_attribute(Attribute::new(
+                        "http.response.status_code",
+                        i64::from(status),
+                    ));
+                }
+                if let Some(error_code) = error.error_code() {
+                    span.set_attribute(Attribute::new("az.service_error_code", error_code));
+                }
+                span.set_attribute(Attribute::new("error.type", error.kind().to_string()));
+                span.add_event(
+                    "exception",
+                    vec![
+                        Attribute::new("exception.type", error.kind().to_string()),
+                        Attribute::new("exception.message", error.to_string()),
+                    ],
+                );
+            }
+        }
+        span.end();
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        options::{ClientOptions, RetryOptions, TransportOptions},
+        pipeline::Pipeline,
+        trace::{AttributeValue, TraceSpan},
+        transport::HttpClient,
+        Error, ErrorKind, Response, Url,
+    };
+    use std::sync::Mutex;
+
+    #[derive(Debug, Default)]
+    struct RecordingTracer(Arc<Mutex<Vec<Attribute>>>);
+
+    struct RecordingSpan(Arc<Mutex<Vec<Attribute>>>);
+
+    impl Tracer for RecordingTracer {
+        fn start_span(&self, _name: &str, attributes: Vec<Attribute>) -> Box<dyn TraceSpan> {
+            self.0.lock().unwrap().extend(attributes);
+            Box::new(RecordingSpan(self.0.clone()))
+        }
+    }
+
+    impl TraceSpan for RecordingSpan {
+        fn set_attribute(&mut self, attribute: Attribute) {
+            self.0.lock().unwrap().push(attribute);
+        }
+
+        fn add_event(&mut self, _name: &'static str, attributes: Vec<Attribute>) {
+            self.0.lock().unwrap().extend(attributes);
+        }
+
+        fn end(self: Box<Self>) {}
+    }
+
+    #[tokio::test]
+    async fn records_attributes() {
+        let tracer = RecordingTracer::default();
+        let attributes = tracer.0.clone();
+        let options = ClientOptions {
+            retry: RetryOptions::none(),
+            transport: TransportOptions::new(""),
+            tracer: Some(Arc::new(tracer)),
+            ..Default::default()
+        };
+        let pipeline = Pipeline::new(None, None, &options, Vec::new(), Vec::new());
+
+        let mut ctx = Context::default();
+        ctx.insert(Namespace::from("Microsoft.KeyVault"));
+        let url = Url::parse("https://example.vault.azure.net/secrets?sig=secret").unwrap();
+        let mut request = Request::new(url, "GET");
+        request.insert_header(headers::CLIENT_REQUEST_ID, "1234");
+        pipeline.send(&mut ctx, &mut request).await.unwrap();
+
+        let attributes = attributes.lock().unwrap();
+        let value = |key| {
+            attributes
+                .iter()
+                .find(|attribute| attribute.key == key)
+                .map(|attribute| attribute.value.clone())
+        };
+        assert_eq!(
+            value("url.full"),
+            Some(AttributeValue::from(
+                "https://example.vault.azure.net/secrets?sig=REDACTED"
+            ))
+        );
+        assert_eq!(
+            value("az.namespace"),
+            Some(AttributeValue::from("Microsoft.KeyVault"))
+        );
+        assert_eq!(
+            value("az.client_request_id"),
+            Some(AttributeValue::from("1234"))
+        );
+        assert_eq!(
+            value("http.response.status_code"),
+            Some(AttributeValue::I64(200))
+        );
+        assert_eq!(value("http.request.resend_count"), None);
+    }
+
+    #[derive(Debug)]
+    struct FailingClient;
+
+    #[async_trait::async_trait]
+    impl HttpClient for FailingClient {
+        async fn execute_request(&self, _request: &Request) -> crate::Result<Response> {
+            Err(Error::message(ErrorKind::Io, "connection reset"))
+        }
+    }
+
+    #[tokio::test]
+    async fn records_errors() {
+        let tracer = RecordingTracer::default();
+        let attributes = tracer.0.clone();
+        let options = ClientOptions {
+            retry: RetryOptions::none(),
+            transport: TransportOptions::with_http_client(Arc::new(FailingClient)),
+            tracer: Some(Arc::new(tracer)),
+            ..Default::default()
+        };
+        let pipeline = Pipeline::new(None, None, &options, Vec::new(), Vec::new());
+
+        let mut ctx = Context::default();
+        let mut request = Request::new(Url::parse("https://example.com").unwrap(), "GET");
+        pipeline.send(&mut ctx, &mut request).await.unwrap_err();
+
+        let attributes = attributes.lock().unwrap();
+        assert!(attributes.contains(&Attribute::new("error.type", "Io")));
+        assert!(attributes.contains(&Attribute::new("exception.message", "connection reset")));
+        assert!(!attributes
+            .iter()
+            .any(|attribute| attribute.key == "http.response.status_code"));
+    }
+
+    #[cfg(feature = "tracing")]
+    mod tracing_tracer {
+        use super::*;
+        use crate::{trace::TracingTracer, ResponseBuilder};
+        use tracing::{span, Event, Subscriber};
+        use tracing_subscriber::{
+            layer::Context as LayerContext, prelude::*, registry::LookupSpan,
+        };
+
+        type Parent = (String, Option<String>);
+
+        /// Records the name of each span and event with the name of its parent span.
+        #[derive(Clone, Default)]
+        struct Parents(Arc<Mutex<Vec<Parent>>>);
+
+        impl<S> tracing_subscriber::Layer<S> for Parents
+        where
+            S: Subscriber + for<'a> LookupSpan<'a>,
+        {
+            fn on_new_span(
+                &self,
+                _attrs: &span::Attributes<'_>,
+                id: &span::Id,
+                ctx: LayerContext<'_, S>,
+            ) {
+                let span = ctx.span(id).unwrap();
+                let parent = span.parent().map(|parent| parent.name().to_string());
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push((span.name().to_string(), parent));
+            }
+
+            fn on_event(&self, event: &Event<'_>, ctx: LayerContext<'_, S>) {
+                let parent = ctx.event_span(event).map(|span| span.name().to_string());
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push((event.metadata().name().to_string(), parent));
+            }
+        }
+
+        #[derive(Debug)]
+        struct TracedClient;
+
+        #[async_trait::async_trait]
+        impl HttpClient for TracedClient {
+            async fn execute_request(&self, _request: &Request) -> crate::Result<Response> {
+                tracing::info_span!("connect").in_scope(|| {});
+                tracing::info!(name: "sending", "sending request");
+                Ok(ResponseBuilder::new(200).build())
+            }
+        }
+
+        #[tokio::test]
+        async fn children_of_request_span() {
+            let parents = Parents::default();
+            let _guard = tracing_subscriber::registry()
+                .with(parents.clone())
+                .set_default();
+
+            let options = ClientOptions {
+                retry: RetryOptions::none(),
+                transport: TransportOptions::with_http_client(Arc::new(TracedClient)),
+                tracer: Some(Arc::new(TracingTracer)),
+                ..Default::default()
+            };
+            let pipeline = Pipeline::new(None, None, &options, Vec::new(), Vec::new());
+            let mut ctx = Context::default();
+            let mut request = Request::new(Url::parse("https://example.com").unwrap(), "GET");
+            pipeline.send(&mut ctx, &mut request).await.unwrap();
+
+            let parents = parents.0.lock().unwrap();
+            let parent = |name: &str| {
+                parents
+                    .iter()
+                    .find(|(child, _)| child == name)
+                    .and_then(|(_, parent)| parent.clone())
+            };
+            assert_eq!(parent("connect").as_deref(), Some("request"));
+            assert_eq!(parent("sending").as_deref(), Some("request"));
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{
+    context::Context,
+    http_error::HttpError,
+    policies::{Policy, PolicyResult},
+    request::Request,
+    response::Response,
+};
+use std::sync::Arc;
+
+/// Observes or modifies requests and responses without managing the rest of the pipeline.
+///
+/// Wrap an implementation in an [`InterceptorPolicy`] and add it to either
+/// `ClientOptions::per_call_policies` or `ClientOptions::per_retry_policies`.
+pub trait Interceptor: Send + Sync + std::fmt::Debug {
+    /// Called before the request is passed to the next policy.
+    fn on_request(&self, _request: &mut Request) {}
+
+    /// Called with the response returned from the next policy.
+    ///
+    /// Also called with error responses, even after the retry policy has returned them as an
+    /// [`HttpError`].
+    fn on_response(&self, _response: &Response) {}
+}
+
+/// Adapts an [`Interceptor`] into a [`Policy`].
+#[derive(Clone, Debug)]
+pub struct InterceptorPolicy {
+    interceptor: Arc<dyn Interceptor>,
+}
+
+impl InterceptorPolicy {
+    pub fn new(interceptor: impl Interceptor + 'static) -> Self {
+        Self {
+            interceptor: Arc::new(interceptor),
+        }
+    }
+}
+
+impl From<Arc<dyn Interceptor>> for InterceptorPolicy {
+    fn from(interceptor: Arc<dyn Interceptor>) -> Self {
+        Self { interceptor }
+    }
+}
+
+#[async_trait::async_trait]
+impl Policy for InterceptorPolicy {
+    async fn send(
+        &self,
+        ctx: &mut Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        self.interceptor.on_request(request);
+        let result = next[0].send(ctx, request, &next[1..]).await;
+        match &result {
+            Ok(response) => self.interceptor.on_response(response),
+            Err(error) => {
+                if let Some(error) = error.downcast_ref::<HttpError>() {
+                    self.interceptor.on_response(&error.to_response());
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        options::RetryOptions,
+        policies::{tests::MockPolicy, RetryPolicy},
+        HeaderName, ResponseBuilder, Url,
+    };
+    use std::sync::atomic::{AtomicU16, Ordering};
+
+    #[derive(Debug, Default)]
+    struct Stamp {
+        status: AtomicU16,
+    }
+
+    impl Interceptor for Stamp {
+        fn on_request(&self, request: &mut Request) {
+            request.insert_header("x-ms-stamp", "stamped");
+        }
+
+        fn on_response(&self, response: &Response) {
+            self.status.store(response.status(), Ordering::SeqCst);
+        }
+    }
+
+    /// Responds with 204 to stamped requests.
+    fn echo() -> MockPolicy {
+        MockPolicy::respond(|request, _| {
+            match request
+                .headers()
+                .get_optional_str(&HeaderName::from_static("x-ms-stamp"))
+            {
+                Some("stamped") => ResponseBuilder::new(204),
+                _ => ResponseBuilder::new(400),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn interceptor_called() {
+        let interceptor: Arc<Stamp> = Arc::default();
+        let policy = InterceptorPolicy::from(interceptor.clone() as Arc<dyn Interceptor>);
+
+        let mut ctx = Context::default();
+        let mut request = Request::new(Url::parse("https://localhost").unwrap(), "GET");
+        let next: Vec<Arc<dyn Policy>> = vec![Arc::new(echo())];
+        let response = policy
+            .send(&mut ctx, &mut request, &next)
+            .await
+            .expect("expected response");
+
+        assert_eq!(response.status(), 204);
+        assert_eq!(interceptor.status.load(Ordering::SeqCst), 204);
+    }
+
+    #[tokio::test]
+    async fn interceptor_called_with_error_response() {
+        let interceptor: Arc<Stamp> = Arc::default();
+        let policy = InterceptorPolicy::from(interceptor.clone() as Arc<dyn Interceptor>);
+
+        // The retry policy returns the 404 as an error.
+        let mut ctx = Context::default();
+        let mut request = Request::new(Url::parse("https://localhost").unwrap(), "GET");
+        let next: Vec<Arc<dyn Policy>> = vec![
+            Arc::new(RetryPolicy::new(RetryOptions::none())),
+            Arc::new(MockPolicy::new(404)),
+        ];
+        let error = policy
+            .send(&mut ctx, &mut request, &next)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.downcast_ref::<HttpError>().unwrap().status(), 404);
+        assert_eq!(interceptor.status.load(Ordering::SeqCst), 404);
+    }
+}
@@ -0,0 +1,309 @@
+use crate::{
+    context::{Attempt, Context},
+    headers::{self, HeaderName, Headers},
+    options::{LogVerbosity, LoggingOptions},
+    policies::{Policy, PolicyResult},
+    request::Request,
+    trace::sanitize_url,
+};
+#[cfg(feature = "log-body")]
+use crate::{options::SensitiveFields, request::Body, response::BodyPeek};
+use std::{sync::Arc, time::Instant};
+
+/// Logs through `tracing` when enabled, otherwise through `log`.
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(target: "azure_core::policies::logging", $($arg)+);
+        #[cfg(all(feature = "log", not(feature = "tracing")))]
+        log::debug!(target: "azure_core::policies::logging", $($arg)+);
+    };
+}
+
+/// Headers whose values are logged; the values of all other headers are redacted.
+const ALLOWED_HEADERS: &[HeaderName] = &[
+    headers::ACCEPT,
+    headers::CLIENT_REQUEST_ID,
+    headers::CONTENT_ENCODING,
+    headers::CONTENT_LENGTH,
+    headers::CONTENT_TYPE,
+    headers::DATE,
+    headers::ERROR_CODE,
+    headers::ETAG,
+    headers::IDEMPOTENCY_KEY,
+    headers::IF_MATCH,
+    headers::IF_MODIFIED_SINCE,
+    headers::IF_NONE_MATCH,
+    headers::IF_UNMODIFIED_SINCE,
+    headers::REQUEST_ID,
+    headers::RETRY_AFTER,
+    headers::RETRY_AFTER_MS,
+    headers::USER_AGENT,
+    headers::X_MS_RETRY_AFTER_MS,
+];
+
+/// Logs each attempt to send a request and its response or error.
+///
+/// URLs and headers are redacted so that secrets are not logged.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LoggingPolicy {
+    options: LoggingOptions,
+}
+
+impl LoggingPolicy {
+    pub fn new(options: LoggingOptions) -> Self {
+        Self { options }
+    }
+
+    fn headers(&self, headers: &Headers) -> String {
+        match self.options.verbosity {
+            LogVerbosity::None | LogVerbosity::Basic => String::new(),
+            LogVerbosity::Headers | LogVerbosity::Body => format_headers(headers),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Policy for LoggingPolicy {
+    async fn send(
+        &self,
+        ctx: &mut Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        // Only retries are marked so the first attempt of most requests is not noisy.
+        let attempt = ctx
+            .value::<Attempt>()
+            .filter(|attempt| attempt.is_retry())
+            .map(|attempt| format!(" (attempt {})", attempt.number()))
+            .unwrap_or_default();
+        debug!(
+            "==> {} {}{attempt} {}",
+            request.method(),
+            sanitize_url(request.url()),
+            self.headers(request.headers()),
+        );
+
+        #[cfg(feature = "log-body")]
+        let sensitive_fields = ctx
+            .value::<SensitiveFields>()
+            .map_or(&[][..], SensitiveFields::fields);
+        #[cfg(feature = "log-body")]
+        // Streamed bodies are not logged since reading them would consume them.
+        if let Body::Bytes(body) = request.body() {
+            if self.options.verbosity == LogVerbosity::Body && !body.is_empty() {
+                debug!("==> body: {}", format_body(body, sensitive_fields));
+            }
+        }
+
+        let start = Instant::now();
+        #[allow(unused_mut)]
+        let mut result = next[0].send(ctx, request, &next[1..]).await;
+        let elapsed = start.elapsed();
+
+        #[cfg(feature = "log-body")]
+        if let Ok(response) = &mut result {
+            let is_json = response
+                .headers()
+                .get_optional_str(&headers::CONTENT_TYPE)
+                .is_some_and(|content_type| content_type.contains("json"));
+            if self.options.verbosity == LogVerbosity::Body && is_json {
+                // Read errors are left in the body for the caller rather than failing the request.
+                match response.peek_body(MAX_BUFFERED_BODY_LENGTH).await {
+                    BodyPeek::Complete(body) => {
+                        debug!("<== body: {}", format_body(&body, sensitive_fields));
+                    }
+                    BodyPeek::TooLong => {
+                        debug!("<== body: (more than {MAX_BUFFERED_BODY_LENGTH} bytes)");
+                    }
+                    BodyPeek::Failed(message) => {
+                        debug!("<== body: failed to read: {message}");
+                    }
+                }
+            }
+        }
+
+        match &result {
+            Ok(response) => {
+                debug!(
+                    "<== {} ({:?}){attempt} {}",
+                    response.status(),
+                    elapsed,
+                    self.headers(response.headers()),
+                );
+            }
+            Err(error) => {
+                debug!("<== error ({:?}){attempt}: {}", elapsed, error);
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(feature = "log-body")]
+const MAX_BODY_LENGTH: usize = 4096;
+
+/// Responses with longer bodies are not logged since the whole body must be read to redact it.
+#[cfg(feature = "log-body")]
+const MAX_BUFFERED_BODY_LENGTH: usize = 64 * 1024;
+
+/// Names of JSON properties whose string values are always redacted when bodies are logged.
+#[cfg(feature = "log-body")]
+const DEFAULT_SENSITIVE_FIELDS: &[&str] = &[
+    "accessToken",
+    "access_token",
+    "client_secret",
+    "password",
+    "refreshToken",
+    "refresh_token",
+];
+
+/// Formats a JSON body truncated to [`MAX_BODY_LENGTH`] with sensitive string properties redacted.
+/// Other bodies may not be redacted reliably, so only their length is formatted.
+#[cfg(feature = "log-body")]
+fn format_body(body: &[u8], sensitive_fields: &[&str]) -> String {
+    fn redact(value: &mut serde_json::Value, sensitive_fields: &[&str]) {
+        match value {
+            serde_json::Value::Object(properties) => {
+                for (name, value) in properties {
+                    let sensitive = DEFAULT_SENSITIVE_FIELDS.contains(&name.as_str())
+                        || sensitive_fields.contains(&name.as_str());
+                    if sensitive && value.is_string() {
+                        *value = "REDACTED".into();
+                    } else {
+                        redact(value, sensitive_fields);
+                    }
+                }
+            }
+            serde_json::Value::Array(values) => {
+                for value in values {
+                    redact(value, sensitive_fields);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let Ok(mut json) = serde_json::from_slice::<serde_json::Value>(body) else {
+        return format!("({} bytes)", body.len());
+    };
+    redact(&mut json, sensitive_fields);
+
+    let mut body = json.to_string();
+    if body.len() > MAX_BODY_LENGTH {
+        let mut end = MAX_BODY_LENGTH;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        body.truncate(end);
+        body.push_str("...");
+    }
+    body
+}
+
+/// Formats headers sorted by name with the values of headers not in [`ALLOWED_HEADERS`] redacted.
+fn format_headers(headers: &Headers) -> String {
+    let mut headers: Vec<_> = headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if ALLOWED_HEADERS.contains(name) {
+                value.as_str()
+            } else {
+                "REDACTED"
+            };
+            format!("{}: {}", name.as_str(), value)
+        })
+        .collect();
+    headers.sort();
+    format!("[{}]", headers.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_headers() {
+        let mut headers = Headers::new();
+        headers.insert(headers::AUTHORIZATION, "Bearer secret");
+        headers.insert(headers::CLIENT_REQUEST_ID, "1234");
+        assert_eq!(
+            format_headers(&headers),
+            "[authorization: REDACTED, x-ms-client-request-id: 1234]"
+        );
+    }
+
+    #[cfg(feature = "log-body")]
+    #[test]
+    fn redacts_body() {
+        let body = br#"{"value":[{"id":"a","value":"secret","password":"secret"}]}"#;
+        assert_eq!(
+            format_body(body, &["value"]),
+            r#"{"value":[{"id":"a","password":"REDACTED","value":"REDACTED"}]}"#
+        );
+        assert_eq!(format_body(b"secret", &[]), "(6 bytes)");
+
+        let body = format!(r#"{{"id":"{}"}}"#, "a".repeat(MAX_BODY_LENGTH));
+        assert!(format_body(body.as_bytes(), &[]).ends_with("..."));
+    }
+
+    #[cfg(feature = "log-body")]
+    #[derive(Debug)]
+    struct BodyPolicy(Vec<Result<&'static str, &'static str>>);
+
+    #[cfg(feature = "log-body")]
+    #[async_trait::async_trait]
+    impl Policy for BodyPolicy {
+        async fn send(
+            &self,
+            _ctx: &mut Context,
+            _request: &mut Request,
+            _next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            let chunks = self.0.iter().map(|chunk| match chunk {
+                Ok(chunk) => Ok(bytes::Bytes::from_static(chunk.as_bytes())),
+                Err(message) => Err(crate::Error::message(crate::ErrorKind::Io, *message)),
+            });
+            let mut headers = Headers::new();
+            headers.insert(headers::CONTENT_TYPE, "application/json");
+            Ok(crate::Response::new(
+                200,
+                headers,
+                Box::pin(futures::stream::iter(chunks.collect::<Vec<_>>())),
+            ))
+        }
+    }
+
+    #[cfg(feature = "log-body")]
+    async fn read_logged(
+        chunks: Vec<Result<&'static str, &'static str>>,
+    ) -> crate::Result<bytes::Bytes> {
+        let policy = LoggingPolicy::new(LogVerbosity::Body.into());
+        let mut ctx = Context::default();
+        let mut request = Request::new(url::Url::parse("https://localhost").unwrap(), "GET");
+        let next: Vec<Arc<dyn Policy>> = vec![Arc::new(BodyPolicy(chunks))];
+        let response = policy.send(&mut ctx, &mut request, &next).await?;
+        response.into_body().collect().await
+    }
+
+    #[cfg(feature = "log-body")]
+    #[tokio::test]
+    async fn leaves_body_read_errors_to_the_caller() {
+        let error = read_logged(vec![Ok(r#"{"id":"#), Err("connection reset")])
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), &crate::ErrorKind::Io);
+        assert_eq!(error.to_string(), "connection reset");
+    }
+
+    #[cfg(feature = "log-body")]
+    #[tokio::test]
+    async fn keeps_long_bodies_intact() {
+        let long = "a".repeat(MAX_BUFFERED_BODY_LENGTH).leak();
+        let body = read_logged(vec![Ok(r#"{"id":""#), Ok(long), Ok(long), Ok(r#""}"#)])
+            .await
+            .unwrap();
+        assert_eq!(body.len(), 2 * MAX_BUFFERED_BODY_LENGTH + 9);
+    }
+}
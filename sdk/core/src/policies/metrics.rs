@@ -0,0 +1,190 @@
+use crate::{
+    context::{Attempt, Context},
+    policies::{Policy, PolicyResult},
+    request::Request,
+    trace::{Namespace, OperationName},
+};
+use opentelemetry::{
+    global,
+    metrics::{Counter, Histogram, UpDownCounter},
+    KeyValue,
+};
+use std::{sync::Arc, time::Instant};
+
+/// Records OpenTelemetry metrics for each attempt to send a request using the global meter provider.
+#[derive(Clone, Debug)]
+pub(crate) struct MetricsPolicy {
+    duration: Histogram<f64>,
+    retries: Counter<u64>,
+    active_requests: UpDownCounter<i64>,
+}
+
+impl MetricsPolicy {
+    pub fn new() -> Self {
+        let meter = global::meter("azure_core");
+        Self {
+            duration: meter
+                .f64_histogram("http.client.request.duration")
+                .with_unit("s")
+                .with_description("Duration of HTTP client requests.")
+                .build(),
+            retries: meter
+                .u64_counter("az.client.retries")
+                .with_description("Number of requests resent after a failed attempt.")
+                .build(),
+            active_requests: meter
+                .i64_up_down_counter("http.client.active_requests")
+                .with_description("Number of HTTP client requests in flight.")
+                .build(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Policy for MetricsPolicy {
+    async fn send(
+        &self,
+        ctx: &mut Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        let mut attributes = vec![KeyValue::new("http.request.method", request.method())];
+        if let Some(host) = request.url().host_str() {
+            attributes.push(KeyValue::new("server.address", host.to_string()));
+        }
+        if let Some(namespace) = ctx.value::<Namespace>() {
+            attributes.push(KeyValue::new("az.namespace", namespace.as_str()));
+        }
+        if let Some(operation) = ctx.value::<OperationName>() {
+            attributes.push(KeyValue::new("az.operation", operation.name()));
+        }
+        if ctx.value::<Attempt>().is_some_and(Attempt::is_retry) {
+            self.retries.add(1, &attributes);
+        }
+
+        let active = ActiveRequest::new(&self.active_requests, attributes.clone());
+        let start = Instant::now();
+        let result = next[0].send(ctx, request, &next[1..]).await;
+        drop(active);
+
+        match &result {
+            Ok(response) => attributes.push(KeyValue::new(
+                "http.response.status_code",
+                i64::from(response.status()),
+            )),
+            Err(error) => attributes.push(KeyValue::new("error.type", error.kind().to_string())),
+        }
+        self.duration
+            .record(start.elapsed().as_secs_f64(), &attributes);
+
+        result
+    }
+}
+
+/// Counts a request as active until dropped, even if the request is canceled.
+struct ActiveRequest<'a> {
+    active_requests: &'a UpDownCounter<i64>,
+    attributes: Vec<KeyValue>,
+}
+
+impl<'a> ActiveRequest<'a> {
+    fn new(active_requests: &'a UpDownCounter<i64>, attributes: Vec<KeyValue>) -> Self {
+        active_requests.add(1, &attributes);
+        Self {
+            active_requests,
+            attributes,
+        }
+    }
+}
+
+impl Drop for ActiveRequest<'_> {
+    fn drop(&mut self) {
+        self.active_requests.add(-1, &self.attributes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::{Error, ErrorKind},
+        ResponseBuilder, Url,
+    };
+    use futures::FutureExt;
+    use opentelemetry::metrics::SyncInstrument;
+    use std::sync::Mutex;
+
+    #[derive(Debug, Default)]
+    struct Recorder<T>(Mutex<Vec<T>>);
+
+    impl<T: Send> SyncInstrument<T> for Recorder<T> {
+        fn measure(&self, measurement: T, _attributes: &[KeyValue]) {
+            self.0.lock().unwrap().push(measurement);
+        }
+    }
+
+    #[derive(Debug)]
+    enum Next {
+        Ok,
+        Err,
+        Pending,
+    }
+
+    #[async_trait::async_trait]
+    impl Policy for Next {
+        async fn send(
+            &self,
+            _ctx: &mut Context,
+            _request: &mut Request,
+            _next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            match self {
+                Next::Ok => Ok(ResponseBuilder::new(200).build()),
+                Next::Err => Err(Error::message(ErrorKind::Io, "connection reset")),
+                Next::Pending => futures::future::pending().await,
+            }
+        }
+    }
+
+    fn policy() -> (MetricsPolicy, Arc<Recorder<i64>>) {
+        let active = Arc::new(Recorder::default());
+        let policy = MetricsPolicy {
+            duration: Histogram::new(Arc::new(Recorder::<f64>::default())),
+            retries: Counter::new(Arc::new(Recorder::<u64>::default())),
+            active_requests: UpDownCounter::new(active.clone()),
+        };
+        (policy, active)
+    }
+
+    async fn send(policy: &MetricsPolicy, next: Next) -> PolicyResult {
+        let mut ctx = Context::default();
+        let mut request = Request::new(Url::parse("https://localhost").unwrap(), "GET");
+        let next: Vec<Arc<dyn Policy>> = vec![Arc::new(next)];
+        policy.send(&mut ctx, &mut request, &next).await
+    }
+
+    #[tokio::test]
+    async fn active_requests_on_success() {
+        let (policy, active) = policy();
+        send(&policy, Next::Ok).await.unwrap();
+        assert_eq!(*active.0.lock().unwrap(), vec![1, -1]);
+    }
+
+    #[tokio::test]
+    async fn active_requests_on_error() {
+        let (policy, active) = policy();
+        send(&policy, Next::Err).await.unwrap_err();
+        assert_eq!(*active.0.lock().unwrap(), vec![1, -1]);
+    }
+
+    #[tokio::test]
+    async fn active_requests_on_cancel() {
+        let (policy, active) = policy();
+        let mut request = send(&policy, Next::Pending).boxed();
+        assert!((&mut request).now_or_never().is_none());
+        assert_eq!(*active.0.lock().unwrap(), vec![1]);
+
+        drop(request);
+        assert_eq!(*active.0.lock().unwrap(), vec![1, -1]);
+    }
+}
@@ -2,11 +2,35 @@ use crate::{context::Context, request::Request, response::Response};
 use std::sync::Arc;
 
 mod auth;
+mod chaos;
+mod concurrency;
 mod custom_header;
+mod failover;
+mod hedging;
+mod host_override;
+mod instrumentation;
+mod interceptor;
+#[cfg(any(feature = "log", feature = "tracing"))]
+mod logging;
+#[cfg(feature = "opentelemetry")]
+mod metrics;
+mod retry;
 mod transport;
 
 pub use auth::*;
+pub use chaos::*;
+pub(crate) use concurrency::*;
 pub use custom_header::*;
+pub(crate) use failover::*;
+pub(crate) use hedging::*;
+pub(crate) use host_override::*;
+pub(crate) use instrumentation::*;
+pub use interceptor::*;
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub(crate) use logging::*;
+#[cfg(feature = "opentelemetry")]
+pub(crate) use metrics::*;
+pub(crate) use retry::*;
 pub use transport::*;
 
 pub type PolicyResult = crate::error::Result<Response>;
@@ -19,4 +43,173 @@ pub trait Policy: Send + Sync + std::fmt::Debug {
         request: &mut Request,
         next: &[Arc<dyn Policy>],
     ) -> PolicyResult;
+
+    /// Gets the name of the policy reported by [`Pipeline::diagnostics`](crate::Pipeline::diagnostics),
+    /// which defaults to the name of the type without its module path.
+    fn name(&self) -> &'static str {
+        let name = std::any::type_name::<Self>();
+        let name = name.split('<').next().unwrap_or(name);
+        name.rsplit("::").next().unwrap_or(name)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{
+        context::Attempt,
+        sleep::sleep,
+        stream::{BodyStream, BytesStream},
+        ResponseBuilder, Url,
+    };
+    use bytes::Bytes;
+    use std::{
+        fmt,
+        pin::Pin,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Mutex,
+        },
+        task::{self, Poll},
+        time::Duration,
+    };
+
+    type Respond = Box<dyn Fn(&Request, usize) -> ResponseBuilder + Send + Sync>;
+
+    /// Responds to requests instead of sending them, and records what it was sent.
+    pub(crate) struct MockPolicy {
+        respond: Respond,
+        delays: Vec<Duration>,
+        calls: AtomicUsize,
+        completed: AtomicUsize,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+        attempts: Mutex<Vec<u32>>,
+        requests: Mutex<Vec<Request>>,
+    }
+
+    impl MockPolicy {
+        /// Responds to each request with `status` and an empty body.
+        pub(crate) fn new(status: u16) -> Self {
+            Self::statuses(&[status])
+        }
+
+        /// Responds to each call with the next status, repeating the last one.
+        pub(crate) fn statuses(statuses: &[u16]) -> Self {
+            let statuses = statuses.to_vec();
+            Self::respond(move |_, call| {
+                ResponseBuilder::new(statuses[call.min(statuses.len() - 1)])
+            })
+        }
+
+        /// Responds with the response built from the request and the 0-based number of the call.
+        pub(crate) fn respond(
+            respond: impl Fn(&Request, usize) -> ResponseBuilder + Send + Sync + 'static,
+        ) -> Self {
+            Self {
+                respond: Box::new(respond),
+                delays: Vec::new(),
+                calls: AtomicUsize::new(0),
+                completed: AtomicUsize::new(0),
+                in_flight: AtomicUsize::new(0),
+                max_in_flight: AtomicUsize::new(0),
+                attempts: Mutex::default(),
+                requests: Mutex::default(),
+            }
+        }
+
+        /// Waits the given milliseconds before responding to each call, repeating the last delay.
+        pub(crate) fn with_delays(mut self, delays: &[u64]) -> Self {
+            self.delays = delays.iter().copied().map(Duration::from_millis).collect();
+            self
+        }
+
+        pub(crate) fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+
+        /// How many calls responded, i.e., were not canceled while waiting.
+        pub(crate) fn completed(&self) -> usize {
+            self.completed.load(Ordering::SeqCst)
+        }
+
+        pub(crate) fn max_in_flight(&self) -> usize {
+            self.max_in_flight.load(Ordering::SeqCst)
+        }
+
+        /// The [`Attempt`] numbers of the calls which had one.
+        pub(crate) fn attempts(&self) -> Vec<u32> {
+            self.attempts.lock().unwrap().clone()
+        }
+
+        pub(crate) fn requests(&self) -> Vec<Request> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    impl fmt::Debug for MockPolicy {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("MockPolicy")
+                .field("calls", &self.calls)
+                .finish_non_exhaustive()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Policy for MockPolicy {
+        async fn send(
+            &self,
+            ctx: &mut Context,
+            request: &mut Request,
+            _next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if let Some(attempt) = ctx.value::<Attempt>() {
+                self.attempts.lock().unwrap().push(attempt.number());
+            }
+            self.requests.lock().unwrap().push(request.clone());
+
+            if let Some(delay) = self.delays.get(call).or(self.delays.last()) {
+                let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+                sleep(*delay).await;
+                self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            }
+            self.completed.fetch_add(1, Ordering::SeqCst);
+            Ok((self.respond)(request, call).build())
+        }
+    }
+
+    /// Sends a request with `method` to `url` through `policy`, followed by `next`.
+    pub(crate) async fn send(
+        policy: &dyn Policy,
+        method: &'static str,
+        url: &str,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        let mut ctx = Context::default();
+        let mut request = Request::new(Url::parse(url).unwrap(), method);
+        policy.send(&mut ctx, &mut request, next).await
+    }
+
+    /// A stream which cannot be rewound.
+    #[derive(Debug)]
+    pub(crate) struct OnceStream(pub(crate) BytesStream);
+
+    impl futures::Stream for OnceStream {
+        type Item = crate::Result<Bytes>;
+
+        fn poll_next(
+            mut self: Pin<&mut Self>,
+            cx: &mut task::Context<'_>,
+        ) -> Poll<Option<Self::Item>> {
+            Pin::new(&mut self.0).poll_next(cx)
+        }
+    }
+
+    impl BodyStream for OnceStream {
+        fn len(&self) -> usize {
+            self.0.len()
+        }
+    }
 }
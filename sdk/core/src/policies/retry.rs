@@ -0,0 +1,509 @@
+use crate::{
+    context::{Attempt, Context, Idempotent},
+    error::{Error, ErrorKind},
+    http_error::HttpError,
+    options::RetryOptions,
+    policies::{Policy, PolicyResult},
+    request::{Body, Request},
+    sleep::sleep,
+};
+use std::{io, sync::Arc};
+
+/// Retries requests that failed with transient errors according to [`RetryOptions`].
+///
+/// Responses with non-success status codes are converted into [`ErrorKind::HttpResponse`](crate::ErrorKind::HttpResponse) errors.
+///
+/// Operations which are not [`Idempotent`] are only retried after errors where the service may
+/// have applied the request if [`RetryOptions::retry_non_idempotent`].
+///
+/// Streamed bodies are rewound before each retry, so requests whose bodies are not a
+/// [`SeekableBody`](crate::stream::SeekableBody) are not retried.
+#[derive(Clone, Debug)]
+pub(crate) struct RetryPolicy {
+    options: RetryOptions,
+}
+
+impl RetryPolicy {
+    pub fn new(options: RetryOptions) -> Self {
+        Self { options }
+    }
+}
+
+#[async_trait::async_trait]
+impl Policy for RetryPolicy {
+    async fn send(
+        &self,
+        ctx: &mut Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        let mut retrier = self.options.retrier();
+        let retryable = self.options.retry_non_idempotent()
+            || ctx
+                .value::<Idempotent>()
+                .is_some_and(Idempotent::is_idempotent);
+
+        loop {
+            let number = retrier.retries() + 1;
+            ctx.insert(Attempt::new(number));
+            let mut attempt = request.clone();
+            let error = match next[0].send(ctx, &mut attempt, &next[1..]).await {
+                Ok(mut response) if (200..300).contains(&response.status()) => {
+                    response.set_attempt(number);
+                    return Ok(response);
+                }
+                Ok(response) => Error::from(HttpError::new(response).await),
+                Err(error) => error,
+            };
+
+            if !retryable && may_have_been_applied(&error) {
+                return Err(error);
+            }
+            let Some(delay) = retrier.next_delay(&error) else {
+                return Err(error);
+            };
+            if let Body::Stream(body) = request.body() {
+                if let Err(rewind_error) = body.rewind().await {
+                    let mut error = error;
+                    error.add_note(format!("the request was not retried: {rewind_error}"));
+                    return Err(error);
+                }
+            }
+            sleep(delay).await;
+        }
+    }
+}
+
+/// Whether the service may have applied a request which failed with `error`, i.e., it responded
+/// with a server error or the connection failed after it was established.
+fn may_have_been_applied(error: &Error) -> bool {
+    match error.kind() {
+        ErrorKind::HttpResponse { status, .. } => *status >= 500,
+        ErrorKind::Io => !is_connect_error(error),
+        _ => false,
+    }
+}
+
+/// Whether `error` was caused by failing to connect, so the request was never sent.
+fn is_connect_error(error: &Error) -> bool {
+    let mut source = error
+        .get_ref()
+        .map(|error| error as &(dyn std::error::Error + 'static));
+    while let Some(error) = source {
+        if let Some(error) = error.downcast_ref::<std::io::Error>() {
+            if matches!(
+                error.kind(),
+                io::ErrorKind::ConnectionRefused | io::ErrorKind::AddrNotAvailable
+            ) {
+                return true;
+            }
+        }
+        #[cfg(feature = "reqwest")]
+        if error
+            .downcast_ref::<::reqwest::Error>()
+            .is_some_and(::reqwest::Error::is_connect)
+        {
+            return true;
+        }
+        #[cfg(feature = "hyper")]
+        if error
+            .downcast_ref::<hyper_util::client::legacy::Error>()
+            .is_some_and(hyper_util::client::legacy::Error::is_connect)
+        {
+            return true;
+        }
+        source = error.source();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        headers::HeaderName,
+        options::{self, ExponentialRetryOptions, FixedRetryOptions},
+        policies::tests::{self, MockPolicy, OnceStream},
+        stream::{BytesStream, StreamBody},
+        ResponseBuilder, Url,
+    };
+    use bytes::Bytes;
+    use futures::TryStreamExt;
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Mutex,
+        },
+        time::Duration,
+    };
+
+    fn options(max_retries: u32) -> RetryOptions {
+        RetryOptions::exponential(ExponentialRetryOptions {
+            initial_delay: Duration::from_millis(1),
+            max_retries,
+            ..Default::default()
+        })
+    }
+
+    async fn send(policy: RetryPolicy, transport: Arc<MockPolicy>) -> PolicyResult {
+        tests::send(&policy, "GET", "https://localhost", &[transport]).await
+    }
+
+    #[tokio::test]
+    async fn on_retry_called_per_attempt() {
+        let attempts = Arc::new(Mutex::new(Vec::new()));
+        let mut options = options(3);
+        options.set_on_retry({
+            let attempts = attempts.clone();
+            move |attempt| {
+                attempts.lock().unwrap().push((
+                    attempt.attempt,
+                    attempt.delay,
+                    attempt.cause.kind().clone(),
+                ));
+            }
+        });
+
+        let transport = Arc::new(MockPolicy::statuses(&[503, 429, 200]));
+        let response = send(RetryPolicy::new(options), transport.clone())
+            .await
+            .expect("expected response");
+
+        assert_eq!(response.status(), 200);
+        assert_eq!(transport.calls(), 3);
+        assert_eq!(
+            *attempts.lock().unwrap(),
+            vec![
+                (
+                    1,
+                    Duration::from_millis(1),
+                    ErrorKind::HttpResponse {
+                        status: 503,
+                        error_code: None
+                    }
+                ),
+                (
+                    2,
+                    Duration::from_millis(2),
+                    ErrorKind::HttpResponse {
+                        status: 429,
+                        error_code: None
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn stamps_attempt_number() {
+        let transport = Arc::new(MockPolicy::statuses(&[503, 500, 200]));
+        let response = send(RetryPolicy::new(options(3)), transport.clone())
+            .await
+            .expect("expected response");
+
+        assert_eq!(response.attempt(), Some(3));
+        assert_eq!(transport.attempts(), [1, 2, 3]);
+    }
+
+    /// Reads the whole body, like a transport, before returning the next status.
+    #[derive(Debug)]
+    struct ReadingPolicy(MockPolicy, Mutex<Vec<Bytes>>);
+
+    #[async_trait::async_trait]
+    impl Policy for ReadingPolicy {
+        async fn send(
+            &self,
+            ctx: &mut Context,
+            request: &mut Request,
+            next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            if let Body::Stream(body) = request.body() {
+                let chunks: Vec<Bytes> = body.reader().try_collect().await?;
+                self.1.lock().unwrap().push(chunks.concat().into());
+            }
+            self.0.send(ctx, request, next).await
+        }
+    }
+
+    async fn send_stream(body: StreamBody, statuses: &[u16]) -> (PolicyResult, Vec<Bytes>) {
+        let transport = Arc::new(ReadingPolicy(
+            MockPolicy::statuses(statuses),
+            Mutex::default(),
+        ));
+        let mut ctx = Context::default();
+        let mut request = Request::new(Url::parse("https://localhost").unwrap(), "PUT");
+        request.set_body(body);
+        let next: Vec<Arc<dyn Policy>> = vec![transport.clone()];
+        let result = RetryPolicy::new(options(3))
+            .send(&mut ctx, &mut request, &next)
+            .await;
+        let bodies = transport.1.lock().unwrap().clone();
+        (result, bodies)
+    }
+
+    #[tokio::test]
+    async fn rewinds_streamed_bodies() {
+        let (result, bodies) =
+            send_stream(StreamBody::new(BytesStream::new("data")), &[503, 200]).await;
+        assert_eq!(result.expect("expected response").status(), 200);
+        assert_eq!(bodies, ["data", "data"]);
+
+        let (result, bodies) = send_stream(
+            StreamBody::new(OnceStream(BytesStream::new("data"))),
+            &[503, 200],
+        )
+        .await;
+        let error = result.expect_err("expected error");
+        // The error is still that of the attempt, explaining why it was not retried.
+        assert_eq!(
+            error.kind(),
+            &ErrorKind::HttpResponse {
+                status: 503,
+                error_code: None
+            }
+        );
+        assert!(error.to_string().contains("cannot be rewound"), "{error}");
+        assert_eq!(
+            error.downcast_ref::<HttpError>().map(HttpError::status),
+            Some(503)
+        );
+        assert_eq!(bodies, ["data"]);
+    }
+
+    /// Throttles the first request with the given headers.
+    fn throttling(headers: &[(HeaderName, &'static str)]) -> MockPolicy {
+        let headers = headers.to_vec();
+        MockPolicy::respond(move |_, call| {
+            if call > 0 {
+                return ResponseBuilder::new(200);
+            }
+            let mut response = ResponseBuilder::new(429);
+            for (name, value) in &headers {
+                response.with_header(name.clone(), *value);
+            }
+            response
+        })
+    }
+
+    async fn retry_delays(throttling: MockPolicy) -> Vec<Duration> {
+        let delays = Arc::new(Mutex::new(Vec::new()));
+        let mut options = options(3);
+        options.set_on_retry({
+            let delays = delays.clone();
+            move |attempt| delays.lock().unwrap().push(attempt.delay)
+        });
+
+        let mut ctx = Context::default();
+        let mut request = Request::new(Url::parse("https://localhost").unwrap(), "GET");
+        let next: Vec<Arc<dyn Policy>> = vec![Arc::new(throttling)];
+        let response = RetryPolicy::new(options)
+            .send(&mut ctx, &mut request, &next)
+            .await
+            .expect("expected response");
+
+        assert_eq!(response.status(), 200);
+        let delays = delays.lock().unwrap().clone();
+        delays
+    }
+
+    #[tokio::test]
+    async fn waits_for_retry_after() {
+        let throttling = throttling(&[(crate::headers::X_MS_RETRY_AFTER_MS, "5")]);
+        assert_eq!(retry_delays(throttling).await, [Duration::from_millis(5)]);
+    }
+
+    #[tokio::test]
+    async fn waits_for_retry_after_date() {
+        // The service's clock is decades behind, so the delay must be relative to its `Date`.
+        let throttling = throttling(&[
+            (crate::headers::RETRY_AFTER, "Sun, 06 Nov 1994 08:49:38 GMT"),
+            (crate::headers::DATE, "Sun, 06 Nov 1994 08:49:37 GMT"),
+        ]);
+        assert_eq!(retry_delays(throttling).await, [Duration::from_secs(1)]);
+    }
+
+    #[tokio::test]
+    async fn exhausted_retries_return_last_error() {
+        let transport = Arc::new(MockPolicy::statuses(&[500]));
+        let error = send(RetryPolicy::new(options(2)), transport.clone())
+            .await
+            .expect_err("expected error");
+
+        assert_eq!(transport.calls(), 3);
+        assert_eq!(
+            error.kind(),
+            &ErrorKind::HttpResponse {
+                status: 500,
+                error_code: None
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn non_retryable_status_not_retried() {
+        let transport = Arc::new(MockPolicy::statuses(&[404]));
+        let error = send(RetryPolicy::new(options(2)), transport.clone())
+            .await
+            .expect_err("expected error");
+
+        assert_eq!(transport.calls(), 1);
+        assert_eq!(
+            error.kind(),
+            &ErrorKind::HttpResponse {
+                status: 404,
+                error_code: None
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn non_idempotent_not_retried() {
+        let mut options = options(2);
+        options.set_retry_non_idempotent(false);
+
+        let transport = Arc::new(MockPolicy::statuses(&[503, 200]));
+        let error = send(RetryPolicy::new(options.clone()), transport.clone())
+            .await
+            .expect_err("expected error");
+        assert_eq!(error.status(), Some(503));
+        assert_eq!(transport.calls(), 1);
+
+        let transport = Arc::new(MockPolicy::statuses(&[503, 200]));
+        let mut ctx = Context::default();
+        ctx.insert(Idempotent::from(true));
+        let mut request = Request::new(Url::parse("https://localhost").unwrap(), "PUT");
+        let next: Vec<Arc<dyn Policy>> = vec![transport.clone()];
+        let response = RetryPolicy::new(options)
+            .send(&mut ctx, &mut request, &next)
+            .await
+            .expect("expected response");
+        assert_eq!(response.status(), 200);
+        assert_eq!(transport.calls(), 2);
+    }
+
+    /// Fails the first attempt with an I/O error of `kind`, then responds with 200.
+    #[derive(Debug)]
+    struct IoErrorPolicy(io::ErrorKind, AtomicUsize);
+
+    #[async_trait::async_trait]
+    impl Policy for IoErrorPolicy {
+        async fn send(
+            &self,
+            _ctx: &mut Context,
+            _request: &mut Request,
+            _next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            if self.1.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Err(Error::full(
+                    ErrorKind::Io,
+                    io::Error::from(self.0),
+                    "failed to execute request",
+                ));
+            }
+            Ok(ResponseBuilder::new(200).build())
+        }
+    }
+
+    #[tokio::test]
+    async fn non_idempotent_retried_if_not_applied() {
+        let mut options = options(2);
+        options.set_retry_non_idempotent(false);
+        let mut request = Request::new(Url::parse("https://localhost").unwrap(), "PUT");
+
+        for status in [429, 408] {
+            let transport = Arc::new(MockPolicy::statuses(&[status, 200]));
+            let next: Vec<Arc<dyn Policy>> = vec![transport.clone()];
+            let response = RetryPolicy::new(options.clone())
+                .send(&mut Context::default(), &mut request, &next)
+                .await
+                .expect("expected response");
+            assert_eq!(response.status(), 200);
+            assert_eq!(transport.calls(), 2, "{status}");
+        }
+
+        let transport = Arc::new(IoErrorPolicy(
+            io::ErrorKind::ConnectionRefused,
+            AtomicUsize::new(0),
+        ));
+        let next: Vec<Arc<dyn Policy>> = vec![transport.clone()];
+        let response = RetryPolicy::new(options.clone())
+            .send(&mut Context::default(), &mut request, &next)
+            .await
+            .expect("expected response");
+        assert_eq!(response.status(), 200);
+
+        // The connection was established, so the service may have received the request.
+        let transport = Arc::new(IoErrorPolicy(
+            io::ErrorKind::ConnectionReset,
+            AtomicUsize::new(0),
+        ));
+        let next: Vec<Arc<dyn Policy>> = vec![transport.clone()];
+        let error = RetryPolicy::new(options)
+            .send(&mut Context::default(), &mut request, &next)
+            .await
+            .expect_err("expected error");
+        assert_eq!(error.kind(), &ErrorKind::Io);
+        assert_eq!(transport.1.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn none_not_retried() {
+        let transport = Arc::new(MockPolicy::statuses(&[503, 200]));
+        let error = send(RetryPolicy::new(RetryOptions::none()), transport.clone())
+            .await
+            .expect_err("expected error");
+
+        assert_eq!(transport.calls(), 1);
+        assert_eq!(error.status(), Some(503));
+    }
+
+    #[tokio::test]
+    async fn fixed_delay() {
+        let delays = Arc::new(Mutex::new(Vec::new()));
+        let mut options = RetryOptions::from(FixedRetryOptions {
+            delay: Duration::from_millis(1),
+            max_retries: 3,
+            ..Default::default()
+        });
+        options.set_on_retry({
+            let delays = delays.clone();
+            move |attempt| delays.lock().unwrap().push(attempt.delay)
+        });
+
+        let transport = Arc::new(MockPolicy::statuses(&[500]));
+        send(RetryPolicy::new(options), transport.clone())
+            .await
+            .expect_err("expected error");
+
+        assert_eq!(transport.calls(), 4);
+        assert_eq!(*delays.lock().unwrap(), vec![Duration::from_millis(1); 3]);
+    }
+
+    #[derive(Debug)]
+    struct NotFoundPolicy;
+
+    impl options::RetryPolicy for NotFoundPolicy {
+        fn retry_delay(&self, error: &Error, retry: u32, _elapsed: Duration) -> Option<Duration> {
+            (error.is_not_found() && retry <= 2).then_some(Duration::from_millis(1))
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_policy() {
+        let options = RetryOptions::custom(Arc::new(NotFoundPolicy));
+
+        let transport = Arc::new(MockPolicy::statuses(&[404, 404, 200]));
+        let response = send(RetryPolicy::new(options.clone()), transport.clone())
+            .await
+            .expect("expected response");
+        assert_eq!(response.status(), 200);
+        assert_eq!(transport.calls(), 3);
+
+        let transport = Arc::new(MockPolicy::statuses(&[503, 200]));
+        let error = send(RetryPolicy::new(options), transport.clone())
+            .await
+            .expect_err("expected error");
+        assert_eq!(error.status(), Some(503));
+        assert_eq!(transport.calls(), 1);
+    }
+}
@@ -0,0 +1,570 @@
+use crate::{
+    context::Context,
+    error::{Error, ErrorKind},
+    headers::{retry_after, Headers},
+    json::from_json,
+    pipeline::Pipeline,
+    request::Request,
+    response::Response,
+    sleep::sleep,
+};
+use futures::{future::BoxFuture, stream, Future, FutureExt, Stream};
+use serde::Deserialize;
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+mod strategy;
+mod terminal;
+pub use strategy::*;
+pub use terminal::*;
+
+const DEFAULT_POLLING_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The status of a long-running operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PollerStatus {
+    InProgress,
+    Succeeded,
+    Failed,
+    Canceled,
+}
+
+impl PollerStatus {
+    /// Whether the operation has finished and will not be polled again.
+    pub fn is_terminal(&self) -> bool {
+        !matches!(self, PollerStatus::InProgress)
+    }
+}
+
+impl From<&str> for PollerStatus {
+    fn from(status: &str) -> Self {
+        match status.to_ascii_lowercase().as_str() {
+            "succeeded" => PollerStatus::Succeeded,
+            "failed" => PollerStatus::Failed,
+            "canceled" | "cancelled" => PollerStatus::Canceled,
+            _ => PollerStatus::InProgress,
+        }
+    }
+}
+
+/// The status of a long-running operation returned each time it is polled.
+#[derive(Clone, Debug)]
+pub struct StatusUpdate<T> {
+    status: PollerStatus,
+    percent_complete: Option<f64>,
+    value: Option<T>,
+    headers: Headers,
+}
+
+impl<T> StatusUpdate<T> {
+    pub fn new(status: PollerStatus, value: Option<T>, headers: Headers) -> Self {
+        Self {
+            status,
+            percent_complete: None,
+            value,
+            headers,
+        }
+    }
+
+    pub fn status(&self) -> PollerStatus {
+        self.status
+    }
+
+    /// Gets the percentage of the operation completed, from 0 to 100, if the service reported it.
+    pub fn percent_complete(&self) -> Option<f64> {
+        self.percent_complete
+    }
+
+    pub fn set_percent_complete(&mut self, percent_complete: impl Into<f64>) {
+        self.percent_complete = Some(percent_complete.into());
+    }
+
+    /// Gets the value of the operation, typically only present once it has succeeded.
+    pub fn value(&self) -> Option<&T> {
+        self.value.as_ref()
+    }
+
+    pub fn into_value(self) -> Option<T> {
+        self.value
+    }
+
+    /// Gets the headers of the response which returned this status.
+    pub fn headers(&self) -> &Headers {
+        &self.headers
+    }
+
+    /// Deserializes a status from a status monitor response body following Azure REST API guidelines,
+    /// with a `status` and an optional `percentComplete`.
+    pub async fn from_response(response: Response) -> crate::Result<Self> {
+        let headers = response.headers().clone();
+        let body: StatusBody = response.json().await?;
+        let mut update = Self::new(PollerStatus::from(body.status.as_str()), None, headers);
+        update.percent_complete = body.percent_complete;
+        Ok(update)
+    }
+}
+
+#[derive(Deserialize)]
+struct Progress {
+    #[serde(rename = "percentComplete")]
+    percent_complete: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct StatusBody {
+    status: String,
+    #[serde(rename = "percentComplete")]
+    percent_complete: Option<f64>,
+}
+
+type PollFn<T> = Box<dyn Fn() -> BoxFuture<'static, crate::Result<StatusUpdate<T>>> + Send + Sync>;
+type CancelFn = Box<dyn Fn() -> BoxFuture<'static, crate::Result<()>> + Send + Sync>;
+
+/// A long-running operation which is polled until it reaches a terminal [`PollerStatus`].
+pub struct Poller<T> {
+    poll: PollFn<T>,
+    cancel: PollerCancel,
+    interval: Duration,
+}
+
+/// Cancels a [`Poller`] from anywhere, e.g., from another task while it waits for completion.
+///
+/// Clones cancel the same operation, and every clone sees the request set by
+/// [`Poller::set_cancel`], even if it is set after the handle was created.
+#[derive(Clone, Default)]
+pub struct PollerCancel(Arc<CancelState>);
+
+#[derive(Default)]
+struct CancelState {
+    cancel: Mutex<Option<Arc<CancelFn>>>,
+    canceled: AtomicBool,
+}
+
+impl PollerCancel {
+    /// Requests the service cancel the operation.
+    ///
+    /// Once the request succeeds, the poller reports [`PollerStatus::Canceled`] without polling
+    /// the service again. Returns an error if the operation does not support cancellation.
+    pub async fn cancel(&self) -> crate::Result<()> {
+        let cancel = self
+            .0
+            .cancel
+            .lock()
+            .expect("poller cancel lock")
+            .clone()
+            .ok_or_else(|| {
+                Error::message(ErrorKind::Other, "operation does not support cancellation")
+            })?;
+        cancel().await?;
+        self.0.canceled.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Whether the operation has been canceled.
+    pub fn is_canceled(&self) -> bool {
+        self.0.canceled.load(Ordering::SeqCst)
+    }
+}
+
+impl fmt::Debug for PollerCancel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PollerCancel")
+            .field("canceled", &self.is_canceled())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Poller<T>
+where
+    T: Send + 'static,
+{
+    /// Creates a `Poller` which calls `poll` to get the current status of the operation.
+    pub fn new<F, Fut>(poll: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = crate::Result<StatusUpdate<T>>> + Send + 'static,
+    {
+        // Polls are children of the span current when the poller was created.
+        #[cfg(feature = "tracing")]
+        let poll = {
+            use tracing::Instrument;
+
+            let parent = tracing::Span::current();
+            move || poll().instrument(parent.clone())
+        };
+
+        Self {
+            poll: Box::new(move || poll().boxed()),
+            cancel: PollerCancel::default(),
+            interval: DEFAULT_POLLING_INTERVAL,
+        }
+    }
+
+    /// Sets how long to wait between polls when the service does not return a `Retry-After` header.
+    pub fn set_polling_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// Sets the request sent by [`Poller::cancel`] for operations the service supports canceling.
+    pub fn set_cancel<F, Fut>(&mut self, cancel: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = crate::Result<()>> + Send + 'static,
+    {
+        let cancel: CancelFn = Box::new(move || cancel().boxed());
+        *self.cancel.0.cancel.lock().expect("poller cancel lock") = Some(Arc::new(cancel));
+    }
+
+    /// Gets a handle which cancels the operation, e.g., from another task while waiting for it
+    /// to complete.
+    pub fn cancel_handle(&self) -> PollerCancel {
+        self.cancel.clone()
+    }
+
+    /// Requests the service cancel the operation.
+    ///
+    /// See [`PollerCancel::cancel`].
+    pub async fn cancel(&self) -> crate::Result<()> {
+        self.cancel.cancel().await
+    }
+
+    /// Polls the current status of the operation once.
+    pub async fn poll_once(&self) -> crate::Result<StatusUpdate<T>> {
+        if self.cancel.is_canceled() {
+            return Ok(StatusUpdate::new(
+                PollerStatus::Canceled,
+                None,
+                Headers::new(),
+            ));
+        }
+        (self.poll)().await
+    }
+
+    /// Polls the operation until it reaches a terminal status and returns its value.
+    ///
+    /// Returns an error if polling fails, the operation failed or was canceled, or it succeeded
+    /// without a value.
+    pub async fn wait_for_completion(self) -> crate::Result<T> {
+        loop {
+            let update = self.poll_once().await?;
+            match update.status {
+                PollerStatus::InProgress => sleep(self.delay(&update)).await,
+                PollerStatus::Succeeded => {
+                    return update.value.ok_or_else(|| {
+                        Error::message(ErrorKind::Other, "operation succeeded without a value")
+                    })
+                }
+                PollerStatus::Failed => {
+                    return Err(Error::message(ErrorKind::Other, "operation failed"))
+                }
+                PollerStatus::Canceled => {
+                    return Err(Error::message(ErrorKind::Other, "operation was canceled"))
+                }
+            }
+        }
+    }
+
+    /// Gets a stream of each status until the operation reaches a terminal status or polling fails.
+    pub fn into_stream(self) -> impl Stream<Item = crate::Result<StatusUpdate<T>>> + Send {
+        stream::unfold(Some((self, Duration::ZERO)), |state| async move {
+            let (poller, delay) = state?;
+            sleep(delay).await;
+
+            match poller.poll_once().await {
+                Ok(update) if update.status.is_terminal() => Some((Ok(update), None)),
+                Ok(update) => {
+                    let delay = poller.delay(&update);
+                    Some((Ok(update), Some((poller, delay))))
+                }
+                Err(error) => Some((Err(error), None)),
+            }
+        })
+    }
+
+    fn delay(&self, update: &StatusUpdate<T>) -> Duration {
+        retry_after(&update.headers).unwrap_or(self.interval)
+    }
+}
+
+impl<T> Poller<T>
+where
+    T: TerminalState + Send + 'static,
+{
+    /// Creates a `Poller` for the operation started by `request` using the first of the
+    /// [`default_strategies`] which recognizes the initial `response`.
+    pub fn from_response(
+        pipeline: Pipeline,
+        ctx: &Context,
+        request: &Request,
+        response: &Response,
+    ) -> crate::Result<Self> {
+        Self::with_strategies(pipeline, ctx, request, response, &default_strategies())
+    }
+
+    /// Creates a `Poller` for the operation started by `request` using the first of the
+    /// `strategies` which recognizes the initial `response`.
+    pub fn with_strategies(
+        pipeline: Pipeline,
+        ctx: &Context,
+        request: &Request,
+        response: &Response,
+        strategies: &[Arc<dyn PollingStrategy>],
+    ) -> crate::Result<Self> {
+        let (strategy, urls) = strategies
+            .iter()
+            .find_map(|strategy| {
+                Some((
+                    strategy.clone(),
+                    strategy.start(request, response.headers())?,
+                ))
+            })
+            .ok_or_else(|| {
+                Error::message(
+                    ErrorKind::Other,
+                    "response does not describe a long-running operation",
+                )
+            })?;
+
+        let ctx = ctx.clone();
+        Ok(Self::new(move || {
+            let pipeline = pipeline.clone();
+            let mut ctx = ctx.clone();
+            let strategy = strategy.clone();
+            let urls = urls.clone();
+            async move {
+                let mut request = Request::new(urls.monitor, "GET");
+                let response = pipeline.send(&mut ctx, &mut request).await?;
+                let status_code = response.status();
+                let headers = response.headers().clone();
+                let body = response.into_body().collect().await?;
+
+                let status = strategy.status(status_code, &body)?;
+                let value = match (status, urls.resource) {
+                    (PollerStatus::Succeeded, Some(resource)) => {
+                        let mut request = Request::new(resource, "GET");
+                        let response = pipeline.send(&mut ctx, &mut request).await?;
+                        let headers = response.headers().clone();
+                        let body = response.into_body().collect().await?;
+                        Some(T::from_success(&headers, &body)?)
+                    }
+                    (PollerStatus::Succeeded, None) => Some(T::from_success(&headers, &body)?),
+                    (PollerStatus::Failed, _) => {
+                        return Err(T::from_failure(OperationFailure::new(
+                            status_code,
+                            headers,
+                            &body,
+                        )))
+                    }
+                    _ => None,
+                };
+
+                let mut update = StatusUpdate::new(status, value, headers);
+                update.percent_complete = from_json::<_, Progress>(&body)
+                    .ok()
+                    .and_then(|progress| progress.percent_complete);
+                Ok(update)
+            }
+        }))
+    }
+}
+
+impl<T> fmt::Debug for Poller<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Poller")
+            .field("canceled", &self.cancel.is_canceled())
+            .field("interval", &self.interval)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        headers,
+        options::{ClientOptions, RetryOptions, TransportOptions},
+        stream::BytesStream,
+        transport::HttpClient,
+        Url,
+    };
+    use bytes::Bytes;
+    use futures::TryStreamExt;
+    use std::sync::{atomic::AtomicU32, Mutex};
+
+    #[tokio::test]
+    async fn into_stream() {
+        let polls = Arc::new(AtomicU32::new(0));
+        let mut poller = Poller::new(move || {
+            let poll = polls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                let (status, value) = match poll {
+                    2 => (PollerStatus::Succeeded, Some("done")),
+                    _ => (PollerStatus::InProgress, None),
+                };
+                let mut update = StatusUpdate::new(status, value, Headers::new());
+                update.set_percent_complete(poll * 50);
+                Ok(update)
+            }
+        });
+        poller.set_polling_interval(Duration::ZERO);
+
+        let updates: Vec<_> = poller.into_stream().try_collect().await.unwrap();
+        let progress: Vec<_> = updates.iter().map(StatusUpdate::percent_complete).collect();
+        assert_eq!(progress, vec![Some(0.0), Some(50.0), Some(100.0)]);
+        assert_eq!(updates[2].value(), Some(&"done"));
+    }
+
+    #[tokio::test]
+    async fn wait_for_completion() {
+        let polls = Arc::new(AtomicU32::new(0));
+        let poller = Poller::new(move || {
+            let poll = polls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                let mut headers = Headers::new();
+                headers.insert(headers::RETRY_AFTER_MS, "1");
+                Ok(match poll {
+                    0 => StatusUpdate::new(PollerStatus::InProgress, None, headers),
+                    _ => StatusUpdate::new(PollerStatus::Succeeded, Some(poll), headers),
+                })
+            }
+        });
+
+        // The 1 second default interval is overridden by the service.
+        assert_eq!(poller.wait_for_completion().await.unwrap(), 1);
+    }
+
+    #[derive(Debug)]
+    struct ScriptedClient(Mutex<Vec<Response>>);
+
+    #[async_trait::async_trait]
+    impl HttpClient for ScriptedClient {
+        async fn execute_request(&self, _request: &Request) -> crate::Result<Response> {
+            Ok(self.0.lock().unwrap().remove(0))
+        }
+    }
+
+    fn response(status: u16, headers: Headers, body: &'static str) -> Response {
+        Response::new(
+            status,
+            headers,
+            Box::pin(BytesStream::new(Bytes::from_static(body.as_bytes()))),
+        )
+    }
+
+    #[tokio::test]
+    async fn from_response() {
+        let client = ScriptedClient(Mutex::new(vec![
+            response(
+                200,
+                Headers::new(),
+                r#"{"status":"Running","percentComplete":50}"#,
+            ),
+            response(200, Headers::new(), r#"{"status":"Succeeded"}"#),
+            response(200, Headers::new(), r#"{"name":"secret"}"#),
+        ]));
+        let options = ClientOptions {
+            retry: RetryOptions::none(),
+            transport: TransportOptions::with_http_client(Arc::new(client)),
+            ..Default::default()
+        };
+        let pipeline = Pipeline::new(None, None, &options, Vec::new(), Vec::new());
+
+        let request = Request::new(Url::parse("https://example.com/items/1").unwrap(), "PUT");
+        let mut headers = Headers::new();
+        headers.insert(
+            headers::OPERATION_LOCATION,
+            "https://example.com/operations/1",
+        );
+        let initial = response(201, headers, "");
+
+        #[derive(Deserialize)]
+        struct Item {
+            name: String,
+        }
+
+        impl TerminalState for Item {
+            fn from_success(_headers: &Headers, body: &[u8]) -> crate::Result<Self> {
+                from_json(body)
+            }
+        }
+
+        let mut poller: Poller<Item> =
+            Poller::from_response(pipeline, &Context::default(), &request, &initial).unwrap();
+        poller.set_polling_interval(Duration::ZERO);
+
+        let updates: Vec<_> = poller.into_stream().try_collect().await.unwrap();
+        assert_eq!(updates[0].percent_complete(), Some(50.0));
+        assert_eq!(updates[1].value().unwrap().name, "secret");
+    }
+
+    #[tokio::test]
+    async fn cancel() {
+        let mut poller = Poller::new(|| async {
+            Ok(StatusUpdate::<()>::new(
+                PollerStatus::InProgress,
+                None,
+                Headers::new(),
+            ))
+        });
+        poller
+            .cancel()
+            .await
+            .expect_err("cancellation not supported");
+
+        let cancels = Arc::new(AtomicU32::new(0));
+        poller.set_cancel({
+            let cancels = cancels.clone();
+            move || {
+                cancels.fetch_add(1, Ordering::SeqCst);
+                async { Ok(()) }
+            }
+        });
+        poller.cancel().await.unwrap();
+        assert_eq!(cancels.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            poller.poll_once().await.unwrap().status(),
+            PollerStatus::Canceled
+        );
+        poller.wait_for_completion().await.expect_err("canceled");
+    }
+
+    #[tokio::test]
+    async fn cancel_handle() {
+        let polls = Arc::new(AtomicU32::new(0));
+        let mut poller = Poller::new({
+            let polls = polls.clone();
+            move || {
+                polls.fetch_add(1, Ordering::SeqCst);
+                async {
+                    let mut headers = Headers::new();
+                    headers.insert(headers::RETRY_AFTER_MS, "1");
+                    Ok(StatusUpdate::<()>::new(
+                        PollerStatus::InProgress,
+                        None,
+                        headers,
+                    ))
+                }
+            }
+        });
+        // The handle sees a cancel request set after it was created.
+        let handle = poller.cancel_handle();
+        poller.set_cancel(|| async { Ok(()) });
+
+        let waiting = tokio::spawn(poller.wait_for_completion());
+        while polls.load(Ordering::SeqCst) < 2 {
+            tokio::task::yield_now().await;
+        }
+        handle.clone().cancel().await.unwrap();
+        assert!(handle.is_canceled());
+
+        let error = waiting.await.unwrap().unwrap_err();
+        assert_eq!(error.to_string(), "operation was canceled");
+    }
+}
@@ -0,0 +1,216 @@
+use super::PollerStatus;
+use crate::{
+    headers::{self, HeaderName, Headers},
+    json::from_json,
+    request::Request,
+    Url,
+};
+use serde::Deserialize;
+use std::{fmt::Debug, sync::Arc};
+
+/// A protocol used by a service to report the status of a long-running operation.
+pub trait PollingStrategy: Debug + Send + Sync {
+    /// Gets the URLs used to poll the operation started by `request`,
+    /// or `None` if the `headers` of the initial response do not use this protocol.
+    fn start(&self, request: &Request, headers: &Headers) -> Option<OperationUrls>;
+
+    /// Gets the status of the operation from the response to polling its monitor URL.
+    fn status(&self, status: u16, body: &[u8]) -> crate::Result<PollerStatus>;
+}
+
+/// The URLs used to poll a long-running operation.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct OperationUrls {
+    /// The URL polled for the status of the operation.
+    pub monitor: Url,
+    /// The URL of the resource to get once the operation succeeds,
+    /// or `None` if the final response from the monitor URL contains it.
+    pub resource: Option<Url>,
+}
+
+impl OperationUrls {
+    pub fn new(monitor: Url, resource: Option<Url>) -> Self {
+        Self { monitor, resource }
+    }
+}
+
+/// Gets the strategies tried in order by [`Poller::from_response`](super::Poller::from_response).
+pub fn default_strategies() -> Vec<Arc<dyn PollingStrategy>> {
+    vec![
+        Arc::new(AzureAsyncOperationStrategy),
+        Arc::new(OperationLocationStrategy),
+        Arc::new(LocationStrategy),
+        Arc::new(BodyStrategy),
+    ]
+}
+
+/// Polls the status monitor in the `Operation-Location` header.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OperationLocationStrategy;
+
+impl PollingStrategy for OperationLocationStrategy {
+    fn start(&self, request: &Request, headers: &Headers) -> Option<OperationUrls> {
+        Some(OperationUrls {
+            monitor: header_url(request, headers, &headers::OPERATION_LOCATION)?,
+            resource: resource_url(request, headers),
+        })
+    }
+
+    fn status(&self, _status: u16, body: &[u8]) -> crate::Result<PollerStatus> {
+        monitor_status(body)
+    }
+}
+
+/// Polls the status monitor in the `Azure-AsyncOperation` header used by Azure Resource Manager.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AzureAsyncOperationStrategy;
+
+impl PollingStrategy for AzureAsyncOperationStrategy {
+    fn start(&self, request: &Request, headers: &Headers) -> Option<OperationUrls> {
+        Some(OperationUrls {
+            monitor: header_url(request, headers, &headers::AZURE_ASYNCOPERATION)?,
+            resource: resource_url(request, headers),
+        })
+    }
+
+    fn status(&self, _status: u16, body: &[u8]) -> crate::Result<PollerStatus> {
+        monitor_status(body)
+    }
+}
+
+/// Polls the `Location` header, which returns `202 Accepted` until the operation completes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LocationStrategy;
+
+impl PollingStrategy for LocationStrategy {
+    fn start(&self, request: &Request, headers: &Headers) -> Option<OperationUrls> {
+        Some(OperationUrls {
+            monitor: header_url(request, headers, &headers::LOCATION)?,
+            resource: None,
+        })
+    }
+
+    fn status(&self, status: u16, _body: &[u8]) -> crate::Result<PollerStatus> {
+        Ok(match status {
+            202 => PollerStatus::InProgress,
+            _ => PollerStatus::Succeeded,
+        })
+    }
+}
+
+/// Polls the resource created or updated by a `PUT` or `PATCH` until its `provisioningState` is terminal.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BodyStrategy;
+
+impl PollingStrategy for BodyStrategy {
+    fn start(&self, request: &Request, _headers: &Headers) -> Option<OperationUrls> {
+        matches!(request.method(), "PUT" | "PATCH").then(|| OperationUrls {
+            monitor: request.url().clone(),
+            resource: None,
+        })
+    }
+
+    fn status(&self, _status: u16, body: &[u8]) -> crate::Result<PollerStatus> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Properties {
+            provisioning_state: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Body {
+            provisioning_state: Option<String>,
+            properties: Option<Properties>,
+        }
+
+        // A resource without a provisioning state has already been provisioned.
+        let body: Body = from_json(body)?;
+        Ok(body
+            .properties
+            .and_then(|properties| properties.provisioning_state)
+            .or(body.provisioning_state)
+            .map_or(PollerStatus::Succeeded, |state| {
+                PollerStatus::from(state.as_str())
+            }))
+    }
+}
+
+fn header_url(request: &Request, headers: &Headers, name: &HeaderName) -> Option<Url> {
+    request.url().join(headers.get_optional_str(name)?).ok()
+}
+
+/// Gets the resource to get once a status monitor reports success: the original URL for a
+/// `PUT` or `PATCH`, otherwise the `Location` header if present.
+fn resource_url(request: &Request, headers: &Headers) -> Option<Url> {
+    match request.method() {
+        "PUT" | "PATCH" => Some(request.url().clone()),
+        _ => header_url(request, headers, &headers::LOCATION),
+    }
+}
+
+fn monitor_status(body: &[u8]) -> crate::Result<PollerStatus> {
+    #[derive(Deserialize)]
+    struct Body {
+        status: String,
+    }
+
+    let body: Body = from_json(body)?;
+    Ok(PollerStatus::from(body.status.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start(request: &Request, headers: &Headers) -> Option<(String, OperationUrls)> {
+        default_strategies().iter().find_map(|strategy| {
+            let urls = strategy.start(request, headers)?;
+            Some((format!("{strategy:?}"), urls))
+        })
+    }
+
+    #[test]
+    fn selects_strategy() {
+        let url = Url::parse("https://example.com/resources/1").unwrap();
+        let put = Request::new(url.clone(), "PUT");
+        let delete = Request::new(url, "DELETE");
+
+        let mut headers = Headers::new();
+        let (strategy, urls) = start(&put, &headers).unwrap();
+        assert_eq!(strategy, "BodyStrategy");
+        assert_eq!(urls.monitor.as_str(), "https://example.com/resources/1");
+        assert!(start(&delete, &headers).is_none());
+
+        headers.insert(headers::LOCATION, "/results/1");
+        let (strategy, urls) = start(&delete, &headers).unwrap();
+        assert_eq!(strategy, "LocationStrategy");
+        assert_eq!(urls.monitor.as_str(), "https://example.com/results/1");
+
+        headers.insert(
+            headers::OPERATION_LOCATION,
+            "https://example.com/operations/1",
+        );
+        let (strategy, urls) = start(&delete, &headers).unwrap();
+        assert_eq!(strategy, "OperationLocationStrategy");
+        assert_eq!(
+            urls.resource.unwrap().as_str(),
+            "https://example.com/results/1"
+        );
+    }
+
+    #[test]
+    fn body_status() {
+        let status = |body: &str| BodyStrategy.status(200, body.as_bytes()).unwrap();
+        assert_eq!(status("{}"), PollerStatus::Succeeded);
+        assert_eq!(
+            status(r#"{"properties":{"provisioningState":"Updating"}}"#),
+            PollerStatus::InProgress
+        );
+        assert_eq!(
+            status(r#"{"provisioningState":"Failed"}"#),
+            PollerStatus::Failed
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{
+    error::{Error, ErrorKind},
+    headers::Headers,
+    http_error::body_snippet,
+};
+use serde::Deserialize;
+
+/// Maps the final response of a long-running operation into its typed result or failure.
+///
+/// Implemented by the result type of each operation polled with
+/// [`Poller::from_response`](super::Poller::from_response).
+pub trait TerminalState: Sized {
+    /// Gets the result from the final response of an operation which succeeded.
+    fn from_success(headers: &Headers, body: &[u8]) -> crate::Result<Self>;
+
+    /// Gets the error from the final response of an operation which failed.
+    ///
+    /// By default, the `error` object of Azure REST API guidelines is used if present.
+    fn from_failure(failure: OperationFailure) -> Error {
+        match failure.error {
+            Some(OperationError { code, message }) => Error::with_message(ErrorKind::Other, || {
+                format!(
+                    "operation failed ({}): {}",
+                    code.as_deref().unwrap_or("unknown"),
+                    message.as_deref().unwrap_or("no message"),
+                )
+            }),
+            None => match failure.snippet {
+                Some(snippet) => {
+                    Error::with_message(ErrorKind::Other, || format!("operation failed: {snippet}"))
+                }
+                None => Error::message(ErrorKind::Other, "operation failed"),
+            },
+        }
+    }
+}
+
+/// The final response of a long-running operation which failed.
+#[derive(Clone, Debug)]
+pub struct OperationFailure {
+    status: u16,
+    headers: Headers,
+    error: Option<OperationError>,
+    snippet: Option<String>,
+}
+
+impl OperationFailure {
+    pub(crate) fn new(status: u16, headers: Headers, body: &[u8]) -> Self {
+        #[derive(Deserialize)]
+        struct Body {
+            error: Option<OperationError>,
+        }
+
+        let error = serde_json::from_slice::<Body>(body)
+            .ok()
+            .and_then(|body| body.error);
+        Self {
+            status,
+            headers,
+            error,
+            snippet: body_snippet(body),
+        }
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    pub fn headers(&self) -> &Headers {
+        &self.headers
+    }
+
+    /// Gets the `error` object of Azure REST API guidelines, if the body contained one.
+    pub fn error(&self) -> Option<&OperationError> {
+        self.error.as_ref()
+    }
+
+    /// Gets the start of the body if it is not JSON, e.g., an HTML page returned by a gateway.
+    pub fn snippet(&self) -> Option<&str> {
+        self.snippet.as_deref()
+    }
+}
+
+/// The reason a long-running operation failed.
+#[derive(Clone, Debug, Deserialize)]
+#[non_exhaustive]
+pub struct OperationError {
+    pub code: Option<String>,
+    pub message: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Empty;
+
+    impl TerminalState for Empty {
+        fn from_success(_headers: &Headers, _body: &[u8]) -> crate::Result<Self> {
+            Ok(Empty)
+        }
+    }
+
+    #[test]
+    fn default_failure() {
+        let failure = |body: &[u8]| OperationFailure::new(200, Headers::new(), body);
+
+        let body = br#"{"status":"Failed","error":{"code":"Conflict","message":"already exists"}}"#;
+        let error = Empty::from_failure(failure(body));
+        assert_eq!(error.kind(), &ErrorKind::Other);
+        assert!(error.to_string().contains("Conflict"));
+
+        let error = Empty::from_failure(failure(b"<html>Bad Gateway</html>"));
+        assert_eq!(
+            error.to_string(),
+            "operation failed: <html>Bad Gateway</html>"
+        );
+        let error = Empty::from_failure(failure(b""));
+        assert_eq!(error.to_string(), "operation failed");
+    }
+}
@@ -2,17 +2,22 @@ use bytes::Bytes;
 use serde::Serialize;
 use url::Url;
 
-use crate::{AsHeaders, Headers};
+use crate::{stream::StreamBody, AsHeaders, Headers};
 
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum Body {
     Bytes(bytes::Bytes),
+    /// A body read as it is sent. Requests with it are only retried if it is a
+    /// [`SeekableBody`](crate::stream::SeekableBody).
+    Stream(StreamBody),
 }
 
 impl Body {
     pub fn len(&self) -> usize {
         match self {
             Body::Bytes(bytes) => bytes.len(),
+            Body::Stream(stream) => stream.len(),
         }
     }
 
@@ -21,6 +26,12 @@ impl Body {
     }
 }
 
+impl From<StreamBody> for Body {
+    fn from(stream: StreamBody) -> Self {
+        Self::Stream(stream)
+    }
+}
+
 impl<B> From<B> for Body
 where
     B: Into<Bytes>,
@@ -52,6 +63,12 @@ impl Request {
         &self.url
     }
 
+    /// Gets the URL to modify, e.g., for a [`RequestSigner`](crate::RequestSigner) to add a
+    /// shared access signature to its query.
+    pub fn url_mut(&mut self) -> &mut Url {
+        &mut self.url
+    }
+
     pub fn path_and_query(&self) -> String {
         let mut result = self.url.path().to_owned();
         if let Some(query) = self.url.query() {
@@ -98,4 +115,13 @@ impl Request {
         self.set_body(crate::json::to_json(data)?);
         Ok(())
     }
+
+    #[cfg(feature = "xml")]
+    pub fn set_xml<T>(&mut self, data: &T) -> crate::Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.set_body(crate::xml::to_xml(data)?);
+        Ok(())
+    }
 }
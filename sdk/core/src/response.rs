@@ -1,15 +1,27 @@
-use crate::headers::Headers;
+use crate::{
+    headers::{HeaderName, HeaderValue, Headers, CONTENT_TYPE},
+    transport::ConnectionTimings,
+};
 use bytes::Bytes;
 use futures::{Stream, StreamExt};
-use serde::de::DeserializeOwned;
-use std::pin::Pin;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
 
 pub(crate) type PinnedStream = Pin<Box<dyn Stream<Item = crate::Result<Bytes>> + Send + Sync>>;
 
-pub struct Response {
+/// An HTTP response, optionally typed with the model `T` its body deserializes into.
+pub struct Response<T = ()> {
     status: u16,
     headers: Headers,
     body: ResponseBody,
+    timings: Option<ConnectionTimings>,
+    attempt: Option<u32>,
+    idempotency_key: Option<String>,
+    model: PhantomData<fn() -> T>,
 }
 
 impl Response {
@@ -18,9 +30,15 @@ impl Response {
             status,
             headers,
             body: ResponseBody::new(stream),
+            timings: None,
+            attempt: None,
+            idempotency_key: None,
+            model: PhantomData,
         }
     }
+}
 
+impl<T> Response<T> {
     pub fn status(&self) -> u16 {
         self.status
     }
@@ -29,18 +47,201 @@ impl Response {
         &self.headers
     }
 
+    /// Gets the network timings measured by the transport, if supported.
+    pub fn timings(&self) -> Option<&ConnectionTimings> {
+        self.timings.as_ref()
+    }
+
+    pub fn set_timings(&mut self, timings: impl Into<ConnectionTimings>) {
+        self.timings = Some(timings.into());
+    }
+
+    /// Gets the 1-based number of the attempt which returned this response, e.g., 3 after two
+    /// retries, or `None` if it was not sent through a pipeline.
+    pub fn attempt(&self) -> Option<u32> {
+        self.attempt
+    }
+
+    pub(crate) fn set_attempt(&mut self, attempt: u32) {
+        self.attempt = Some(attempt);
+    }
+
+    /// Gets the `idempotency-key` sent with the request, if any, e.g., one generated because
+    /// [`ClientOptions::idempotency_keys`](crate::ClientOptions::idempotency_keys) is set.
+    pub fn idempotency_key(&self) -> Option<&str> {
+        self.idempotency_key.as_deref()
+    }
+
+    pub(crate) fn set_idempotency_key(&mut self, idempotency_key: Option<String>) {
+        self.idempotency_key = idempotency_key;
+    }
+
+    /// Reads up to `limit` bytes of the body so it can be inspected.
+    ///
+    /// Whatever is read, including a read error, is put back so the body can still be read in full.
+    #[cfg(feature = "log-body")]
+    pub(crate) async fn peek_body(&mut self, limit: usize) -> BodyPeek {
+        let mut rest = std::mem::replace(
+            &mut self.body,
+            ResponseBody::new(Box::pin(futures::stream::empty())),
+        );
+        let mut chunks = Vec::new();
+        let mut length = 0;
+        let peek = loop {
+            match rest.next().await {
+                Some(Ok(chunk)) => {
+                    length += chunk.len();
+                    chunks.push(Ok(chunk));
+                    if length > limit {
+                        break BodyPeek::TooLong;
+                    }
+                }
+                Some(Err(error)) => {
+                    let message = error.to_string();
+                    chunks.push(Err(error));
+                    break BodyPeek::Failed(message);
+                }
+                None => {
+                    let mut bytes = bytes::BytesMut::with_capacity(length);
+                    for chunk in chunks.into_iter().flatten() {
+                        bytes.extend_from_slice(&chunk);
+                    }
+                    let bytes = bytes.freeze();
+                    self.body =
+                        ResponseBody::new(Box::pin(crate::stream::BytesStream::new(bytes.clone())));
+                    return BodyPeek::Complete(bytes);
+                }
+            }
+        };
+        self.body = ResponseBody::new(Box::pin(futures::stream::iter(chunks).chain(rest)));
+        peek
+    }
+
     pub fn into_body(self) -> ResponseBody {
         self.body
     }
 
-    pub async fn json<T>(self) -> crate::Result<T>
+    pub async fn json<U>(self) -> crate::Result<U>
     where
-        T: DeserializeOwned,
+        U: DeserializeOwned,
     {
         self.into_body().json().await
     }
+
+    #[cfg(feature = "xml")]
+    pub async fn xml<U>(self) -> crate::Result<U>
+    where
+        U: DeserializeOwned,
+    {
+        self.into_body().xml().await
+    }
+
+    /// Types the response with the model `U` returned by [`Response::into_model`].
+    pub fn with_model<U>(self) -> Response<U> {
+        Response {
+            status: self.status,
+            headers: self.headers,
+            body: self.body,
+            timings: self.timings,
+            attempt: self.attempt,
+            idempotency_key: self.idempotency_key,
+            model: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> Response<T> {
+    /// Deserializes the JSON body into the model.
+    pub async fn into_model(self) -> crate::Result<T> {
+        self.json().await
+    }
+}
+
+impl<T> std::fmt::Debug for Response<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Response")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .field("timings", &self.timings)
+            .field("attempt", &self.attempt)
+            .field("idempotency_key", &self.idempotency_key)
+            .field("body", &"(body)")
+            .finish()
+    }
+}
+
+/// Builds a [`Response`], e.g., for an application's tests to return from a mock of a client.
+///
+/// ```
+/// use azure_core::{Response, ResponseBuilder};
+/// # #[derive(serde::Deserialize)] struct Secret { value: String }
+///
+/// let response: Response<Secret> = ResponseBuilder::new(200)
+///     .with_header("x-ms-request-id", "1234")
+///     .with_body(r#"{"value":"secret"}"#)
+///     .build();
+/// ```
+#[derive(Clone, Debug)]
+pub struct ResponseBuilder {
+    status: u16,
+    headers: Headers,
+    body: Bytes,
+}
+
+impl ResponseBuilder {
+    pub fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: Headers::new(),
+            body: Bytes::new(),
+        }
+    }
+
+    pub fn with_header(
+        &mut self,
+        name: impl Into<HeaderName>,
+        value: impl Into<HeaderValue>,
+    ) -> &mut Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    pub fn with_body(&mut self, body: impl Into<Bytes>) -> &mut Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Sets the body to `model` serialized as JSON, and the `content-type` to `application/json`.
+    pub fn with_json(&mut self, model: &impl Serialize) -> crate::Result<&mut Self> {
+        self.body = crate::json::to_json(model)?;
+        self.headers.insert(CONTENT_TYPE, "application/json");
+        Ok(self)
+    }
+
+    /// Builds a response whose body deserializes into `T` with [`Response::into_model`].
+    pub fn build<T>(&self) -> Response<T> {
+        Response::new(
+            self.status,
+            self.headers.clone(),
+            Box::pin(crate::stream::BytesStream::new(self.body.clone())),
+        )
+        .with_model()
+    }
 }
 
+/// What [`Response::peek_body`] read of a body.
+#[cfg(feature = "log-body")]
+#[derive(Debug)]
+pub(crate) enum BodyPeek {
+    /// The whole body, which was no longer than the limit.
+    Complete(Bytes),
+    /// The body is longer than the limit.
+    TooLong,
+    /// Reading the body failed with this message.
+    Failed(String),
+}
+
+/// The body of a [`Response`], which can be read all at once or streamed as it is received.
 pub struct ResponseBody(PinnedStream);
 
 impl ResponseBody {
@@ -64,4 +265,85 @@ impl ResponseBody {
         let body = self.collect().await?;
         crate::json::from_json(body)
     }
+
+    #[cfg(feature = "xml")]
+    pub async fn xml<T>(self) -> crate::Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let body = self.collect().await?;
+        crate::xml::from_xml(body)
+    }
+}
+
+impl Stream for ResponseBody {
+    type Item = crate::Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.as_mut().poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::BytesStream;
+    use futures::TryStreamExt;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Model {
+        name: String,
+    }
+
+    #[tokio::test]
+    async fn into_model() {
+        let response = Response::new(
+            200,
+            Headers::new(),
+            Box::pin(BytesStream::new(r#"{"name":"secret"}"#)),
+        );
+        let response: Response<Model> = response.with_model();
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.into_model().await.unwrap(),
+            Model {
+                name: "secret".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn builder() {
+        let response: Response<Model> = ResponseBuilder::new(201)
+            .with_header("x-ms-request-id", "1234")
+            .with_json(&serde_json::json!({ "name": "secret" }))
+            .unwrap()
+            .build();
+        assert_eq!(response.status(), 201);
+        assert_eq!(
+            response
+                .headers()
+                .get_optional_str(&crate::headers::REQUEST_ID),
+            Some("1234")
+        );
+        assert_eq!(
+            response.headers().get_optional_str(&CONTENT_TYPE),
+            Some("application/json")
+        );
+        assert_eq!(
+            response.into_model().await.unwrap(),
+            Model {
+                name: "secret".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_body() {
+        let chunks = futures::stream::iter([Ok(Bytes::from("a")), Ok(Bytes::from("bc"))]);
+        let response = Response::new(200, Headers::new(), Box::pin(chunks));
+        let chunks: Vec<Bytes> = response.into_body().try_collect().await.unwrap();
+        assert_eq!(chunks, ["a", "bc"]);
+    }
 }
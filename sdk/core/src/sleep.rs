@@ -0,0 +1,236 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// Waits asynchronously for the given duration.
+///
+/// With the `tokio` feature, which is enabled by default, this is a [`tokio::time::sleep`] and
+/// must be awaited on a tokio runtime. Without it, it is woken by a timer thread started on first
+/// use, so it does not depend on a specific async runtime.
+pub fn sleep(duration: Duration) -> Sleep {
+    #[cfg(feature = "tokio")]
+    return Sleep(Box::pin(tokio::time::sleep(duration)));
+    #[cfg(not(feature = "tokio"))]
+    return Sleep(timer::sleep(duration));
+}
+
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Sleep(
+    #[cfg(feature = "tokio")] Pin<Box<tokio::time::Sleep>>,
+    #[cfg(not(feature = "tokio"))] timer::Sleep,
+);
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx)
+    }
+}
+
+#[cfg(any(test, not(feature = "tokio")))]
+mod timer {
+    use std::{
+        collections::BTreeMap,
+        future::Future,
+        pin::Pin,
+        sync::{
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Arc, Condvar, Mutex, MutexGuard, Once, PoisonError,
+        },
+        task::{Context, Poll, Waker},
+        thread,
+        time::{Duration, Instant},
+    };
+
+    pub(super) fn sleep(duration: Duration) -> Sleep {
+        Sleep {
+            duration,
+            scheduled: None,
+        }
+    }
+
+    #[derive(Debug)]
+    pub(super) struct Sleep {
+        duration: Duration,
+        scheduled: Option<(Key, Arc<SleepState>)>,
+    }
+
+    /// Orders deadlines by when they elapse, then by when they were scheduled.
+    type Key = (Instant, u64);
+
+    #[derive(Debug, Default)]
+    struct SleepState {
+        elapsed: AtomicBool,
+        waker: Mutex<Option<Waker>>,
+    }
+
+    impl SleepState {
+        fn wake(&self) {
+            self.elapsed.store(true, Ordering::Release);
+            if let Some(waker) = lock(&self.waker).take() {
+                waker.wake();
+            }
+        }
+    }
+
+    impl Future for Sleep {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let this = self.get_mut();
+            if this.duration.is_zero() {
+                return Poll::Ready(());
+            }
+
+            match &this.scheduled {
+                Some((_, state)) => {
+                    // Replace the waker before checking so a concurrent wake isn't missed.
+                    *lock(&state.waker) = Some(cx.waker().clone());
+                    if state.elapsed.load(Ordering::Acquire) {
+                        Poll::Ready(())
+                    } else {
+                        Poll::Pending
+                    }
+                }
+                None => {
+                    let state = Arc::new(SleepState::default());
+                    *lock(&state.waker) = Some(cx.waker().clone());
+                    let key = TIMER.schedule(Instant::now() + this.duration, state.clone());
+                    this.scheduled = Some((key, state));
+                    Poll::Pending
+                }
+            }
+        }
+    }
+
+    impl Drop for Sleep {
+        fn drop(&mut self) {
+            // An abandoned sleep, e.g., a canceled retry delay, would otherwise keep its task's
+            // waker until the deadline, which may be minutes away.
+            if let Some((key, state)) = &self.scheduled {
+                if !state.elapsed.load(Ordering::Acquire) {
+                    TIMER.cancel(key);
+                }
+            }
+        }
+    }
+
+    static TIMER: Timer = Timer {
+        deadlines: Mutex::new(BTreeMap::new()),
+        changed: Condvar::new(),
+    };
+
+    /// Wakes every [`Sleep`] from a single thread, started on first use.
+    #[derive(Debug)]
+    struct Timer {
+        deadlines: Mutex<BTreeMap<Key, Arc<SleepState>>>,
+        changed: Condvar,
+    }
+
+    impl Timer {
+        fn schedule(&'static self, at: Instant, state: Arc<SleepState>) -> Key {
+            static STARTED: Once = Once::new();
+            static SCHEDULED: AtomicU64 = AtomicU64::new(0);
+            STARTED.call_once(|| {
+                thread::Builder::new()
+                    .name("azure_core-sleep".to_string())
+                    .spawn(|| TIMER.run())
+                    .expect("failed to spawn the sleep timer thread");
+            });
+
+            let key = (at, SCHEDULED.fetch_add(1, Ordering::Relaxed));
+            lock(&self.deadlines).insert(key, state);
+            self.changed.notify_one();
+            key
+        }
+
+        fn cancel(&self, key: &Key) {
+            lock(&self.deadlines).remove(key);
+        }
+
+        fn run(&self) {
+            let mut deadlines = lock(&self.deadlines);
+            loop {
+                let now = Instant::now();
+                while let Some(next) = deadlines.first_entry() {
+                    if next.key().0 > now {
+                        break;
+                    }
+                    next.remove().wake();
+                }
+                deadlines = match deadlines.keys().next() {
+                    Some(&(at, _)) => {
+                        self.changed
+                            .wait_timeout(deadlines, at - now)
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .0
+                    }
+                    None => self
+                        .changed
+                        .wait(deadlines)
+                        .unwrap_or_else(PoisonError::into_inner),
+                };
+            }
+        }
+    }
+
+    /// Locks `mutex`, which is never poisoned since nothing panics while holding it.
+    fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+        mutex.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use futures::{future::join_all, task::noop_waker};
+
+        #[tokio::test]
+        async fn wakes_in_order() {
+            let start = Instant::now();
+            let finished = Arc::new(Mutex::new(Vec::new()));
+            join_all([30, 10, 20].map(|millis| {
+                let finished = finished.clone();
+                async move {
+                    sleep(Duration::from_millis(millis)).await;
+                    finished.lock().unwrap().push(millis);
+                }
+            }))
+            .await;
+
+            assert_eq!(*finished.lock().unwrap(), vec![10, 20, 30]);
+            assert!(start.elapsed() >= Duration::from_millis(30));
+        }
+
+        #[test]
+        fn removes_dropped_deadlines() {
+            let mut sleep = sleep(Duration::from_secs(600));
+            let waker = noop_waker();
+            let mut cx = Context::from_waker(&waker);
+            assert!(Pin::new(&mut sleep).poll(&mut cx).is_pending());
+
+            let (key, state) = sleep.scheduled.clone().unwrap();
+            assert!(lock(&TIMER.deadlines).contains_key(&key));
+            drop(sleep);
+            assert!(!lock(&TIMER.deadlines).contains_key(&key));
+            assert_eq!(Arc::strong_count(&state), 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn waits_for_duration() {
+        let start = Instant::now();
+        sleep(Duration::from_millis(20)).await;
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+}
@@ -1,7 +1,16 @@
-use std::task::Poll;
+use std::{
+    fmt,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::Poll,
+};
 
+use crate::error::{Error, ErrorKind};
 use bytes::Bytes;
-use futures::Stream;
+use futures::{
+    lock::{Mutex as AsyncMutex, OwnedMutexGuard, OwnedMutexLockFuture},
+    FutureExt, Stream, StreamExt,
+};
 
 #[derive(Clone, Debug)]
 pub struct BytesStream {
@@ -42,3 +51,157 @@ impl Stream for BytesStream {
         }
     }
 }
+
+impl BodyStream for BytesStream {
+    fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn as_seekable(&mut self) -> Option<&mut dyn SeekableBody> {
+        Some(self)
+    }
+}
+
+#[async_trait::async_trait]
+impl SeekableBody for BytesStream {
+    async fn rewind(&mut self) -> crate::Result<()> {
+        self.bytes_read = 0;
+        Ok(())
+    }
+}
+
+/// A request body read as it is sent, e.g., from a file, so it need not be in memory.
+pub trait BodyStream:
+    Stream<Item = crate::Result<Bytes>> + Send + Sync + Unpin + fmt::Debug
+{
+    /// Gets the length in bytes, which is sent as `Content-Length`.
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets this as a [`SeekableBody`] if it can be rewound, so requests with it can be retried.
+    fn as_seekable(&mut self) -> Option<&mut dyn SeekableBody> {
+        None
+    }
+}
+
+/// A [`BodyStream`] which can be rewound to send it again.
+#[async_trait::async_trait]
+pub trait SeekableBody: BodyStream {
+    /// Rewinds to the start of the body.
+    async fn rewind(&mut self) -> crate::Result<()>;
+}
+
+/// A [`BodyStream`] shared by a request and its copies, e.g., each attempt of the retry policy,
+/// which read it in turn.
+#[derive(Clone)]
+pub struct StreamBody {
+    stream: Arc<AsyncMutex<Box<dyn BodyStream>>>,
+    len: usize,
+}
+
+impl StreamBody {
+    pub fn new(stream: impl BodyStream + 'static) -> Self {
+        Self {
+            len: stream.len(),
+            stream: Arc::new(AsyncMutex::new(Box::new(stream))),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Rewinds the body to send it again, returning an error if it is not a [`SeekableBody`].
+    pub async fn rewind(&self) -> crate::Result<()> {
+        let mut stream = self.stream.lock().await;
+        match stream.as_seekable() {
+            Some(stream) => stream.rewind().await,
+            None => Err(Error::message(
+                ErrorKind::Other,
+                "the request body is a stream which cannot be rewound",
+            )),
+        }
+    }
+
+    /// Reads the body from where it was last read, e.g., by a transport.
+    pub fn reader(&self) -> StreamBodyReader {
+        StreamBodyReader(ReaderState::Locking(self.stream.clone().lock_owned()))
+    }
+}
+
+impl fmt::Debug for StreamBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamBody")
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Reads a [`StreamBody`], which other readers wait for until this is dropped.
+pub struct StreamBodyReader(ReaderState);
+
+enum ReaderState {
+    Locking(OwnedMutexLockFuture<Box<dyn BodyStream>>),
+    Reading(OwnedMutexGuard<Box<dyn BodyStream>>),
+}
+
+impl Stream for StreamBodyReader {
+    type Item = crate::Result<Bytes>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match &mut this.0 {
+                ReaderState::Locking(lock) => {
+                    let stream = futures::ready!(lock.poll_unpin(cx));
+                    this.0 = ReaderState::Reading(stream);
+                }
+                ReaderState::Reading(stream) => return stream.poll_next_unpin(cx),
+            }
+        }
+    }
+}
+
+impl fmt::Debug for StreamBodyReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamBodyReader").finish_non_exhaustive()
+    }
+}
+
+/// Adapts a `Send` stream to also be `Sync` as required by [`Response`](crate::Response).
+pub(crate) struct SyncStream<S>(Mutex<Pin<Box<S>>>);
+
+impl<S> SyncStream<S> {
+    pub(crate) fn new(stream: S) -> Self {
+        Self(Mutex::new(Box::pin(stream)))
+    }
+}
+
+impl<S> Stream for SyncStream<S>
+where
+    S: Stream + Send,
+{
+    type Item = S::Item;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.get_mut()
+            .0
+            .get_mut()
+            .expect("stream lock poisoned")
+            .as_mut()
+            .poll_next(cx)
+    }
+}
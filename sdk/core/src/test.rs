@@ -0,0 +1,859 @@
+//! Records the responses to integration tests so they can be played back without a service,
+//! for tests with the [`recorded`] attribute.
+//!
+//! The mode is read from `AZURE_TEST_MODE` unless set with [`TestContext::with_mode`]:
+//!
+//! * `playback`, the default, returns recorded responses in the order they were recorded, and
+//!   fails if a request does not [match](Matcher) that recorded.
+//! * `record` sends requests to the service and writes the responses to
+//!   `tests/recordings/{module}/{test}.json` in the crate.
+//! * `live` sends requests to the service without recording them.
+
+use crate::{
+    error::{Error, ErrorKind},
+    headers::{self, HeaderName},
+    options::{ClientOptions, TransportOptions},
+    request::{Body, Request},
+    response::{Response, ResponseBuilder},
+    transport::HttpClient,
+    AccessToken, Context, TokenCredential, TokenRequestOptions, Url,
+};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+pub use azure_core_macros::recorded;
+
+const MODE: &str = "AZURE_TEST_MODE";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TestMode {
+    Playback,
+    Record,
+    Live,
+}
+
+impl TestMode {
+    /// Gets the mode from `AZURE_TEST_MODE`, which is [`TestMode::Playback`] if not set.
+    pub fn current() -> crate::Result<Self> {
+        match env::var(MODE).as_deref() {
+            Err(_) | Ok("playback") => Ok(Self::Playback),
+            Ok("record") => Ok(Self::Record),
+            Ok("live") => Ok(Self::Live),
+            Ok(mode) => Err(Error::with_message(ErrorKind::InvalidInput, || {
+                format!("{MODE} must be playback, record, or live but was '{mode}'")
+            })),
+        }
+    }
+
+    /// Whether requests are sent to the service, e.g., to only check values which are not
+    /// sanitized when running live.
+    pub fn is_live(&self) -> bool {
+        matches!(self, Self::Record | Self::Live)
+    }
+}
+
+/// Identifies a test and the mode to run it in.
+#[derive(Clone, Debug)]
+pub struct TestContext {
+    manifest_dir: PathBuf,
+    module_path: String,
+    name: String,
+    mode: TestMode,
+}
+
+impl TestContext {
+    /// Creates the context of the test `name` in `module_path` of the crate in `manifest_dir`,
+    /// in the mode from `AZURE_TEST_MODE`.
+    pub fn new(
+        manifest_dir: impl Into<PathBuf>,
+        module_path: impl Into<String>,
+        name: impl Into<String>,
+    ) -> crate::Result<Self> {
+        Ok(Self {
+            manifest_dir: manifest_dir.into(),
+            module_path: module_path.into(),
+            name: name.into(),
+            mode: TestMode::current()?,
+        })
+    }
+
+    /// Overrides the mode from `AZURE_TEST_MODE`, e.g., for tests which can only run live.
+    pub fn with_mode(&mut self, mode: TestMode) -> &mut Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn mode(&self) -> TestMode {
+        self.mode
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets `tests/recordings/{module}/{test}.json` in the crate, without the name of the crate
+    /// in the module.
+    pub fn recording_path(&self) -> PathBuf {
+        let mut path = self.manifest_dir.join("tests").join("recordings");
+        path.extend(self.module_path.split("::").skip(1));
+        path.push(format!("{}.json", self.name));
+        path
+    }
+
+    /// Starts the recording, reading the recorded responses when playing back.
+    pub fn start(&self) -> crate::Result<Recording> {
+        Recording::open(self.mode, self.recording_path())
+    }
+}
+
+/// The recording of a test, which configures clients to record or play back their requests.
+///
+/// Secrets, e.g., the names of the resources a test ran against, are replaced by
+/// [sanitizers](Recording::add_sanitizer) before requests and responses are written. The
+/// `authorization` header is never recorded.
+#[derive(Clone, Debug)]
+pub struct Recording {
+    mode: TestMode,
+    path: PathBuf,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    sanitizers: Vec<(String, String)>,
+    matcher: Matcher,
+    entries: Vec<Entry>,
+    /// The next entry to play back.
+    next: usize,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct RecordingFile {
+    entries: Vec<Entry>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Entry {
+    #[serde(flatten)]
+    request: RecordedRequest,
+    status: u16,
+    response_headers: BTreeMap<String, String>,
+    response_body: RecordedBody,
+}
+
+impl Entry {
+    fn to_har(&self) -> Value {
+        let headers = |headers: &BTreeMap<String, String>| {
+            headers
+                .iter()
+                .map(|(name, value)| json!({ "name": name, "value": value }))
+                .collect::<Vec<_>>()
+        };
+        let mime_type = |headers: &BTreeMap<String, String>| {
+            headers
+                .get(headers::CONTENT_TYPE.as_str())
+                .cloned()
+                .unwrap_or_default()
+        };
+        let query_string: Vec<Value> = Url::parse(&self.request.url)
+            .map(|url| {
+                url.query_pairs()
+                    .map(|(name, value)| json!({ "name": name, "value": value }))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut request = json!({
+            "method": self.request.method,
+            "url": self.request.url,
+            "httpVersion": "HTTP/1.1",
+            "cookies": [],
+            "headers": headers(&self.request.request_headers),
+            "queryString": query_string,
+            "headersSize": -1,
+            "bodySize": self.request.request_body.as_ref().map_or(0, RecordedBody::size),
+        });
+        if let Some(body) = &self.request.request_body {
+            request["postData"] = json!({
+                "mimeType": mime_type(&self.request.request_headers),
+                "text": body.text(),
+            });
+        }
+
+        let mut content = json!({
+            "size": self.response_body.size(),
+            "mimeType": mime_type(&self.response_headers),
+            "text": self.response_body.text(),
+        });
+        if let RecordedBody::Base64(_) = self.response_body {
+            content["encoding"] = json!("base64");
+        }
+        json!({
+            "startedDateTime": "1970-01-01T00:00:00.000Z",
+            "time": 0,
+            "request": request,
+            "response": {
+                "status": self.status,
+                "statusText": "",
+                "httpVersion": "HTTP/1.1",
+                "cookies": [],
+                "headers": headers(&self.response_headers),
+                "content": content,
+                "redirectURL": "",
+                "headersSize": -1,
+                "bodySize": self.response_body.size(),
+            },
+            "cache": {},
+            "timings": { "send": 0, "wait": 0, "receive": 0 },
+        })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct RecordedRequest {
+    method: String,
+    url: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    request_headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    request_body: Option<RecordedBody>,
+}
+
+/// A body, which is Base64-encoded unless it is UTF-8.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RecordedBody {
+    Text(String),
+    Base64(String),
+}
+
+impl RecordedBody {
+    fn text(&self) -> &str {
+        match self {
+            Self::Text(text) | Self::Base64(text) => text,
+        }
+    }
+
+    /// The number of bytes in the body.
+    fn size(&self) -> usize {
+        match self {
+            Self::Text(text) => text.len(),
+            Self::Base64(base64) => STANDARD.decode(base64).map_or(0, |bytes| bytes.len()),
+        }
+    }
+}
+
+impl Recording {
+    /// Starts the recording of the test `name` in `module_path` of the crate in `manifest_dir`,
+    /// in the mode from `AZURE_TEST_MODE`.
+    pub fn start(manifest_dir: &str, module_path: &str, name: &str) -> crate::Result<Self> {
+        TestContext::new(manifest_dir, module_path, name)?.start()
+    }
+
+    fn open(mode: TestMode, path: PathBuf) -> crate::Result<Self> {
+        let mut state = State::default();
+        if mode == TestMode::Playback {
+            let file = fs::read(&path).map_err(|error| {
+                Error::full(
+                    ErrorKind::Io,
+                    error,
+                    format!(
+                        "failed to read recording '{}'; run the test with {MODE}=record",
+                        path.display()
+                    ),
+                )
+            })?;
+            let file: RecordingFile = crate::json::from_json(file)?;
+            state.entries = file.entries;
+        }
+
+        Ok(Self {
+            mode,
+            path,
+            state: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    pub fn mode(&self) -> TestMode {
+        self.mode
+    }
+
+    /// Replaces `value` with `replacement` in the URLs, headers, and bodies which are recorded.
+    pub fn add_sanitizer(&self, value: impl Into<String>, replacement: impl Into<String>) {
+        let value = value.into();
+        if !value.is_empty() {
+            self.state
+                .lock()
+                .unwrap()
+                .sanitizers
+                .push((value, replacement.into()));
+        }
+    }
+
+    /// Sets how requests are matched to those recorded when playing back.
+    pub fn set_matcher(&self, matcher: Matcher) {
+        self.state.lock().unwrap().matcher = matcher;
+    }
+
+    /// Gets the environment variable `name`, e.g., the endpoint to test against, or
+    /// `playback_value` when playing back.
+    ///
+    /// When recording, the value is replaced by `playback_value` in the recording, so requests
+    /// played back are sent to the same URLs as those recorded.
+    pub fn var(&self, name: &str, playback_value: &str) -> crate::Result<String> {
+        if self.mode == TestMode::Playback {
+            return Ok(playback_value.to_string());
+        }
+        let value = env::var(name).map_err(|_| {
+            Error::with_message(ErrorKind::InvalidInput, || {
+                format!("{name} must be set to record or run live tests")
+            })
+        })?;
+        self.add_sanitizer(value.clone(), playback_value);
+        Ok(value)
+    }
+
+    /// Gets options which send requests through the recording.
+    pub fn client_options(&self) -> ClientOptions {
+        let transport = match self.mode {
+            TestMode::Live => TransportOptions::default(),
+            TestMode::Playback | TestMode::Record => {
+                let transport: Arc<dyn HttpClient> = Arc::new(RecordingTransport {
+                    recording: self.clone(),
+                    inner: TransportOptions::default(),
+                });
+                transport.into()
+            }
+        };
+        ClientOptions {
+            transport,
+            ..Default::default()
+        }
+    }
+
+    /// Gets the credential created by `live` when recording or running live, or one which
+    /// returns a fake token when playing back.
+    pub fn credential(
+        &self,
+        live: impl FnOnce() -> Arc<dyn TokenCredential>,
+    ) -> Arc<dyn TokenCredential> {
+        match self.mode {
+            TestMode::Playback => Arc::new(FakeCredential),
+            TestMode::Record | TestMode::Live => live(),
+        }
+    }
+
+    /// Writes the recording when recording, or returns an error if some recorded responses were
+    /// not played back.
+    pub fn finish(&self) -> crate::Result<()> {
+        let state = self.state.lock().unwrap();
+        match self.mode {
+            TestMode::Playback if state.next < state.entries.len() => {
+                Err(Error::with_message(ErrorKind::Other, || {
+                    format!(
+                        "only {} of {} recorded requests were sent",
+                        state.next,
+                        state.entries.len()
+                    )
+                }))
+            }
+            TestMode::Record => {
+                let file = RecordingFile {
+                    entries: state.entries.clone(),
+                };
+                let write = || {
+                    if let Some(dir) = self.path.parent() {
+                        fs::create_dir_all(dir)?;
+                    }
+                    fs::write(&self.path, serde_json::to_string_pretty(&file)?)
+                };
+                write().map_err(|error| {
+                    Error::full(
+                        ErrorKind::Io,
+                        error,
+                        format!("failed to write recording '{}'", self.path.display()),
+                    )
+                })
+            }
+            TestMode::Playback | TestMode::Live => Ok(()),
+        }
+    }
+
+    /// Writes the requests and responses in the recording as a
+    /// [HAR](http://www.softwareishard.com/blog/har-12-spec/) file, e.g., to inspect them in a
+    /// browser's developer tools or to compare what different clients send.
+    ///
+    /// Timings are not recorded, so every request starts at the Unix epoch and takes no time.
+    pub fn write_har(&self, path: impl AsRef<Path>) -> crate::Result<()> {
+        let state = self.state.lock().unwrap();
+        let har = json!({
+            "log": {
+                "version": "1.2",
+                "creator": { "name": "azure_core", "version": env!("CARGO_PKG_VERSION") },
+                "entries": state.entries.iter().map(Entry::to_har).collect::<Vec<_>>(),
+            },
+        });
+        let path = path.as_ref();
+        let write = || fs::write(path, serde_json::to_string_pretty(&har)?);
+        write().map_err(|error| {
+            Error::full(
+                ErrorKind::Io,
+                error,
+                format!("failed to write HAR '{}'", path.display()),
+            )
+        })
+    }
+
+    async fn record(&self, request: &Request, inner: &TransportOptions) -> crate::Result<Response> {
+        let response = inner
+            .send(&Context::default(), &mut request.clone())
+            .await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.into_body().collect().await?;
+
+        let mut state = self.state.lock().unwrap();
+        let entry = Entry {
+            request: state.sanitize_request(request),
+            status,
+            response_headers: headers
+                .iter()
+                .map(|(name, value)| (name.as_str().to_string(), state.sanitize(value.as_str())))
+                .collect(),
+            response_body: state.sanitize_body(&body),
+        };
+        state.entries.push(entry);
+
+        let mut builder = ResponseBuilder::new(status);
+        for (name, value) in headers.iter() {
+            builder.with_header(name.clone(), value.clone());
+        }
+        Ok(builder.with_body(body).build())
+    }
+
+    fn play_back(&self, request: &Request) -> crate::Result<Response> {
+        let mut state = self.state.lock().unwrap();
+        let actual = state.sanitize_request(request);
+        let Some(entry) = state.entries.get(state.next).cloned() else {
+            return Err(Error::with_message(ErrorKind::Other, || {
+                format!(
+                    "{} {} was not recorded; run the test with {MODE}=record",
+                    actual.method, actual.url
+                )
+            }));
+        };
+        if let Some(mismatch) = state.matcher.mismatch(&entry.request, &actual) {
+            return Err(Error::with_message(ErrorKind::Other, || {
+                format!("{mismatch}; run the test with {MODE}=record")
+            }));
+        }
+        state.next += 1;
+
+        let mut builder = ResponseBuilder::new(entry.status);
+        for (name, value) in entry.response_headers {
+            builder.with_header(name, value);
+        }
+        let body = match entry.response_body {
+            RecordedBody::Text(text) => text.into_bytes(),
+            RecordedBody::Base64(base64) => STANDARD.decode(base64).map_err(|error| {
+                Error::full(ErrorKind::DataConversion, error, "invalid recorded body")
+            })?,
+        };
+        Ok(builder.with_body(body).build())
+    }
+}
+
+impl State {
+    fn sanitize(&self, value: &str) -> String {
+        self.sanitizers
+            .iter()
+            .fold(value.to_string(), |value, (secret, replacement)| {
+                value.replace(secret, replacement)
+            })
+    }
+
+    fn sanitize_request(&self, request: &Request) -> RecordedRequest {
+        RecordedRequest {
+            method: request.method().to_string(),
+            url: self.sanitize(request.url().as_str()),
+            request_headers: request
+                .headers()
+                .iter()
+                .filter(|(name, _)| **name != headers::AUTHORIZATION)
+                .map(|(name, value)| (name.as_str().to_string(), self.sanitize(value.as_str())))
+                .collect(),
+            request_body: match request.body() {
+                Body::Bytes(bytes) if bytes.is_empty() => None,
+                Body::Bytes(bytes) => Some(self.sanitize_body(bytes)),
+                Body::Stream(_) => None,
+            },
+        }
+    }
+
+    fn sanitize_body(&self, body: &[u8]) -> RecordedBody {
+        match std::str::from_utf8(body) {
+            Ok(text) => RecordedBody::Text(self.sanitize(text)),
+            Err(_) => RecordedBody::Base64(STANDARD.encode(body)),
+        }
+    }
+}
+
+/// Matches the requests played back to those recorded, ignoring the headers and query parameters
+/// which change every time a test runs.
+///
+/// By default, requests must have the same method, URL, headers, and body, ignoring the
+/// `authorization`, `date`, `x-ms-date`, `x-ms-client-request-id`, `idempotency-key`,
+/// `traceparent`, and `user-agent` headers.
+#[derive(Clone, Debug)]
+pub struct Matcher {
+    ignored_headers: Vec<HeaderName>,
+    ignored_query_parameters: Vec<String>,
+    compare_bodies: bool,
+}
+
+impl Matcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Matches requests with any value of the header `name`, or without it.
+    pub fn with_ignored_header(&mut self, name: impl Into<HeaderName>) -> &mut Self {
+        self.ignored_headers.push(name.into());
+        self
+    }
+
+    /// Matches requests with any value of the query parameter `name`, or without it.
+    pub fn with_ignored_query_parameter(&mut self, name: impl Into<String>) -> &mut Self {
+        self.ignored_query_parameters.push(name.into());
+        self
+    }
+
+    /// Whether to match request bodies, which may be disabled for bodies with generated values.
+    pub fn with_compare_bodies(&mut self, compare_bodies: bool) -> &mut Self {
+        self.compare_bodies = compare_bodies;
+        self
+    }
+
+    /// Describes how `actual` differs from `recorded`, if it does.
+    fn mismatch(&self, recorded: &RecordedRequest, actual: &RecordedRequest) -> Option<String> {
+        let (recorded_url, actual_url) = (self.url(&recorded.url), self.url(&actual.url));
+        if recorded.method != actual.method || recorded_url != actual_url {
+            return Some(format!(
+                "expected {} {recorded_url} but got {} {actual_url}",
+                recorded.method, actual.method
+            ));
+        }
+
+        let names = recorded
+            .request_headers
+            .keys()
+            .chain(actual.request_headers.keys())
+            .filter(|name| {
+                !self
+                    .ignored_headers
+                    .iter()
+                    .any(|ignored| ignored.as_str() == name.as_str())
+            });
+        for name in names {
+            let (recorded_value, actual_value) = (
+                recorded.request_headers.get(name),
+                actual.request_headers.get(name),
+            );
+            if recorded_value != actual_value {
+                return Some(format!(
+                    "expected header '{name}' to be {recorded_value:?} but was {actual_value:?} for {} {actual_url}",
+                    actual.method
+                ));
+            }
+        }
+
+        if self.compare_bodies && recorded.request_body != actual.request_body {
+            return Some(format!(
+                "expected the recorded body for {} {actual_url}",
+                actual.method
+            ));
+        }
+        None
+    }
+
+    /// Removes the ignored query parameters from `url`.
+    fn url(&self, url: &str) -> String {
+        let Ok(mut parsed) = Url::parse(url) else {
+            return url.to_string();
+        };
+        if self.ignored_query_parameters.is_empty() || parsed.query().is_none() {
+            return url.to_string();
+        }
+        let pairs: Vec<(String, String)> = parsed
+            .query_pairs()
+            .into_owned()
+            .filter(|(name, _)| !self.ignored_query_parameters.contains(name))
+            .collect();
+        if pairs.is_empty() {
+            parsed.set_query(None);
+        } else {
+            parsed.query_pairs_mut().clear().extend_pairs(pairs);
+        }
+        parsed.to_string()
+    }
+}
+
+impl Default for Matcher {
+    fn default() -> Self {
+        Self {
+            ignored_headers: vec![
+                headers::AUTHORIZATION,
+                headers::DATE,
+                HeaderName::from_static("x-ms-date"),
+                headers::CLIENT_REQUEST_ID,
+                headers::IDEMPOTENCY_KEY,
+                HeaderName::from_static("traceparent"),
+                headers::USER_AGENT,
+            ],
+            ignored_query_parameters: Vec::new(),
+            compare_bodies: true,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct RecordingTransport {
+    recording: Recording,
+    inner: TransportOptions,
+}
+
+#[async_trait::async_trait]
+impl HttpClient for RecordingTransport {
+    async fn execute_request(&self, request: &Request) -> crate::Result<Response> {
+        match self.recording.mode {
+            TestMode::Record => self.recording.record(request, &self.inner).await,
+            TestMode::Playback | TestMode::Live => self.recording.play_back(request),
+        }
+    }
+}
+
+/// Returns a fake token valid for an hour, for requests to services which do not check them.
+#[derive(Clone, Debug, Default)]
+pub struct FakeCredential;
+
+#[async_trait::async_trait]
+impl TokenCredential for FakeCredential {
+    async fn get_token(
+        &self,
+        _scopes: &[&str],
+        _options: &TokenRequestOptions,
+    ) -> crate::Result<AccessToken> {
+        Ok(AccessToken::new(
+            "fake",
+            SystemTime::now() + Duration::from_secs(3600),
+        ))
+    }
+}
+
+/// The credential and options for a client which sends requests to a [`wiremock::MockServer`].
+///
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use azure_core::test::MockServerConfig;
+/// use wiremock::MockServer;
+///
+/// let server = MockServer::start().await;
+/// let config = MockServerConfig::new(&server);
+/// // Create the client with its usual endpoint, `config.credential`, and `config.options`.
+/// # }
+/// ```
+#[cfg(feature = "wiremock")]
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct MockServerConfig {
+    /// A [`FakeCredential`].
+    pub credential: Arc<dyn TokenCredential>,
+    /// Options which send requests to the server instead of the client's endpoint, without
+    /// retrying them.
+    pub options: ClientOptions,
+}
+
+#[cfg(feature = "wiremock")]
+impl MockServerConfig {
+    pub fn new(server: &wiremock::MockServer) -> Self {
+        let host_override =
+            crate::HostOverride::new(server.uri()).expect("mock server URI is an endpoint");
+        Self {
+            credential: Arc::new(FakeCredential),
+            options: ClientOptions {
+                retry: crate::RetryOptions::none(),
+                host_override: Some(host_override),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Whether a test passed, so its recording is only finished if it did.
+#[doc(hidden)]
+pub trait Outcome {
+    fn passed(&self) -> bool;
+}
+
+impl Outcome for () {
+    fn passed(&self) -> bool {
+        true
+    }
+}
+
+impl<T, E> Outcome for Result<T, E> {
+    fn passed(&self) -> bool {
+        self.is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{headers::Headers, stream::BytesStream, Url};
+
+    #[derive(Debug)]
+    struct EchoClient;
+
+    #[async_trait::async_trait]
+    impl HttpClient for EchoClient {
+        async fn execute_request(&self, request: &Request) -> crate::Result<Response> {
+            let mut headers = Headers::new();
+            headers.insert("x-ms-url", request.url().to_string());
+            Ok(Response::new(
+                200,
+                headers,
+                Box::pin(BytesStream::new(request.url().to_string())),
+            ))
+        }
+    }
+
+    async fn send(
+        transport: &RecordingTransport,
+        url: &str,
+    ) -> crate::Result<(Headers, bytes::Bytes)> {
+        let request = Request::new(Url::parse(url).unwrap(), "GET");
+        let response = transport.execute_request(&request).await?;
+        let headers = response.headers().clone();
+        Ok((headers, response.into_body().collect().await?))
+    }
+
+    #[tokio::test]
+    async fn records_and_plays_back() {
+        let dir = env::temp_dir().join(format!("azure_core_{}", std::process::id()));
+        let mut context =
+            TestContext::new(&dir, "azure_core::test", "records_and_plays_back").unwrap();
+        assert_eq!(
+            context.recording_path(),
+            dir.join("tests/recordings/test/records_and_plays_back.json")
+        );
+
+        context.with_mode(TestMode::Record);
+        let recording = context.start().unwrap();
+        recording.add_sanitizer("my-vault", "fake-vault");
+        let transport = RecordingTransport {
+            recording: recording.clone(),
+            inner: TransportOptions::with_http_client(Arc::new(EchoClient)),
+        };
+        // Responses are returned unsanitized while recording.
+        let (_, body) = send(&transport, "https://my-vault.vault.azure.net/")
+            .await
+            .unwrap();
+        assert_eq!(body, "https://my-vault.vault.azure.net/");
+        recording.finish().unwrap();
+
+        let recording = context.with_mode(TestMode::Playback).start().unwrap();
+        let har = dir.join("recording.har");
+        recording.write_har(&har).unwrap();
+        let har: Value = serde_json::from_slice(&fs::read(&har).unwrap()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(har["log"]["version"], "1.2");
+        let entry = &har["log"]["entries"][0];
+        assert_eq!(
+            entry["request"]["url"],
+            "https://fake-vault.vault.azure.net/"
+        );
+        assert_eq!(entry["response"]["status"], 200);
+        assert_eq!(
+            entry["response"]["content"]["text"],
+            "https://fake-vault.vault.azure.net/"
+        );
+
+        let transport = RecordingTransport {
+            recording: recording.clone(),
+            inner: TransportOptions::with_http_client(Arc::new(EchoClient)),
+        };
+        assert!(send(&transport, "https://other.vault.azure.net/")
+            .await
+            .is_err());
+        let (headers, body) = send(&transport, "https://fake-vault.vault.azure.net/")
+            .await
+            .unwrap();
+        assert_eq!(
+            headers.get_optional_str(&"x-ms-url".into()),
+            Some("https://fake-vault.vault.azure.net/")
+        );
+        assert_eq!(body, "https://fake-vault.vault.azure.net/");
+        recording.finish().unwrap();
+        assert!(send(&transport, "https://fake-vault.vault.azure.net/")
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn matches_ignoring_volatile_fields() {
+        let request = |url: &str, headers: &[(&str, &str)], body: &str| RecordedRequest {
+            method: "PUT".to_string(),
+            url: url.to_string(),
+            request_headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            request_body: Some(RecordedBody::Text(body.to_string())),
+        };
+        let recorded = request(
+            "https://localhost/secrets/a?api-version=7.5&t=1",
+            &[("x-ms-client-request-id", "1"), ("if-match", "\"1\"")],
+            "{}",
+        );
+
+        let mut matcher = Matcher::new();
+        let actual = request(
+            "https://localhost/secrets/a?api-version=7.5&t=1",
+            &[("x-ms-client-request-id", "2"), ("if-match", "\"1\"")],
+            "{}",
+        );
+        assert_eq!(matcher.mismatch(&recorded, &actual), None);
+
+        let actual = request(
+            "https://localhost/secrets/a?api-version=7.5&t=2",
+            &[("if-match", "\"2\"")],
+            "{\"id\":2}",
+        );
+        assert!(matcher
+            .mismatch(&recorded, &actual)
+            .unwrap()
+            .starts_with("expected PUT https://localhost/secrets/a?api-version=7.5&t=1"));
+        matcher.with_ignored_query_parameter("t");
+        assert!(matcher
+            .mismatch(&recorded, &actual)
+            .unwrap()
+            .starts_with("expected header 'if-match'"));
+        matcher.with_ignored_header("if-match");
+        assert!(matcher
+            .mismatch(&recorded, &actual)
+            .unwrap()
+            .starts_with("expected the recorded body"));
+        matcher.with_compare_bodies(false);
+        assert_eq!(matcher.mismatch(&recorded, &actual), None);
+    }
+}
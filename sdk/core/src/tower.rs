@@ -0,0 +1,191 @@
+//! Adapters between the [`Pipeline`] and [`tower`] services and layers.
+//!
+//! Enable the `tower` feature to use a [`Pipeline`] wherever a `tower::Service<Request>` is expected,
+//! or to reuse existing tower middleware like rate limiting or load shedding as a [`Policy`].
+
+use crate::{
+    context::Context,
+    error::Result,
+    pipeline::Pipeline,
+    policies::{Policy, PolicyResult},
+    request::Request,
+    response::Response,
+};
+use ::tower::{Layer, Service, ServiceExt};
+use futures::{future::BoxFuture, lock::Mutex};
+use std::{
+    fmt,
+    sync::Arc,
+    task::{Context as TaskContext, Poll},
+};
+
+impl Service<Request> for Pipeline {
+    type Response = Response;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Response>>;
+
+    fn poll_ready(&mut self, _cx: &mut TaskContext<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        let pipeline = self.clone();
+        Box::pin(async move {
+            let mut ctx = Context::default();
+            pipeline.send(&mut ctx, &mut request).await
+        })
+    }
+}
+
+/// The request passed through tower layers wrapped by a [`TowerPolicy`].
+#[derive(Clone, Debug)]
+pub struct PolicyRequest {
+    pub context: Context,
+    pub request: Request,
+    next: Vec<Arc<dyn Policy>>,
+}
+
+/// The innermost service of a [`TowerPolicy`] which sends a [`PolicyRequest`] to the next policy in the pipeline.
+#[derive(Clone, Debug, Default)]
+pub struct NextPolicy {
+    _private: (),
+}
+
+impl Service<PolicyRequest> for NextPolicy {
+    type Response = Response;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Response>>;
+
+    fn poll_ready(&mut self, _cx: &mut TaskContext<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut request: PolicyRequest) -> Self::Future {
+        Box::pin(async move {
+            request.next[0]
+                .send(
+                    &mut request.context,
+                    &mut request.request,
+                    &request.next[1..],
+                )
+                .await
+        })
+    }
+}
+
+/// Wraps a tower [`Layer`] as a [`Policy`].
+///
+/// The layered service is created once and shared by all requests, so stateful middleware like
+/// `tower::limit::RateLimitLayer` applies across every request sent through the pipeline.
+/// Changes layers make to the [`Context`] are not visible to policies before the `TowerPolicy`.
+pub struct TowerPolicy<S> {
+    service: Mutex<S>,
+}
+
+impl<S> TowerPolicy<S>
+where
+    S: Service<PolicyRequest, Response = Response> + Send + 'static,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    S::Future: Send,
+{
+    pub fn new<L>(layer: L) -> Self
+    where
+        L: Layer<NextPolicy, Service = S>,
+    {
+        Self {
+            service: Mutex::new(layer.layer(NextPolicy::default())),
+        }
+    }
+}
+
+impl<S> fmt::Debug for TowerPolicy<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TowerPolicy")
+            .field("service", &std::any::type_name::<S>())
+            .finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> Policy for TowerPolicy<S>
+where
+    S: Service<PolicyRequest, Response = Response> + Send + 'static,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    S::Future: Send,
+{
+    async fn send(
+        &self,
+        ctx: &mut Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        let request = PolicyRequest {
+            context: ctx.clone(),
+            request: request.clone(),
+            next: next.to_vec(),
+        };
+
+        let response = {
+            let mut service = self.service.lock().await;
+            service.ready().await.map_err(into_error)?.call(request)
+        };
+        response.await.map_err(into_error)
+    }
+}
+
+fn into_error(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> crate::Error {
+    match error.into().downcast::<crate::Error>() {
+        Ok(error) => *error,
+        Err(error) => crate::Error::new(crate::ErrorKind::Other, error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{policies::tests::MockPolicy, ClientOptions, TransportOptions, Url};
+    use ::tower::{layer::layer_fn, service_fn};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn pipeline_as_service() {
+        let options = ClientOptions {
+            transport: TransportOptions::new("{}"),
+            ..Default::default()
+        };
+        let pipeline = Pipeline::new(None, None, &options, Vec::new(), Vec::new());
+        let request = Request::new(Url::parse("https://localhost").unwrap(), "GET");
+
+        let response = pipeline.oneshot(request).await.expect("expected response");
+        assert_eq!(response.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn layer_as_policy() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let layer = layer_fn({
+            let calls = calls.clone();
+            move |inner: NextPolicy| {
+                let calls = calls.clone();
+                service_fn(move |request: PolicyRequest| {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    inner.clone().oneshot(request)
+                })
+            }
+        });
+        let policy: Arc<dyn Policy> = Arc::new(TowerPolicy::new(layer));
+
+        let next: Vec<Arc<dyn Policy>> = vec![Arc::new(MockPolicy::new(204))];
+        for _ in 0..2 {
+            let mut ctx = Context::default();
+            let mut request = Request::new(Url::parse("https://localhost").unwrap(), "GET");
+            let response = policy
+                .send(&mut ctx, &mut request, &next)
+                .await
+                .expect("expected response");
+            assert_eq!(response.status(), 204);
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
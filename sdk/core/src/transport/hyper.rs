@@ -0,0 +1,405 @@
+//! An [`HttpClient`] using [`hyper`] directly.
+//!
+//! Use this instead of the default transport when you need control over the hyper connector
+//! e.g., to use a custom TLS implementation or connect over a Unix domain socket using the [`UnixConnector`].
+
+mod timing;
+#[cfg(unix)]
+mod unix;
+
+pub use timing::{TimedStream, TimingConnector};
+#[cfg(unix)]
+pub use unix::*;
+
+use crate::{
+    error::{Error, ErrorKind, ResultExt},
+    headers::{self, HeaderName, HeaderValue, Headers, CONTENT_LENGTH},
+    options::{ConnectionOptions, HappyEyeballs},
+    request::{Body, Request},
+    response::Response,
+    stream::SyncStream,
+    transport::{ConnectionTimings, HttpClient, Resolver, SystemResolver},
+};
+use bytes::Bytes;
+use futures::{future::BoxFuture, TryStreamExt};
+use http_body_util::{combinators::BoxBody, BodyExt, BodyStream, Full, StreamBody};
+use hyper::body::Frame;
+use hyper_util::{
+    client::legacy::{
+        connect::{dns::Name, Connect, HttpConnector},
+        Client,
+    },
+    rt::TokioExecutor,
+};
+use std::{
+    net::SocketAddr,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Instant,
+};
+use timing::{LastWrite, NewConnection, DNS_TIMING};
+
+/// The body of requests sent by a [`HyperClient`], which streams a [`Body::Stream`] as it is
+/// sent rather than reading it into memory first.
+pub type HyperBody = BoxBody<Bytes, Error>;
+
+/// An [`HttpClient`] built on a [`hyper_util::client::legacy::Client`].
+///
+/// [`HyperClient::new`] supports only `http` URLs. Pass a client with a TLS-capable connector to
+/// [`HyperClient::with_client`] to support `https` URLs. Wrap the connector in a [`TimingConnector`]
+/// to report [`ConnectionTimings`] on responses.
+#[derive(Clone, Debug)]
+pub struct HyperClient<C = TimingConnector<HttpConnector>> {
+    client: Client<C, HyperBody>,
+}
+
+impl HyperClient<TimingConnector<HttpConnector>> {
+    /// Creates a client using an [`HttpConnector`] on a tokio runtime.
+    pub fn new() -> Self {
+        Self::with_connector(TimingConnector::new(HttpConnector::new()))
+    }
+}
+
+impl HyperClient<TimingConnector<HttpConnector<HyperResolver>>> {
+    /// Creates a client using an [`HttpConnector`] configured with the given [`ConnectionOptions`].
+    pub fn with_connection_options(options: &ConnectionOptions) -> Self {
+        let resolver = HyperResolver(
+            options
+                .effective_resolver()
+                .unwrap_or_else(|| Arc::new(SystemResolver)),
+        );
+
+        let mut connector = HttpConnector::new_with_resolver(resolver);
+        match options.happy_eyeballs {
+            HappyEyeballs::Default => {}
+            HappyEyeballs::Disabled => connector.set_happy_eyeballs_timeout(None),
+            HappyEyeballs::Timeout(timeout) => connector.set_happy_eyeballs_timeout(Some(timeout)),
+        }
+
+        Self::with_connector(TimingConnector::new(connector))
+    }
+}
+
+impl Default for HyperClient<TimingConnector<HttpConnector>> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> HyperClient<C>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    pub fn with_client(client: Client<C, HyperBody>) -> Self {
+        Self { client }
+    }
+
+    /// Creates a client using the given connector on a tokio runtime.
+    pub fn with_connector(connector: C) -> Self {
+        Self::with_client(Client::builder(TokioExecutor::new()).build(connector))
+    }
+}
+
+#[async_trait::async_trait]
+impl<C> HttpClient for HyperClient<C>
+where
+    C: Connect + Clone + Send + Sync + std::fmt::Debug + 'static,
+{
+    async fn execute_request(&self, request: &Request) -> crate::Result<Response> {
+        let mut builder = ::hyper::Request::builder().method(request.method());
+        match request.url().scheme() {
+            #[cfg(unix)]
+            unix::SCHEME => {
+                builder = builder.uri(unix::to_uri(request.url())?);
+                if request.headers().get_optional_str(&headers::HOST).is_none() {
+                    builder = builder.header(headers::HOST.as_str(), "localhost");
+                }
+            }
+            _ => builder = builder.uri(request.url().as_str()),
+        }
+        for (name, value) in request.headers().iter() {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        let body = match request.body() {
+            Body::Bytes(bytes) => Full::new(bytes.clone())
+                .map_err(|never| match never {})
+                .boxed(),
+            Body::Stream(stream) => {
+                // Streams are otherwise sent chunked, which not all services support.
+                if request
+                    .headers()
+                    .get_optional_str(&CONTENT_LENGTH)
+                    .is_none()
+                {
+                    builder = builder.header(CONTENT_LENGTH.as_str(), stream.len());
+                }
+                StreamBody::new(SyncStream::new(stream.reader().map_ok(Frame::data))).boxed()
+            }
+        };
+        let http_request = builder
+            .body(body)
+            .with_context(ErrorKind::DataConversion, || {
+                format!("failed to build {} request", request.method())
+            })?;
+
+        let http_response = self
+            .client
+            .request(http_request)
+            .await
+            .context(ErrorKind::Io, "failed to execute request")?;
+        let received = Instant::now();
+
+        let (parts, body) = http_response.into_parts();
+        let timings = ConnectionTimings {
+            time_to_first_byte: parts
+                .extensions
+                .get::<LastWrite>()
+                .and_then(LastWrite::get)
+                .map(|written| received.saturating_duration_since(written)),
+            ..parts
+                .extensions
+                .get::<NewConnection>()
+                .and_then(NewConnection::take)
+                .unwrap_or_default()
+        };
+        let mut headers = Headers::new();
+        for (name, value) in parts.headers.iter() {
+            headers.insert(
+                HeaderName::from(name.as_str().to_owned()),
+                HeaderValue::from(String::from_utf8_lossy(value.as_bytes()).into_owned()),
+            );
+        }
+
+        let stream = BodyStream::new(body.map_err(|error| Error::new(ErrorKind::Io, error)))
+            .try_filter_map(|frame| async move { Ok(frame.into_data().ok()) });
+
+        let mut response = Response::new(
+            parts.status.as_u16(),
+            headers,
+            Box::pin(SyncStream::new(stream)),
+        );
+        response.set_timings(timings);
+        Ok(response)
+    }
+}
+
+/// Adapts a [`Resolver`] for use by an [`HttpConnector`].
+#[derive(Clone, Debug)]
+pub struct HyperResolver(Arc<dyn Resolver>);
+
+impl tower_service::Service<Name> for HyperResolver {
+    type Response = std::vec::IntoIter<SocketAddr>;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, crate::Result<Self::Response>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<crate::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, name: Name) -> Self::Future {
+        let resolver = self.0.clone();
+        Box::pin(async move {
+            let start = Instant::now();
+            let addrs = resolver.resolve(name.as_str()).await?;
+            let _ = DNS_TIMING.try_with(|dns| dns.record(start.elapsed()));
+            Ok(addrs
+                .into_iter()
+                .map(|ip| SocketAddr::new(ip, 0))
+                .collect::<Vec<_>>()
+                .into_iter())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Url;
+    use std::time::Duration;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    #[tokio::test]
+    async fn execute_request() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = [0u8; 1024];
+            let _ = stream.read(&mut buffer).await.unwrap();
+            stream
+                .write_all(
+                    b"HTTP/1.1 201 Created\r\nx-ms-request-id: 1\r\ncontent-length: 2\r\n\r\n{}",
+                )
+                .await
+                .unwrap();
+        });
+
+        let client = HyperClient::new();
+        let request = Request::new(Url::parse(&format!("http://{address}/")).unwrap(), "PUT");
+        let response = client
+            .execute_request(&request)
+            .await
+            .expect("expected response");
+
+        assert_eq!(response.status(), 201);
+        assert_eq!(
+            response
+                .headers()
+                .get_optional_str(&HeaderName::from_static("x-ms-request-id")),
+            Some("1")
+        );
+        assert_eq!(response.into_body().collect().await.unwrap(), "{}");
+    }
+
+    #[derive(Debug)]
+    struct ChannelStream(futures::channel::mpsc::UnboundedReceiver<crate::Result<Bytes>>);
+
+    impl futures::Stream for ChannelStream {
+        type Item = crate::Result<Bytes>;
+
+        fn poll_next(
+            mut self: std::pin::Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Option<Self::Item>> {
+            std::pin::Pin::new(&mut self.0).poll_next(cx)
+        }
+    }
+
+    impl crate::stream::BodyStream for ChannelStream {
+        fn len(&self) -> usize {
+            6
+        }
+    }
+
+    #[tokio::test]
+    async fn streams_request_body() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let (sender, receiver) = futures::channel::mpsc::unbounded();
+        sender.unbounded_send(Ok(Bytes::from("abc"))).unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 1024];
+            // The rest of the body is only sent once the first chunk was received.
+            for (chunk, next) in [("abc", Some("def")), ("abcdef", None)] {
+                while !request.ends_with(chunk.as_bytes()) {
+                    let read = stream.read(&mut buffer).await.unwrap();
+                    assert_ne!(read, 0, "{}", String::from_utf8_lossy(&request));
+                    request.extend_from_slice(&buffer[..read]);
+                }
+                if let Some(next) = next {
+                    sender.unbounded_send(Ok(Bytes::from(next))).unwrap();
+                    sender.close_channel();
+                }
+            }
+            let request = String::from_utf8(request).unwrap();
+            assert!(request.contains("content-length: 6\r\n"), "{request}");
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .unwrap();
+        });
+
+        let mut request = Request::new(Url::parse(&format!("http://{address}/")).unwrap(), "PUT");
+        request.set_body(crate::stream::StreamBody::new(ChannelStream(receiver)));
+        let response = tokio::time::timeout(
+            Duration::from_secs(10),
+            HyperClient::new().execute_request(&request),
+        )
+        .await
+        .expect("the body was not streamed")
+        .expect("expected response");
+        assert_eq!(response.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn execute_request_with_resolver() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = [0u8; 1024];
+            let _ = stream.read(&mut buffer).await.unwrap();
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\n\r\n")
+                .await
+                .unwrap();
+        });
+
+        let mut resolver = crate::transport::StaticResolver::new();
+        resolver.insert("vault.test", [std::net::Ipv4Addr::LOCALHOST.into()]);
+        let client = HyperClient::with_connection_options(&ConnectionOptions {
+            resolver: Some(Arc::new(SlowResolver(resolver))),
+            ip_preference: crate::IpPreference::Ipv4Only,
+            happy_eyeballs: HappyEyeballs::Disabled,
+        });
+
+        let url = Url::parse(&format!("http://vault.test:{port}/")).unwrap();
+        let response = client
+            .execute_request(&Request::new(url, "GET"))
+            .await
+            .expect("expected response");
+        assert_eq!(response.status(), 204);
+
+        let timings = response.timings().expect("expected timings");
+        assert!(timings.dns.is_some());
+        assert!(timings.connect.is_some());
+        // Measured from writing the request, so it excludes the time spent connecting.
+        assert!(timings.dns.unwrap() >= Duration::from_millis(50));
+        assert!(timings.time_to_first_byte.unwrap() < Duration::from_millis(50));
+    }
+
+    #[derive(Debug)]
+    struct SlowResolver(crate::transport::StaticResolver);
+
+    #[async_trait::async_trait]
+    impl Resolver for SlowResolver {
+        async fn resolve(&self, host: &str) -> crate::Result<Vec<std::net::IpAddr>> {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            self.0.resolve(host).await
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn execute_request_unix_socket() {
+        let socket_path =
+            std::env::temp_dir().join(format!("azure_core-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = tokio::net::UnixListener::bind(&socket_path).unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = [0u8; 1024];
+            let read = stream.read(&mut buffer).await.unwrap();
+            let request = String::from_utf8_lossy(&buffer[..read]).into_owned();
+            assert!(request.starts_with("GET /metadata/identity?api-version=1 HTTP/1.1\r\n"));
+            assert!(request.contains("host: localhost\r\n"));
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .unwrap();
+        });
+
+        let encoded = percent_encoding::utf8_percent_encode(
+            socket_path.to_str().unwrap(),
+            percent_encoding::NON_ALPHANUMERIC,
+        );
+        let url = Url::parse(&format!(
+            "http+unix://{encoded}/metadata/identity?api-version=1"
+        ))
+        .unwrap();
+
+        let client = HyperClient::with_connector(UnixConnector::new());
+        let response = client
+            .execute_request(&Request::new(url, "GET"))
+            .await
+            .expect("expected response");
+        let _ = std::fs::remove_file(&socket_path);
+
+        assert_eq!(response.status(), 200);
+    }
+}
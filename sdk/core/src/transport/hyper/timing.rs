@@ -0,0 +1,183 @@
+//! Measures connection timings for connectors used by a [`HyperClient`](super::HyperClient).
+
+use crate::transport::ConnectionTimings;
+use futures::future::BoxFuture;
+use hyper::{
+    rt::{Read, ReadBufCursor, Write},
+    Uri,
+};
+use hyper_util::client::legacy::connect::{Connected, Connection};
+use std::{
+    io,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+tokio::task_local! {
+    // Lets the HyperResolver report how long resolution took for the connection being established.
+    pub(super) static DNS_TIMING: DnsTiming;
+}
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct DnsTiming(Arc<Mutex<Option<Duration>>>);
+
+impl DnsTiming {
+    pub(super) fn record(&self, duration: Duration) {
+        *self.0.lock().expect("timing lock poisoned") = Some(duration);
+    }
+
+    fn take(&self) -> Option<Duration> {
+        self.0.lock().expect("timing lock poisoned").take()
+    }
+}
+
+/// Timings for a new connection attached to the first response sent over it.
+#[derive(Clone, Debug)]
+pub(super) struct NewConnection(Arc<Mutex<Option<ConnectionTimings>>>);
+
+impl NewConnection {
+    /// Takes the timings so that only the first response on a connection reports them.
+    pub(super) fn take(&self) -> Option<ConnectionTimings> {
+        self.0.lock().expect("timing lock poisoned").take()
+    }
+}
+
+/// When a request was last written to a connection, to measure the time to first byte of its response.
+#[derive(Clone, Debug, Default)]
+pub(super) struct LastWrite(Arc<Mutex<Option<Instant>>>);
+
+impl LastWrite {
+    pub(super) fn get(&self) -> Option<Instant> {
+        *self.0.lock().expect("timing lock poisoned")
+    }
+
+    fn record(&self) {
+        *self.0.lock().expect("timing lock poisoned") = Some(Instant::now());
+    }
+}
+
+/// Wraps a connector to measure how long connections take to establish.
+///
+/// DNS resolution is measured separately when the inner connector is an
+/// [`HttpConnector`](hyper_util::client::legacy::connect::HttpConnector) using a
+/// [`HyperResolver`](super::HyperResolver); otherwise, it is included in the connect time.
+/// TLS negotiation cannot be distinguished from connecting and is always included in the connect time.
+/// The time to first byte is measured from when the request was last written to the connection.
+#[derive(Clone, Debug)]
+pub struct TimingConnector<C> {
+    inner: C,
+}
+
+impl<C> TimingConnector<C> {
+    pub fn new(inner: C) -> Self {
+        Self { inner }
+    }
+}
+
+impl<C> tower_service::Service<Uri> for TimingConnector<C>
+where
+    C: tower_service::Service<Uri>,
+    C::Response: Send + 'static,
+    C::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    C::Future: Send + 'static,
+{
+    type Response = TimedStream<C::Response>;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let dns = DnsTiming::default();
+        let connecting = self.inner.call(uri);
+        Box::pin(DNS_TIMING.scope(dns.clone(), async move {
+            let start = Instant::now();
+            let stream = connecting.await.map_err(Into::into)?;
+            let elapsed = start.elapsed();
+
+            let dns = dns.take();
+            let timings = ConnectionTimings {
+                dns,
+                connect: Some(elapsed.saturating_sub(dns.unwrap_or_default())),
+                ..Default::default()
+            };
+
+            Ok(TimedStream {
+                inner: stream,
+                timings: NewConnection(Arc::new(Mutex::new(Some(timings)))),
+                last_write: LastWrite::default(),
+            })
+        }))
+    }
+}
+
+/// A connection created by a [`TimingConnector`].
+#[derive(Debug)]
+pub struct TimedStream<S> {
+    inner: S,
+    timings: NewConnection,
+    last_write: LastWrite,
+}
+
+impl<S: Connection> Connection for TimedStream<S> {
+    fn connected(&self) -> Connected {
+        self.inner
+            .connected()
+            .extra(self.timings.clone())
+            .extra(self.last_write.clone())
+    }
+}
+
+impl<S: Read + Unpin> Read for TimedStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: ReadBufCursor<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_read(cx, buf)
+    }
+}
+
+impl<S: Write + Unpin> Write for TimedStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_write(cx, buf);
+        if matches!(poll, Poll::Ready(Ok(_))) {
+            this.last_write.record();
+        }
+        poll
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_write_vectored(cx, bufs);
+        if matches!(poll, Poll::Ready(Ok(_))) {
+            this.last_write.record();
+        }
+        poll
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
@@ -0,0 +1,172 @@
+//! Connects to `http+unix://` URLs over Unix domain sockets.
+//!
+//! The socket path is the percent-encoded host of the URL e.g., `http+unix://%2Fvar%2Frun%2Fmsi.sock/metadata/identity`.
+
+use crate::error::{Error, ErrorKind, ResultExt};
+use futures::future::BoxFuture;
+use hyper::{
+    rt::{Read, ReadBufCursor, Write},
+    Uri,
+};
+use hyper_util::{
+    client::legacy::connect::{Connected, Connection, HttpConnector},
+    rt::TokioIo,
+};
+use percent_encoding::percent_decode_str;
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::net::{TcpStream, UnixStream};
+use url::Url;
+
+pub(super) const SCHEME: &str = "http+unix";
+
+// http::Uri does not allow percent-encoded authorities, so the socket path is hex-encoded instead.
+const URI_SCHEME: &str = "unix";
+
+/// Converts an `http+unix://` URL into a [`Uri`] the [`UnixConnector`] can connect to.
+pub(super) fn to_uri(url: &Url) -> crate::Result<Uri> {
+    let host = url.host_str().ok_or_else(|| {
+        Error::with_message(ErrorKind::DataConversion, || {
+            format!("missing socket path in {url}")
+        })
+    })?;
+    let socket_path = percent_decode_str(host)
+        .decode_utf8()
+        .context(ErrorKind::DataConversion, "invalid socket path encoding")?;
+
+    let mut uri = String::from(URI_SCHEME);
+    uri.push_str("://");
+    for byte in socket_path.as_bytes() {
+        uri.push_str(&format!("{byte:02x}"));
+    }
+    uri.push_str(url.path());
+    if let Some(query) = url.query() {
+        uri.push('?');
+        uri.push_str(query);
+    }
+
+    uri.parse()
+        .with_context(ErrorKind::DataConversion, || format!("invalid URL {url}"))
+}
+
+fn socket_path(uri: &Uri) -> io::Result<String> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidInput, format!("invalid URI {uri}"));
+    let host = uri.host().ok_or_else(invalid)?;
+    if host.len() % 2 != 0 {
+        return Err(invalid());
+    }
+
+    let bytes = (0..host.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&host[i..i + 2], 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .map_err(|_| invalid())?;
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+/// A connector for [`HyperClient`](super::HyperClient) that connects to `http+unix://` URLs over
+/// Unix domain sockets and to all other URLs using an [`HttpConnector`].
+#[derive(Clone, Debug)]
+pub struct UnixConnector {
+    http: HttpConnector,
+}
+
+impl UnixConnector {
+    pub fn new() -> Self {
+        Self::with_http_connector(HttpConnector::new())
+    }
+
+    pub fn with_http_connector(http: HttpConnector) -> Self {
+        Self { http }
+    }
+}
+
+impl Default for UnixConnector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl tower_service::Service<Uri> for UnixConnector {
+    type Response = UnixOrTcpStream;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.http.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        if uri.scheme_str() == Some(URI_SCHEME) {
+            Box::pin(async move {
+                let stream = UnixStream::connect(socket_path(&uri)?).await?;
+                Ok(UnixOrTcpStream(Inner::Unix(TokioIo::new(stream))))
+            })
+        } else {
+            let connecting = self.http.call(uri);
+            Box::pin(async move { Ok(UnixOrTcpStream(Inner::Tcp(connecting.await?))) })
+        }
+    }
+}
+
+/// A connection created by a [`UnixConnector`].
+#[derive(Debug)]
+pub struct UnixOrTcpStream(Inner);
+
+#[derive(Debug)]
+enum Inner {
+    Tcp(TokioIo<TcpStream>),
+    Unix(TokioIo<UnixStream>),
+}
+
+impl Connection for UnixOrTcpStream {
+    fn connected(&self) -> Connected {
+        match &self.0 {
+            Inner::Tcp(stream) => stream.connected(),
+            Inner::Unix(_) => Connected::new(),
+        }
+    }
+}
+
+impl Read for UnixOrTcpStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: ReadBufCursor<'_>,
+    ) -> Poll<io::Result<()>> {
+        match &mut self.get_mut().0 {
+            Inner::Tcp(stream) => Pin::new(stream).poll_read(cx, buf),
+            Inner::Unix(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl Write for UnixOrTcpStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match &mut self.get_mut().0 {
+            Inner::Tcp(stream) => Pin::new(stream).poll_write(cx, buf),
+            Inner::Unix(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut self.get_mut().0 {
+            Inner::Tcp(stream) => Pin::new(stream).poll_flush(cx),
+            Inner::Unix(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut self.get_mut().0 {
+            Inner::Tcp(stream) => Pin::new(stream).poll_shutdown(cx),
+            Inner::Unix(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
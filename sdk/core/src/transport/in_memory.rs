@@ -0,0 +1,43 @@
+use crate::{
+    options::TransportOptions, request::Request, response::Response, response::ResponseBuilder,
+    transport::HttpClient,
+};
+use std::sync::Arc;
+
+/// Returns the same response to every request without sending it, e.g., so examples run
+/// without a network.
+///
+/// ```
+/// use azure_core::{transport::InMemoryTransport, ClientOptions, ResponseBuilder};
+///
+/// let mut options = ClientOptions::default();
+/// options.transport = InMemoryTransport::new(
+///     ResponseBuilder::new(200).with_body(r#"{"name":"my-secret","value":"my-value"}"#),
+/// )
+/// .into();
+/// ```
+#[derive(Clone, Debug)]
+pub struct InMemoryTransport {
+    response: ResponseBuilder,
+}
+
+impl InMemoryTransport {
+    pub fn new(response: &ResponseBuilder) -> Self {
+        Self {
+            response: response.clone(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpClient for InMemoryTransport {
+    async fn execute_request(&self, _request: &Request) -> crate::Result<Response> {
+        Ok(self.response.build())
+    }
+}
+
+impl From<InMemoryTransport> for TransportOptions {
+    fn from(transport: InMemoryTransport) -> Self {
+        Self::with_http_client(Arc::new(transport))
+    }
+}
@@ -0,0 +1,38 @@
+//! HTTP transports used by the [`TransportPolicy`](crate::policies::TransportPolicy) at the end of a pipeline.
+
+#[cfg(feature = "hyper")]
+pub mod hyper;
+mod in_memory;
+#[cfg(feature = "reqwest")]
+pub mod reqwest;
+mod resolver;
+
+pub use in_memory::*;
+pub use resolver::*;
+
+use crate::{request::Request, response::Response};
+use std::time::Duration;
+
+/// Sends a [`Request`] over the network and returns the [`Response`].
+///
+/// Implement this to replace the HTTP stack used by a client and pass it to
+/// [`TransportOptions::with_http_client`](crate::TransportOptions::with_http_client).
+#[async_trait::async_trait]
+pub trait HttpClient: Send + Sync + std::fmt::Debug {
+    async fn execute_request(&self, request: &Request) -> crate::Result<Response>;
+}
+
+/// Network timings for a request measured by the transport that sent it.
+///
+/// Timings a transport cannot measure are `None`. Connection timings are reported only on the
+/// response to the request that established a new connection, not on requests reusing that connection.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConnectionTimings {
+    /// Time spent resolving the host name.
+    pub dns: Option<Duration>,
+    /// Time spent establishing the connection after the host name was resolved, including
+    /// negotiating TLS.
+    pub connect: Option<Duration>,
+    /// Time from writing the request until the response headers were received.
+    pub time_to_first_byte: Option<Duration>,
+}
@@ -0,0 +1,112 @@
+//! The default [`HttpClient`] using [`reqwest`].
+//!
+//! Select the TLS implementation with either the `native-tls` (default) or `rustls` feature.
+
+use crate::{
+    error::{Error, ErrorKind, ResultExt},
+    headers::{HeaderName, HeaderValue, Headers, CONTENT_LENGTH},
+    options::ConnectionOptions,
+    request::{Body, Request},
+    response::Response,
+    stream::SyncStream,
+    transport::{HttpClient, Resolver},
+};
+use futures::TryStreamExt;
+use std::{net::SocketAddr, sync::Arc};
+
+/// Creates a new [`reqwest::Client`] with the TLS implementation selected by crate features.
+///
+/// Returns an error if the TLS implementation could not be initialized.
+pub fn new_reqwest_client() -> crate::Result<Arc<dyn HttpClient>> {
+    new_reqwest_client_with_options(&ConnectionOptions::default())
+}
+
+/// Creates a new [`reqwest::Client`] configured with the given [`ConnectionOptions`].
+pub fn new_reqwest_client_with_options(
+    options: &ConnectionOptions,
+) -> crate::Result<Arc<dyn HttpClient>> {
+    let mut builder = ::reqwest::ClientBuilder::new();
+    if let Some(resolver) = options.effective_resolver() {
+        builder = builder.dns_resolver(Arc::new(ReqwestResolver(resolver)));
+    }
+
+    // Prefer rustls when both are enabled e.g., when built with --all-features.
+    #[cfg(feature = "rustls")]
+    {
+        builder = builder.use_rustls_tls();
+    }
+
+    let client = builder
+        .build()
+        .context(ErrorKind::Other, "failed to create HTTP client")?;
+    Ok(Arc::new(client))
+}
+
+#[async_trait::async_trait]
+impl HttpClient for ::reqwest::Client {
+    async fn execute_request(&self, request: &Request) -> crate::Result<Response> {
+        let method = ::reqwest::Method::from_bytes(request.method().as_bytes())
+            .with_context(ErrorKind::DataConversion, || {
+                format!("invalid HTTP method {}", request.method())
+            })?;
+
+        let mut builder = self.request(method, request.url().clone());
+        for (name, value) in request.headers().iter() {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        let body = match request.body() {
+            Body::Bytes(bytes) => ::reqwest::Body::from(bytes.clone()),
+            Body::Stream(stream) => {
+                // Streams are otherwise sent chunked, which not all services support.
+                if request
+                    .headers()
+                    .get_optional_str(&CONTENT_LENGTH)
+                    .is_none()
+                {
+                    builder = builder.header(CONTENT_LENGTH.as_str(), stream.len());
+                }
+                ::reqwest::Body::wrap_stream(stream.reader())
+            }
+        };
+
+        let response = builder
+            .body(body)
+            .send()
+            .await
+            .context(ErrorKind::Io, "failed to execute request")?;
+
+        let status = response.status().as_u16();
+        let mut headers = Headers::new();
+        for (name, value) in response.headers().iter() {
+            headers.insert(
+                HeaderName::from(name.as_str().to_owned()),
+                HeaderValue::from(String::from_utf8_lossy(value.as_bytes()).into_owned()),
+            );
+        }
+
+        let stream = response
+            .bytes_stream()
+            .map_err(|error| Error::new(ErrorKind::Io, error));
+
+        // reqwest does not expose connection timings or when the request was written.
+        Ok(Response::new(
+            status,
+            headers,
+            Box::pin(SyncStream::new(stream)),
+        ))
+    }
+}
+
+struct ReqwestResolver(Arc<dyn Resolver>);
+
+impl ::reqwest::dns::Resolve for ReqwestResolver {
+    fn resolve(&self, name: ::reqwest::dns::Name) -> ::reqwest::dns::Resolving {
+        let resolver = self.0.clone();
+        Box::pin(async move {
+            let addrs = resolver.resolve(name.as_str()).await?;
+            let addrs: ::reqwest::dns::Addrs =
+                Box::new(addrs.into_iter().map(|ip| SocketAddr::new(ip, 0)));
+            Ok(addrs)
+        })
+    }
+}
@@ -0,0 +1,120 @@
+use crate::error::{Error, ErrorKind};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, ToSocketAddrs},
+    sync::Arc,
+};
+
+/// Resolves host names to IP addresses for a transport.
+///
+/// Set on [`ConnectionOptions::resolver`](crate::ConnectionOptions::resolver) to pin hosts to
+/// specific addresses e.g., for private endpoints or split-horizon DNS in tests.
+#[async_trait::async_trait]
+pub trait Resolver: Send + Sync + std::fmt::Debug {
+    async fn resolve(&self, host: &str) -> crate::Result<Vec<IpAddr>>;
+}
+
+/// Resolves host names using the operating system resolver.
+#[derive(Clone, Debug, Default)]
+pub struct SystemResolver;
+
+#[async_trait::async_trait]
+impl Resolver for SystemResolver {
+    async fn resolve(&self, host: &str) -> crate::Result<Vec<IpAddr>> {
+        let host = host.to_owned();
+        let (tx, rx) = futures::channel::oneshot::channel();
+
+        // Resolution blocks, so resolve on a separate thread to avoid depending on a specific async runtime.
+        std::thread::spawn(move || {
+            let result = (host.as_str(), 0)
+                .to_socket_addrs()
+                .map(|addrs| addrs.map(|addr| addr.ip()).collect());
+            let _ = tx.send(result);
+        });
+
+        rx.await
+            .map_err(|error| Error::new(ErrorKind::Other, error))?
+            .map_err(Into::into)
+    }
+}
+
+/// Resolves known host names to static addresses and all other host names using a fallback [`Resolver`].
+#[derive(Clone, Debug)]
+pub struct StaticResolver {
+    hosts: HashMap<String, Vec<IpAddr>>,
+    fallback: Arc<dyn Resolver>,
+}
+
+impl StaticResolver {
+    /// Creates a `StaticResolver` that falls back to the [`SystemResolver`].
+    pub fn new() -> Self {
+        Self::with_fallback(Arc::new(SystemResolver))
+    }
+
+    pub fn with_fallback(fallback: Arc<dyn Resolver>) -> Self {
+        Self {
+            hosts: HashMap::new(),
+            fallback,
+        }
+    }
+
+    /// Pins the `host` to the given addresses.
+    pub fn insert(
+        &mut self,
+        host: impl Into<String>,
+        addrs: impl IntoIterator<Item = IpAddr>,
+    ) -> &mut Self {
+        self.hosts
+            .insert(host.into().to_lowercase(), addrs.into_iter().collect());
+        self
+    }
+}
+
+impl Default for StaticResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Resolver for StaticResolver {
+    async fn resolve(&self, host: &str) -> crate::Result<Vec<IpAddr>> {
+        match self.hosts.get(&host.to_lowercase()) {
+            Some(addrs) => Ok(addrs.clone()),
+            None => self.fallback.resolve(host).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    #[derive(Debug)]
+    struct Unreachable;
+
+    #[async_trait::async_trait]
+    impl Resolver for Unreachable {
+        async fn resolve(&self, host: &str) -> crate::Result<Vec<IpAddr>> {
+            Err(Error::with_message(ErrorKind::Io, || {
+                format!("cannot resolve {host}")
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn static_resolver_pins_hosts() {
+        let mut resolver = StaticResolver::with_fallback(Arc::new(Unreachable));
+        resolver.insert(
+            "Vault.Vault.Azure.Net",
+            [IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))],
+        );
+
+        assert_eq!(
+            resolver.resolve("vault.vault.azure.net").await.unwrap(),
+            vec![IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))]
+        );
+        assert!(resolver.resolve("other.vault.azure.net").await.is_err());
+    }
+}
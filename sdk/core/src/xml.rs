@@ -0,0 +1,58 @@
+use crate::error::{Error, ErrorKind};
+use bytes::Bytes;
+use serde::{de::DeserializeOwned, Serialize};
+
+const DECLARATION: &str = r#"<?xml version="1.0" encoding="utf-8"?>"#;
+
+/// Serializes `value` as an XML document whose root element is named after its type, e.g.,
+/// `<BlockList>` for a `struct BlockList`.
+pub fn to_xml<T>(value: &T) -> crate::Result<Bytes>
+where
+    T: ?Sized + Serialize,
+{
+    let xml = quick_xml::se::to_string(value)
+        .map_err(|error| Error::new(ErrorKind::DataConversion, error))?;
+    Ok(Bytes::from(format!("{DECLARATION}{xml}")))
+}
+
+/// Deserializes an XML body, returning an [`ErrorKind::DataConversion`] error if the body does not
+/// match `T`.
+pub fn from_xml<S, T>(body: S) -> crate::Result<T>
+where
+    S: AsRef<[u8]>,
+    T: DeserializeOwned,
+{
+    let body = std::str::from_utf8(body.as_ref())
+        .map_err(|error| Error::new(ErrorKind::DataConversion, error))?;
+    // Some services prefix documents with a byte order mark.
+    let body = body.trim_start_matches('\u{feff}');
+    quick_xml::de::from_str(body).map_err(|error| Error::new(ErrorKind::DataConversion, error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct BlockList {
+        #[serde(rename = "Latest")]
+        latest: Vec<String>,
+    }
+
+    #[test]
+    fn round_trip() {
+        let blocks = BlockList {
+            latest: vec!["AA==".to_string(), "AQ==".to_string()],
+        };
+        let xml = to_xml(&blocks).unwrap();
+        assert_eq!(
+            xml,
+            r#"<?xml version="1.0" encoding="utf-8"?><BlockList><Latest>AA==</Latest><Latest>AQ==</Latest></BlockList>"#
+        );
+        assert_eq!(from_xml::<_, BlockList>(&xml).unwrap(), blocks);
+
+        let error = from_xml::<_, BlockList>("<BlockList><Latest>").unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::DataConversion);
+    }
+}
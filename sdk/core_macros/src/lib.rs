@@ -0,0 +1,218 @@
+//! Procedural macros for client libraries built on `azure_core`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::ToTokens;
+use syn::{parse_quote, visit_mut::VisitMut, Block, Expr, Ident, ItemFn, LitStr, Pat, Stmt};
+
+mod options;
+mod recorded;
+
+/// Inserts the `azure_core::OperationName` naming a client method into its `Context`.
+///
+/// The name is inserted right after the first `let mut ctx = ...;` in the method,
+/// and the name must be of the form `Client::method`. The name may also be a `&'static str`
+/// constant, e.g., when the method uses it elsewhere too.
+///
+/// ```ignore
+/// #[instrumented("SecretClient::set_secret")]
+/// pub async fn set_secret(&self, name: &str, value: &str) -> Result<Response> {
+///     let mut ctx = Context::default();
+///     // ...
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn instrumented(attr: TokenStream, item: TokenStream) -> TokenStream {
+    expand(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates `Default`, `with_*` setters, and conversions into `Cow` for an options struct.
+///
+/// Setters take `&mut self` unless the struct has `#[options(fluent)]`, and take the `T` of fields
+/// of type `T` or `Option<T>`. Fields may have:
+///
+/// * `#[options(default = expr)]` to default to `expr` instead of `Default::default()`.
+/// * `#[options(into)]` to take `impl Into<T>`, e.g., for `String` fields.
+/// * `#[options(skip)]` to not generate a setter.
+///
+/// ```ignore
+/// #[derive(Clone, Debug, Options)]
+/// pub struct SecretClientOptions {
+///     #[options(default = "7.5".to_string(), into)]
+///     pub api_version: String,
+///     pub options: ClientOptions,
+/// }
+/// ```
+#[proc_macro_derive(Options, attributes(options))]
+pub fn derive_options(item: TokenStream) -> TokenStream {
+    options::expand(item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Runs an async test with a `Recording` of its requests, named after the test, which
+/// configures clients to record or play back their requests.
+///
+/// The test takes the `Recording` as its only argument. Its recording is written or checked when
+/// it finishes, unless it failed. The mode from `AZURE_TEST_MODE` can be overridden with
+/// `#[recorded(playback)]`, `#[recorded(record)]`, or `#[recorded(live)]`. The crate must depend on `tokio` with the `macros` and `rt`
+/// features and on `azure_core` with the `test` feature.
+///
+/// ```ignore
+/// #[recorded]
+/// async fn get_secret(recording: Recording) -> Result<()> {
+///     let endpoint = recording.var("AZURE_KEYVAULT_URL", "https://my-vault.vault.azure.net")?;
+///     let credential = recording.credential(|| Arc::new(DefaultAzureCredential::default()));
+///     let mut options = SecretClientOptions::default();
+///     options.options = recording.client_options();
+///     let client = SecretClient::new(&endpoint, credential, Some(options))?;
+///     // ...
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn recorded(attr: TokenStream, item: TokenStream) -> TokenStream {
+    recorded::expand(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(attr: TokenStream2, item: TokenStream2) -> syn::Result<TokenStream2> {
+    let name: Expr = match syn::parse2::<LitStr>(attr.clone()) {
+        Ok(name) => {
+            let valid = name
+                .value()
+                .split_once("::")
+                .is_some_and(|(client, method)| {
+                    syn::parse_str::<Ident>(client).is_ok()
+                        && syn::parse_str::<Ident>(method).is_ok()
+                });
+            if !valid {
+                return Err(syn::Error::new_spanned(
+                    &name,
+                    "expected an operation name like \"Client::method\"",
+                ));
+            }
+            parse_quote!(#name)
+        }
+        Err(_) => match syn::parse2(attr)? {
+            Expr::Path(path) => Expr::Path(path),
+            name => {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "expected an operation name like \"Client::method\" or a constant",
+                ))
+            }
+        },
+    };
+
+    let mut item: ItemFn = syn::parse2(item)?;
+    let mut inserter = OperationNameInserter {
+        name,
+        inserted: false,
+    };
+    inserter.visit_block_mut(&mut item.block);
+    if !inserter.inserted {
+        return Err(syn::Error::new_spanned(
+            &item.sig.ident,
+            "expected `let mut ctx = ...;` to insert the operation name into",
+        ));
+    }
+
+    Ok(item.into_token_stream())
+}
+
+struct OperationNameInserter {
+    name: Expr,
+    inserted: bool,
+}
+
+impl VisitMut for OperationNameInserter {
+    fn visit_block_mut(&mut self, block: &mut Block) {
+        for i in 0..block.stmts.len() {
+            if is_ctx_declaration(&block.stmts[i]) {
+                let name = &self.name;
+                let stmt: Stmt =
+                    parse_quote!(ctx.insert(::azure_core::OperationName::from(#name)););
+                block.stmts.insert(i + 1, stmt);
+                self.inserted = true;
+            } else {
+                self.visit_stmt_mut(&mut block.stmts[i]);
+            }
+
+            if self.inserted {
+                return;
+            }
+        }
+    }
+}
+
+fn is_ctx_declaration(stmt: &Stmt) -> bool {
+    let Stmt::Local(local) = stmt else {
+        return false;
+    };
+    let pat = match &local.pat {
+        Pat::Type(pat) => &*pat.pat,
+        pat => pat,
+    };
+    matches!(pat, Pat::Ident(pat) if pat.ident == "ctx" && pat.mutability.is_some())
+        && local.init.is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    #[test]
+    fn inserts_operation_name() {
+        let item = quote! {
+            fn list(&self) -> Pageable<Item> {
+                Pageable::new(move |token| {
+                    let mut ctx = Context::default();
+                    async move { send(&mut ctx, token).await }
+                })
+            }
+        };
+        let expanded = expand(quote!("Client::list"), item).unwrap().to_string();
+        assert!(expanded.contains(
+            "let mut ctx = Context :: default () ; ctx . insert (:: azure_core :: OperationName :: from (\"Client::list\")) ;"
+        ));
+    }
+
+    #[test]
+    fn inserts_span_from_constant() {
+        let item = quote! {
+            fn get(&self) {
+                let mut ctx = Context::default();
+            }
+        };
+        let expanded = expand(quote!(GET_SPAN), item).unwrap().to_string();
+        assert!(
+            expanded.contains("ctx . insert (:: azure_core :: OperationName :: from (GET_SPAN)) ;")
+        );
+
+        let item = quote! {
+            fn get(&self) {
+                let mut ctx = Context::default();
+            }
+        };
+        assert!(expand(quote!(1 + 1), item).is_err());
+    }
+
+    #[test]
+    fn requires_ctx() {
+        let item = quote!(
+            fn get(&self) {}
+        );
+        assert!(expand(quote!("Client::get"), item).is_err());
+        assert!(expand(
+            quote!("get"),
+            quote!(
+                fn get() {}
+            )
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Attribute, Data, DeriveInput, Expr, Fields, GenericArgument, PathArguments, Type};
+
+pub fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let input: DeriveInput = syn::parse2(input)?;
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "options cannot be generic",
+        ));
+    }
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(name, "options must be a struct"));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            name,
+            "options must have named fields",
+        ));
+    };
+
+    let mut fluent = false;
+    parse_options(&input.attrs, |meta| {
+        if meta.path.is_ident("fluent") {
+            fluent = true;
+            Ok(())
+        } else {
+            Err(meta.error("expected `fluent`"))
+        }
+    })?;
+
+    let mut defaults = Vec::new();
+    let mut setters = Vec::new();
+    for field in &fields.named {
+        let ident = field.ident.as_ref().expect("named field");
+
+        let mut default: Option<Expr> = None;
+        let mut skip = false;
+        let mut into = false;
+        parse_options(&field.attrs, |meta| {
+            if meta.path.is_ident("default") {
+                default = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                skip = true;
+                Ok(())
+            } else if meta.path.is_ident("into") {
+                into = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `default = ...`, `into`, or `skip`"))
+            }
+        })?;
+
+        let default = default
+            .map(|default| quote!(#default))
+            .unwrap_or_else(|| quote!(::core::default::Default::default()));
+        defaults.push(quote!(#ident: #default));
+
+        if skip {
+            continue;
+        }
+        let vis = &field.vis;
+        let docs = field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"));
+        let setter = format_ident!("with_{}", ident);
+        let option = option_type(&field.ty);
+        let ty = option.unwrap_or(&field.ty);
+        let (ty, mut value) = if into {
+            (
+                quote!(impl ::core::convert::Into<#ty>),
+                quote!(#ident.into()),
+            )
+        } else {
+            (quote!(#ty), quote!(#ident))
+        };
+        if option.is_some() {
+            value = quote!(::core::option::Option::Some(#value));
+        }
+        setters.push(if fluent {
+            quote! {
+                #(#docs)*
+                #vis fn #setter(mut self, #ident: #ty) -> Self {
+                    self.#ident = #value;
+                    self
+                }
+            }
+        } else {
+            quote! {
+                #(#docs)*
+                #vis fn #setter(&mut self, #ident: #ty) -> &mut Self {
+                    self.#ident = #value;
+                    self
+                }
+            }
+        });
+    }
+
+    Ok(quote! {
+        impl ::core::default::Default for #name {
+            fn default() -> Self {
+                Self {
+                    #(#defaults,)*
+                }
+            }
+        }
+
+        impl #name {
+            #(#setters)*
+        }
+
+        impl<'a> ::core::convert::From<&'a #name> for ::std::borrow::Cow<'a, #name> {
+            fn from(original: &'a #name) -> Self {
+                ::std::borrow::Cow::Borrowed(original)
+            }
+        }
+
+        impl<'a> ::core::convert::From<#name> for ::std::borrow::Cow<'a, #name> {
+            fn from(original: #name) -> Self {
+                ::std::borrow::Cow::Owned(original)
+            }
+        }
+    })
+}
+
+fn parse_options(
+    attrs: &[Attribute],
+    mut parse: impl FnMut(syn::meta::ParseNestedMeta) -> syn::Result<()>,
+) -> syn::Result<()> {
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("options")) {
+        attr.parse_nested_meta(&mut parse)?;
+    }
+    Ok(())
+}
+
+/// Gets `T` from `Option<T>`.
+fn option_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(ty) if args.args.len() == 1 => Some(ty),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn setters() {
+        let input = quote! {
+            #[options(fluent)]
+            struct GetOptions {
+                /// The version to get.
+                #[options(into)]
+                pub version: Option<String>,
+                #[options(default = "7.5".to_string())]
+                pub api_version: String,
+                #[options(skip)]
+                pub internal: bool,
+            }
+        };
+        let expanded = expand(input).unwrap().to_string();
+        assert!(expanded.contains(
+            "# [doc = r\" The version to get.\"] pub fn with_version (mut self , version : impl :: core :: convert :: Into < String >) -> Self { self . version = :: core :: option :: Option :: Some (version . into ()) ; self }"
+        ));
+        assert!(expanded.contains("api_version : \"7.5\" . to_string ()"));
+        assert!(expanded.contains("internal : :: core :: default :: Default :: default ()"));
+        assert!(!expanded.contains("with_internal"));
+
+        let input = quote! {
+            struct GetOptions {
+                #[options(into)]
+                pub api_version: String,
+                pub max_results: Option<u32>,
+            }
+        };
+        let expanded = expand(input).unwrap().to_string();
+        assert!(expanded.contains(
+            "pub fn with_api_version (& mut self , api_version : impl :: core :: convert :: Into < String >) -> & mut Self { self . api_version = api_version . into () ; self }"
+        ));
+        assert!(expanded.contains(
+            "pub fn with_max_results (& mut self , max_results : u32) -> & mut Self { self . max_results = :: core :: option :: Option :: Some (max_results) ; self }"
+        ));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(expand(quote!(
+            struct Options<T>(T);
+        ))
+        .is_err());
+        assert!(expand(quote!(
+            enum Options {}
+        ))
+        .is_err());
+        assert!(expand(quote!(
+            struct Options {
+                #[options(required)]
+                value: String,
+            }
+        ))
+        .is_err());
+    }
+}
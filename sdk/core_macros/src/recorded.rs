@@ -0,0 +1,121 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{FnArg, Ident, ItemFn, LitStr, Pat, ReturnType};
+
+pub fn expand(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let mode = if attr.is_empty() {
+        None
+    } else {
+        let mode: Ident = syn::parse2(attr)?;
+        let variant = match mode.to_string().as_str() {
+            "playback" => "Playback",
+            "record" => "Record",
+            "live" => "Live",
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    mode,
+                    "expected `playback`, `record`, or `live`",
+                ))
+            }
+        };
+        let variant = Ident::new(variant, mode.span());
+        Some(quote!(context.with_mode(::azure_core::test::TestMode::#variant);))
+    };
+
+    let item: ItemFn = syn::parse2(item)?;
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = &item;
+    if sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            sig.fn_token,
+            "recorded tests must be async",
+        ));
+    }
+    let (recording, ty) = match sig.inputs.iter().collect::<Vec<_>>().as_slice() {
+        [FnArg::Typed(arg)] => match &*arg.pat {
+            Pat::Ident(pat) => (&pat.ident, &arg.ty),
+            pat => return Err(syn::Error::new_spanned(pat, "expected an identifier")),
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &sig.inputs,
+                "expected one argument, e.g., `recording: Recording`",
+            ))
+        }
+    };
+
+    let name = &sig.ident;
+    let test = LitStr::new(&name.to_string(), name.span());
+    let output = match &sig.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ty) => quote!(#ty),
+    };
+    Ok(quote! {
+        #[::tokio::test]
+        #(#attrs)*
+        #vis async fn #name() -> #output {
+            let #recording: #ty = ::azure_core::test::TestContext::new(
+                ::core::env!("CARGO_MANIFEST_DIR"),
+                ::core::module_path!(),
+                #test,
+            )
+            .and_then(|mut context| {
+                #mode
+                context.start()
+            })
+            .unwrap_or_else(|error| ::core::panic!("{error}"));
+            let result: #output = async { #block }.await;
+            if ::azure_core::test::Outcome::passed(&result) {
+                if let ::core::result::Result::Err(error) = #recording.finish() {
+                    ::core::panic!("{error}");
+                }
+            }
+            result
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_and_finishes_recording() {
+        let item = quote! {
+            async fn get_secret(recording: Recording) -> Result<()> {
+                Ok(())
+            }
+        };
+        let expanded = expand(TokenStream::new(), item).unwrap().to_string();
+        assert!(expanded.starts_with(
+            "# [:: tokio :: test] async fn get_secret () -> Result < () > { let recording : Recording = :: azure_core :: test :: TestContext :: new"
+        ));
+        assert!(expanded.contains("\"get_secret\""));
+        assert!(expanded.contains("recording . finish ()"));
+
+        let item = quote! {
+            async fn get_secret(recording: Recording) {}
+        };
+        let expanded = expand(quote!(live), item).unwrap().to_string();
+        assert!(
+            expanded.contains("context . with_mode (:: azure_core :: test :: TestMode :: Live) ;")
+        );
+        let item = quote! {
+            async fn get_secret(recording: Recording) {}
+        };
+        assert!(expand(quote!(offline), item).is_err());
+
+        let item = quote! {
+            fn get_secret(recording: Recording) {}
+        };
+        assert!(expand(TokenStream::new(), item).is_err());
+        let item = quote! {
+            async fn get_secret() {}
+        };
+        assert!(expand(TokenStream::new(), item).is_err());
+    }
+}
@@ -0,0 +1,45 @@
+use azure_data_cosmos::{ContainerClient, Query, QueryItemsOptions, RequestCharge};
+use azure_identity::DefaultAzureCredential;
+use futures::TryStreamExt;
+use serde_json::Value;
+use std::{env, sync::Arc};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let container_url = env::var("AZURE_COSMOS_CONTAINER_URL")?;
+
+    let credential = Arc::new(DefaultAzureCredential::default());
+    let client = ContainerClient::new(container_url, credential, None)?;
+
+    let mut query = Query::new("SELECT * FROM c WHERE c.status = @status");
+    query.with_parameter("@status", "shipped");
+
+    // Read only the first page, then resume from its continuation token.
+    let mut options = QueryItemsOptions::default();
+    options.max_item_count = Some(10);
+    let mut pages = client
+        .query_items::<Value>(query.clone(), Some(options.clone()))
+        .by_page();
+    let Some(first) = pages.try_next().await? else {
+        return Ok(());
+    };
+    println!(
+        "first page: {} items for {:?} RU",
+        first.items().len(),
+        first.request_charge()
+    );
+
+    let mut charge = first.request_charge().unwrap_or_default();
+    let mut count = first.items().len();
+    options.continuation_token = first.continuation_token().map(String::from);
+    if options.continuation_token.is_some() {
+        let mut pages = client.query_items::<Value>(query, Some(options)).by_page();
+        while let Some(page) = pages.try_next().await? {
+            charge += page.request_charge().unwrap_or_default();
+            count += page.items().len();
+        }
+    }
+    println!("{count} items for {charge} RU");
+
+    Ok(())
+}
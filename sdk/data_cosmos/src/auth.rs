@@ -0,0 +1,114 @@
+use azure_core::{
+    format_http_date, AccessToken, HeaderName, Request, RequestSigner, Result, TokenCredential,
+    TokenRequestOptions, AUTHORIZATION,
+};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+use url::form_urlencoded;
+
+const MS_DATE: HeaderName = HeaderName::from_static("x-ms-date");
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(300);
+
+/// Authorizes requests with a Microsoft Entra ID token formatted as Cosmos DB requires, i.e., an
+/// `Authorization` of `type=aad&ver=1.0&sig=<token>`, URL-encoded, and the `x-ms-date` it was sent.
+///
+/// Tokens are cached until they are about to expire.
+#[derive(Debug)]
+pub(crate) struct CosmosTokenSigner {
+    credential: Arc<dyn TokenCredential>,
+    scope: String,
+    token: Mutex<Option<AccessToken>>,
+}
+
+impl CosmosTokenSigner {
+    pub(crate) fn new(credential: Arc<dyn TokenCredential>, scope: impl Into<String>) -> Self {
+        Self {
+            credential,
+            scope: scope.into(),
+            token: Mutex::default(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl RequestSigner for CosmosTokenSigner {
+    async fn sign(&self, request: &mut Request) -> Result<()> {
+        let cached = self
+            .token
+            .lock()
+            .unwrap()
+            .clone()
+            .filter(|token| !token.expires_within(TOKEN_REFRESH_MARGIN));
+        let token = match cached {
+            Some(token) => token,
+            None => {
+                let token = self
+                    .credential
+                    .get_token(&[&self.scope], &TokenRequestOptions::default())
+                    .await?;
+                *self.token.lock().unwrap() = Some(token.clone());
+                token
+            }
+        };
+
+        let authorization: String = form_urlencoded::byte_serialize(
+            format!("type=aad&ver=1.0&sig={}", token.token).as_bytes(),
+        )
+        .collect();
+        request.insert_header(MS_DATE, format_http_date(SystemTime::now()));
+        request.insert_header(AUTHORIZATION, authorization);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_core::{parse_http_date, Url};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[derive(Debug, Default)]
+    struct CountingCredential {
+        count: AtomicU32,
+    }
+
+    #[async_trait::async_trait]
+    impl TokenCredential for CountingCredential {
+        async fn get_token(
+            &self,
+            scopes: &[&str],
+            _options: &TokenRequestOptions,
+        ) -> Result<AccessToken> {
+            assert_eq!(scopes, ["https://cosmos.azure.com/.default"]);
+            self.count.fetch_add(1, Ordering::SeqCst);
+            Ok(AccessToken::new(
+                "eyJ0eXAi.eyJhdWQi.c2ln",
+                SystemTime::now() + Duration::from_secs(3600),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn signs_with_aad_token() {
+        let credential = Arc::new(CountingCredential::default());
+        let signer =
+            CosmosTokenSigner::new(credential.clone(), "https://cosmos.azure.com/.default");
+        let url =
+            Url::parse("https://myaccount.documents.azure.com/dbs/mydb/colls/mycontainer/docs")
+                .unwrap();
+
+        for _ in 0..2 {
+            let mut request = Request::new(url.clone(), "POST");
+            signer.sign(&mut request).await.unwrap();
+            assert_eq!(
+                request.headers().get_optional_str(&AUTHORIZATION),
+                Some("type%3Daad%26ver%3D1.0%26sig%3DeyJ0eXAi.eyJhdWQi.c2ln")
+            );
+            let date = request.headers().get_optional_str(&MS_DATE).unwrap();
+            assert!(parse_http_date(date).is_some(), "{date}");
+        }
+        assert_eq!(credential.count.load(Ordering::SeqCst), 1);
+    }
+}
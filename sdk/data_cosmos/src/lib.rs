@@ -0,0 +1,173 @@
+#![doc = include_str!("../README.md")]
+
+mod auth;
+mod models;
+
+use auth::CosmosTokenSigner;
+use azure_core::{
+    instrumented, parse_endpoint,
+    policies::{Policy, SigningPolicy},
+    ClientDiagnostics, ClientOptions, Context, Error, ErrorKind, HeaderName, Idempotent, Namespace,
+    Options, Page, Pageable, Pipeline, Request, Result, TokenCredential, Url, CONTENT_TYPE,
+};
+pub use models::*;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::sync::Arc;
+
+const NAMESPACE: &str = "Microsoft.DocumentDB";
+
+const CONTINUATION: HeaderName = HeaderName::from_static("x-ms-continuation");
+const ENABLE_CROSS_PARTITION: HeaderName =
+    HeaderName::from_static("x-ms-documentdb-query-enablecrosspartition");
+const IS_QUERY: HeaderName = HeaderName::from_static("x-ms-documentdb-isquery");
+const MAX_ITEM_COUNT: HeaderName = HeaderName::from_static("x-ms-max-item-count");
+const PARTITION_KEY: HeaderName = HeaderName::from_static("x-ms-documentdb-partitionkey");
+const VERSION: HeaderName = HeaderName::from_static("x-ms-version");
+
+/// A client for the items of a single container, e.g.,
+/// `https://myaccount.documents.azure.com/dbs/mydb/colls/mycontainer`.
+#[derive(Debug, Clone)]
+pub struct ContainerClient {
+    url: Url,
+    version: String,
+    pipeline: Pipeline,
+}
+
+impl ContainerClient {
+    pub fn new(
+        container_url: impl AsRef<str>,
+        credential: Arc<dyn TokenCredential>,
+        options: Option<ContainerClientOptions>,
+    ) -> Result<Self> {
+        let options = options.unwrap_or_default();
+        if options.version.is_empty() {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "version must not be empty",
+            ));
+        }
+        options.options.validate()?;
+
+        let url = parse_endpoint(container_url)?;
+        let segments: Vec<_> = url.path_segments().map_or(Vec::new(), Iterator::collect);
+        if !matches!(segments.as_slice(), ["dbs", _, "colls", _]) {
+            return Err(Error::with_message(ErrorKind::InvalidInput, || {
+                format!("container URL '{url}' must have a path like /dbs/{{database}}/colls/{{container}}")
+            }));
+        }
+
+        let auth_policy: Arc<dyn Policy> = Arc::new(SigningPolicy::new(Arc::new(
+            CosmosTokenSigner::new(credential, "https://cosmos.azure.com/.default"),
+        )));
+
+        Ok(Self {
+            url,
+            version: options.version,
+            pipeline: Pipeline::new(
+                option_env!("CARGO_PKG_NAME"),
+                option_env!("CARGO_PKG_VERSION"),
+                &options.options,
+                Vec::default(),
+                vec![auth_policy],
+            ),
+        })
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Gets the resolved configuration of the client without sending any requests.
+    pub fn diagnostics(&self) -> ClientDiagnostics {
+        let mut diagnostics = self.pipeline.diagnostics(&self.url);
+        diagnostics.api_version = Some(self.version.clone());
+        diagnostics
+    }
+
+    /// Queries items, returning pages of up to [`QueryItemsOptions::max_item_count`] items.
+    ///
+    /// The continuation token of each page is returned in the `x-ms-continuation` header rather
+    /// than the body, and pages may be empty even when more follow, e.g., for partitions with no
+    /// matching items. Get the charge for each page using [`RequestCharge`].
+    #[instrumented("ContainerClient::query_items")]
+    pub fn query_items<T>(
+        &self,
+        query: impl Into<Query>,
+        options: Option<QueryItemsOptions>,
+    ) -> Pageable<T>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let options = options.unwrap_or_default();
+        let client = self.clone();
+        let query = query.into();
+        let continuation_token = options.continuation_token.clone();
+
+        Pageable::with_continuation_token(
+            continuation_token,
+            move |continuation_token: Option<String>| {
+                let client = client.clone();
+                let query = query.clone();
+                let mut ctx = options.context.clone().unwrap_or_default();
+                let partition_key = options.partition_key.clone();
+                let max_item_count = options.max_item_count;
+                async move {
+                    ctx.insert(Namespace::from(NAMESPACE));
+                    ctx.insert(Idempotent::from(true));
+
+                    let mut url = client.url.clone();
+                    url.path_segments_mut()
+                        .map_err(|_| Error::message(ErrorKind::InvalidInput, "invalid URL"))?
+                        .push("docs");
+
+                    let mut request = Request::new(url, "POST");
+                    request.insert_header(VERSION, client.version.clone());
+                    request.insert_header(IS_QUERY, "true");
+                    match &partition_key {
+                        Some(partition_key) => request
+                            .insert_header(PARTITION_KEY, serde_json::to_string(&[partition_key])?),
+                        None => request.insert_header(ENABLE_CROSS_PARTITION, "true"),
+                    }
+                    if let Some(max_item_count) = max_item_count {
+                        request.insert_header(MAX_ITEM_COUNT, max_item_count.to_string());
+                    }
+                    if let Some(continuation_token) = continuation_token {
+                        request.insert_header(CONTINUATION, continuation_token);
+                    }
+                    request.set_json(&query)?;
+                    request.insert_header(CONTENT_TYPE, "application/query+json");
+
+                    let response = client.pipeline.send(&mut ctx, &mut request).await?;
+                    let headers = response.headers().clone();
+                    let continuation_token = headers.get_optional_string(&CONTINUATION);
+                    let results: QueryResults<T> = response.json().await?;
+                    Ok(Page::new(results.documents, headers, continuation_token))
+                }
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone, Options)]
+#[non_exhaustive]
+pub struct ContainerClientOptions {
+    /// The `x-ms-version` sent with each request.
+    #[options(default = "2018-12-31".to_string(), into)]
+    pub version: String,
+    pub options: ClientOptions,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct QueryItemsOptions {
+    /// Queries a single logical partition, or all partitions if not set.
+    #[options(into)]
+    pub partition_key: Option<Value>,
+    /// The most items the service returns in each page, which may return fewer.
+    pub max_item_count: Option<u32>,
+    /// Resumes a query from the continuation token of a previous [`Page`].
+    #[options(into)]
+    pub continuation_token: Option<String>,
+    pub context: Option<Context>,
+}
@@ -0,0 +1,82 @@
+use azure_core::{HeaderName, Headers, Page};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub(crate) const REQUEST_CHARGE: HeaderName = HeaderName::from_static("x-ms-request-charge");
+
+/// A SQL query with optional parameters, e.g., `SELECT * FROM c WHERE c.status = @status`.
+#[derive(Clone, Debug, Serialize)]
+pub struct Query {
+    #[serde(rename = "query")]
+    text: String,
+    parameters: Vec<QueryParameter>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct QueryParameter {
+    name: String,
+    value: Value,
+}
+
+impl Query {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            parameters: Vec::new(),
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Adds a parameter, e.g., `@status`, referenced by the query text.
+    pub fn with_parameter(
+        &mut self,
+        name: impl Into<String>,
+        value: impl Into<Value>,
+    ) -> &mut Self {
+        self.parameters.push(QueryParameter {
+            name: name.into(),
+            value: value.into(),
+        });
+        self
+    }
+}
+
+impl From<&str> for Query {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<String> for Query {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+/// The items of a page of query results.
+#[derive(Deserialize)]
+pub(crate) struct QueryResults<T> {
+    #[serde(rename = "Documents", default = "Vec::new")]
+    pub documents: Vec<T>,
+}
+
+/// Gets the request units charged for a page of query results.
+pub trait RequestCharge {
+    /// Gets the request units charged, or `None` if the service did not return a charge.
+    fn request_charge(&self) -> Option<f64>;
+}
+
+impl RequestCharge for Headers {
+    fn request_charge(&self) -> Option<f64> {
+        self.get_optional_str(&REQUEST_CHARGE)?.parse().ok()
+    }
+}
+
+impl<T> RequestCharge for Page<T> {
+    fn request_charge(&self) -> Option<f64> {
+        self.headers().request_charge()
+    }
+}
@@ -0,0 +1,144 @@
+use crate::process;
+use azure_core::{
+    AccessToken, Error, ErrorKind, Options, Result, TokenCredential, TokenRequestOptions,
+};
+use serde::Deserialize;
+use std::{
+    io,
+    process::{Command, Output},
+    time::{Duration, SystemTime},
+};
+
+/// How long to wait for the Azure CLI, which may refresh its own tokens first.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Authenticates as the account signed in to the Azure CLI with `az login`, e.g., on a
+/// developer's machine.
+#[derive(Clone, Debug, Default)]
+pub struct AzureCliCredential {
+    tenant_id: Option<String>,
+}
+
+impl AzureCliCredential {
+    pub fn new(options: Option<AzureCliCredentialOptions>) -> Self {
+        let options = options.unwrap_or_default();
+        Self {
+            tenant_id: options.tenant_id,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenCredential for AzureCliCredential {
+    async fn get_token(
+        &self,
+        scopes: &[&str],
+        options: &TokenRequestOptions,
+    ) -> Result<AccessToken> {
+        // The CLI only accepts a single scope.
+        let [scope] = scopes else {
+            return Err(Error::message(
+                ErrorKind::Credential,
+                "AzureCliCredential requires exactly one scope",
+            ));
+        };
+        if options.claims.is_some() {
+            return Err(Error::message(
+                ErrorKind::Credential,
+                "AzureCliCredential does not support claims challenges; run `az login` again",
+            ));
+        }
+        let tenant_id = options.tenant_id.as_ref().or(self.tenant_id.as_ref());
+
+        // Arguments are passed through a shell on Windows, so only allow characters which are
+        // valid in scopes and tenants.
+        if !is_safe_argument(scope) || !tenant_id.is_none_or(|tenant| is_safe_argument(tenant)) {
+            return Err(Error::message(
+                ErrorKind::Credential,
+                "scope and tenant_id may only contain alphanumeric characters and '.-_:/'",
+            ));
+        }
+
+        let mut args = vec![
+            "account",
+            "get-access-token",
+            "--output",
+            "json",
+            "--scope",
+            scope,
+        ];
+        if let Some(tenant_id) = tenant_id {
+            args.extend(["--tenant", tenant_id]);
+        }
+        let output = run_az(args.into_iter().map(str::to_string).collect()).await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let message = if stderr.contains("az login") {
+                "Azure CLI is not logged in; run `az login`".to_string()
+            } else {
+                format!("Azure CLI failed to get a token: {}", stderr.trim())
+            };
+            return Err(Error::message(ErrorKind::Credential, message));
+        }
+
+        let token: CliToken = serde_json::from_slice(&output.stdout).map_err(|error| {
+            Error::full(
+                ErrorKind::Credential,
+                error,
+                "Azure CLI returned an unexpected token",
+            )
+        })?;
+        Ok(AccessToken::new(
+            token.access_token,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(token.expires_on),
+        ))
+    }
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct AzureCliCredentialOptions {
+    /// The tenant to get tokens from instead of that of the CLI's current subscription.
+    #[options(into)]
+    pub tenant_id: Option<String>,
+}
+
+async fn run_az(args: Vec<String>) -> Result<Output> {
+    #[cfg(windows)]
+    let mut command = Command::new("cmd");
+    #[cfg(windows)]
+    command.arg("/C").arg("az");
+    #[cfg(not(windows))]
+    let mut command = Command::new("az");
+    command.args(args);
+
+    match process::output(command, TIMEOUT).await {
+        Ok(output) => Ok(output),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Err(Error::message(
+            ErrorKind::Credential,
+            "Azure CLI is not installed",
+        )),
+        Err(error) => Err(Error::full(
+            ErrorKind::Credential,
+            error,
+            "failed to run Azure CLI",
+        )),
+    }
+}
+
+fn is_safe_argument(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ".-_:/".contains(c))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CliToken {
+    access_token: String,
+    /// Seconds since the Unix epoch, unlike `expiresOn` which is in local time.
+    #[serde(rename = "expires_on")]
+    expires_on: u64,
+}
@@ -0,0 +1,399 @@
+use azure_core::{
+    parse_endpoint, AccessToken, ClientOptions, Cloud, Context, Error, ErrorKind, Idempotent,
+    Options, Pipeline, Request, Result, TokenCredential, TokenRequestOptions, Url, AUTHORIZATION,
+    CONTENT_TYPE,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    fmt,
+    time::{Duration, SystemTime},
+};
+use url::form_urlencoded;
+
+/// The environment variable Azure Pipelines sets to the URL of its OIDC token endpoint.
+const OIDC_REQUEST_URI: &str = "SYSTEM_OIDCREQUESTURI";
+const OIDC_API_VERSION: &str = "7.1";
+const CLIENT_ASSERTION_TYPE: &str = "urn:ietf:params:oauth:client-assertion-type:jwt-bearer";
+
+/// Authenticates as the app registration or managed identity of an Azure Pipelines service
+/// connection which uses workload identity federation, so pipelines need no secrets.
+///
+/// Exchanges an OIDC token for the service connection, requested with the job's
+/// `System.AccessToken`, for a Microsoft Entra ID token. Map `System.AccessToken` into the
+/// environment or pass it directly, e.g., from `SYSTEM_ACCESSTOKEN`.
+pub struct AzurePipelinesCredential {
+    tenant_id: String,
+    client_id: String,
+    service_connection_id: String,
+    system_access_token: String,
+    oidc_request_uri: Url,
+    authority_host: Url,
+    pipeline: Pipeline,
+}
+
+impl AzurePipelinesCredential {
+    /// Creates a credential for the service connection `service_connection_id` whose app
+    /// registration or managed identity `client_id` is in `tenant_id`.
+    ///
+    /// Returns an [`ErrorKind::Credential`] error if not running in Azure Pipelines, or if any
+    /// parameter is empty.
+    pub fn new(
+        tenant_id: impl Into<String>,
+        client_id: impl Into<String>,
+        service_connection_id: impl Into<String>,
+        system_access_token: impl Into<String>,
+        options: Option<AzurePipelinesCredentialOptions>,
+    ) -> Result<Self> {
+        let options = options.unwrap_or_default();
+        options.options.validate()?;
+
+        let tenant_id = tenant_id.into();
+        let client_id = client_id.into();
+        let service_connection_id = service_connection_id.into();
+        let system_access_token = system_access_token.into();
+        for (name, value) in [
+            ("tenant_id", &tenant_id),
+            ("client_id", &client_id),
+            ("service_connection_id", &service_connection_id),
+            ("system_access_token", &system_access_token),
+        ] {
+            if value.is_empty() {
+                return Err(Error::with_message(ErrorKind::Credential, || {
+                    format!("{name} must not be empty")
+                }));
+            }
+        }
+
+        let oidc_request_uri = match options.oidc_request_uri {
+            Some(uri) => uri,
+            None => std::env::var(OIDC_REQUEST_URI).map_err(|_| {
+                Error::with_message(ErrorKind::Credential, || {
+                    format!("{OIDC_REQUEST_URI} is not set, so not running in Azure Pipelines")
+                })
+            })?,
+        };
+        let oidc_request_uri = parse_endpoint(oidc_request_uri)?;
+        let authority_host = match options.authority_host {
+            Some(host) => parse_endpoint(host)?,
+            None => authority_host(options.options.cloud),
+        };
+
+        Ok(Self {
+            tenant_id,
+            client_id,
+            service_connection_id,
+            system_access_token,
+            oidc_request_uri,
+            authority_host,
+            pipeline: Pipeline::new(
+                option_env!("CARGO_PKG_NAME"),
+                option_env!("CARGO_PKG_VERSION"),
+                &options.options,
+                Vec::new(),
+                Vec::new(),
+            ),
+        })
+    }
+
+    /// Gets an OIDC token for the service connection from Azure Pipelines.
+    async fn oidc_token(&self) -> Result<String> {
+        let mut url = self.oidc_request_uri.clone();
+        url.query_pairs_mut()
+            .append_pair("api-version", OIDC_API_VERSION)
+            .append_pair("serviceConnectionId", &self.service_connection_id);
+
+        let mut request = Request::new(url, "POST");
+        request.insert_header(
+            AUTHORIZATION,
+            format!("Bearer {}", self.system_access_token),
+        );
+        request.insert_header(CONTENT_TYPE, "application/json");
+
+        let response: OidcTokenResponse = self.send(request).await?.json().await?;
+        response.oidc_token.ok_or_else(|| {
+            Error::message(
+                ErrorKind::Credential,
+                "Azure Pipelines did not return an OIDC token for the service connection",
+            )
+        })
+    }
+
+    async fn send(&self, mut request: Request) -> Result<azure_core::Response> {
+        let mut ctx = Context::default();
+        // Token requests have no side effects, so they are safe to retry.
+        ctx.insert(Idempotent::from(true));
+        self.pipeline
+            .send(&mut ctx, &mut request)
+            .await
+            .map_err(|error| {
+                Error::full(
+                    ErrorKind::Credential,
+                    error,
+                    "AzurePipelinesCredential failed to authenticate",
+                )
+            })
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenCredential for AzurePipelinesCredential {
+    async fn get_token(
+        &self,
+        scopes: &[&str],
+        options: &TokenRequestOptions,
+    ) -> Result<AccessToken> {
+        let assertion = self.oidc_token().await?;
+
+        let tenant_id = options.tenant_id.as_deref().unwrap_or(&self.tenant_id);
+        let mut url = self.authority_host.clone();
+        url.path_segments_mut()
+            .map_err(|_| Error::message(ErrorKind::Credential, "invalid authority host"))?
+            .pop_if_empty()
+            .extend([tenant_id, "oauth2", "v2.0", "token"]);
+
+        let claims = claims(options)?;
+        let body = {
+            let mut body = form_urlencoded::Serializer::new(String::new());
+            body.append_pair("client_assertion_type", CLIENT_ASSERTION_TYPE)
+                .append_pair("client_assertion", &assertion)
+                .append_pair("client_id", &self.client_id)
+                .append_pair("grant_type", "client_credentials")
+                .append_pair("scope", &scopes.join(" "));
+            if let Some(claims) = &claims {
+                body.append_pair("claims", claims);
+            }
+            body.finish()
+        };
+        let mut request = Request::new(url, "POST");
+        request.insert_header(CONTENT_TYPE, "application/x-www-form-urlencoded");
+        request.set_body(body);
+
+        let response: TokenResponse = self.send(request).await?.json().await?;
+        Ok(AccessToken::new(
+            response.access_token,
+            SystemTime::now() + Duration::from_secs(response.expires_in),
+        ))
+    }
+}
+
+impl fmt::Debug for AzurePipelinesCredential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AzurePipelinesCredential")
+            .field("tenant_id", &self.tenant_id)
+            .field("client_id", &self.client_id)
+            .field("service_connection_id", &self.service_connection_id)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct AzurePipelinesCredentialOptions {
+    /// The OIDC token endpoint to use instead of `SYSTEM_OIDCREQUESTURI`.
+    #[options(into)]
+    pub oidc_request_uri: Option<String>,
+    /// The Microsoft Entra ID host to use instead of that of [`ClientOptions::cloud`].
+    #[options(into)]
+    pub authority_host: Option<String>,
+    pub options: ClientOptions,
+}
+
+fn authority_host(cloud: Cloud) -> Url {
+    let host = match cloud {
+        Cloud::AzureChina => "https://login.chinacloudapi.cn",
+        Cloud::AzureUsGovernment => "https://login.microsoftonline.us",
+        _ => "https://login.microsoftonline.com",
+    };
+    Url::parse(host).expect("valid authority host")
+}
+
+/// Gets the `claims` parameter of a token request, which includes the `CP1` client capability if
+/// [`TokenRequestOptions::enable_cae`] so Microsoft Entra ID issues tokens which can be revoked.
+fn claims(options: &TokenRequestOptions) -> Result<Option<String>> {
+    let mut claims = match &options.claims {
+        Some(claims) => serde_json::from_str(claims).map_err(|error| {
+            Error::full(ErrorKind::Credential, error, "claims must be a JSON object")
+        })?,
+        None if options.enable_cae => json!({}),
+        None => return Ok(None),
+    };
+    if options.enable_cae {
+        let Some(Value::Object(access_token)) = claims
+            .as_object_mut()
+            .map(|claims| claims.entry("access_token").or_insert_with(|| json!({})))
+        else {
+            return Err(Error::message(
+                ErrorKind::Credential,
+                "claims must be a JSON object",
+            ));
+        };
+        access_token.insert("xms_cc".to_string(), json!({ "values": ["CP1"] }));
+    }
+    Ok(Some(claims.to_string()))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OidcTokenResponse {
+    oidc_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_core::{
+        stream::BytesStream, transport::HttpClient, Headers, Response, RetryOptions,
+        TransportOptions,
+    };
+    use std::sync::{Arc, Mutex};
+
+    /// Returns an OIDC token from Azure Pipelines and exchanges it, recording each request.
+    #[derive(Debug, Default)]
+    struct TokenService {
+        requests: Mutex<Vec<(String, Option<String>, String)>>,
+        oidc_response: Option<Value>,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClient for TokenService {
+        async fn execute_request(&self, request: &Request) -> Result<Response> {
+            let body = match request.body() {
+                azure_core::Body::Bytes(bytes) => String::from_utf8(bytes.to_vec()).unwrap(),
+                _ => String::new(),
+            };
+            self.requests.lock().unwrap().push((
+                request.url().to_string(),
+                request.headers().get_optional_string(&AUTHORIZATION),
+                body,
+            ));
+
+            let body = if request.url().host_str() == Some("dev.azure.com") {
+                self.oidc_response
+                    .clone()
+                    .unwrap_or_else(|| json!({ "oidcToken": "oidc" }))
+            } else {
+                json!({ "access_token": "entra", "expires_in": 3600 })
+            };
+            Ok(Response::new(
+                200,
+                Headers::new(),
+                Box::pin(BytesStream::new(serde_json::to_vec(&body)?)),
+            ))
+        }
+    }
+
+    fn credential(service: &Arc<TokenService>) -> AzurePipelinesCredential {
+        let mut options = AzurePipelinesCredentialOptions {
+            oidc_request_uri: Some(
+                "https://dev.azure.com/org/project/_apis/distributedtask/oidc".to_string(),
+            ),
+            ..Default::default()
+        };
+        options.options.retry = RetryOptions::none();
+        options.options.transport = TransportOptions::with_http_client(service.clone());
+        AzurePipelinesCredential::new("tenant", "client", "connection", "system", Some(options))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn exchanges_oidc_token() {
+        let service = Arc::new(TokenService::default());
+        let before = SystemTime::now();
+        let token = credential(&service)
+            .get_token(
+                &["https://vault.azure.net/.default"],
+                &TokenRequestOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(token.token, "entra");
+        assert!(token.expires_on >= before + Duration::from_secs(3600));
+
+        let requests = service.requests.lock().unwrap();
+        assert_eq!(
+            requests[0],
+            (
+                "https://dev.azure.com/org/project/_apis/distributedtask/oidc?api-version=7.1&serviceConnectionId=connection"
+                    .to_string(),
+                Some("Bearer system".to_string()),
+                String::new(),
+            )
+        );
+        assert_eq!(
+            requests[1],
+            (
+                "https://login.microsoftonline.com/tenant/oauth2/v2.0/token".to_string(),
+                None,
+                "client_assertion_type=urn%3Aietf%3Aparams%3Aoauth%3Aclient-assertion-type%3Ajwt-bearer\
+                 &client_assertion=oidc&client_id=client&grant_type=client_credentials\
+                 &scope=https%3A%2F%2Fvault.azure.net%2F.default"
+                    .to_string(),
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn requires_oidc_token() {
+        let service = Arc::new(TokenService {
+            oidc_response: Some(json!({ "oidcToken": null })),
+            ..Default::default()
+        });
+        let error = credential(&service)
+            .get_token(
+                &["https://vault.azure.net/.default"],
+                &TokenRequestOptions::default(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(*error.kind(), ErrorKind::Credential);
+        assert_eq!(service.requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn requires_parameters() {
+        let options = AzurePipelinesCredentialOptions {
+            oidc_request_uri: Some("https://dev.azure.com/org".to_string()),
+            ..Default::default()
+        };
+        let error =
+            AzurePipelinesCredential::new("tenant", "", "connection", "system", Some(options))
+                .unwrap_err();
+        assert_eq!(*error.kind(), ErrorKind::Credential);
+        assert_eq!(error.to_string(), "client_id must not be empty");
+    }
+
+    #[test]
+    fn adds_client_capability_to_claims() {
+        let mut options = TokenRequestOptions::default();
+        assert_eq!(claims(&options).unwrap(), None);
+
+        options.enable_cae = true;
+        assert_eq!(
+            claims(&options).unwrap().as_deref(),
+            Some(r#"{"access_token":{"xms_cc":{"values":["CP1"]}}}"#)
+        );
+
+        options.claims = Some(r#"{"access_token":{"nbf":{"essential":true}}}"#.to_string());
+        let merged: Value = serde_json::from_str(&claims(&options).unwrap().unwrap()).unwrap();
+        assert_eq!(
+            merged,
+            json!({
+                "access_token": {
+                    "nbf": { "essential": true },
+                    "xms_cc": { "values": ["CP1"] },
+                },
+            })
+        );
+
+        options.claims = Some("[]".to_string());
+        let error = claims(&options).unwrap_err();
+        assert_eq!(*error.kind(), ErrorKind::Credential);
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{AzureCliCredential, AzurePipelinesCredential};
+use azure_core::{AccessToken, Error, ErrorKind, Result, TokenCredential, TokenRequestOptions};
+use std::{env, error::Error as _, sync::Arc};
+
+/// Gets tokens from the first of these credentials which can authenticate:
+///
+/// 1. [`AzurePipelinesCredential`] for the service connection of an `AzureCLI@2` or similar task,
+///    from `AZURESUBSCRIPTION_TENANT_ID`, `AZURESUBSCRIPTION_CLIENT_ID`,
+///    `AZURESUBSCRIPTION_SERVICE_CONNECTION_ID`, and `SYSTEM_ACCESSTOKEN`.
+/// 2. [`AzureCliCredential`].
+///
+/// If none can, the [`ErrorKind::Credential`] error says why each was skipped or failed. With
+/// the `tracing` feature, each attempt is also traced.
+///
+/// Unlike other Azure SDKs, managed identity is not tried since this crate has no credential for
+/// the instance metadata service (IMDS) yet, so applications hosted in Azure without the Azure CLI
+/// need another [`TokenCredential`].
+#[derive(Debug)]
+pub struct DefaultAzureCredential {
+    sources: Vec<Source>,
+}
+
+#[derive(Debug)]
+struct Source {
+    name: &'static str,
+    /// The credential, or why it was skipped.
+    credential: Result<Arc<dyn TokenCredential>>,
+}
+
+impl Default for DefaultAzureCredential {
+    fn default() -> Self {
+        Self {
+            sources: vec![
+                Source {
+                    name: "AzurePipelinesCredential",
+                    credential: azure_pipelines_from_env(|name| env::var(name).ok()),
+                },
+                Source {
+                    name: "AzureCliCredential",
+                    credential: Ok(Arc::new(AzureCliCredential::default())),
+                },
+            ],
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenCredential for DefaultAzureCredential {
+    async fn get_token(
+        &self,
+        scopes: &[&str],
+        options: &TokenRequestOptions,
+    ) -> Result<AccessToken> {
+        let mut attempts = Vec::with_capacity(self.sources.len());
+        for source in &self.sources {
+            let error = match &source.credential {
+                Ok(credential) => match credential.get_token(scopes, options).await {
+                    Ok(token) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(credential = source.name, "got token");
+                        return Ok(token);
+                    }
+                    Err(error) => format!("failed: {}", describe(&error)),
+                },
+                Err(error) => format!("skipped: {}", describe(error)),
+            };
+            #[cfg(feature = "tracing")]
+            tracing::debug!(credential = source.name, "{error}");
+            attempts.push(format!("{}: {error}", source.name));
+        }
+
+        Err(Error::with_message(ErrorKind::Credential, || {
+            format!(
+                "DefaultAzureCredential could not get a token from any credential:\n{}",
+                attempts.join("\n")
+            )
+        }))
+    }
+}
+
+/// Creates an [`AzurePipelinesCredential`] from the environment variables returned by `env_var`.
+fn azure_pipelines_from_env(
+    env_var: impl Fn(&str) -> Option<String>,
+) -> Result<Arc<dyn TokenCredential>> {
+    let var = |name: &str| {
+        env_var(name).ok_or_else(|| {
+            Error::with_message(ErrorKind::Credential, || format!("{name} is not set"))
+        })
+    };
+    // Check every variable before the credential checks SYSTEM_OIDCREQUESTURI.
+    let tenant_id = var("AZURESUBSCRIPTION_TENANT_ID")?;
+    let client_id = var("AZURESUBSCRIPTION_CLIENT_ID")?;
+    let service_connection_id = var("AZURESUBSCRIPTION_SERVICE_CONNECTION_ID")?;
+    let system_access_token = var("SYSTEM_ACCESSTOKEN")?;
+    Ok(Arc::new(AzurePipelinesCredential::new(
+        tenant_id,
+        client_id,
+        service_connection_id,
+        system_access_token,
+        None,
+    )?))
+}
+
+/// Describes `error` and its sources, since credentials may replace the message of the error
+/// which caused them to fail.
+fn describe(error: &Error) -> String {
+    let mut description = error.to_string();
+    let mut source = error.source();
+    while let Some(error) = source {
+        description.push_str(": ");
+        description.push_str(&error.to_string());
+        source = error.source();
+    }
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{io, time::SystemTime};
+
+    /// Returns a token, or fails with an error caused by `io::ErrorKind::NotFound`.
+    #[derive(Debug)]
+    struct MockCredential(Option<&'static str>);
+
+    #[async_trait::async_trait]
+    impl TokenCredential for MockCredential {
+        async fn get_token(
+            &self,
+            _scopes: &[&str],
+            _options: &TokenRequestOptions,
+        ) -> Result<AccessToken> {
+            match self.0 {
+                Some(token) => Ok(AccessToken::new(token, SystemTime::now())),
+                None => Err(Error::full(
+                    ErrorKind::Credential,
+                    io::Error::from(io::ErrorKind::NotFound),
+                    "az is not installed",
+                )),
+            }
+        }
+    }
+
+    fn credential(
+        sources: Vec<(&'static str, Result<Arc<dyn TokenCredential>>)>,
+    ) -> DefaultAzureCredential {
+        DefaultAzureCredential {
+            sources: sources
+                .into_iter()
+                .map(|(name, credential)| Source { name, credential })
+                .collect(),
+        }
+    }
+
+    const SCOPES: &[&str] = &["https://vault.azure.net/.default"];
+
+    #[tokio::test]
+    async fn describes_each_credential() {
+        let credential = credential(vec![
+            (
+                "AzurePipelinesCredential",
+                azure_pipelines_from_env(|_| None),
+            ),
+            ("AzureCliCredential", Ok(Arc::new(MockCredential(None)))),
+        ]);
+        let error = credential
+            .get_token(SCOPES, &TokenRequestOptions::default())
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::Credential);
+        assert_eq!(
+            error.to_string(),
+            "DefaultAzureCredential could not get a token from any credential:\n\
+             AzurePipelinesCredential: skipped: AZURESUBSCRIPTION_TENANT_ID is not set\n\
+             AzureCliCredential: failed: az is not installed: entity not found"
+        );
+    }
+
+    #[tokio::test]
+    async fn uses_first_credential_with_token() {
+        let credential = credential(vec![
+            ("Failing", Ok(Arc::new(MockCredential(None)))),
+            ("First", Ok(Arc::new(MockCredential(Some("first"))))),
+            ("Second", Ok(Arc::new(MockCredential(Some("second"))))),
+        ]);
+        let token = credential
+            .get_token(SCOPES, &TokenRequestOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(token.token, "first");
+    }
+
+    #[test]
+    fn skips_azure_pipelines_without_every_variable() {
+        let error = azure_pipelines_from_env(|name| {
+            (name != "SYSTEM_ACCESSTOKEN").then(|| "value".to_string())
+        })
+        .unwrap_err();
+        assert_eq!(error.to_string(), "SYSTEM_ACCESSTOKEN is not set");
+    }
+}
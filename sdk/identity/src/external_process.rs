@@ -0,0 +1,207 @@
+use crate::process;
+use azure_core::{
+    AccessToken, Error, ErrorKind, Options, Result, TokenCredential, TokenRequestOptions,
+};
+use serde::Deserialize;
+use std::{
+    ffi::OsString,
+    fmt,
+    process::Command,
+    time::{Duration, SystemTime},
+};
+
+/// Gets tokens by running a command, e.g., an organization's token broker, and parsing the JSON
+/// it writes to stdout.
+///
+/// The request is passed to the command in environment variables, so it needs no arguments for
+/// them:
+///
+/// * `AZURE_TOKEN_SCOPES`: the scopes separated by spaces.
+/// * `AZURE_TOKEN_TENANT_ID`: the tenant, if any.
+/// * `AZURE_TOKEN_CLAIMS`: the claims as JSON, if any.
+/// * `AZURE_TOKEN_ENABLE_CAE`: `true` or `false`.
+///
+/// The command must exit successfully and write an object with an `access_token` and either
+/// an `expires_on` in seconds since the Unix epoch or an `expires_in` in seconds, e.g.,
+/// `{"access_token":"...","expires_in":3600}`. If it fails, its stderr is returned in the error,
+/// so it should not write secrets there. It is killed if it runs longer than
+/// [`ExternalProcessCredentialOptions::timeout`].
+#[derive(Clone)]
+pub struct ExternalProcessCredential {
+    program: OsString,
+    args: Vec<OsString>,
+    env: Vec<(OsString, OsString)>,
+    timeout: Duration,
+}
+
+impl ExternalProcessCredential {
+    /// Creates a credential which runs `program`, found in `PATH` if not a path, with `args`.
+    pub fn new<I, S>(
+        program: impl Into<OsString>,
+        args: I,
+        options: Option<ExternalProcessCredentialOptions>,
+    ) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<OsString>,
+    {
+        let options = options.unwrap_or_default();
+        Self {
+            program: program.into(),
+            args: args.into_iter().map(Into::into).collect(),
+            env: options.env,
+            timeout: options.timeout,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenCredential for ExternalProcessCredential {
+    async fn get_token(
+        &self,
+        scopes: &[&str],
+        options: &TokenRequestOptions,
+    ) -> Result<AccessToken> {
+        let program = self.program.to_string_lossy();
+        let mut command = Command::new(&self.program);
+        command
+            .args(&self.args)
+            .envs(self.env.iter().map(|(name, value)| (name, value)))
+            .env("AZURE_TOKEN_SCOPES", scopes.join(" "))
+            .env("AZURE_TOKEN_ENABLE_CAE", options.enable_cae.to_string())
+            .env_remove("AZURE_TOKEN_TENANT_ID")
+            .env_remove("AZURE_TOKEN_CLAIMS");
+        if let Some(tenant_id) = &options.tenant_id {
+            command.env("AZURE_TOKEN_TENANT_ID", tenant_id);
+        }
+        if let Some(claims) = &options.claims {
+            command.env("AZURE_TOKEN_CLAIMS", claims);
+        }
+
+        let output = process::output(command, self.timeout)
+            .await
+            .map_err(|error| {
+                Error::full(
+                    ErrorKind::Credential,
+                    error,
+                    format!("failed to run '{program}'"),
+                )
+            })?;
+        if !output.status.success() {
+            return Err(Error::with_message(ErrorKind::Credential, || {
+                format!(
+                    "'{program}' failed with {}: {}",
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )
+            }));
+        }
+
+        let token: ProcessToken = serde_json::from_slice(&output.stdout).map_err(|error| {
+            Error::full(
+                ErrorKind::Credential,
+                error,
+                format!("'{program}' did not write a token"),
+            )
+        })?;
+        let expires_on = match (token.expires_on, token.expires_in) {
+            (Some(expires_on), _) => SystemTime::UNIX_EPOCH + Duration::from_secs(expires_on),
+            (None, Some(expires_in)) => SystemTime::now() + Duration::from_secs(expires_in),
+            (None, None) => {
+                return Err(Error::with_message(ErrorKind::Credential, || {
+                    format!("'{program}' did not write when its token expires")
+                }))
+            }
+        };
+        Ok(AccessToken::new(token.access_token, expires_on))
+    }
+}
+
+impl fmt::Debug for ExternalProcessCredential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Arguments and environment variables may contain secrets.
+        f.debug_struct("ExternalProcessCredential")
+            .field("program", &self.program)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct ExternalProcessCredentialOptions {
+    /// Environment variables to set for the command in addition to those of this process.
+    pub env: Vec<(OsString, OsString)>,
+    /// How long to wait for the command before killing it, which is 30 seconds by default.
+    #[options(default = Duration::from_secs(30))]
+    pub timeout: Duration,
+}
+
+#[derive(Deserialize)]
+struct ProcessToken {
+    access_token: String,
+    expires_on: Option<u64>,
+    expires_in: Option<u64>,
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn credential(script: &str) -> ExternalProcessCredential {
+        ExternalProcessCredential::new("sh", ["-c", script], None)
+    }
+
+    async fn get_token(script: &str) -> Result<AccessToken> {
+        let options = TokenRequestOptions::default();
+        credential(script)
+            .get_token(
+                &["https://vault.azure.net/.default", "offline_access"],
+                &options,
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn parses_tokens() {
+        let token = get_token(
+            r#"echo "{\"access_token\":\"$AZURE_TOKEN_SCOPES\",\"expires_on\":1893456000}""#,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            token.token,
+            "https://vault.azure.net/.default offline_access"
+        );
+        assert_eq!(
+            token.expires_on,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1_893_456_000)
+        );
+
+        let before = SystemTime::now();
+        let token = get_token(r#"echo '{"access_token":"secret","expires_in":3600}'"#)
+            .await
+            .unwrap();
+        assert_eq!(token.token, "secret");
+        assert!(token.expires_on >= before + Duration::from_secs(3600));
+    }
+
+    #[tokio::test]
+    async fn returns_errors() {
+        let error = get_token("echo 'not signed in' >&2; exit 1")
+            .await
+            .unwrap_err();
+        assert_eq!(*error.kind(), ErrorKind::Credential);
+        assert!(error.to_string().ends_with(": not signed in"), "{error}");
+
+        let error = get_token("echo 'not json'").await.unwrap_err();
+        assert_eq!(error.to_string(), "'sh' did not write a token");
+
+        let error = get_token(r#"echo '{"access_token":"secret"}'"#)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "'sh' did not write when its token expires"
+        );
+    }
+}
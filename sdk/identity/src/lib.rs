@@ -1,4 +1,12 @@
-#[derive(Debug, Default)]
-pub struct DefaultAzureCredential {}
+mod azure_cli;
+mod azure_pipelines;
+mod default_azure_credential;
+mod external_process;
+mod process;
+mod refreshing_credential;
 
-impl azure_core::TokenCredential for DefaultAzureCredential {}
+pub use azure_cli::*;
+pub use azure_pipelines::*;
+pub use default_azure_credential::*;
+pub use external_process::*;
+pub use refreshing_credential::*;
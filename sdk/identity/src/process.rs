@@ -0,0 +1,91 @@
+use azure_core::sleep::sleep;
+use futures::future::{self, Either};
+use std::{
+    io,
+    pin::pin,
+    process::{Command, Output, Stdio},
+    time::Duration,
+};
+
+/// Runs `command` to completion without blocking the caller's executor or a thread, killing it
+/// if it runs longer than `timeout` or the returned future is dropped.
+///
+/// Output is read from pipes while the command runs so it is never written to disk, where
+/// other users might read the tokens it contains.
+pub(crate) async fn output(command: Command, timeout: Duration) -> io::Result<Output> {
+    let child = tokio::process::Command::from(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    // Dropping the child when the timeout wins kills it.
+    match future::select(pin!(child.wait_with_output()), pin!(sleep(timeout))).await {
+        Either::Left((output, _)) => output,
+        Either::Right(_) => Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("the process did not exit within {timeout:?}"),
+        )),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn captures_output() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "echo out; echo err >&2; exit 3"]);
+        let output = output(command, Duration::from_secs(10)).await.unwrap();
+        assert_eq!(output.status.code(), Some(3));
+        assert_eq!(output.stdout, b"out\n");
+        assert_eq!(output.stderr, b"err\n");
+    }
+
+    #[tokio::test]
+    async fn kills_after_timeout() {
+        let mut command = Command::new("sleep");
+        command.arg("10");
+        let start = Instant::now();
+        let error = output(command, Duration::from_millis(100))
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn kills_when_dropped() {
+        let path = std::env::temp_dir().join(format!("azure_identity-{}.pid", std::process::id()));
+        let mut command = Command::new("sh");
+        command.args([
+            "-c",
+            &format!("echo $$ > {}; exec sleep 10", path.display()),
+        ]);
+        let output = output(command, Duration::from_secs(10));
+        let started = async {
+            while !std::fs::read_to_string(&path).is_ok_and(|pid| pid.ends_with('\n')) {
+                sleep(Duration::from_millis(10)).await;
+            }
+        };
+        match future::select(pin!(output), pin!(started)).await {
+            Either::Left(_) => panic!("the process exited"),
+            Either::Right(_) => {}
+        }
+
+        let pid = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let start = Instant::now();
+        while std::path::Path::new(&format!("/proc/{}", pid.trim())).exists() {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "the process is running"
+            );
+            sleep(Duration::from_millis(10)).await;
+        }
+    }
+}
@@ -0,0 +1,373 @@
+use azure_core::{
+    sleep::sleep, AccessToken, Options, Result, TokenCredential, TokenRequestOptions,
+};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime},
+};
+
+/// Tokens expiring within this are refreshed by [`RefreshingCredential::get_token`] even if
+/// [`RefreshingCredential::run`] has not refreshed them.
+const MIN_REMAINING: Duration = Duration::from_secs(60);
+/// The longest [`RefreshingCredential::run`] waits before checking for newly registered scopes.
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+/// The longest [`RefreshingCredential::run`] backs off after consecutive failures, unless
+/// [`RefreshingCredentialOptions::retry_delay`] is longer.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(5 * 60);
+
+/// Caches the tokens of another credential and, while [`run`](Self::run) is polled, e.g., on a
+/// task spawned by the caller, refreshes tokens for [registered](Self::register) scopes before
+/// they expire so [`get_token`](TokenCredential::get_token) never waits for Microsoft Entra ID.
+///
+/// Tokens for scopes which are not registered are cached but only refreshed when they are about
+/// to expire. Tokens for [`TokenRequestOptions::claims`] are never cached.
+#[derive(Clone, Debug)]
+pub struct RefreshingCredential {
+    credential: Arc<dyn TokenCredential>,
+    entries: Arc<Mutex<Vec<Entry>>>,
+    refresh_before: Duration,
+    min_refresh_interval: Duration,
+    retry_delay: Duration,
+    closed: Arc<AtomicBool>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    scopes: Vec<String>,
+    options: TokenRequestOptions,
+    token: Option<AccessToken>,
+    registered: bool,
+    /// When [`RefreshingCredential::run`] should next get a token.
+    refresh_at: SystemTime,
+    /// How many times getting a token has failed since it last succeeded.
+    failures: u32,
+}
+
+impl RefreshingCredential {
+    pub fn new(
+        credential: Arc<dyn TokenCredential>,
+        options: Option<RefreshingCredentialOptions>,
+    ) -> Self {
+        let options = options.unwrap_or_default();
+        Self {
+            credential,
+            entries: Arc::default(),
+            refresh_before: options.refresh_before,
+            min_refresh_interval: options.min_refresh_interval,
+            retry_delay: options.retry_delay,
+            closed: Arc::default(),
+        }
+    }
+
+    /// Keeps a token for `scopes` warm, getting one as soon as [`run`](Self::run) is polled.
+    pub fn register(&self, scopes: &[&str], options: &TokenRequestOptions) {
+        let mut entries = self.entries.lock().unwrap();
+        match entries
+            .iter_mut()
+            .find(|entry| entry.matches(scopes, options))
+        {
+            Some(entry) => entry.registered = true,
+            None => entries.push(Entry {
+                scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+                options: options.clone(),
+                token: None,
+                registered: true,
+                refresh_at: SystemTime::UNIX_EPOCH,
+                failures: 0,
+            }),
+        }
+    }
+
+    /// Refreshes tokens for registered scopes until [`close`](Self::close) is called.
+    ///
+    /// Failures are retried after [`RefreshingCredentialOptions::retry_delay`], doubling after each
+    /// consecutive failure up to 5 minutes, and only returned by
+    /// [`get_token`](TokenCredential::get_token) if the cached token expires in the meantime.
+    pub async fn run(&self) {
+        while !self.closed.load(Ordering::SeqCst) {
+            let next = self
+                .entries
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|entry| entry.registered)
+                .min_by_key(|entry| entry.refresh_at)
+                .map(|entry| {
+                    (
+                        entry.scopes.clone(),
+                        entry.options.clone(),
+                        entry.refresh_at,
+                    )
+                });
+
+            let now = SystemTime::now();
+            match next {
+                Some((scopes, options, refresh_at)) if refresh_at <= now => {
+                    let scopes: Vec<&str> = scopes.iter().map(String::as_str).collect();
+                    if let Err(_error) = self.refresh(&scopes, &options).await {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(?scopes, "failed to refresh token: {_error}");
+                    }
+                }
+                Some((_, _, refresh_at)) => {
+                    let wait = refresh_at.duration_since(now).unwrap_or_default();
+                    sleep(wait.min(POLL_INTERVAL)).await;
+                }
+                None => sleep(POLL_INTERVAL).await,
+            }
+        }
+    }
+
+    /// Stops [`run`](Self::run) from refreshing tokens, which returns within a minute.
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+    }
+
+    async fn refresh(&self, scopes: &[&str], options: &TokenRequestOptions) -> Result<AccessToken> {
+        let result = self.credential.get_token(scopes, options).await;
+
+        let mut entries = self.entries.lock().unwrap();
+        let index = match entries
+            .iter()
+            .position(|entry| entry.matches(scopes, options))
+        {
+            Some(index) => index,
+            None => {
+                entries.push(Entry {
+                    scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+                    options: options.clone(),
+                    token: None,
+                    registered: false,
+                    refresh_at: SystemTime::UNIX_EPOCH,
+                    failures: 0,
+                });
+                entries.len() - 1
+            }
+        };
+        let entry = &mut entries[index];
+        let now = SystemTime::now();
+        match &result {
+            Ok(token) => {
+                // Tokens which live less than `refresh_before` would otherwise be refreshed continuously.
+                entry.refresh_at = token
+                    .expires_on
+                    .checked_sub(self.refresh_before)
+                    .unwrap_or(SystemTime::UNIX_EPOCH)
+                    .max(now + self.min_refresh_interval);
+                entry.token = Some(token.clone());
+                entry.failures = 0;
+            }
+            Err(_) => {
+                entry.failures = entry.failures.saturating_add(1);
+                let delay = self
+                    .retry_delay
+                    .saturating_mul(1 << (entry.failures - 1).min(16))
+                    .min(MAX_RETRY_DELAY.max(self.retry_delay));
+                entry.refresh_at = now + delay;
+            }
+        }
+        result
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenCredential for RefreshingCredential {
+    async fn get_token(
+        &self,
+        scopes: &[&str],
+        options: &TokenRequestOptions,
+    ) -> Result<AccessToken> {
+        if options.claims.is_some() {
+            return self.credential.get_token(scopes, options).await;
+        }
+        let cached = self
+            .entries
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|entry| entry.matches(scopes, options))
+            .and_then(|entry| entry.token.clone())
+            .filter(|token| !token.expires_within(MIN_REMAINING));
+        match cached {
+            Some(token) => Ok(token),
+            None => self.refresh(scopes, options).await,
+        }
+    }
+}
+
+impl Entry {
+    fn matches(&self, scopes: &[&str], options: &TokenRequestOptions) -> bool {
+        self.scopes
+            .iter()
+            .map(String::as_str)
+            .eq(scopes.iter().copied())
+            && &self.options == options
+    }
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct RefreshingCredentialOptions {
+    /// How long before tokens for registered scopes expire to refresh them.
+    #[options(default = Duration::from_secs(10 * 60))]
+    pub refresh_before: Duration,
+    /// The least time between refreshing a token for registered scopes, even if it expires sooner
+    /// than `refresh_before`.
+    #[options(default = Duration::from_secs(30))]
+    pub min_refresh_interval: Duration,
+    /// How long to wait after failing to refresh a token before trying again.
+    #[options(default = Duration::from_secs(30))]
+    pub retry_delay: Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_core::{Error, ErrorKind};
+    use std::sync::atomic::AtomicU32;
+
+    /// Returns tokens which expire after `lifetime`, or fails if `lifetime` is `None`.
+    #[derive(Debug)]
+    struct MockCredential {
+        lifetime: Option<Duration>,
+        calls: AtomicU32,
+    }
+
+    impl MockCredential {
+        fn new(lifetime: Option<Duration>) -> Arc<Self> {
+            Arc::new(Self {
+                lifetime,
+                calls: AtomicU32::new(0),
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl TokenCredential for MockCredential {
+        async fn get_token(
+            &self,
+            _scopes: &[&str],
+            _options: &TokenRequestOptions,
+        ) -> Result<AccessToken> {
+            let calls = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            match self.lifetime {
+                Some(lifetime) => Ok(AccessToken::new(
+                    format!("token-{calls}"),
+                    SystemTime::now() + lifetime,
+                )),
+                None => Err(Error::message(ErrorKind::Credential, "unavailable")),
+            }
+        }
+    }
+
+    const SCOPES: &[&str] = &["https://vault.azure.net/.default"];
+
+    /// Gets how long after `since` the first token is refreshed.
+    fn refresh_after(credential: &RefreshingCredential, since: SystemTime) -> Duration {
+        let entries = credential.entries.lock().unwrap();
+        entries[0]
+            .refresh_at
+            .duration_since(since)
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn caches_tokens() {
+        let mock = MockCredential::new(Some(Duration::from_secs(3600)));
+        let credential = RefreshingCredential::new(mock.clone(), None);
+        let options = TokenRequestOptions::default();
+
+        let token = credential.get_token(SCOPES, &options).await.unwrap();
+        assert_eq!(token.token, "token-1");
+        let token = credential.get_token(SCOPES, &options).await.unwrap();
+        assert_eq!(token.token, "token-1");
+        assert_eq!(mock.calls.load(Ordering::SeqCst), 1);
+
+        // Tokens for other scopes are cached separately.
+        let token = credential
+            .get_token(&["https://storage.azure.com/.default"], &options)
+            .await
+            .unwrap();
+        assert_eq!(token.token, "token-2");
+    }
+
+    #[tokio::test]
+    async fn refreshes_tokens_about_to_expire() {
+        let mock = MockCredential::new(Some(MIN_REMAINING / 2));
+        let credential = RefreshingCredential::new(mock.clone(), None);
+        let options = TokenRequestOptions::default();
+
+        credential.get_token(SCOPES, &options).await.unwrap();
+        let token = credential.get_token(SCOPES, &options).await.unwrap();
+        assert_eq!(token.token, "token-2");
+    }
+
+    #[tokio::test]
+    async fn waits_between_refreshing_short_lived_tokens() {
+        let mock = MockCredential::new(Some(Duration::from_secs(5 * 60)));
+        let options = RefreshingCredentialOptions {
+            min_refresh_interval: Duration::from_secs(60),
+            ..Default::default()
+        };
+        let credential = RefreshingCredential::new(mock, Some(options));
+
+        // The token expires before `refresh_before`, which would schedule a refresh in the past.
+        credential.register(SCOPES, &TokenRequestOptions::default());
+        let since = SystemTime::now();
+        credential
+            .refresh(SCOPES, &TokenRequestOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(refresh_after(&credential, since).as_secs(), 60);
+    }
+
+    #[tokio::test]
+    async fn backs_off_after_failures() {
+        let mock = MockCredential::new(None);
+        let options = RefreshingCredentialOptions {
+            retry_delay: Duration::from_secs(60),
+            ..Default::default()
+        };
+        let credential = RefreshingCredential::new(mock, Some(options));
+        credential.register(SCOPES, &TokenRequestOptions::default());
+
+        let mut delays = Vec::new();
+        for _ in 0..4 {
+            let since = SystemTime::now();
+            let error = credential
+                .refresh(SCOPES, &TokenRequestOptions::default())
+                .await
+                .unwrap_err();
+            assert_eq!(*error.kind(), ErrorKind::Credential);
+            delays.push(refresh_after(&credential, since).as_secs());
+        }
+        assert_eq!(delays, [60, 120, 240, 300]);
+    }
+
+    #[tokio::test]
+    async fn run_refreshes_registered_tokens() {
+        let mock = MockCredential::new(Some(Duration::from_secs(3600)));
+        let credential = RefreshingCredential::new(mock.clone(), None);
+        credential.register(SCOPES, &TokenRequestOptions::default());
+
+        let running = tokio::spawn({
+            let credential = credential.clone();
+            async move { credential.run().await }
+        });
+        while mock.calls.load(Ordering::SeqCst) == 0 {
+            sleep(Duration::from_millis(5)).await;
+        }
+        let token = credential
+            .get_token(SCOPES, &TokenRequestOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(token.token, "token-1");
+        assert_eq!(mock.calls.load(Ordering::SeqCst), 1);
+
+        // `run` would not notice `close` until it next wakes, in a minute.
+        running.abort();
+    }
+}
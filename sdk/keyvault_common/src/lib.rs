@@ -0,0 +1,7 @@
+#![doc = include_str!("../README.md")]
+
+mod retry;
+mod secrets;
+
+pub use retry::*;
+pub use secrets::*;
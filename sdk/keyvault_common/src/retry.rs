@@ -0,0 +1,144 @@
+use azure_core::{ClientOptions, Error, HttpError, RetryOptions, RetryPolicy};
+use serde::Deserialize;
+use std::{sync::Arc, time::Duration};
+
+const SOFT_DELETE_CONFLICT_RETRIES: u32 = 5;
+const SOFT_DELETE_CONFLICT_DELAY: Duration = Duration::from_secs(2);
+
+/// Retries the 409 conflicts Key Vault returns when re-creating a deleted secret which is still being
+/// deleted or purged, then falls back to the client's retry options.
+///
+/// Other conflicts, e.g., for a deleted secret which must be recovered or purged first, are not
+/// retried.
+#[derive(Debug)]
+pub struct SoftDeleteConflictRetry(RetryOptions);
+
+impl SoftDeleteConflictRetry {
+    /// Gets `options` with this retry policy wrapping its retry options if `enabled`.
+    pub fn apply(options: &ClientOptions, enabled: bool) -> ClientOptions {
+        let mut options = options.clone();
+        if enabled {
+            let retry = options.retry.clone();
+            options.retry.set_policy(Arc::new(Self(retry)));
+        }
+        options
+    }
+}
+
+impl RetryPolicy for SoftDeleteConflictRetry {
+    fn retry_delay(&self, error: &Error, retry: u32, elapsed: Duration) -> Option<Duration> {
+        if is_being_deleted(error) {
+            return (retry <= SOFT_DELETE_CONFLICT_RETRIES).then_some(SOFT_DELETE_CONFLICT_DELAY);
+        }
+        self.0.retry_delay(error, retry, elapsed)
+    }
+}
+
+/// Whether `error` is a conflict because the object is still being deleted or purged.
+fn is_being_deleted(error: &Error) -> bool {
+    let Some(error) = error.downcast_ref::<HttpError>() else {
+        return false;
+    };
+    if error.status() != 409 {
+        return false;
+    }
+    let Ok(ConflictResponse { error: details }) = serde_json::from_slice(error.body()) else {
+        return false;
+    };
+    if details
+        .innererror
+        .is_some_and(|inner| inner.code.as_deref() == Some("ObjectIsBeingDeleted"))
+    {
+        return true;
+    }
+    details.message.is_some_and(|message| {
+        let message = message.to_ascii_lowercase();
+        message.contains("being deleted") || message.contains("being purged")
+    })
+}
+
+#[derive(Deserialize)]
+struct ConflictResponse {
+    error: ConflictDetails,
+}
+
+#[derive(Deserialize)]
+struct ConflictDetails {
+    message: Option<String>,
+    innererror: Option<InnerError>,
+}
+
+#[derive(Deserialize)]
+struct InnerError {
+    code: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_core::{stream::BytesStream, Headers, Response};
+
+    async fn http_error(status: u16, body: &'static str) -> Error {
+        let response = Response::new(status, Headers::new(), Box::pin(BytesStream::new(body)));
+        Error::from(HttpError::new(response).await)
+    }
+
+    #[tokio::test]
+    async fn retries_conflicts_while_being_deleted() {
+        let mut options = ClientOptions::default();
+        options.retry = RetryOptions::none();
+        let retry = SoftDeleteConflictRetry(options.retry.clone());
+
+        let being_deleted = http_error(
+            409,
+            r#"{"error":{"code":"Conflict","message":"Secret is currently being deleted.","innererror":{"code":"ObjectIsBeingDeleted"}}}"#,
+        )
+        .await;
+        assert_eq!(
+            retry.retry_delay(&being_deleted, 1, Duration::ZERO),
+            Some(SOFT_DELETE_CONFLICT_DELAY)
+        );
+        assert_eq!(
+            retry.retry_delay(
+                &being_deleted,
+                SOFT_DELETE_CONFLICT_RETRIES + 1,
+                Duration::ZERO
+            ),
+            None
+        );
+
+        let being_purged = http_error(
+            409,
+            r#"{"error":{"code":"Conflict","message":"Secret my-secret is currently being purged."}}"#,
+        )
+        .await;
+        assert!(retry
+            .retry_delay(&being_purged, 1, Duration::ZERO)
+            .is_some());
+
+        for error in [
+            http_error(
+                409,
+                r#"{"error":{"code":"Conflict","message":"Secret my-secret is currently in a deleted but recoverable state."}}"#,
+            )
+            .await,
+            http_error(409, "").await,
+            http_error(
+                400,
+                r#"{"error":{"code":"BadParameter","innererror":{"code":"ObjectIsBeingDeleted"}}}"#,
+            )
+            .await,
+        ] {
+            assert_eq!(retry.retry_delay(&error, 1, Duration::ZERO), None, "{error}");
+        }
+
+        assert!(SoftDeleteConflictRetry::apply(&options, true)
+            .retry
+            .retry_delay(&being_deleted, 1, Duration::ZERO)
+            .is_some());
+        assert!(SoftDeleteConflictRetry::apply(&options, false)
+            .retry
+            .retry_delay(&being_deleted, 1, Duration::ZERO)
+            .is_none());
+    }
+}
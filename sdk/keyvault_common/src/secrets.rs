@@ -0,0 +1,279 @@
+use azure_core::{
+    Context, Error, ErrorKind, Headers, HttpError, Pipeline, Poller, PollerStatus, Request, Result,
+    StatusUpdate, TerminalState, Url,
+};
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+/// Validates a secret name is 1 to 127 alphanumeric characters or dashes, which Key Vault requires.
+pub fn validate_secret_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || name.len() > 127
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return Err(Error::message(
+            ErrorKind::InvalidInput,
+            format!("secret name '{name}' must be 1 to 127 alphanumeric characters or dashes"),
+        ));
+    }
+    Ok(())
+}
+
+/// Gets the URL of the first page of secrets in the vault at `endpoint`.
+///
+/// Returns an error if `max_results` is not from 1 to 25, which Key Vault rejects.
+pub fn list_secrets_url(
+    endpoint: &Url,
+    max_results: Option<u32>,
+    include_managed: Option<bool>,
+) -> Result<Url> {
+    let mut url = endpoint.clone();
+    url.set_path("secrets");
+    if let Some(max_results) = max_results {
+        if !(1..=25).contains(&max_results) {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                format!("max_results must be between 1 and 25 but was {max_results}"),
+            ));
+        }
+        url.query_pairs_mut()
+            .append_pair("maxresults", &max_results.to_string());
+    }
+    if let Some(include_managed) = include_managed {
+        url.query_pairs_mut()
+            .append_pair("includeManaged", &include_managed.to_string());
+    }
+    Ok(url)
+}
+
+/// How many times [`begin_delete_secret`] polls for a deleted secret which is not found before
+/// failing.
+pub const MAX_NOT_FOUND_POLLS: u32 = 60;
+
+/// Deletes the secret `name` in the vault at `endpoint`, then gets a [`Poller`] which polls until
+/// the deleted secret can be retrieved.
+///
+/// The `DELETE` is sent before this returns, so validation and authorization errors, or a secret
+/// which does not exist, are returned here rather than from the first poll. Polling fails if the
+/// deleted secret is still not found after [`MAX_NOT_FOUND_POLLS`] polls.
+pub async fn begin_delete_secret<T>(
+    pipeline: &Pipeline,
+    ctx: &Context,
+    endpoint: &Url,
+    name: &str,
+) -> Result<Poller<T>>
+where
+    T: TerminalState + Send + 'static,
+{
+    validate_secret_name(name)?;
+
+    let mut url = endpoint.clone();
+    url.set_path(&format!("secrets/{name}"));
+    let mut request = Request::new(url, "DELETE");
+    pipeline.send(&mut ctx.clone(), &mut request).await?;
+
+    let pipeline = pipeline.clone();
+    let ctx = ctx.clone();
+    let not_found = Arc::new(AtomicU32::new(0));
+    let name = name.to_string();
+    let mut url = endpoint.clone();
+    url.set_path(&format!("deletedsecrets/{name}"));
+    Ok(Poller::new(move || {
+        let pipeline = pipeline.clone();
+        let mut ctx = ctx.clone();
+        let mut request = Request::new(url.clone(), "GET");
+        let not_found = not_found.clone();
+        let name = name.clone();
+        async move {
+            match pipeline.send(&mut ctx, &mut request).await {
+                Ok(response) => {
+                    let headers = response.headers().clone();
+                    let body = response.into_body().collect().await?;
+                    let deleted = T::from_success(&headers, &body)?;
+                    Ok(StatusUpdate::new(
+                        PollerStatus::Succeeded,
+                        Some(deleted),
+                        headers,
+                    ))
+                }
+                // The deleted secret is not found until the deletion has been processed, which
+                // should not take long, so stop polling if it is never found, e.g., without soft
+                // delete.
+                Err(error) if error.is_not_found() => {
+                    if not_found.fetch_add(1, Ordering::Relaxed) >= MAX_NOT_FOUND_POLLS {
+                        return Err(Error::with_message(ErrorKind::Other, || {
+                            format!(
+                                "deleted secret '{name}' was not found after \
+                                 {MAX_NOT_FOUND_POLLS} polls"
+                            )
+                        }));
+                    }
+                    let headers = error
+                        .downcast_ref::<HttpError>()
+                        .map(|error| error.headers().clone())
+                        .unwrap_or_else(Headers::new);
+                    Ok(StatusUpdate::new(PollerStatus::InProgress, None, headers))
+                }
+                Err(error) => Err(error),
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_core::{
+        json::from_json, stream::BytesStream, transport::HttpClient, ClientOptions, Response,
+        RetryOptions, TransportOptions, RETRY_AFTER_MS,
+    };
+    use serde::Deserialize;
+    use std::sync::Mutex;
+
+    #[test]
+    fn secret_names() {
+        assert!(validate_secret_name("my-secret-1").is_ok());
+        assert!(validate_secret_name(&"a".repeat(127)).is_ok());
+
+        for name in ["", "my_secret", "my/secret", "sécret", &"a".repeat(128)] {
+            let error = validate_secret_name(name).unwrap_err();
+            assert_eq!(error.kind(), &ErrorKind::InvalidInput, "{name}");
+        }
+    }
+
+    #[test]
+    fn list_secrets_urls() {
+        let endpoint = Url::parse("https://my-vault.vault.azure.net?api-version=7.5").unwrap();
+        assert_eq!(
+            list_secrets_url(&endpoint, Some(25), Some(true))
+                .unwrap()
+                .as_str(),
+            "https://my-vault.vault.azure.net/secrets?api-version=7.5&maxresults=25&includeManaged=true"
+        );
+
+        for max_results in [0, 26] {
+            let error = list_secrets_url(&endpoint, Some(max_results), None).unwrap_err();
+            assert_eq!(error.kind(), &ErrorKind::InvalidInput);
+        }
+    }
+    #[derive(Debug, Default)]
+    struct ScriptedClient {
+        responses: Mutex<Vec<(u16, &'static str)>>,
+        requests: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClient for ScriptedClient {
+        async fn execute_request(&self, request: &Request) -> Result<Response> {
+            self.requests.lock().unwrap().push(format!(
+                "{} {}",
+                request.method(),
+                request.url().path()
+            ));
+            let (status, body) = self.responses.lock().unwrap().remove(0);
+            let mut headers = Headers::new();
+            headers.insert(RETRY_AFTER_MS, "1");
+            Ok(Response::new(
+                status,
+                headers,
+                Box::pin(BytesStream::new(body)),
+            ))
+        }
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Deleted {
+        id: String,
+    }
+
+    impl TerminalState for Deleted {
+        fn from_success(_headers: &Headers, body: &[u8]) -> Result<Self> {
+            from_json(body)
+        }
+    }
+
+    fn pipeline(client: Arc<ScriptedClient>) -> Pipeline {
+        let mut options = ClientOptions::default();
+        options.retry = RetryOptions::none();
+        options.transport = TransportOptions::with_http_client(client);
+        Pipeline::new(None, None, &options, Vec::new(), Vec::new())
+    }
+
+    #[tokio::test]
+    async fn deletes_secret_before_polling() {
+        let client = Arc::new(ScriptedClient {
+            responses: Mutex::new(vec![
+                (200, r#"{"id":"my-secret"}"#),
+                (404, r#"{"error":{"code":"SecretNotFound"}}"#),
+                (200, r#"{"id":"my-secret"}"#),
+            ]),
+            ..Default::default()
+        });
+        let pipeline = pipeline(client.clone());
+        let endpoint = Url::parse("https://my-vault.vault.azure.net").unwrap();
+
+        let poller: Poller<Deleted> =
+            begin_delete_secret(&pipeline, &Context::default(), &endpoint, "my-secret")
+                .await
+                .unwrap();
+        assert_eq!(
+            *client.requests.lock().unwrap(),
+            ["DELETE /secrets/my-secret"]
+        );
+
+        let update = poller.poll_once().await.unwrap();
+        assert_eq!(update.status(), PollerStatus::InProgress);
+        assert_eq!(
+            update.headers().get_optional_str(&RETRY_AFTER_MS),
+            Some("1")
+        );
+        assert_eq!(poller.wait_for_completion().await.unwrap().id, "my-secret");
+        assert_eq!(
+            *client.requests.lock().unwrap(),
+            [
+                "DELETE /secrets/my-secret",
+                "GET /deletedsecrets/my-secret",
+                "GET /deletedsecrets/my-secret",
+            ]
+        );
+
+        let error =
+            begin_delete_secret::<Deleted>(&pipeline, &Context::default(), &endpoint, "a/b")
+                .await
+                .unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::InvalidInput);
+        assert_eq!(client.requests.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn fails_if_deleted_secret_is_never_found() {
+        let mut responses = vec![(200, r#"{"id":"my-secret"}"#)];
+        responses.resize(
+            MAX_NOT_FOUND_POLLS as usize + 2,
+            (404, r#"{"error":{"code":"SecretNotFound"}}"#),
+        );
+        let client = Arc::new(ScriptedClient {
+            responses: Mutex::new(responses),
+            ..Default::default()
+        });
+        let endpoint = Url::parse("https://my-vault.vault.azure.net").unwrap();
+
+        let poller: Poller<Deleted> = begin_delete_secret(
+            &pipeline(client.clone()),
+            &Context::default(),
+            &endpoint,
+            "my-secret",
+        )
+        .await
+        .unwrap();
+        let error = poller.wait_for_completion().await.unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::Other);
+        assert!(error.to_string().contains("my-secret"), "{error}");
+        assert_eq!(
+            client.requests.lock().unwrap().len(),
+            MAX_NOT_FOUND_POLLS as usize + 2
+        );
+    }
+}
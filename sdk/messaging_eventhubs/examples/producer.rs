@@ -0,0 +1,54 @@
+use azure_identity::DefaultAzureCredential;
+use azure_messaging_eventhubs::{InMemoryTransport, ProducerClient};
+use std::{sync::Arc, time::Duration};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let transport = Arc::new(InMemoryTransport::default());
+
+    let client = ProducerClient::builder()
+        .with_namespace("my-namespace.servicebus.windows.net")
+        .with_event_hub("telemetry")
+        .with_credential(Arc::new(DefaultAzureCredential::default()))
+        .with_transport(transport.clone())
+        .with_max_batch_size(64)
+        .build()?;
+
+    // Fill batches explicitly, sending each when the next event does not fit.
+    let mut batch = client.create_batch();
+    for reading in ["21.5", "21.7", "21.6", "22.0"] {
+        if let Err(event) = batch.try_add(reading) {
+            client.send_batch(batch).send().await?;
+            batch = client.create_batch();
+            batch.try_add(event).expect("event fits an empty batch");
+        }
+    }
+    client.send_batch(batch).send().await?;
+
+    // Or enqueue events to be sent when a batch has 3 events or has waited 200 milliseconds.
+    let producer = client
+        .buffered_producer()
+        .with_max_events(3)
+        .with_max_wait_time(Duration::from_millis(200))
+        .build()?;
+    let run = tokio::spawn({
+        let producer = producer.clone();
+        async move { producer.run().await }
+    });
+
+    for reading in ["22.1", "22.3", "22.2", "22.4"] {
+        producer.enqueue(reading).await?;
+    }
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    producer.enqueue("22.6").await?;
+    producer.close().await?;
+    run.await??;
+
+    for (i, batch) in transport.sent_batches().iter().enumerate() {
+        let bodies: Vec<_> = batch.iter().map(|event| event.body.clone()).collect();
+        println!("batch {i}: {bodies:?}");
+    }
+
+    Ok(())
+}
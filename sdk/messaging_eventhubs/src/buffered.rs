@@ -0,0 +1,373 @@
+use crate::{EventData, EventDataBatch, ProducerClient, NAMESPACE};
+use azure_core::{
+    instrumented, sleep::sleep, BuildError, Context, Error, ErrorKind, Namespace, Result,
+};
+use std::{
+    fmt, mem,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+#[derive(Clone, Debug)]
+pub struct BufferedProducerBuilder {
+    client: ProducerClient,
+    max_events: Option<usize>,
+    max_wait_time: Duration,
+    context: Option<Context>,
+    on_send_error: Option<OnSendError>,
+}
+
+impl BufferedProducerBuilder {
+    pub(crate) fn new(client: ProducerClient) -> Self {
+        Self {
+            client,
+            max_events: None,
+            max_wait_time: Duration::from_secs(1),
+            context: None,
+            on_send_error: None,
+        }
+    }
+
+    /// Sends a batch once it has `max_events`, even if more events would fit.
+    pub fn with_max_events(&mut self, max_events: usize) -> &mut Self {
+        self.max_events = Some(max_events);
+        self
+    }
+
+    /// Sends a batch once its first event has waited `max_wait_time`, which defaults to 1 second.
+    pub fn with_max_wait_time(&mut self, max_wait_time: Duration) -> &mut Self {
+        self.max_wait_time = max_wait_time;
+        self
+    }
+
+    /// Sets the context used to send each batch.
+    pub fn with_context(&mut self, context: Context) -> &mut Self {
+        self.context = Some(context);
+        self
+    }
+
+    /// Sets a callback which is given each batch that fails to send, e.g., to enqueue its events
+    /// again or record them, instead of the error being returned from `enqueue`, `flush`, or `close`.
+    ///
+    /// The callback runs on the task sending the batch, so it must return quickly.
+    pub fn with_on_send_error<F>(&mut self, on_send_error: F) -> &mut Self
+    where
+        F: Fn(FailedBatch) + Send + Sync + 'static,
+    {
+        self.on_send_error = Some(OnSendError(Arc::new(on_send_error)));
+        self
+    }
+
+    /// Builds the producer, returning an [`ErrorKind::InvalidInput`] error wrapping a
+    /// [`BuildError`] which lists every setting that is not valid.
+    pub fn build(&self) -> Result<BufferedProducer> {
+        let mut error = BuildError::default();
+        if self.max_events == Some(0) {
+            error.invalid("max_events", "must be greater than zero");
+        }
+        if self.max_wait_time.is_zero() {
+            error.invalid("max_wait_time", "must be greater than zero");
+        }
+        error.into_result()?;
+
+        Ok(BufferedProducer {
+            buffer: Arc::new(Mutex::new(Buffer {
+                batch: self.client.create_batch(),
+                oldest: None,
+            })),
+            client: self.client.clone(),
+            max_events: self.max_events.unwrap_or(usize::MAX),
+            max_wait_time: self.max_wait_time,
+            context: self.context.clone().unwrap_or_default(),
+            on_send_error: self.on_send_error.clone(),
+            closed: Arc::new(AtomicBool::new(false)),
+        })
+    }
+}
+
+/// Accumulates events and sends them in batches when a batch is full, has the maximum number of
+/// events, or its first event has waited the maximum wait time.
+///
+/// Full batches are sent by [`enqueue`](Self::enqueue). Batches are sent after the maximum wait
+/// time only while [`run`](Self::run) is polled, e.g., on a task spawned by the caller. Events in
+/// a batch which fails to send are not enqueued again, but are given to the callback set with
+/// [`BufferedProducerBuilder::with_on_send_error`], if any.
+#[derive(Clone, Debug)]
+pub struct BufferedProducer {
+    client: ProducerClient,
+    buffer: Arc<Mutex<Buffer>>,
+    max_events: usize,
+    max_wait_time: Duration,
+    context: Context,
+    on_send_error: Option<OnSendError>,
+    closed: Arc<AtomicBool>,
+}
+
+/// A batch which failed to send, given to the callback set with
+/// [`BufferedProducerBuilder::with_on_send_error`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct FailedBatch {
+    /// The error from the last attempt to send the batch.
+    pub error: Error,
+    pub events: Vec<EventData>,
+}
+
+#[derive(Clone)]
+struct OnSendError(Arc<dyn Fn(FailedBatch) + Send + Sync>);
+
+impl fmt::Debug for OnSendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("OnSendError")
+    }
+}
+
+#[derive(Debug)]
+struct Buffer {
+    batch: EventDataBatch,
+    /// When the first event in the batch was enqueued.
+    oldest: Option<Instant>,
+}
+
+impl Buffer {
+    fn take(&mut self, client: &ProducerClient) -> EventDataBatch {
+        self.oldest = None;
+        mem::replace(&mut self.batch, client.create_batch())
+    }
+}
+
+impl BufferedProducer {
+    /// Adds `event` to the current batch, sending the batch first if `event` does not fit and
+    /// after if it is then full.
+    #[instrumented("BufferedProducer::enqueue")]
+    pub async fn enqueue(&self, event: impl Into<EventData>) -> Result<()> {
+        let mut ctx = self.context.clone();
+        ctx.insert(Namespace::from(NAMESPACE));
+
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "the producer has been closed",
+            ));
+        }
+
+        let mut ready = Vec::new();
+        {
+            let mut buffer = self.buffer.lock().unwrap();
+            if let Err(event) = buffer.batch.try_add(event) {
+                let max_size_in_bytes = buffer.batch.max_size_in_bytes();
+                if event.size_in_bytes() > max_size_in_bytes {
+                    return Err(Error::with_message(ErrorKind::InvalidInput, || {
+                        format!(
+                            "an event of {} bytes is larger than a batch of {max_size_in_bytes} bytes",
+                            event.size_in_bytes(),
+                        )
+                    }));
+                }
+                ready.push(buffer.take(&self.client));
+                if buffer.batch.try_add(event).is_err() {
+                    unreachable!("an empty batch fits any event no larger than a batch");
+                }
+            }
+            buffer.oldest.get_or_insert_with(Instant::now);
+            if buffer.batch.len() >= self.max_events {
+                ready.push(buffer.take(&self.client));
+            }
+        }
+
+        for batch in ready {
+            self.send(&ctx, batch).await?;
+        }
+        Ok(())
+    }
+
+    /// Sends the current batch now, if it has any events.
+    #[instrumented("BufferedProducer::flush")]
+    pub async fn flush(&self) -> Result<()> {
+        let mut ctx = self.context.clone();
+        ctx.insert(Namespace::from(NAMESPACE));
+
+        let batch = self.buffer.lock().unwrap().take(&self.client);
+        if batch.is_empty() {
+            return Ok(());
+        }
+        self.send(&ctx, batch).await
+    }
+
+    /// Sends each batch once its first event has waited the maximum wait time, until
+    /// [`close`](Self::close) is called.
+    ///
+    /// Batches which fail with an [`ErrorKind::Io`] error, which are transient, are dropped and
+    /// the next batch is sent as usual; any other error is returned.
+    pub async fn run(&self) -> Result<()> {
+        while !self.closed.load(Ordering::SeqCst) {
+            let oldest = self.buffer.lock().unwrap().oldest;
+            let wait = oldest.map_or(self.max_wait_time, |oldest| {
+                self.max_wait_time.saturating_sub(oldest.elapsed())
+            });
+            if wait.is_zero() {
+                match self.flush().await {
+                    Err(error) if *error.kind() != ErrorKind::Io => return Err(error),
+                    _ => {}
+                }
+            } else {
+                sleep(wait).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Stops accepting events and sends any which have been enqueued.
+    pub async fn close(&self) -> Result<()> {
+        self.closed.store(true, Ordering::SeqCst);
+        self.flush().await
+    }
+
+    /// Sends `batch`, giving it to the `on_send_error` callback if it fails and one is set.
+    async fn send(&self, ctx: &Context, batch: EventDataBatch) -> Result<()> {
+        let error = match self.client.send_events(ctx, batch.events()).await {
+            Ok(()) => return Ok(()),
+            Err(error) => error,
+        };
+        match &self.on_send_error {
+            Some(OnSendError(on_send_error)) => {
+                on_send_error(FailedBatch {
+                    error,
+                    events: batch.events().to_vec(),
+                });
+                Ok(())
+            }
+            None => Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventHubTarget, EventHubsTransport, InMemoryTransport};
+    use azure_core::{AccessToken, RetryOptions, TokenCredential, TokenRequestOptions};
+    use std::{sync::atomic::AtomicUsize, time::SystemTime};
+
+    #[derive(Debug)]
+    struct MockCredential;
+
+    #[async_trait::async_trait]
+    impl TokenCredential for MockCredential {
+        async fn get_token(
+            &self,
+            _scopes: &[&str],
+            _options: &TokenRequestOptions,
+        ) -> Result<AccessToken> {
+            Ok(AccessToken::new(
+                "mock",
+                SystemTime::now() + Duration::from_secs(3600),
+            ))
+        }
+    }
+
+    /// Fails the first `failures` sends with an I/O error, then records each batch sent.
+    #[derive(Debug, Default)]
+    struct FlakyTransport {
+        failures: AtomicUsize,
+        sent: InMemoryTransport,
+    }
+
+    #[async_trait::async_trait]
+    impl EventHubsTransport for FlakyTransport {
+        async fn send(
+            &self,
+            ctx: &Context,
+            target: &EventHubTarget,
+            events: &[EventData],
+        ) -> Result<()> {
+            let failed = self
+                .failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |failures| {
+                    failures.checked_sub(1)
+                })
+                .is_ok();
+            if failed {
+                return Err(Error::message(ErrorKind::Io, "connection reset"));
+            }
+            self.sent.send(ctx, target, events).await
+        }
+    }
+
+    fn client(transport: &Arc<FlakyTransport>) -> ProducerClient {
+        ProducerClient::builder()
+            .with_namespace("my-namespace.servicebus.windows.net")
+            .with_event_hub("telemetry")
+            .with_credential(Arc::new(MockCredential))
+            .with_transport(transport.clone())
+            .with_retry(RetryOptions::none())
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn gives_failed_batches_to_callback() {
+        let transport = Arc::new(FlakyTransport {
+            failures: AtomicUsize::new(1),
+            ..Default::default()
+        });
+        let failed = Arc::new(Mutex::new(Vec::new()));
+        let producer = client(&transport)
+            .buffered_producer()
+            .with_max_events(2)
+            .with_on_send_error({
+                let failed = failed.clone();
+                move |batch: FailedBatch| {
+                    assert_eq!(*batch.error.kind(), ErrorKind::Io);
+                    failed.lock().unwrap().push(batch.events);
+                }
+            })
+            .build()
+            .unwrap();
+
+        for event in ["1", "2", "3", "4"] {
+            producer.enqueue(event).await.unwrap();
+        }
+        assert_eq!(
+            *failed.lock().unwrap(),
+            [vec![EventData::new("1"), EventData::new("2")]]
+        );
+        assert_eq!(
+            transport.sent.sent_batches(),
+            [vec![EventData::new("3"), EventData::new("4")]]
+        );
+    }
+
+    #[tokio::test]
+    async fn runs_after_transient_errors() {
+        let transport = Arc::new(FlakyTransport {
+            failures: AtomicUsize::new(1),
+            ..Default::default()
+        });
+        let producer = client(&transport)
+            .buffered_producer()
+            .with_max_wait_time(Duration::from_millis(10))
+            .build()
+            .unwrap();
+        let running = tokio::spawn({
+            let producer = producer.clone();
+            async move { producer.run().await }
+        });
+
+        producer.enqueue("1").await.unwrap();
+        while transport.failures.load(Ordering::SeqCst) > 0 {
+            sleep(Duration::from_millis(5)).await;
+        }
+        producer.enqueue("2").await.unwrap();
+        while transport.sent.sent_batches().is_empty() {
+            sleep(Duration::from_millis(5)).await;
+        }
+        producer.close().await.unwrap();
+
+        running.await.unwrap().unwrap();
+        assert_eq!(transport.sent.sent_batches(), [vec![EventData::new("2")]]);
+    }
+}
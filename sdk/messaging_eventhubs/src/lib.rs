@@ -0,0 +1,214 @@
+#![doc = include_str!("../README.md")]
+
+mod buffered;
+mod models;
+mod transport;
+
+use azure_core::{
+    instrumented, sleep::sleep, BuildError, Context, Error, ErrorKind, Namespace, Result,
+    RetryOptions, TokenCredential,
+};
+pub use buffered::*;
+pub use models::*;
+use std::sync::Arc;
+pub use transport::*;
+
+const NAMESPACE: &str = "Microsoft.EventHub";
+
+/// The most bytes Event Hubs accepts in a single batch on the Standard tier.
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 1024 * 1024;
+
+#[derive(Clone, Debug, Default)]
+pub struct ProducerClientBuilder {
+    namespace: Option<String>,
+    event_hub: Option<String>,
+    credential: Option<Arc<dyn TokenCredential>>,
+    transport: Option<Arc<dyn EventHubsTransport>>,
+    max_batch_size: Option<usize>,
+    retry: RetryOptions,
+}
+
+impl ProducerClientBuilder {
+    /// Sets the fully qualified namespace, e.g., `my-namespace.servicebus.windows.net`. Required.
+    pub fn with_namespace(&mut self, namespace: impl Into<String>) -> &mut Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// Sets the name of the Event Hub to send to. Required.
+    pub fn with_event_hub(&mut self, event_hub: impl Into<String>) -> &mut Self {
+        self.event_hub = Some(event_hub.into());
+        self
+    }
+
+    /// Sets the credential used to authorize the link. Required.
+    pub fn with_credential(&mut self, credential: Arc<dyn TokenCredential>) -> &mut Self {
+        self.credential = Some(credential);
+        self
+    }
+
+    /// Sets the transport, which defaults to an [`InMemoryTransport`] in this prototype.
+    pub fn with_transport(&mut self, transport: Arc<dyn EventHubsTransport>) -> &mut Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Sets the most bytes in each batch, which defaults to [`DEFAULT_MAX_BATCH_SIZE`].
+    pub fn with_max_batch_size(&mut self, max_batch_size: usize) -> &mut Self {
+        self.max_batch_size = Some(max_batch_size);
+        self
+    }
+
+    pub fn with_retry(&mut self, retry: RetryOptions) -> &mut Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Builds the client, returning an [`ErrorKind::InvalidInput`] error wrapping a [`BuildError`]
+    /// which lists every setting that is missing or not valid.
+    pub fn build(&self) -> Result<ProducerClient> {
+        let mut error = BuildError::default();
+        match self.namespace.as_deref() {
+            Some(namespace) if namespace.is_empty() || namespace.contains('/') => {
+                error.invalid(
+                    "namespace",
+                    "must be a host name like my-namespace.servicebus.windows.net",
+                );
+            }
+            Some(_) => {}
+            None => {
+                error.missing("namespace");
+            }
+        }
+        match self.event_hub.as_deref() {
+            Some("") => {
+                error.invalid("event_hub", "must not be empty");
+            }
+            Some(_) => {}
+            None => {
+                error.missing("event_hub");
+            }
+        }
+        if self.credential.is_none() {
+            error.missing("credential");
+        }
+        let max_batch_size = self.max_batch_size.unwrap_or(DEFAULT_MAX_BATCH_SIZE);
+        if max_batch_size == 0 {
+            error.invalid("max_batch_size", "must be greater than zero");
+        }
+        if let Err(err) = self.retry.validate() {
+            error.invalid("retry", err.to_string());
+        }
+        error.into_result()?;
+        let (Some(namespace), Some(event_hub), Some(credential)) = (
+            self.namespace.clone(),
+            self.event_hub.clone(),
+            self.credential.clone(),
+        ) else {
+            unreachable!("missing settings were returned");
+        };
+
+        Ok(ProducerClient {
+            target: EventHubTarget {
+                namespace,
+                event_hub,
+                credential,
+            },
+            transport: self
+                .transport
+                .clone()
+                .unwrap_or_else(|| Arc::new(InMemoryTransport::default())),
+            max_batch_size,
+            retry: self.retry.clone(),
+        })
+    }
+}
+
+/// A client which sends events to a single Event Hub.
+#[derive(Clone, Debug)]
+pub struct ProducerClient {
+    target: EventHubTarget,
+    transport: Arc<dyn EventHubsTransport>,
+    max_batch_size: usize,
+    retry: RetryOptions,
+}
+
+impl ProducerClient {
+    pub fn builder() -> ProducerClientBuilder {
+        ProducerClientBuilder::default()
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.target.namespace
+    }
+
+    pub fn event_hub(&self) -> &str {
+        &self.target.event_hub
+    }
+
+    /// Creates an empty batch holding up to the client's maximum batch size.
+    pub fn create_batch(&self) -> EventDataBatch {
+        EventDataBatch::new(self.max_batch_size)
+    }
+
+    pub fn send_batch(&self, batch: EventDataBatch) -> send_batch::SendBatchRequestBuilder {
+        send_batch::SendBatchRequestBuilder {
+            client: self.clone(),
+            batch,
+            context: None,
+        }
+    }
+
+    /// Accumulates events and sends them in batches once full or after a maximum wait, instead
+    /// of sending each batch as it is created.
+    pub fn buffered_producer(&self) -> BufferedProducerBuilder {
+        BufferedProducerBuilder::new(self.clone())
+    }
+
+    /// Sends `events` until the transport succeeds or [`RetryOptions`] decides not to retry.
+    async fn send_events(&self, ctx: &Context, events: &[EventData]) -> Result<()> {
+        let mut retrier = self.retry.retrier();
+        loop {
+            let error = match self.transport.send(ctx, &self.target, events).await {
+                Ok(()) => return Ok(()),
+                Err(error) => error,
+            };
+            match retrier.next_delay(&error) {
+                Some(delay) => sleep(delay).await,
+                None => return Err(error),
+            }
+        }
+    }
+}
+
+mod send_batch {
+    use super::*;
+
+    #[derive(Clone, Debug)]
+    pub struct SendBatchRequestBuilder {
+        pub(crate) client: ProducerClient,
+        pub(crate) batch: EventDataBatch,
+        pub(crate) context: Option<Context>,
+    }
+
+    impl SendBatchRequestBuilder {
+        pub fn with_context(&mut self, context: Context) -> &mut Self {
+            self.context = Some(context);
+            self
+        }
+
+        #[instrumented("ProducerClient::send_batch")]
+        pub async fn send(&self) -> Result<()> {
+            let mut ctx = self.context.clone().unwrap_or_default();
+            ctx.insert(Namespace::from(NAMESPACE));
+
+            if self.batch.is_empty() {
+                return Err(Error::message(
+                    ErrorKind::InvalidInput,
+                    "batch must not be empty",
+                ));
+            }
+            self.client.send_events(&ctx, self.batch.events()).await
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use bytes::Bytes;
+use std::collections::HashMap;
+
+/// The bytes each event adds to a batch beyond its body and properties, e.g., for AMQP framing.
+const EVENT_OVERHEAD: usize = 16;
+
+/// An event sent to an Event Hub.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct EventData {
+    pub body: Bytes,
+    pub content_type: Option<String>,
+    /// Application properties, which are sent as AMQP application properties.
+    pub properties: HashMap<String, String>,
+}
+
+impl EventData {
+    pub fn new(body: impl Into<Bytes>) -> Self {
+        Self {
+            body: body.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Gets the bytes this event adds to an [`EventDataBatch`].
+    pub fn size_in_bytes(&self) -> usize {
+        EVENT_OVERHEAD
+            + self.body.len()
+            + self.content_type.as_ref().map_or(0, String::len)
+            + self
+                .properties
+                .iter()
+                .map(|(name, value)| name.len() + value.len())
+                .sum::<usize>()
+    }
+}
+
+impl From<&'static str> for EventData {
+    fn from(body: &'static str) -> Self {
+        Self::new(body)
+    }
+}
+
+impl From<String> for EventData {
+    fn from(body: String) -> Self {
+        Self::new(body)
+    }
+}
+
+impl From<Vec<u8>> for EventData {
+    fn from(body: Vec<u8>) -> Self {
+        Self::new(body)
+    }
+}
+
+/// Events sent together in a single message, up to the size an Event Hub accepts.
+///
+/// Create a batch using [`ProducerClient::create_batch`](crate::ProducerClient::create_batch).
+#[derive(Clone, Debug)]
+pub struct EventDataBatch {
+    events: Vec<EventData>,
+    size_in_bytes: usize,
+    max_size_in_bytes: usize,
+}
+
+impl EventDataBatch {
+    pub(crate) fn new(max_size_in_bytes: usize) -> Self {
+        Self {
+            events: Vec::new(),
+            size_in_bytes: 0,
+            max_size_in_bytes,
+        }
+    }
+
+    /// Adds `event` if it fits in the batch, or returns it so it can be added to another batch.
+    pub fn try_add(&mut self, event: impl Into<EventData>) -> Result<(), EventData> {
+        let event = event.into();
+        let size_in_bytes = self.size_in_bytes + event.size_in_bytes();
+        if size_in_bytes > self.max_size_in_bytes {
+            return Err(event);
+        }
+        self.size_in_bytes = size_in_bytes;
+        self.events.push(event);
+        Ok(())
+    }
+
+    pub fn events(&self) -> &[EventData] {
+        &self.events
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn size_in_bytes(&self) -> usize {
+        self.size_in_bytes
+    }
+
+    pub fn max_size_in_bytes(&self) -> usize {
+        self.max_size_in_bytes
+    }
+}
@@ -0,0 +1,66 @@
+use crate::EventData;
+use azure_core::{Context, Result, TokenCredential};
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+/// The Event Hub a producer sends to and the credential used to authorize it.
+#[derive(Clone)]
+#[non_exhaustive]
+pub struct EventHubTarget {
+    /// The fully qualified namespace, e.g., `my-namespace.servicebus.windows.net`.
+    pub namespace: String,
+    pub event_hub: String,
+    pub credential: Arc<dyn TokenCredential>,
+}
+
+impl fmt::Debug for EventHubTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventHubTarget")
+            .field("namespace", &self.namespace)
+            .field("event_hub", &self.event_hub)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Sends batches of events over an AMQP link, which replaces the HTTP `Pipeline` for Event Hubs.
+///
+/// Transports should return [`ErrorKind::Io`](azure_core::ErrorKind::Io) errors for transient
+/// failures so they are retried according to the client's `RetryOptions`.
+#[async_trait::async_trait]
+pub trait EventHubsTransport: Send + Sync + fmt::Debug {
+    /// Sends `events` as a single message so they are all accepted or none are.
+    async fn send(
+        &self,
+        ctx: &Context,
+        target: &EventHubTarget,
+        events: &[EventData],
+    ) -> Result<()>;
+}
+
+/// Records each batch sent in place of a connection to a namespace, for examples and tests.
+#[derive(Debug, Default)]
+pub struct InMemoryTransport {
+    batches: Mutex<Vec<Vec<EventData>>>,
+}
+
+impl InMemoryTransport {
+    /// Gets the events of each batch sent, in the order they were sent.
+    pub fn sent_batches(&self) -> Vec<Vec<EventData>> {
+        self.batches.lock().unwrap().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl EventHubsTransport for InMemoryTransport {
+    async fn send(
+        &self,
+        _ctx: &Context,
+        _target: &EventHubTarget,
+        events: &[EventData],
+    ) -> Result<()> {
+        self.batches.lock().unwrap().push(events.to_vec());
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use azure_core::{FixedRetryOptions, RetryOptions};
+use azure_identity::DefaultAzureCredential;
+use azure_messaging_servicebus::{
+    InMemoryTransport, Message, ServiceBusClient, ServiceBusClientOptions,
+};
+use std::{sync::Arc, time::Duration};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let transport = Arc::new(InMemoryTransport::default());
+
+    // Retry options and callbacks are the same as for HTTP clients.
+    let mut fixed = FixedRetryOptions::default();
+    fixed.delay = Duration::from_millis(100);
+    let mut retry = RetryOptions::fixed(fixed);
+    retry.set_on_retry(|attempt| {
+        eprintln!(
+            "retry {} in {:?} after: {}",
+            attempt.attempt, attempt.delay, attempt.cause
+        );
+    });
+
+    let mut options = ServiceBusClientOptions::default();
+    options.transport = transport.clone();
+    options.retry = retry;
+
+    let credential = Arc::new(DefaultAzureCredential::default());
+    let client = ServiceBusClient::new(
+        "my-namespace.servicebus.windows.net",
+        credential,
+        Some(options),
+    )?;
+
+    let sender = client.sender("orders");
+    sender.send_message("order 1", None).await?;
+
+    // Transient transport errors are retried.
+    transport.fail_next(2);
+    let mut message = Message::new("order 2");
+    message.content_type = Some("text/plain".to_string());
+    sender.send_message(message, None).await?;
+
+    // Abandoned messages are delivered again.
+    let receiver = client.receiver("orders");
+    let messages = receiver.receive_messages(1, None).await?;
+    receiver.abandon_message(&messages[0], None).await?;
+
+    for message in receiver.receive_messages(10, None).await? {
+        println!(
+            "received {:?} (delivery {})",
+            message.message.body, message.delivery_count
+        );
+        receiver.complete_message(&message, None).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,299 @@
+#![doc = include_str!("../README.md")]
+
+mod credential;
+mod models;
+mod transport;
+
+use azure_core::{
+    instrumented, sleep::sleep, Attribute, ConnectionString, Context, Error, ErrorKind, Namespace,
+    OperationName, Options, Result, RetryOptions, TokenCredential, Tracer, Url,
+};
+pub use credential::*;
+pub use models::*;
+use std::{future::Future, sync::Arc};
+pub use transport::*;
+
+const NAMESPACE: &str = "Microsoft.ServiceBus";
+
+/// A client for a Service Bus namespace which gets senders and receivers for its queues, all
+/// sharing the same transport.
+#[derive(Clone, Debug)]
+pub struct ServiceBusClient {
+    namespace: String,
+    credential: Arc<dyn TokenCredential>,
+    options: ServiceBusClientOptions,
+}
+
+impl ServiceBusClient {
+    pub fn new(
+        fully_qualified_namespace: impl Into<String>,
+        credential: Arc<dyn TokenCredential>,
+        options: Option<ServiceBusClientOptions>,
+    ) -> Result<Self> {
+        let namespace = fully_qualified_namespace.into();
+        if namespace.is_empty() || namespace.contains('/') {
+            return Err(Error::with_message(ErrorKind::InvalidInput, || {
+                format!(
+                    "namespace '{namespace}' must be a host name like my-namespace.servicebus.windows.net"
+                )
+            }));
+        }
+        let options = options.unwrap_or_default();
+        options.retry.validate()?;
+
+        Ok(Self {
+            namespace,
+            credential,
+            options,
+        })
+    }
+
+    /// Creates a client from a connection string with an `Endpoint`, `SharedAccessKeyName`,
+    /// and `SharedAccessKey`, e.g., copied from a shared access policy of the namespace.
+    pub fn from_connection_string(
+        connection_string: &str,
+        options: Option<ServiceBusClientOptions>,
+    ) -> Result<Self> {
+        let connection_string = ConnectionString::parse(connection_string)?;
+        let endpoint = connection_string.require("Endpoint")?;
+        let namespace = Url::parse(endpoint)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .ok_or_else(|| {
+                Error::with_message(ErrorKind::InvalidInput, || {
+                    format!(
+                        "endpoint '{endpoint}' must be a URL like sb://my-namespace.servicebus.windows.net/"
+                    )
+                })
+            })?;
+        let credential = SharedAccessKeyCredential::new(
+            connection_string.require("SharedAccessKeyName")?,
+            connection_string.require("SharedAccessKey")?,
+        );
+        Self::new(namespace, Arc::new(credential), options)
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub fn sender(&self, queue: impl Into<String>) -> ServiceBusSender {
+        ServiceBusSender {
+            link: Link::new(self, queue.into()),
+        }
+    }
+
+    pub fn receiver(&self, queue: impl Into<String>) -> ServiceBusReceiver {
+        ServiceBusReceiver {
+            link: Link::new(self, queue.into()),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ServiceBusSender {
+    link: Link,
+}
+
+impl ServiceBusSender {
+    pub fn queue(&self) -> &str {
+        &self.link.target.entity
+    }
+
+    #[instrumented("ServiceBusSender::send_message")]
+    pub async fn send_message(
+        &self,
+        message: impl Into<Message>,
+        options: Option<SendMessageOptions>,
+    ) -> Result<()> {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+
+        let message = message.into();
+        self.link
+            .execute(&ctx, |transport, target| {
+                transport.send(&ctx, target, &message)
+            })
+            .await
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ServiceBusReceiver {
+    link: Link,
+}
+
+impl ServiceBusReceiver {
+    pub fn queue(&self) -> &str {
+        &self.link.target.entity
+    }
+
+    /// Receives up to `max_messages`, which are locked until they are settled.
+    #[instrumented("ServiceBusReceiver::receive_messages")]
+    pub async fn receive_messages(
+        &self,
+        max_messages: usize,
+        options: Option<ReceiveMessagesOptions>,
+    ) -> Result<Vec<ReceivedMessage>> {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+
+        if max_messages == 0 {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "max_messages must be greater than zero",
+            ));
+        }
+        self.link
+            .execute(&ctx, |transport, target| {
+                transport.receive(&ctx, target, max_messages)
+            })
+            .await
+    }
+
+    /// Removes a received message from the queue.
+    #[instrumented("ServiceBusReceiver::complete_message")]
+    pub async fn complete_message(
+        &self,
+        message: &ReceivedMessage,
+        options: Option<SettleMessageOptions>,
+    ) -> Result<()> {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+
+        self.link
+            .execute(&ctx, |transport, target| {
+                transport.settle(&ctx, target, &message.lock_token, Disposition::Complete)
+            })
+            .await
+    }
+
+    /// Releases the lock on a received message so it can be received again.
+    #[instrumented("ServiceBusReceiver::abandon_message")]
+    pub async fn abandon_message(
+        &self,
+        message: &ReceivedMessage,
+        options: Option<SettleMessageOptions>,
+    ) -> Result<()> {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+
+        self.link
+            .execute(&ctx, |transport, target| {
+                transport.settle(&ctx, target, &message.lock_token, Disposition::Abandon)
+            })
+            .await
+    }
+}
+
+/// The target of a sender or receiver and the options of the client it came from.
+#[derive(Clone, Debug)]
+struct Link {
+    target: AmqpTarget,
+    transport: Arc<dyn AmqpTransport>,
+    retry: RetryOptions,
+    tracer: Option<Arc<dyn Tracer>>,
+}
+
+impl Link {
+    fn new(client: &ServiceBusClient, entity: String) -> Self {
+        Self {
+            target: AmqpTarget {
+                namespace: client.namespace.clone(),
+                entity,
+                credential: client.credential.clone(),
+            },
+            transport: client.options.transport.clone(),
+            retry: client.options.retry.clone(),
+            tracer: client.options.tracer.clone(),
+        }
+    }
+
+    /// Calls `operation` until it succeeds or [`RetryOptions`] decides not to retry, within a span
+    /// named after the client method in `ctx` like the requests an HTTP `Pipeline` sends.
+    async fn execute<'a, T, F, Fut>(&'a self, ctx: &Context, mut operation: F) -> Result<T>
+    where
+        F: FnMut(&'a dyn AmqpTransport, &'a AmqpTarget) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut span = self.tracer.as_ref().map(|tracer| {
+            let name = ctx
+                .value::<OperationName>()
+                .map_or("ServiceBus", OperationName::name);
+            let mut attributes = vec![
+                Attribute::new("messaging.system", "servicebus"),
+                Attribute::new("messaging.destination.name", self.target.entity.as_str()),
+                Attribute::new("server.address", self.target.namespace.as_str()),
+            ];
+            if let Some(namespace) = ctx.value::<Namespace>() {
+                attributes.push(Attribute::new("az.namespace", namespace.as_str()));
+            }
+            tracer.start_span(name, attributes)
+        });
+
+        let mut retrier = self.retry.retrier();
+        let result = loop {
+            let error = match operation(self.transport.as_ref(), &self.target).await {
+                Ok(value) => break Ok(value),
+                Err(error) => error,
+            };
+            match retrier.next_delay(&error) {
+                Some(delay) => sleep(delay).await,
+                None => break Err(error),
+            }
+        };
+
+        if let Some(span) = &mut span {
+            if retrier.retries() > 0 {
+                span.set_attribute(Attribute::new(
+                    "az.retry_count",
+                    i64::from(retrier.retries()),
+                ));
+            }
+            if let Err(error) = &result {
+                span.set_attribute(Attribute::new("error.type", error.kind().to_string()));
+            }
+        }
+        if let Some(span) = span {
+            span.end();
+        }
+        result
+    }
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct ServiceBusClientOptions {
+    /// Sends and receives messages, which defaults to an [`InMemoryTransport`] in this prototype.
+    #[options(default = Arc::new(InMemoryTransport::default()))]
+    pub transport: Arc<dyn AmqpTransport>,
+    pub retry: RetryOptions,
+    /// Records a span for each operation when set.
+    pub tracer: Option<Arc<dyn Tracer>>,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct SendMessageOptions {
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct ReceiveMessagesOptions {
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct SettleMessageOptions {
+    pub context: Option<Context>,
+}
@@ -0,0 +1,62 @@
+use bytes::Bytes;
+use std::collections::HashMap;
+
+/// A message sent to a queue.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct Message {
+    pub body: Bytes,
+    pub content_type: Option<String>,
+    /// Identifies the message, e.g., for duplicate detection.
+    pub message_id: Option<String>,
+    /// Application properties, which are sent as AMQP application properties.
+    pub properties: HashMap<String, String>,
+}
+
+impl Message {
+    pub fn new(body: impl Into<Bytes>) -> Self {
+        Self {
+            body: body.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&'static str> for Message {
+    fn from(body: &'static str) -> Self {
+        Self::new(body)
+    }
+}
+
+impl From<String> for Message {
+    fn from(body: String) -> Self {
+        Self::new(body)
+    }
+}
+
+impl From<Vec<u8>> for Message {
+    fn from(body: Vec<u8>) -> Self {
+        Self::new(body)
+    }
+}
+
+/// A message received from a queue, which is locked until it is settled or the lock expires.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ReceivedMessage {
+    pub message: Message,
+    /// Identifies the lock to [settle](crate::ServiceBusReceiver::complete_message) the message.
+    pub lock_token: String,
+    /// How many times the message has been delivered, including this delivery.
+    pub delivery_count: u32,
+}
+
+impl ReceivedMessage {
+    pub fn new(message: Message, lock_token: impl Into<String>, delivery_count: u32) -> Self {
+        Self {
+            message,
+            lock_token: lock_token.into(),
+            delivery_count,
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{Message, ReceivedMessage};
+use azure_core::{Context, Error, ErrorKind, Result, TokenCredential};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// The entity a link is attached to and the credential used to authorize it, e.g., using AMQP
+/// claims-based security.
+#[derive(Clone)]
+#[non_exhaustive]
+pub struct AmqpTarget {
+    /// The fully qualified namespace, e.g., `my-namespace.servicebus.windows.net`.
+    pub namespace: String,
+    /// The queue name.
+    pub entity: String,
+    pub credential: Arc<dyn TokenCredential>,
+}
+
+impl fmt::Debug for AmqpTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AmqpTarget")
+            .field("namespace", &self.namespace)
+            .field("entity", &self.entity)
+            .finish_non_exhaustive()
+    }
+}
+
+/// How a received message is settled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Disposition {
+    /// Removes the message from the queue.
+    Complete,
+    /// Releases the lock so the message can be received again.
+    Abandon,
+}
+
+/// Sends and receives messages over AMQP links, which replaces the HTTP `Pipeline` for Service Bus.
+///
+/// Transports should return [`ErrorKind::Io`] errors for transient failures, e.g., a detached link,
+/// so they are retried according to the client's `RetryOptions`.
+#[async_trait::async_trait]
+pub trait AmqpTransport: Send + Sync + fmt::Debug {
+    async fn send(&self, ctx: &Context, target: &AmqpTarget, message: &Message) -> Result<()>;
+
+    /// Receives up to `max_messages`, returning fewer or none if no more are available.
+    async fn receive(
+        &self,
+        ctx: &Context,
+        target: &AmqpTarget,
+        max_messages: usize,
+    ) -> Result<Vec<ReceivedMessage>>;
+
+    async fn settle(
+        &self,
+        ctx: &Context,
+        target: &AmqpTarget,
+        lock_token: &str,
+        disposition: Disposition,
+    ) -> Result<()>;
+}
+
+/// Keeps queues in memory in place of a connection to a namespace, for examples and tests.
+#[derive(Debug, Default)]
+pub struct InMemoryTransport {
+    queues: Mutex<HashMap<String, VecDeque<(Message, u32)>>>,
+    locked: Mutex<HashMap<String, (String, Message, u32)>>,
+    next_lock_token: AtomicU64,
+    failures: AtomicU32,
+}
+
+impl InMemoryTransport {
+    /// Fails the next `count` operations with transient [`ErrorKind::Io`] errors.
+    pub fn fail_next(&self, count: u32) {
+        self.failures.store(count, Ordering::SeqCst);
+    }
+
+    fn fail(&self) -> Result<()> {
+        let failed = self
+            .failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                count.checked_sub(1)
+            })
+            .is_ok();
+        if failed {
+            return Err(Error::message(ErrorKind::Io, "link detached"));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl AmqpTransport for InMemoryTransport {
+    async fn send(&self, _ctx: &Context, target: &AmqpTarget, message: &Message) -> Result<()> {
+        self.fail()?;
+        self.queues
+            .lock()
+            .unwrap()
+            .entry(target.entity.clone())
+            .or_default()
+            .push_back((message.clone(), 0));
+        Ok(())
+    }
+
+    async fn receive(
+        &self,
+        _ctx: &Context,
+        target: &AmqpTarget,
+        max_messages: usize,
+    ) -> Result<Vec<ReceivedMessage>> {
+        self.fail()?;
+        let mut queues = self.queues.lock().unwrap();
+        let Some(queue) = queues.get_mut(&target.entity) else {
+            return Ok(Vec::new());
+        };
+
+        let mut locked = self.locked.lock().unwrap();
+        let count = max_messages.min(queue.len());
+        Ok(queue
+            .drain(..count)
+            .map(|(message, delivery_count)| {
+                let lock_token = self
+                    .next_lock_token
+                    .fetch_add(1, Ordering::SeqCst)
+                    .to_string();
+                let delivery_count = delivery_count + 1;
+                locked.insert(
+                    lock_token.clone(),
+                    (target.entity.clone(), message.clone(), delivery_count),
+                );
+                ReceivedMessage::new(message, lock_token, delivery_count)
+            })
+            .collect())
+    }
+
+    async fn settle(
+        &self,
+        _ctx: &Context,
+        _target: &AmqpTarget,
+        lock_token: &str,
+        disposition: Disposition,
+    ) -> Result<()> {
+        self.fail()?;
+        let Some((entity, message, delivery_count)) =
+            self.locked.lock().unwrap().remove(lock_token)
+        else {
+            return Err(Error::with_message(ErrorKind::InvalidInput, || {
+                format!("lock '{lock_token}' was already settled or has expired")
+            }));
+        };
+        if disposition == Disposition::Abandon {
+            self.queues
+                .lock()
+                .unwrap()
+                .entry(entity)
+                .or_default()
+                .push_front((message, delivery_count));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+//! Measures getting a secret with each API shape, from an in-memory transport or, if
+//! `AZURE_KEYVAULT_URL` is set, from that vault.
+//!
+//! ```bash
+//! cargo run -p azure_perf --release --example get_secret -- --duration 5 --parallel 8
+//! ```
+
+use azure_client_new_method_builder as builder;
+use azure_client_new_methods_params as params;
+use azure_core::{
+    test::FakeCredential, transport::InMemoryTransport, ClientOptions, ResponseBuilder, Result,
+    TokenCredential,
+};
+use azure_identity::DefaultAzureCredential;
+use azure_perf::{PerfOptions, PerfRunner, PerfTest};
+use std::{env, sync::Arc};
+
+const NAME: &str = "perf-secret";
+
+struct ParamsGetSecret(params::SecretClient);
+
+#[async_trait::async_trait]
+impl PerfTest for ParamsGetSecret {
+    async fn setup(&self) -> Result<()> {
+        self.0.set_secret(NAME, "perf-value", None).await?;
+        Ok(())
+    }
+
+    async fn run(&self) -> Result<()> {
+        self.0.get_secret(NAME, None).await?.into_model().await?;
+        Ok(())
+    }
+}
+
+struct BuilderGetSecret(builder::SecretClient);
+
+#[async_trait::async_trait]
+impl PerfTest for BuilderGetSecret {
+    async fn setup(&self) -> Result<()> {
+        self.0.set_secret(NAME, "perf-value").send().await?;
+        Ok(())
+    }
+
+    async fn run(&self) -> Result<()> {
+        self.0.get_secret(NAME).send().await?.into_model().await?;
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let options = PerfOptions::from_args(env::args().skip(1))?;
+
+    let (endpoint, credential, client_options) = match env::var("AZURE_KEYVAULT_URL") {
+        Ok(endpoint) => {
+            let credential: Arc<dyn TokenCredential> = Arc::new(DefaultAzureCredential::default());
+            (endpoint, credential, ClientOptions::default())
+        }
+        Err(_) => {
+            let body = format!(
+                r#"{{"name":"{NAME}","version":"1","value":"perf-value","attributes":{{"enabled":true}}}}"#
+            );
+            let mut client_options = ClientOptions::default();
+            client_options.transport =
+                InMemoryTransport::new(ResponseBuilder::new(200).with_body(body)).into();
+            let credential: Arc<dyn TokenCredential> = Arc::new(FakeCredential);
+            (
+                "https://my-vault.vault.azure.net".to_string(),
+                credential,
+                client_options,
+            )
+        }
+    };
+
+    let mut params_options = params::SecretClientOptions::default();
+    params_options.options = client_options.clone();
+    let mut builder_options = builder::SecretClientOptions::default();
+    builder_options.options = client_options;
+
+    let results = PerfRunner::new(options)
+        .with_scenario(
+            "client_new_method_params",
+            ParamsGetSecret(params::SecretClient::new(
+                &endpoint,
+                credential.clone(),
+                Some(params_options),
+            )?),
+        )
+        .with_scenario(
+            "client_new_method_builder",
+            BuilderGetSecret(builder::SecretClient::new(
+                &endpoint,
+                credential,
+                Some(builder_options),
+            )?),
+        )
+        .run()
+        .await?;
+    for result in results {
+        println!("{result}");
+    }
+    Ok(())
+}
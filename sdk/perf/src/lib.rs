@@ -0,0 +1,360 @@
+#![doc = include_str!("../README.md")]
+
+use azure_core::{Error, ErrorKind, Result};
+use futures::future;
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+/// A scenario whose operations per second are measured.
+#[async_trait::async_trait]
+pub trait PerfTest: Send + Sync {
+    /// Creates any resources the scenario needs, once before it runs.
+    async fn setup(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Runs one operation, e.g., a request, concurrently with up to
+    /// [`PerfOptions::parallel`] others.
+    async fn run(&self) -> Result<()>;
+
+    /// Deletes any resources created by [`PerfTest::setup`], once after it runs.
+    async fn cleanup(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct PerfOptions {
+    /// How long to run each scenario before measuring it, e.g., to open connections.
+    pub warmup: Duration,
+    /// How long to measure each scenario.
+    pub duration: Duration,
+    /// How many operations to run at once.
+    pub parallel: usize,
+    /// Only run scenarios whose name contains this.
+    pub filter: Option<String>,
+}
+
+impl PerfOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses `--warmup <seconds>`, `--duration <seconds>`, `--parallel <count>`, and a filter,
+    /// e.g., from `std::env::args().skip(1)`.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next().ok_or_else(|| {
+                    Error::with_message(ErrorKind::InvalidInput, || {
+                        format!("{name} requires a value")
+                    })
+                })
+            };
+            match arg.as_str() {
+                "--warmup" => options.warmup = Duration::from_secs(parse(&arg, value(&arg)?)?),
+                "--duration" => options.duration = Duration::from_secs(parse(&arg, value(&arg)?)?),
+                "--parallel" => options.parallel = parse(&arg, value(&arg)?)?,
+                _ if arg.starts_with("--") => {
+                    return Err(Error::with_message(ErrorKind::InvalidInput, || {
+                        format!(
+                            "unknown option {arg}; expected --warmup, --duration, or --parallel"
+                        )
+                    }))
+                }
+                _ => options.filter = Some(arg),
+            }
+        }
+        if options.parallel == 0 {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "--parallel must be at least 1",
+            ));
+        }
+        Ok(options)
+    }
+}
+
+impl Default for PerfOptions {
+    fn default() -> Self {
+        Self {
+            warmup: Duration::from_secs(5),
+            duration: Duration::from_secs(10),
+            parallel: 1,
+            filter: None,
+        }
+    }
+}
+
+fn parse<T: std::str::FromStr>(name: &str, value: String) -> Result<T> {
+    value.parse().map_err(|_| {
+        Error::with_message(ErrorKind::InvalidInput, || {
+            format!("{name} must be a number but was '{value}'")
+        })
+    })
+}
+
+/// The operations completed while a scenario was measured.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PerfResults {
+    pub name: String,
+    pub operations: u64,
+    pub elapsed: Duration,
+}
+
+impl PerfResults {
+    pub fn operations_per_second(&self) -> f64 {
+        self.operations as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+impl fmt::Display for PerfResults {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} operations in {:.2}s ({:.2} ops/s)",
+            self.name,
+            self.operations,
+            self.elapsed.as_secs_f64(),
+            self.operations_per_second()
+        )
+    }
+}
+
+/// Runs each scenario added to it in turn, returning their results.
+pub struct PerfRunner {
+    options: PerfOptions,
+    scenarios: Vec<(String, Arc<dyn PerfTest>)>,
+}
+
+impl PerfRunner {
+    pub fn new(options: PerfOptions) -> Self {
+        Self {
+            options,
+            scenarios: Vec::new(),
+        }
+    }
+
+    pub fn with_scenario(
+        &mut self,
+        name: impl Into<String>,
+        scenario: impl PerfTest + 'static,
+    ) -> &mut Self {
+        self.scenarios.push((name.into(), Arc::new(scenario)));
+        self
+    }
+
+    /// Runs the scenarios matching [`PerfOptions::filter`], stopping at the first which fails.
+    pub async fn run(&self) -> Result<Vec<PerfResults>> {
+        let mut results = Vec::new();
+        for (name, scenario) in &self.scenarios {
+            if self
+                .options
+                .filter
+                .as_ref()
+                .is_some_and(|filter| !name.contains(filter.as_str()))
+            {
+                continue;
+            }
+
+            scenario.setup().await?;
+            let result = async {
+                measure(
+                    scenario.as_ref(),
+                    self.options.warmup,
+                    self.options.parallel,
+                )
+                .await?;
+                let (operations, elapsed) = measure(
+                    scenario.as_ref(),
+                    self.options.duration,
+                    self.options.parallel,
+                )
+                .await?;
+                Ok::<_, Error>(PerfResults {
+                    name: name.clone(),
+                    operations,
+                    elapsed,
+                })
+            }
+            .await;
+            scenario.cleanup().await?;
+
+            results.push(result?);
+        }
+        Ok(results)
+    }
+}
+
+/// Runs `parallel` operations at a time for `duration`, returning how many completed.
+async fn measure(
+    scenario: &dyn PerfTest,
+    duration: Duration,
+    parallel: usize,
+) -> Result<(u64, Duration)> {
+    let start = Instant::now();
+    let workers = (0..parallel).map(|_| async move {
+        let mut operations = 0;
+        while start.elapsed() < duration {
+            scenario.run().await?;
+            operations += 1;
+            // Operations which complete without waiting would otherwise starve the other workers
+            // and, on a single-threaded runtime, the rest of the program until `duration` elapses.
+            yield_now().await;
+        }
+        Ok::<u64, Error>(operations)
+    });
+    let operations = future::try_join_all(workers).await?.into_iter().sum();
+    Ok((operations, start.elapsed()))
+}
+
+/// Returns `Pending` once so other tasks can run before the caller continues.
+fn yield_now() -> YieldNow {
+    YieldNow(false)
+}
+
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    };
+
+    #[derive(Clone, Default)]
+    struct Counter {
+        runs: Arc<AtomicU64>,
+        events: Arc<Mutex<Vec<&'static str>>>,
+        fail: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl PerfTest for Counter {
+        async fn setup(&self) -> Result<()> {
+            self.events.lock().unwrap().push("setup");
+            Ok(())
+        }
+
+        async fn run(&self) -> Result<()> {
+            if self.fail {
+                return Err(Error::message(ErrorKind::Other, "failed"));
+            }
+            self.runs.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+
+        async fn cleanup(&self) -> Result<()> {
+            self.events.lock().unwrap().push("cleanup");
+            Ok(())
+        }
+    }
+
+    fn options(filter: Option<&str>) -> PerfOptions {
+        PerfOptions {
+            warmup: Duration::from_millis(10),
+            duration: Duration::from_millis(50),
+            parallel: 2,
+            filter: filter.map(String::from),
+        }
+    }
+
+    #[test]
+    fn parses_args() {
+        let args = ["--warmup", "1", "--duration", "2", "--parallel", "4", "get"];
+        let options = PerfOptions::from_args(args.map(String::from)).unwrap();
+        assert_eq!(options.warmup, Duration::from_secs(1));
+        assert_eq!(options.duration, Duration::from_secs(2));
+        assert_eq!(options.parallel, 4);
+        assert_eq!(options.filter.as_deref(), Some("get"));
+
+        for args in [
+            &["--duration"][..],
+            &["--duration", "soon"],
+            &["--parallel", "0"],
+            &["--verbose"],
+        ] {
+            let error = PerfOptions::from_args(args.iter().map(|arg| arg.to_string())).unwrap_err();
+            assert!(matches!(error.kind(), ErrorKind::InvalidInput), "{args:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn returns_results_of_matching_scenarios() {
+        let get = Counter::default();
+        let set = Counter::default();
+        let results = PerfRunner::new(options(Some("get")))
+            .with_scenario("get_secret", get.clone())
+            .with_scenario("set_secret", set.clone())
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "get_secret");
+        assert!(results[0].operations > 0);
+        assert!(results[0].operations < get.runs.load(Ordering::Relaxed));
+        assert!(results[0].elapsed >= Duration::from_millis(50));
+        assert_eq!(*get.events.lock().unwrap(), ["setup", "cleanup"]);
+        assert_eq!(set.runs.load(Ordering::Relaxed), 0);
+        assert!(set.events.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn cleans_up_failed_scenarios() {
+        let failing = Counter {
+            fail: true,
+            ..Default::default()
+        };
+        let next = Counter::default();
+        let error = PerfRunner::new(options(None))
+            .with_scenario("failing", failing.clone())
+            .with_scenario("next", next.clone())
+            .run()
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error.kind(), ErrorKind::Other));
+        assert_eq!(*failing.events.lock().unwrap(), ["setup", "cleanup"]);
+        assert!(next.events.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn yields_between_operations() {
+        // Both futures share one task, so the second only runs while measuring if the workers yield.
+        let scenario = Counter::default();
+        let measured = async {
+            measure(&scenario, Duration::from_millis(50), 2)
+                .await
+                .unwrap()
+        };
+        let observed = async { scenario.runs.load(Ordering::Relaxed) };
+        let ((operations, _), observed) = futures::join!(measured, observed);
+
+        assert_eq!(operations, scenario.runs.load(Ordering::Relaxed));
+        assert!(observed < operations);
+    }
+}
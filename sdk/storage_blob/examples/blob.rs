@@ -0,0 +1,58 @@
+use azure_core::MatchCondition;
+use azure_identity::DefaultAzureCredential;
+use azure_storage_blob::{BlobClient, BlobProperties, DownloadBlobOptions, UploadBlobOptions};
+use bytes::Bytes;
+use futures::{stream, TryStreamExt};
+use std::{env, sync::Arc};
+
+const MIB: usize = 1024 * 1024;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let blob_url = env::var("AZURE_STORAGE_BLOB_URL")?;
+
+    let credential = Arc::new(DefaultAzureCredential::default());
+    let client = BlobClient::new(blob_url, credential, None)?;
+
+    // Small blobs can be uploaded in a single request.
+    client.upload("hello, world", None).await?;
+
+    // Larger data is streamed as blocks staged concurrently, then committed.
+    let chunks = stream::iter((0..64).map(|i| Ok(Bytes::from(vec![i as u8; MIB]))));
+    let mut options = UploadBlobOptions::default();
+    options.content_type = Some("application/octet-stream".to_string());
+    options.block_size = 8 * MIB;
+    options.max_concurrency = 8;
+    client.upload_stream(chunks, Some(options)).await?;
+
+    // Download the first half without buffering it.
+    let mut options = DownloadBlobOptions::default();
+    options.range = Some(0..32 * MIB as u64);
+    let response = client.download(Some(options)).await?;
+    let properties = BlobProperties::from_headers(response.headers())?;
+
+    let mut received = 0;
+    let mut body = response.into_body();
+    while let Some(chunk) = body.try_next().await? {
+        received += chunk.len();
+    }
+    println!(
+        "received {received} of {:?} bytes",
+        properties.content_length
+    );
+
+    // Download the second half only if the blob has not changed since.
+    let mut options = DownloadBlobOptions::default();
+    options.range = Some(32 * MIB as u64..64 * MIB as u64);
+    options.match_condition = properties.etag.map(MatchCondition::IfMatch);
+    let body = client.download(Some(options)).await?.into_body();
+    let received = body
+        .try_fold(
+            0,
+            |received, chunk| async move { Ok(received + chunk.len()) },
+        )
+        .await?;
+    println!("received {received} more bytes");
+
+    Ok(())
+}
@@ -0,0 +1,636 @@
+#![doc = include_str!("../README.md")]
+
+mod models;
+
+use azure_core::{
+    instrumented,
+    policies::{BearerTokenSigner, SigningPolicy},
+    ClientDiagnostics, ClientOptions, Context, Error, ErrorKind, HeaderName, Idempotent,
+    MatchCondition, Namespace, Options, Pipeline, Poller, PollerStatus, Request, RequestSigner,
+    Response, Result, StatusUpdate, TokenCredential, Url, CONTENT_TYPE,
+};
+pub use azure_storage_common::{
+    SasCredential, StorageConnectionString, StorageSharedKeyCredential,
+};
+use bytes::{Bytes, BytesMut};
+use futures::{stream, Stream, TryStreamExt};
+pub use models::*;
+use std::{ops::Range, sync::Arc};
+
+const NAMESPACE: &str = "Microsoft.Storage";
+
+const BLOB_CONTENT_TYPE: HeaderName = HeaderName::from_static("x-ms-blob-content-type");
+const BLOB_TYPE: HeaderName = HeaderName::from_static("x-ms-blob-type");
+const COPY_ACTION: HeaderName = HeaderName::from_static("x-ms-copy-action");
+const COPY_SOURCE: HeaderName = HeaderName::from_static("x-ms-copy-source");
+const RANGE: HeaderName = HeaderName::from_static("x-ms-range");
+const VERSION: HeaderName = HeaderName::from_static("x-ms-version");
+
+/// The most bytes Storage accepts in a single block.
+const MAX_BLOCK_SIZE: usize = 4000 * 1024 * 1024;
+
+/// A client for a single block blob, e.g., `https://myaccount.blob.core.windows.net/container/blob`.
+#[derive(Debug, Clone)]
+pub struct BlobClient {
+    url: Url,
+    version: String,
+    pipeline: Pipeline,
+}
+
+impl BlobClient {
+    /// Creates a client which authenticates using a token from `credential`.
+    pub fn new(
+        blob_url: impl AsRef<str>,
+        credential: Arc<dyn TokenCredential>,
+        options: Option<BlobClientOptions>,
+    ) -> Result<Self> {
+        let signer = BearerTokenSigner::new(credential, "https://storage.azure.com/.default");
+        Self::with_signer(blob_url, Arc::new(signer), options)
+    }
+
+    /// Creates a client which signs each request using an account key.
+    pub fn with_shared_key(
+        blob_url: impl AsRef<str>,
+        credential: Arc<StorageSharedKeyCredential>,
+        options: Option<BlobClientOptions>,
+    ) -> Result<Self> {
+        Self::with_signer(blob_url, credential, options)
+    }
+
+    /// Creates a client which adds a shared access signature to each request.
+    pub fn with_sas(
+        blob_url: impl AsRef<str>,
+        credential: Arc<SasCredential>,
+        options: Option<BlobClientOptions>,
+    ) -> Result<Self> {
+        Self::with_signer(blob_url, credential, options)
+    }
+
+    /// Creates a client for `blob_name` in `container_name` from a connection string with an
+    /// `AccountName` and `AccountKey`, or a `SharedAccessSignature`, and optionally a `BlobEndpoint`.
+    pub fn from_connection_string(
+        connection_string: &str,
+        container_name: &str,
+        blob_name: &str,
+        options: Option<BlobClientOptions>,
+    ) -> Result<Self> {
+        let connection_string = StorageConnectionString::parse(connection_string)?;
+        let credential = connection_string.credential()?;
+
+        let mut url = connection_string.blob_endpoint()?;
+        url.path_segments_mut()
+            .map_err(|_| Error::message(ErrorKind::InvalidInput, "invalid blob endpoint"))?
+            .pop_if_empty()
+            .push(container_name)
+            .push(blob_name);
+        Self::with_signer(url, credential, options)
+    }
+
+    fn with_signer(
+        blob_url: impl AsRef<str>,
+        signer: Arc<dyn RequestSigner>,
+        options: Option<BlobClientOptions>,
+    ) -> Result<Self> {
+        let options = options.unwrap_or_default();
+
+        // Sign after every other policy so the signature covers the headers they set.
+        let mut client_options = options.options.clone();
+        client_options
+            .per_retry_policies
+            .push(Arc::new(SigningPolicy::new(signer)));
+        Self::with_policies(blob_url, &client_options, options.version)
+    }
+
+    fn with_policies(
+        blob_url: impl AsRef<str>,
+        options: &ClientOptions,
+        version: String,
+    ) -> Result<Self> {
+        if version.is_empty() {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "version must not be empty",
+            ));
+        }
+        options.validate()?;
+
+        let url = azure_core::parse_endpoint(blob_url)?;
+        if url.path_segments().map_or(0, Iterator::count) < 2 {
+            return Err(Error::with_message(ErrorKind::InvalidInput, || {
+                format!("blob URL '{url}' must include a container and blob name")
+            }));
+        }
+
+        Ok(Self {
+            url,
+            version,
+            pipeline: Pipeline::new(
+                option_env!("CARGO_PKG_NAME"),
+                option_env!("CARGO_PKG_VERSION"),
+                options,
+                Vec::default(),
+                Vec::default(),
+            ),
+        })
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Gets the resolved configuration of the client without sending any requests.
+    pub fn diagnostics(&self) -> ClientDiagnostics {
+        let mut diagnostics = self.pipeline.diagnostics(&self.url);
+        diagnostics.api_version = Some(self.version.clone());
+        diagnostics
+    }
+
+    /// Uploads `data` in a single request, replacing the blob if it exists.
+    ///
+    /// Use [`BlobClient::upload_stream`] for data too large to buffer or to send in one request.
+    #[instrumented("BlobClient::upload")]
+    pub async fn upload(
+        &self,
+        data: impl Into<Bytes>,
+        options: Option<UploadBlobOptions>,
+    ) -> Result<Response> {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.clone().unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        let mut request = self.request(self.url.clone(), "PUT");
+        request.insert_header(BLOB_TYPE, "BlockBlob");
+        request.insert_headers(&options.match_condition);
+        if let Some(content_type) = &options.content_type {
+            request.insert_header(CONTENT_TYPE, content_type.clone());
+        }
+        request.set_body(data.into());
+
+        self.pipeline.send(&mut ctx, &mut request).await
+    }
+
+    /// Uploads a stream of `data` as blocks of [`UploadBlobOptions::block_size`], staging up to
+    /// [`UploadBlobOptions::max_concurrency`] blocks at a time, then commits them.
+    ///
+    /// Only the blocks being staged are buffered, and each block is retried on its own, so the
+    /// stream is read only once.
+    #[instrumented("BlobClient::upload_stream")]
+    pub async fn upload_stream<S>(
+        &self,
+        data: S,
+        options: Option<UploadBlobOptions>,
+    ) -> Result<Response>
+    where
+        S: Stream<Item = Result<Bytes>> + Send,
+    {
+        let options = options.unwrap_or_default();
+        options.validate()?;
+
+        let mut ctx = options.context.clone().unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        // Stage each full block, and the remainder once the stream has ended, while reading the
+        // next block so neither waits on the other.
+        let block_size = options.block_size;
+        let blocks = stream::try_unfold(
+            (Box::pin(data), BytesMut::new(), 0, false),
+            move |(mut data, mut buffer, index, mut done)| async move {
+                while !done && buffer.len() < block_size {
+                    match data.try_next().await? {
+                        Some(chunk) => buffer.extend_from_slice(&chunk),
+                        None => done = true,
+                    }
+                }
+                if buffer.is_empty() {
+                    return Ok(None);
+                }
+                let block = buffer.split_to(block_size.min(buffer.len())).freeze();
+                Ok(Some(((index, block), (data, buffer, index + 1, done))))
+            },
+        );
+        let staged = blocks
+            .map_ok(|(index, block)| self.send_block(ctx.clone(), block_id(index), block))
+            .try_buffer_unordered(options.max_concurrency)
+            .try_fold(0, |staged, _| async move { Ok(staged + 1) })
+            .await?;
+        let block_ids: Vec<_> = (0..staged).map(block_id).collect();
+
+        self.send_block_list(ctx, &block_ids, &options).await
+    }
+
+    /// Stages a block which is not part of the blob until committed by [`BlobClient::commit_block_list`].
+    ///
+    /// Block IDs must be valid Base64 and all blocks of a blob must have IDs of the same length.
+    #[instrumented("BlobClient::stage_block")]
+    pub async fn stage_block(
+        &self,
+        block_id: impl Into<String>,
+        data: impl Into<Bytes>,
+        options: Option<StageBlockOptions>,
+    ) -> Result<Response> {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        self.send_block(ctx, block_id.into(), data.into()).await
+    }
+
+    /// Commits staged blocks in order as the content of the blob, replacing it if it exists.
+    #[instrumented("BlobClient::commit_block_list")]
+    pub async fn commit_block_list(
+        &self,
+        block_ids: &[String],
+        options: Option<UploadBlobOptions>,
+    ) -> Result<Response> {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.clone().unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        self.send_block_list(ctx, block_ids, &options).await
+    }
+
+    /// Downloads the blob, or a range of it. The body is streamed as it is received using
+    /// [`Response::into_body`], and [`BlobProperties::from_headers`] gets its properties.
+    #[instrumented("BlobClient::download")]
+    pub async fn download(&self, options: Option<DownloadBlobOptions>) -> Result<Response> {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        let mut request = self.request(self.url.clone(), "GET");
+        request.insert_headers(&options.match_condition);
+        if let Some(range) = options.range {
+            if range.is_empty() {
+                return Err(Error::with_message(ErrorKind::InvalidInput, || {
+                    format!("range {range:?} must not be empty")
+                }));
+            }
+            request.insert_header(RANGE, format!("bytes={}-{}", range.start, range.end - 1));
+        }
+
+        self.pipeline.send(&mut ctx, &mut request).await
+    }
+
+    /// Starts copying the blob at `source_url` to this blob, replacing it if it exists, and gets a
+    /// [`Poller`] which polls the blob's properties until the copy completes.
+    ///
+    /// The source must be public or include a shared access signature. Canceling the poller aborts
+    /// the copy, leaving an empty blob.
+    #[instrumented("BlobClient::begin_copy_from_url")]
+    pub async fn begin_copy_from_url(
+        &self,
+        source_url: &Url,
+        options: Option<CopyBlobOptions>,
+    ) -> Result<Poller<CopyProperties>> {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        let mut request = self.request(self.url.clone(), "PUT");
+        request.insert_header(COPY_SOURCE, source_url.to_string());
+        request.insert_headers(&options.match_condition);
+        let response = self.pipeline.send(&mut ctx.clone(), &mut request).await?;
+        let copy_id = response
+            .headers()
+            .get_optional_string(&COPY_ID)
+            .ok_or_else(|| Error::message(ErrorKind::DataConversion, "missing x-ms-copy-id"))?;
+
+        let mut poller = Poller::new({
+            let client = self.clone();
+            let ctx = ctx.clone();
+            move || {
+                let client = client.clone();
+                let mut ctx = ctx.clone();
+                async move {
+                    let mut request = client.request(client.url.clone(), "HEAD");
+                    let response = client.pipeline.send(&mut ctx, &mut request).await?;
+                    let headers = response.headers().clone();
+                    let properties = CopyProperties::from_headers(&headers)?;
+                    let (status, value) = match properties.copy_status.as_deref() {
+                        Some("success") => (PollerStatus::Succeeded, Some(properties)),
+                        Some("aborted") => (PollerStatus::Canceled, None),
+                        Some("failed") => {
+                            return Err(Error::with_message(ErrorKind::Other, || {
+                                format!(
+                                    "copy failed: {}",
+                                    properties.copy_status_description.unwrap_or_default()
+                                )
+                            }))
+                        }
+                        _ => {
+                            let percent_complete = properties.percent_complete();
+                            let mut update =
+                                StatusUpdate::new(PollerStatus::InProgress, None, headers);
+                            if let Some(percent_complete) = percent_complete {
+                                update.set_percent_complete(percent_complete);
+                            }
+                            return Ok(update);
+                        }
+                    };
+                    Ok(StatusUpdate::new(status, value, headers))
+                }
+            }
+        });
+        poller.set_cancel({
+            let client = self.clone();
+            move || {
+                let client = client.clone();
+                let mut ctx = ctx.clone();
+                let copy_id = copy_id.clone();
+                async move {
+                    let mut url = client.url.clone();
+                    url.query_pairs_mut()
+                        .append_pair("comp", "copy")
+                        .append_pair("copyid", &copy_id);
+
+                    let mut request = client.request(url, "PUT");
+                    request.insert_header(COPY_ACTION, "abort");
+                    client.pipeline.send(&mut ctx, &mut request).await?;
+                    Ok(())
+                }
+            }
+        });
+        Ok(poller)
+    }
+
+    fn request(&self, url: Url, method: &'static str) -> Request {
+        let mut request = Request::new(url, method);
+        request.insert_header(VERSION, self.version.clone());
+        request
+    }
+
+    async fn send_block(
+        &self,
+        mut ctx: Context,
+        block_id: String,
+        data: Bytes,
+    ) -> Result<Response> {
+        let mut url = self.url.clone();
+        url.query_pairs_mut()
+            .append_pair("comp", "block")
+            .append_pair("blockid", &block_id);
+
+        let mut request = self.request(url, "PUT");
+        request.set_body(data);
+
+        self.pipeline.send(&mut ctx, &mut request).await
+    }
+
+    async fn send_block_list(
+        &self,
+        mut ctx: Context,
+        block_ids: &[String],
+        options: &UploadBlobOptions,
+    ) -> Result<Response> {
+        let mut url = self.url.clone();
+        url.query_pairs_mut().append_pair("comp", "blocklist");
+
+        let mut request = self.request(url, "PUT");
+        request.insert_headers(&options.match_condition);
+        if let Some(content_type) = &options.content_type {
+            request.insert_header(BLOB_CONTENT_TYPE, content_type.clone());
+        }
+        request.insert_header(CONTENT_TYPE, "application/xml");
+        request.set_xml(&BlockList { latest: block_ids })?;
+
+        self.pipeline.send(&mut ctx, &mut request).await
+    }
+}
+
+/// Gets a block ID for the block at `index`: digits are valid Base64, and padding keeps every ID
+/// the same length as Storage requires.
+fn block_id(index: usize) -> String {
+    format!("{index:08}")
+}
+
+#[derive(Debug, Clone, Options)]
+#[non_exhaustive]
+pub struct BlobClientOptions {
+    /// The `x-ms-version` sent with each request.
+    #[options(default = "2023-11-03".to_string(), into)]
+    pub version: String,
+    pub options: ClientOptions,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct UploadBlobOptions {
+    #[options(into)]
+    pub content_type: Option<String>,
+    /// Uploads only if the blob does or does not match an ETag, e.g., [`MatchCondition::IfNoneMatch`]
+    /// with [`ETag::any`](azure_core::ETag::any) to not replace an existing blob.
+    pub match_condition: Option<MatchCondition>,
+    /// The bytes in each block staged by [`BlobClient::upload_stream`], up to 4,000 MiB.
+    #[options(default = 4 * 1024 * 1024)]
+    pub block_size: usize,
+    /// The most blocks [`BlobClient::upload_stream`] stages at a time.
+    #[options(default = 4)]
+    pub max_concurrency: usize,
+    pub context: Option<Context>,
+}
+
+impl UploadBlobOptions {
+    fn validate(&self) -> Result<()> {
+        if !(1..=MAX_BLOCK_SIZE).contains(&self.block_size) {
+            return Err(Error::with_message(ErrorKind::InvalidInput, || {
+                format!(
+                    "block_size must be between 1 and {MAX_BLOCK_SIZE} but was {}",
+                    self.block_size
+                )
+            }));
+        }
+        if self.max_concurrency == 0 {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "max_concurrency must be greater than zero",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct StageBlockOptions {
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct CopyBlobOptions {
+    /// Copies only if this blob does or does not match an ETag, e.g., [`MatchCondition::IfNoneMatch`]
+    /// with [`ETag::any`](azure_core::ETag::any) to not replace an existing blob.
+    pub match_condition: Option<MatchCondition>,
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct DownloadBlobOptions {
+    /// The range of bytes to download, or the entire blob if not set.
+    pub range: Option<Range<u64>>,
+    /// Downloads only if the blob does or does not match an ETag, e.g., [`MatchCondition::IfMatch`]
+    /// to ensure each range of a download is from the same version of the blob.
+    pub match_condition: Option<MatchCondition>,
+    pub context: Option<Context>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_core::{
+        sleep::sleep, stream::BytesStream, transport::HttpClient, Headers, RetryOptions,
+        TransportOptions,
+    };
+    use futures::{
+        channel::oneshot,
+        future::{self, Either},
+        StreamExt,
+    };
+    use std::{sync::Mutex, time::Duration};
+
+    /// Returns a pending copy until the copy is aborted, recording each request.
+    #[derive(Debug, Default)]
+    struct CopyService {
+        requests: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClient for CopyService {
+        async fn execute_request(&self, request: &Request) -> Result<Response> {
+            let mut requests = self.requests.lock().unwrap();
+            requests.push(format!(
+                "{} {} {:?}",
+                request.method(),
+                request.url().query().unwrap_or_default(),
+                request.headers().get_optional_str(&COPY_ACTION),
+            ));
+
+            let mut headers = Headers::new();
+            headers.insert(COPY_ID, "copy-1");
+            let aborted = requests.iter().any(|request| request.contains("abort"));
+            headers.insert(
+                HeaderName::from_static("x-ms-copy-status"),
+                if aborted { "aborted" } else { "pending" },
+            );
+            headers.insert(HeaderName::from_static("x-ms-copy-progress"), "256/1024");
+            headers.insert(HeaderName::from_static("retry-after-ms"), "1");
+            Ok(Response::new(202, headers, Box::pin(BytesStream::new(""))))
+        }
+    }
+
+    #[tokio::test]
+    async fn cancel_aborts_copy() {
+        let service = Arc::new(CopyService::default());
+        let mut options = ClientOptions::default();
+        options.retry = RetryOptions::none();
+        options.transport = TransportOptions::with_http_client(service.clone());
+        let client = BlobClient::with_policies(
+            "https://myaccount.blob.core.windows.net/container/blob",
+            &options,
+            "2023-11-03".to_string(),
+        )
+        .unwrap();
+
+        let source = Url::parse("https://other.blob.core.windows.net/container/blob").unwrap();
+        let poller = client.begin_copy_from_url(&source, None).await.unwrap();
+        let update = poller.poll_once().await.unwrap();
+        assert_eq!(update.status(), PollerStatus::InProgress);
+        assert_eq!(update.percent_complete(), Some(25.0));
+
+        let cancel = poller.cancel_handle();
+        let waiting = tokio::spawn(poller.wait_for_completion());
+        cancel.cancel().await.unwrap();
+        assert!(waiting.await.unwrap().is_err());
+
+        let requests = service.requests.lock().unwrap();
+        assert_eq!(requests[0], "PUT  None");
+        assert!(requests.contains(&"PUT comp=copy&copyid=copy-1 Some(\"abort\")".to_string()));
+        assert!(requests
+            .iter()
+            .skip(1)
+            .any(|request| request == "HEAD  None"));
+    }
+
+    /// Stages blocks and commits the block list, signaling once the first block is staged.
+    #[derive(Debug, Default)]
+    struct BlockService {
+        requests: Mutex<Vec<String>>,
+        staged: Mutex<Option<oneshot::Sender<()>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClient for BlockService {
+        async fn execute_request(&self, request: &Request) -> Result<Response> {
+            let query = request.url().query().unwrap_or_default().to_string();
+            if query.starts_with("comp=block&") {
+                if let Some(staged) = self.staged.lock().unwrap().take() {
+                    let _ = staged.send(());
+                }
+            }
+            self.requests.lock().unwrap().push(query);
+            Ok(Response::new(
+                201,
+                Headers::new(),
+                Box::pin(BytesStream::new("")),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn stages_while_reading_slow_source() {
+        let (staged, first_block_staged) = oneshot::channel();
+        let service = Arc::new(BlockService {
+            staged: Mutex::new(Some(staged)),
+            ..Default::default()
+        });
+        let mut options = ClientOptions::default();
+        options.retry = RetryOptions::none();
+        options.transport = TransportOptions::with_http_client(service.clone());
+        let client = BlobClient::with_policies(
+            "https://myaccount.blob.core.windows.net/container/blob",
+            &options,
+            "2023-11-03".to_string(),
+        )
+        .unwrap();
+
+        // The rest of the data is only read after the first block is staged.
+        let data = stream::once(async { Ok(Bytes::from_static(b"abcd")) }).chain(stream::once(
+            async move {
+                first_block_staged.await.unwrap();
+                Ok(Bytes::from_static(b"ef"))
+            },
+        ));
+        let upload_options = UploadBlobOptions {
+            block_size: 4,
+            ..Default::default()
+        };
+        let upload = client.upload_stream(data, Some(upload_options));
+        match future::select(Box::pin(upload), sleep(Duration::from_secs(10))).await {
+            Either::Left((response, _)) => assert_eq!(response.unwrap().status(), 201),
+            Either::Right(_) => panic!("staging waited for the source"),
+        }
+
+        let mut requests = service.requests.lock().unwrap().clone();
+        requests.sort();
+        assert_eq!(
+            requests,
+            [
+                "comp=block&blockid=00000000",
+                "comp=block&blockid=00000001",
+                "comp=blocklist"
+            ]
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use azure_core::{ETag, HeaderName, Headers, CONTENT_LENGTH, CONTENT_TYPE, ETAG};
+use serde::Serialize;
+
+const LAST_MODIFIED: HeaderName = HeaderName::from_static("last-modified");
+pub(crate) const COPY_ID: HeaderName = HeaderName::from_static("x-ms-copy-id");
+const COPY_PROGRESS: HeaderName = HeaderName::from_static("x-ms-copy-progress");
+const COPY_STATUS: HeaderName = HeaderName::from_static("x-ms-copy-status");
+const COPY_STATUS_DESCRIPTION: HeaderName = HeaderName::from_static("x-ms-copy-status-description");
+
+/// The properties of a blob returned in the headers of a download.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct BlobProperties {
+    /// The length of the content returned, which is the length of the range if one was requested.
+    pub content_length: Option<u64>,
+    pub content_type: Option<String>,
+    pub etag: Option<ETag>,
+    pub last_modified: Option<String>,
+}
+
+impl BlobProperties {
+    pub fn from_headers(headers: &Headers) -> azure_core::Result<Self> {
+        Ok(Self {
+            content_length: headers.get_optional_as(&CONTENT_LENGTH)?,
+            content_type: headers.get_optional_string(&CONTENT_TYPE),
+            etag: headers.get_optional_string(&ETAG).map(ETag::from),
+            last_modified: headers.get_optional_string(&LAST_MODIFIED),
+        })
+    }
+}
+
+/// The status of a copy started by [`BlobClient::begin_copy_from_url`](crate::BlobClient::begin_copy_from_url),
+/// returned in the headers of the blob's properties.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct CopyProperties {
+    pub copy_id: Option<String>,
+    /// `pending`, `success`, `aborted`, or `failed`.
+    pub copy_status: Option<String>,
+    /// The bytes copied and the total bytes, e.g., `1024/4096`.
+    pub copy_progress: Option<String>,
+    /// Why the copy failed or was aborted.
+    pub copy_status_description: Option<String>,
+}
+
+impl CopyProperties {
+    pub fn from_headers(headers: &Headers) -> azure_core::Result<Self> {
+        Ok(Self {
+            copy_id: headers.get_optional_string(&COPY_ID),
+            copy_status: headers.get_optional_string(&COPY_STATUS),
+            copy_progress: headers.get_optional_string(&COPY_PROGRESS),
+            copy_status_description: headers.get_optional_string(&COPY_STATUS_DESCRIPTION),
+        })
+    }
+
+    /// Gets the percentage of bytes copied, from 0 to 100, if the progress is known.
+    pub fn percent_complete(&self) -> Option<f64> {
+        let (copied, total) = self.copy_progress.as_deref()?.split_once('/')?;
+        let (copied, total) = (copied.parse::<f64>().ok()?, total.parse::<f64>().ok()?);
+        (total > 0.0).then(|| copied / total * 100.0)
+    }
+}
+
+/// The blocks committed as the content of a blob by `Put Block List`.
+#[derive(Serialize)]
+pub(crate) struct BlockList<'a> {
+    #[serde(rename = "Latest")]
+    pub latest: &'a [String],
+}
@@ -0,0 +1,105 @@
+use crate::{SasCredential, StorageSharedKeyCredential};
+use azure_core::{ConnectionString, ConnectionStringError, Error, RequestSigner, Result, Url};
+use std::sync::Arc;
+
+/// The settings of a Storage connection string, e.g.,
+/// `DefaultEndpointsProtocol=https;AccountName=myaccount;AccountKey=...;EndpointSuffix=core.windows.net`.
+#[derive(Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct StorageConnectionString {
+    pub account_name: Option<String>,
+    pub account_key: Option<String>,
+    /// A shared access signature used instead of an account key.
+    pub shared_access_signature: Option<String>,
+    /// The Blob service endpoint, which is otherwise derived from the account name.
+    pub blob_endpoint: Option<String>,
+    /// The Queue service endpoint, which is otherwise derived from the account name.
+    pub queue_endpoint: Option<String>,
+    pub default_endpoints_protocol: Option<String>,
+    pub endpoint_suffix: Option<String>,
+}
+
+impl StorageConnectionString {
+    /// Parses the Storage settings of `connection_string` using [`ConnectionString`], ignoring
+    /// settings for other services, e.g., `TableEndpoint`.
+    pub fn parse(connection_string: &str) -> Result<Self> {
+        let settings = ConnectionString::parse(connection_string)?;
+        let get = |name| settings.get(name).map(str::to_string);
+        Ok(Self {
+            account_name: get("AccountName"),
+            account_key: get("AccountKey"),
+            shared_access_signature: get("SharedAccessSignature"),
+            blob_endpoint: get("BlobEndpoint"),
+            queue_endpoint: get("QueueEndpoint"),
+            default_endpoints_protocol: get("DefaultEndpointsProtocol"),
+            endpoint_suffix: get("EndpointSuffix"),
+        })
+    }
+
+    /// Gets a [`StorageSharedKeyCredential`] from the `AccountName` and `AccountKey`, or else a
+    /// [`SasCredential`] from the `SharedAccessSignature`.
+    pub fn credential(&self) -> Result<Arc<dyn RequestSigner>> {
+        match (
+            &self.account_name,
+            &self.account_key,
+            &self.shared_access_signature,
+        ) {
+            (Some(account_name), Some(account_key), _) => Ok(Arc::new(
+                StorageSharedKeyCredential::new(account_name.clone(), account_key)?,
+            )),
+            (_, None, Some(sas)) => Ok(Arc::new(SasCredential::new(sas)?)),
+            (None, Some(_), _) => Err(missing("AccountName")),
+            (_, None, None) => Err(missing("AccountKey")),
+        }
+    }
+
+    /// Gets the Blob service endpoint, e.g., `https://myaccount.blob.core.windows.net`.
+    pub fn blob_endpoint(&self) -> Result<Url> {
+        self.endpoint("blob", self.blob_endpoint.as_deref())
+    }
+
+    /// Gets the Queue service endpoint, e.g., `https://myaccount.queue.core.windows.net`.
+    pub fn queue_endpoint(&self) -> Result<Url> {
+        self.endpoint("queue", self.queue_endpoint.as_deref())
+    }
+
+    fn endpoint(&self, service: &str, endpoint: Option<&str>) -> Result<Url> {
+        if let Some(endpoint) = endpoint {
+            return azure_core::parse_endpoint(endpoint);
+        }
+        let Some(account_name) = &self.account_name else {
+            return Err(missing("AccountName"));
+        };
+        azure_core::parse_endpoint(format!(
+            "{}://{account_name}.{service}.{}",
+            self.default_endpoints_protocol
+                .as_deref()
+                .unwrap_or("https"),
+            self.endpoint_suffix
+                .as_deref()
+                .unwrap_or("core.windows.net"),
+        ))
+    }
+}
+
+impl std::fmt::Debug for StorageConnectionString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StorageConnectionString")
+            .field("account_name", &self.account_name)
+            .field("blob_endpoint", &self.blob_endpoint)
+            .field("queue_endpoint", &self.queue_endpoint)
+            .field(
+                "default_endpoints_protocol",
+                &self.default_endpoints_protocol,
+            )
+            .field("endpoint_suffix", &self.endpoint_suffix)
+            .finish_non_exhaustive()
+    }
+}
+
+fn missing(name: &str) -> Error {
+    ConnectionStringError::Missing {
+        name: name.to_string(),
+    }
+    .into_error()
+}
@@ -0,0 +1,10 @@
+#![doc = include_str!("../README.md")]
+
+mod connection_string;
+mod sas;
+mod shared_key;
+mod time;
+
+pub use connection_string::*;
+pub use sas::*;
+pub use shared_key::*;
@@ -0,0 +1,567 @@
+use crate::{time::iso8601, StorageSharedKeyCredential};
+use azure_core::{Error, ErrorKind, Request, RequestSigner, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::hmac;
+use serde::Deserialize;
+use std::{fmt, time::SystemTime};
+use url::form_urlencoded;
+
+/// The `sv` of generated signatures, which determines how they are signed.
+pub const SAS_VERSION: &str = "2022-11-02";
+
+/// The protocols a shared access signature may be used with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SasProtocol {
+    #[default]
+    Https,
+    HttpsAndHttp,
+}
+
+impl SasProtocol {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SasProtocol::Https => "https",
+            SasProtocol::HttpsAndHttp => "https,http",
+        }
+    }
+}
+
+/// Builds an [account SAS](https://learn.microsoft.com/rest/api/storageservices/create-account-sas),
+/// which grants access to resources in one or more services of an account.
+#[derive(Clone, Debug)]
+pub struct AccountSasBuilder {
+    services: String,
+    resource_types: String,
+    permissions: String,
+    expiry: SystemTime,
+    start: Option<SystemTime>,
+    ip_range: Option<String>,
+    protocol: Option<SasProtocol>,
+}
+
+impl AccountSasBuilder {
+    /// Creates a builder for a signature which expires at `expiry`, e.g.,
+    /// `AccountSasBuilder::new("bq", "sco", "rl", expiry)` to read and list blobs and queues.
+    ///
+    /// `services` are any of `b`lob, `q`ueue, `t`able, and `f`ile, and `resource_types` any of
+    /// `s`ervice, `c`ontainer, and `o`bject.
+    pub fn new(
+        services: impl Into<String>,
+        resource_types: impl Into<String>,
+        permissions: impl Into<String>,
+        expiry: SystemTime,
+    ) -> Self {
+        Self {
+            services: services.into(),
+            resource_types: resource_types.into(),
+            permissions: permissions.into(),
+            expiry,
+            start: None,
+            ip_range: None,
+            protocol: None,
+        }
+    }
+
+    pub fn with_start(&mut self, start: SystemTime) -> &mut Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Sets the IP address, or range like `168.1.5.60-168.1.5.70`, requests must come from.
+    pub fn with_ip_range(&mut self, ip_range: impl Into<String>) -> &mut Self {
+        self.ip_range = Some(ip_range.into());
+        self
+    }
+
+    pub fn with_protocol(&mut self, protocol: SasProtocol) -> &mut Self {
+        self.protocol = Some(protocol);
+        self
+    }
+
+    pub fn sign(&self, credential: &StorageSharedKeyCredential) -> Result<SasToken> {
+        validate("services", &self.services, "bqtf")?;
+        validate("resource_types", &self.resource_types, "sco")?;
+        validate("permissions", &self.permissions, "rwdxylacuptfi")?;
+
+        let start = self.start.map(iso8601).unwrap_or_default();
+        let expiry = iso8601(self.expiry);
+        let ip_range = self.ip_range.as_deref().unwrap_or("");
+        let protocol = self.protocol.map_or("", |protocol| protocol.as_str());
+
+        let string_to_sign = [
+            credential.account(),
+            &self.permissions,
+            &self.services,
+            &self.resource_types,
+            &start,
+            &expiry,
+            ip_range,
+            protocol,
+            SAS_VERSION,
+            // The encryption scope, which is not supported.
+            "",
+            "",
+        ]
+        .join("\n");
+
+        Ok(SasToken::new([
+            ("sv", SAS_VERSION),
+            ("ss", &self.services),
+            ("srt", &self.resource_types),
+            ("sp", &self.permissions),
+            ("st", &start),
+            ("se", &expiry),
+            ("sip", ip_range),
+            ("spr", protocol),
+            ("sig", &credential.compute_signature(&string_to_sign)),
+        ]))
+    }
+}
+
+/// A resource a [service SAS](https://learn.microsoft.com/rest/api/storageservices/create-service-sas)
+/// grants access to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SasResource {
+    Container { container: String },
+    Blob { container: String, blob: String },
+    Queue { queue: String },
+}
+
+impl SasResource {
+    fn canonicalized(&self, account: &str) -> String {
+        match self {
+            SasResource::Container { container } => format!("/blob/{account}/{container}"),
+            SasResource::Blob { container, blob } => format!("/blob/{account}/{container}/{blob}"),
+            SasResource::Queue { queue } => format!("/queue/{account}/{queue}"),
+        }
+    }
+
+    /// Gets the `sr`, which queues do not have.
+    fn signed_resource(&self) -> &'static str {
+        match self {
+            SasResource::Container { .. } => "c",
+            SasResource::Blob { .. } => "b",
+            SasResource::Queue { .. } => "",
+        }
+    }
+
+    fn permissions(&self) -> &'static str {
+        match self {
+            SasResource::Container { .. } | SasResource::Blob { .. } => "racwdxyltmeopi",
+            SasResource::Queue { .. } => "raup",
+        }
+    }
+}
+
+/// Builds a signature which grants access to a single container, blob, or queue, signed with
+/// either an account key or a [`UserDelegationKey`].
+#[derive(Clone, Debug)]
+pub struct ServiceSasBuilder {
+    resource: SasResource,
+    permissions: String,
+    expiry: SystemTime,
+    start: Option<SystemTime>,
+    identifier: Option<String>,
+    ip_range: Option<String>,
+    protocol: Option<SasProtocol>,
+}
+
+impl ServiceSasBuilder {
+    /// Creates a builder for a signature which expires at `expiry`, e.g.,
+    /// `ServiceSasBuilder::new(SasResource::Queue { queue }, "ap", expiry)` to send messages.
+    pub fn new(resource: SasResource, permissions: impl Into<String>, expiry: SystemTime) -> Self {
+        Self {
+            resource,
+            permissions: permissions.into(),
+            expiry,
+            start: None,
+            identifier: None,
+            ip_range: None,
+            protocol: None,
+        }
+    }
+
+    pub fn with_start(&mut self, start: SystemTime) -> &mut Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Sets the stored access policy of the resource the signature is associated with, so it can
+    /// be revoked by changing the policy.
+    pub fn with_identifier(&mut self, identifier: impl Into<String>) -> &mut Self {
+        self.identifier = Some(identifier.into());
+        self
+    }
+
+    /// Sets the IP address, or range like `168.1.5.60-168.1.5.70`, requests must come from.
+    pub fn with_ip_range(&mut self, ip_range: impl Into<String>) -> &mut Self {
+        self.ip_range = Some(ip_range.into());
+        self
+    }
+
+    pub fn with_protocol(&mut self, protocol: SasProtocol) -> &mut Self {
+        self.protocol = Some(protocol);
+        self
+    }
+
+    /// Signs with an account key.
+    pub fn sign(&self, credential: &StorageSharedKeyCredential) -> Result<SasToken> {
+        self.validate()?;
+        let fields = self.signed_fields(credential.account());
+
+        let mut string_to_sign = vec![
+            self.permissions.as_str(),
+            &fields.start,
+            &fields.expiry,
+            &fields.resource,
+            self.identifier.as_deref().unwrap_or(""),
+            fields.ip_range,
+            fields.protocol,
+            SAS_VERSION,
+        ];
+        if let SasResource::Container { .. } | SasResource::Blob { .. } = self.resource {
+            // The signed resource, then the snapshot time, encryption scope, and response headers
+            // `rscc`, `rscd`, `rsce`, `rscl`, and `rsct`, which are not supported.
+            string_to_sign.extend([self.resource.signed_resource(), "", "", "", "", "", "", ""]);
+        }
+        let signature = credential.compute_signature(&string_to_sign.join("\n"));
+
+        Ok(SasToken::new([
+            ("sv", SAS_VERSION),
+            ("sr", self.resource.signed_resource()),
+            ("sp", &self.permissions),
+            ("st", &fields.start),
+            ("se", &fields.expiry),
+            ("si", self.identifier.as_deref().unwrap_or("")),
+            ("sip", fields.ip_range),
+            ("spr", fields.protocol),
+            ("sig", &signature),
+        ]))
+    }
+
+    /// Signs a [user delegation SAS](https://learn.microsoft.com/rest/api/storageservices/create-user-delegation-sas)
+    /// for a container or blob in `account` with a key from Get User Delegation Key, so requests
+    /// are authorized as the Microsoft Entra ID principal the key was issued to.
+    pub fn sign_with_user_delegation_key(
+        &self,
+        account: &str,
+        key: &UserDelegationKey,
+    ) -> Result<SasToken> {
+        if let SasResource::Queue { .. } = self.resource {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "user delegation signatures are only supported for containers and blobs",
+            ));
+        }
+        if self.identifier.is_some() {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "user delegation signatures cannot use a stored access policy",
+            ));
+        }
+        self.validate()?;
+        let fields = self.signed_fields(account);
+
+        let string_to_sign = [
+            self.permissions.as_str(),
+            &fields.start,
+            &fields.expiry,
+            &fields.resource,
+            &key.signed_oid,
+            &key.signed_tid,
+            &key.signed_start,
+            &key.signed_expiry,
+            &key.signed_service,
+            &key.signed_version,
+            // The authorized and unauthorized object IDs, and correlation ID, which are not supported.
+            "",
+            "",
+            "",
+            fields.ip_range,
+            fields.protocol,
+            SAS_VERSION,
+            self.resource.signed_resource(),
+            // The snapshot time, encryption scope, and response headers, which are not supported.
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+        ]
+        .join("\n");
+        let signature = key.compute_signature(&string_to_sign)?;
+
+        Ok(SasToken::new([
+            ("sv", SAS_VERSION),
+            ("sr", self.resource.signed_resource()),
+            ("sp", &self.permissions),
+            ("st", &fields.start),
+            ("se", &fields.expiry),
+            ("sip", fields.ip_range),
+            ("spr", fields.protocol),
+            ("skoid", &key.signed_oid),
+            ("sktid", &key.signed_tid),
+            ("skt", &key.signed_start),
+            ("ske", &key.signed_expiry),
+            ("sks", &key.signed_service),
+            ("skv", &key.signed_version),
+            ("sig", &signature),
+        ]))
+    }
+
+    fn validate(&self) -> Result<()> {
+        // Permissions may come from a stored access policy instead.
+        if self.identifier.is_some() && self.permissions.is_empty() {
+            return Ok(());
+        }
+        validate(
+            "permissions",
+            &self.permissions,
+            self.resource.permissions(),
+        )
+    }
+
+    fn signed_fields(&self, account: &str) -> SignedFields<'_> {
+        SignedFields {
+            resource: self.resource.canonicalized(account),
+            start: self.start.map(iso8601).unwrap_or_default(),
+            expiry: iso8601(self.expiry),
+            ip_range: self.ip_range.as_deref().unwrap_or(""),
+            protocol: self.protocol.map_or("", |protocol| protocol.as_str()),
+        }
+    }
+}
+
+struct SignedFields<'a> {
+    /// The canonicalized resource, e.g., `/blob/myaccount/container/blob`.
+    resource: String,
+    start: String,
+    expiry: String,
+    ip_range: &'a str,
+    protocol: &'a str,
+}
+
+/// A key returned by [Get User Delegation Key](https://learn.microsoft.com/rest/api/storageservices/get-user-delegation-key)
+/// to sign user delegation signatures.
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+#[non_exhaustive]
+pub struct UserDelegationKey {
+    pub signed_oid: String,
+    pub signed_tid: String,
+    pub signed_start: String,
+    pub signed_expiry: String,
+    pub signed_service: String,
+    pub signed_version: String,
+    /// The Base64-encoded key.
+    pub value: String,
+}
+
+impl UserDelegationKey {
+    fn compute_signature(&self, string_to_sign: &str) -> Result<String> {
+        let key = STANDARD.decode(&self.value).map_err(|error| {
+            Error::with_message(ErrorKind::InvalidInput, || {
+                format!("user delegation key must be Base64-encoded: {error}")
+            })
+        })?;
+        let key = hmac::Key::new(hmac::HMAC_SHA256, &key);
+        Ok(STANDARD.encode(hmac::sign(&key, string_to_sign.as_bytes()).as_ref()))
+    }
+}
+
+impl fmt::Debug for UserDelegationKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UserDelegationKey")
+            .field("signed_oid", &self.signed_oid)
+            .field("signed_tid", &self.signed_tid)
+            .field("signed_start", &self.signed_start)
+            .field("signed_expiry", &self.signed_expiry)
+            .field("signed_service", &self.signed_service)
+            .field("signed_version", &self.signed_version)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A generated shared access signature, which is the query string to append to resource URLs,
+/// e.g., `sv=2022-11-02&sp=r&se=...&sig=...`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SasToken(String);
+
+impl SasToken {
+    /// Creates a token from its parameters, omitting any which are empty.
+    fn new<'a>(parameters: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        Self(
+            form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(
+                    parameters
+                        .into_iter()
+                        .filter(|(_, value)| !value.is_empty()),
+                )
+                .finish(),
+        )
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for SasToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SasToken").finish_non_exhaustive()
+    }
+}
+
+/// Authorizes requests by adding a shared access signature to their query, for callers given a
+/// signature instead of an account key or Microsoft Entra ID credential.
+#[derive(Clone)]
+pub struct SasCredential {
+    parameters: Vec<(String, String)>,
+}
+
+impl SasCredential {
+    /// Creates a credential from a signature like `sv=2022-11-02&sp=r&se=...&sig=...`, with or
+    /// without a leading `?`.
+    pub fn new(token: impl AsRef<str>) -> Result<Self> {
+        let token = token.as_ref().trim();
+        let parameters: Vec<(String, String)> =
+            form_urlencoded::parse(token.strip_prefix('?').unwrap_or(token).as_bytes())
+                .into_owned()
+                .collect();
+        // The token is not included in errors since it is a secret.
+        if !parameters.iter().any(|(name, _)| name == "sig") {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "shared access signature must have a sig parameter",
+            ));
+        }
+        Ok(Self { parameters })
+    }
+}
+
+impl From<SasToken> for SasCredential {
+    fn from(token: SasToken) -> Self {
+        Self {
+            parameters: form_urlencoded::parse(token.0.as_bytes())
+                .into_owned()
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Debug for SasCredential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<_> = self.parameters.iter().map(|(name, _)| name).collect();
+        f.debug_struct("SasCredential")
+            .field("parameters", &names)
+            .finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl RequestSigner for SasCredential {
+    async fn sign(&self, request: &mut Request) -> Result<()> {
+        // Replaces the parameters added to a previous attempt, rather than appending them again.
+        let url = request.url_mut();
+        let query: Vec<(String, String)> = url
+            .query_pairs()
+            .into_owned()
+            .filter(|(name, _)| !self.parameters.iter().any(|(sas, _)| sas == name))
+            .collect();
+        url.query_pairs_mut()
+            .clear()
+            .extend_pairs(query)
+            .extend_pairs(&self.parameters);
+        Ok(())
+    }
+}
+
+fn validate(name: &str, value: &str, allowed: &str) -> Result<()> {
+    if value.is_empty() {
+        return Err(Error::with_message(ErrorKind::InvalidInput, || {
+            format!("{name} must not be empty")
+        }));
+    }
+    if let Some(invalid) = value.chars().find(|c| !allowed.contains(*c)) {
+        return Err(Error::with_message(ErrorKind::InvalidInput, || {
+            format!("{name} must be any of '{allowed}' but included '{invalid}'")
+        }));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    // The well-known key of the Azurite and Storage Emulator account.
+    const ACCOUNT: &str = "devstoreaccount1";
+    const KEY: &str =
+        "Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==";
+
+    fn credential() -> StorageSharedKeyCredential {
+        StorageSharedKeyCredential::new(ACCOUNT, KEY).unwrap()
+    }
+
+    /// 2030-01-01T00:00:00Z.
+    fn expiry() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_893_456_000)
+    }
+
+    #[test]
+    fn account_sas() {
+        let token = AccountSasBuilder::new("bq", "sco", "rl", expiry())
+            .with_protocol(SasProtocol::Https)
+            .sign(&credential())
+            .unwrap();
+        assert_eq!(
+            token.as_str(),
+            "sv=2022-11-02&ss=bq&srt=sco&sp=rl&se=2030-01-01T00%3A00%3A00Z&spr=https\
+             &sig=6zNIQM9y%2FNveut%2BRDX5y%2BLXS6v5ko8yQDGLM1UOtXS8%3D"
+        );
+
+        let error = AccountSasBuilder::new("bz", "sco", "rl", expiry())
+            .sign(&credential())
+            .unwrap_err();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn blob_sas() {
+        let resource = SasResource::Blob {
+            container: "photos".to_string(),
+            blob: "cat.jpg".to_string(),
+        };
+        let token = ServiceSasBuilder::new(resource, "r", expiry())
+            .with_start(expiry() - Duration::from_secs(86_400))
+            .with_protocol(SasProtocol::Https)
+            .sign(&credential())
+            .unwrap();
+        assert_eq!(
+            token.as_str(),
+            "sv=2022-11-02&sr=b&sp=r&st=2029-12-31T00%3A00%3A00Z&se=2030-01-01T00%3A00%3A00Z\
+             &spr=https&sig=TGd5Sfrw2o%2FQygatZ7BIljCzpCt7Ue7dT%2F5VPCE%2BL1Y%3D"
+        );
+    }
+
+    #[test]
+    fn queue_sas() {
+        let resource = SasResource::Queue {
+            queue: "orders".to_string(),
+        };
+        let token = ServiceSasBuilder::new(resource, "ap", expiry())
+            .with_ip_range("168.1.5.60-168.1.5.70")
+            .with_protocol(SasProtocol::HttpsAndHttp)
+            .sign(&credential())
+            .unwrap();
+        assert_eq!(
+            token.as_str(),
+            "sv=2022-11-02&sp=ap&se=2030-01-01T00%3A00%3A00Z&sip=168.1.5.60-168.1.5.70\
+             &spr=https%2Chttp&sig=BwQUCc8NhI8wA%2FGnap4tWEnSWNoBgx5aBvjN6ayoHGg%3D"
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use azure_core::{
+    format_http_date, Error, ErrorKind, HeaderName, Request, RequestSigner, Result, AUTHORIZATION,
+    CONTENT_ENCODING, CONTENT_TYPE, IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH,
+    IF_UNMODIFIED_SINCE,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::hmac;
+use std::{fmt, time::SystemTime};
+
+const CONTENT_LANGUAGE: HeaderName = HeaderName::from_static("content-language");
+const CONTENT_MD5: HeaderName = HeaderName::from_static("content-md5");
+const RANGE: HeaderName = HeaderName::from_static("range");
+const MS_DATE: HeaderName = HeaderName::from_static("x-ms-date");
+
+/// Signs requests using the name and key of a Storage account, as described in
+/// [Authorize with Shared Key](https://learn.microsoft.com/rest/api/storageservices/authorize-with-shared-key).
+#[derive(Clone)]
+pub struct StorageSharedKeyCredential {
+    account: String,
+    key: hmac::Key,
+}
+
+impl StorageSharedKeyCredential {
+    /// Creates a credential for `account` from its Base64-encoded `key`.
+    pub fn new(account: impl Into<String>, key: &str) -> Result<Self> {
+        let key = STANDARD.decode(key).map_err(|error| {
+            Error::with_message(ErrorKind::InvalidInput, || {
+                format!("account key must be Base64-encoded: {error}")
+            })
+        })?;
+        Ok(Self {
+            account: account.into(),
+            key: hmac::Key::new(hmac::HMAC_SHA256, &key),
+        })
+    }
+
+    pub fn account(&self) -> &str {
+        &self.account
+    }
+
+    /// Signs `string_to_sign` with the key, returning the Base64-encoded signature.
+    pub(crate) fn compute_signature(&self, string_to_sign: &str) -> String {
+        STANDARD.encode(hmac::sign(&self.key, string_to_sign.as_bytes()).as_ref())
+    }
+
+    fn string_to_sign(&self, request: &Request) -> String {
+        let header = |name: &HeaderName| request.headers().get_optional_str(name).unwrap_or("");
+        let content_length = match request.body().len() {
+            0 => String::new(),
+            len => len.to_string(),
+        };
+
+        let mut string_to_sign = [
+            request.method(),
+            header(&CONTENT_ENCODING),
+            header(&CONTENT_LANGUAGE),
+            &content_length,
+            header(&CONTENT_MD5),
+            header(&CONTENT_TYPE),
+            // Empty since x-ms-date is set instead.
+            "",
+            header(&IF_MODIFIED_SINCE),
+            header(&IF_MATCH),
+            header(&IF_NONE_MATCH),
+            header(&IF_UNMODIFIED_SINCE),
+            header(&RANGE),
+        ]
+        .join("\n");
+        string_to_sign.push('\n');
+
+        let mut ms_headers: Vec<_> = request
+            .headers()
+            .iter()
+            .filter(|(name, _)| name.as_str().starts_with("x-ms-"))
+            .map(|(name, value)| (name.as_str(), value.as_str().trim()))
+            .collect();
+        ms_headers.sort_unstable();
+        for (name, value) in ms_headers {
+            string_to_sign.push_str(&format!("{name}:{value}\n"));
+        }
+
+        string_to_sign.push_str(&format!("/{}{}", self.account, request.url().path()));
+        let mut parameters: Vec<(String, String)> = request
+            .url()
+            .query_pairs()
+            .map(|(name, value)| (name.to_lowercase(), value.into_owned()))
+            .collect();
+        parameters.sort();
+        let mut previous: Option<String> = None;
+        for (name, value) in parameters {
+            if previous.as_ref() == Some(&name) {
+                string_to_sign.push_str(&format!(",{value}"));
+            } else {
+                string_to_sign.push_str(&format!("\n{name}:{value}"));
+                previous = Some(name);
+            }
+        }
+        string_to_sign
+    }
+}
+
+impl fmt::Debug for StorageSharedKeyCredential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StorageSharedKeyCredential")
+            .field("account", &self.account)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait::async_trait]
+impl RequestSigner for StorageSharedKeyCredential {
+    async fn sign(&self, request: &mut Request) -> Result<()> {
+        // Each attempt is signed with the current time, so retries are not rejected as stale.
+        request.insert_header(MS_DATE, format_http_date(SystemTime::now()));
+        let signature = self.compute_signature(&self.string_to_sign(request));
+        request.insert_header(
+            AUTHORIZATION,
+            format!("SharedKey {}:{signature}", self.account),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_core::{parse_http_date, Url};
+
+    // The well-known key of the Azurite and Storage Emulator account.
+    const KEY: &str =
+        "Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==";
+
+    fn request() -> Request {
+        let url = Url::parse(
+            "https://devstoreaccount1.blob.core.windows.net/photos/cat%20one.jpg\
+             ?restype=container&comp=list&include=snapshots&Include=metadata",
+        )
+        .unwrap();
+        let mut request = Request::new(url, "PUT");
+        request.insert_header(CONTENT_TYPE, "text/plain");
+        request.insert_header(IF_MATCH, "\"0x1\"");
+        request.insert_header(MS_DATE, "Tue, 01 Jan 2030 00:00:00 GMT");
+        request.insert_header(HeaderName::from_static("x-ms-version"), "2023-11-03");
+        request.insert_header(HeaderName::from_static("x-ms-meta-color"), " blue ");
+        request.set_body("hello".to_string());
+        request
+    }
+
+    #[test]
+    fn canonicalizes_requests() {
+        let credential = StorageSharedKeyCredential::new("devstoreaccount1", KEY).unwrap();
+        let string_to_sign = credential.string_to_sign(&request());
+        assert_eq!(
+            string_to_sign,
+            "PUT\n\n\n5\n\ntext/plain\n\n\n\"0x1\"\n\n\n\n\
+             x-ms-date:Tue, 01 Jan 2030 00:00:00 GMT\n\
+             x-ms-meta-color:blue\n\
+             x-ms-version:2023-11-03\n\
+             /devstoreaccount1/photos/cat%20one.jpg\n\
+             comp:list\n\
+             include:metadata,snapshots\n\
+             restype:container"
+        );
+        assert_eq!(
+            credential.compute_signature(&string_to_sign),
+            "xFcbJBZ3EX5JFiWz6FYVzjtvOr1LknOuvcJ+cXzDnhI="
+        );
+
+        // Requests without a body have an empty content length rather than 0.
+        let request = Request::new(
+            Url::parse("https://devstoreaccount1.queue.core.windows.net/orders/messages").unwrap(),
+            "GET",
+        );
+        assert_eq!(
+            credential.string_to_sign(&request),
+            "GET\n\n\n\n\n\n\n\n\n\n\n\n/devstoreaccount1/orders/messages"
+        );
+    }
+
+    #[tokio::test]
+    async fn signs_with_current_date() {
+        let credential = StorageSharedKeyCredential::new("devstoreaccount1", KEY).unwrap();
+        let mut request = request();
+        credential.sign(&mut request).await.unwrap();
+
+        let date = request.headers().get_optional_str(&MS_DATE).unwrap();
+        assert_ne!(date, "Tue, 01 Jan 2030 00:00:00 GMT");
+        assert!(parse_http_date(date).is_some(), "{date}");
+        let signature = credential.compute_signature(&credential.string_to_sign(&request));
+        assert_eq!(
+            request.headers().get_optional_str(&AUTHORIZATION),
+            Some(format!("SharedKey devstoreaccount1:{signature}").as_str())
+        );
+    }
+
+    #[test]
+    fn requires_base64_key() {
+        let error = StorageSharedKeyCredential::new("devstoreaccount1", "not base64!").unwrap_err();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,21 @@
+use azure_core::format_http_date;
+use std::time::SystemTime;
+
+/// Formats `time` like `1994-11-06T08:49:37Z` as shared access signatures require.
+pub(crate) fn iso8601(time: SystemTime) -> String {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    // Rearranges the fields of `Sun, 06 Nov 1994 08:49:37 GMT`.
+    let date = format_http_date(time);
+    let [_, day, month, year, time, _] = date.split(' ').collect::<Vec<_>>()[..] else {
+        unreachable!("HTTP dates have six fields");
+    };
+    let month = MONTHS
+        .iter()
+        .position(|name| *name == month)
+        .unwrap_or_default()
+        + 1;
+    format!("{year}-{month:02}-{day}T{time}Z")
+}
@@ -0,0 +1,31 @@
+use azure_storage_queue::{QueueClient, ReceiveMessagesOptions, SendMessageOptions};
+use std::{env, time::Duration};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let connection_string = env::var("AZURE_STORAGE_CONNECTION_STRING")?;
+
+    let client = QueueClient::from_connection_string(&connection_string, "orders", None)?;
+    println!("{:#?}", client.diagnostics());
+
+    client.create(None).await?;
+
+    let mut options = SendMessageOptions::default();
+    options.time_to_live = Some(Duration::from_secs(60 * 60));
+    client.send_message("order 1", Some(options)).await?;
+
+    let mut options = ReceiveMessagesOptions::default();
+    options.max_messages = 10;
+    for message in client.receive_messages(Some(options)).await? {
+        println!(
+            "received {} (dequeue {})",
+            message.message_text, message.dequeue_count
+        );
+        client.delete_message(&message, None).await?;
+    }
+
+    client.clear_messages(None).await?;
+    client.delete(None).await?;
+
+    Ok(())
+}
@@ -0,0 +1,333 @@
+#![doc = include_str!("../README.md")]
+
+mod models;
+
+use azure_core::{
+    instrumented,
+    policies::{BearerTokenSigner, SigningPolicy},
+    ClientDiagnostics, ClientOptions, Context, Error, ErrorKind, HeaderName, Idempotent, Namespace,
+    Options, Pipeline, Request, RequestSigner, Response, Result, TokenCredential, Url,
+    CONTENT_TYPE,
+};
+pub use azure_storage_common::{
+    SasCredential, StorageConnectionString, StorageSharedKeyCredential,
+};
+pub use models::*;
+use std::{sync::Arc, time::Duration};
+
+const NAMESPACE: &str = "Microsoft.Storage";
+
+const VERSION: HeaderName = HeaderName::from_static("x-ms-version");
+
+/// A client for a single queue, e.g., `https://myaccount.queue.core.windows.net/myqueue`.
+#[derive(Debug, Clone)]
+pub struct QueueClient {
+    url: Url,
+    version: String,
+    pipeline: Pipeline,
+}
+
+impl QueueClient {
+    /// Creates a client which authenticates using a token from `credential`.
+    pub fn new(
+        queue_url: impl AsRef<str>,
+        credential: Arc<dyn TokenCredential>,
+        options: Option<QueueClientOptions>,
+    ) -> Result<Self> {
+        let signer = BearerTokenSigner::new(credential, "https://storage.azure.com/.default");
+        Self::with_signer(queue_url, Arc::new(signer), options)
+    }
+
+    /// Creates a client which signs each request using an account key.
+    pub fn with_shared_key(
+        queue_url: impl AsRef<str>,
+        credential: Arc<StorageSharedKeyCredential>,
+        options: Option<QueueClientOptions>,
+    ) -> Result<Self> {
+        Self::with_signer(queue_url, credential, options)
+    }
+
+    /// Creates a client which adds a shared access signature to each request.
+    pub fn with_sas(
+        queue_url: impl AsRef<str>,
+        credential: Arc<SasCredential>,
+        options: Option<QueueClientOptions>,
+    ) -> Result<Self> {
+        Self::with_signer(queue_url, credential, options)
+    }
+
+    /// Creates a client for `queue_name` from a connection string with an `AccountName` and
+    /// `AccountKey`, or a `SharedAccessSignature`, and optionally a `QueueEndpoint`.
+    pub fn from_connection_string(
+        connection_string: &str,
+        queue_name: &str,
+        options: Option<QueueClientOptions>,
+    ) -> Result<Self> {
+        let connection_string = StorageConnectionString::parse(connection_string)?;
+        let credential = connection_string.credential()?;
+
+        let mut url = connection_string.queue_endpoint()?;
+        url.path_segments_mut()
+            .map_err(|_| Error::message(ErrorKind::InvalidInput, "invalid queue endpoint"))?
+            .pop_if_empty()
+            .push(queue_name);
+        Self::with_signer(url, credential, options)
+    }
+
+    fn with_signer(
+        queue_url: impl AsRef<str>,
+        signer: Arc<dyn RequestSigner>,
+        options: Option<QueueClientOptions>,
+    ) -> Result<Self> {
+        let options = options.unwrap_or_default();
+
+        // Sign after every other policy so the signature covers the headers they set.
+        let mut client_options = options.options.clone();
+        client_options
+            .per_retry_policies
+            .push(Arc::new(SigningPolicy::new(signer)));
+        Self::with_policies(queue_url, &client_options, options.version)
+    }
+
+    fn with_policies(
+        queue_url: impl AsRef<str>,
+        options: &ClientOptions,
+        version: String,
+    ) -> Result<Self> {
+        if version.is_empty() {
+            return Err(Error::message(
+                ErrorKind::InvalidInput,
+                "version must not be empty",
+            ));
+        }
+        options.validate()?;
+
+        let url = azure_core::parse_endpoint(queue_url)?;
+        if url.path_segments().map_or(0, Iterator::count) != 1 {
+            return Err(Error::with_message(ErrorKind::InvalidInput, || {
+                format!("queue URL '{url}' must include only a queue name")
+            }));
+        }
+
+        Ok(Self {
+            url,
+            version,
+            pipeline: Pipeline::new(
+                option_env!("CARGO_PKG_NAME"),
+                option_env!("CARGO_PKG_VERSION"),
+                options,
+                Vec::default(),
+                Vec::default(),
+            ),
+        })
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Gets the resolved configuration of the client without sending any requests.
+    pub fn diagnostics(&self) -> ClientDiagnostics {
+        let mut diagnostics = self.pipeline.diagnostics(&self.url);
+        diagnostics.api_version = Some(self.version.clone());
+        diagnostics
+    }
+
+    #[instrumented("QueueClient::create")]
+    pub async fn create(&self, options: Option<CreateQueueOptions>) -> Result<Response> {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        let mut request = self.request(self.url.clone(), "PUT");
+        self.pipeline.send(&mut ctx, &mut request).await
+    }
+
+    #[instrumented("QueueClient::delete")]
+    pub async fn delete(&self, options: Option<DeleteQueueOptions>) -> Result<Response> {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        let mut request = self.request(self.url.clone(), "DELETE");
+        self.pipeline.send(&mut ctx, &mut request).await
+    }
+
+    /// Adds a message to the back of the queue.
+    #[instrumented("QueueClient::send_message")]
+    pub async fn send_message(
+        &self,
+        text: &str,
+        options: Option<SendMessageOptions>,
+    ) -> Result<Response> {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(false));
+
+        let mut url = self.messages_url()?;
+        if let Some(visibility_timeout) = options.visibility_timeout {
+            url.query_pairs_mut().append_pair(
+                "visibilitytimeout",
+                &visibility_timeout.as_secs().to_string(),
+            );
+        }
+        if let Some(time_to_live) = options.time_to_live {
+            url.query_pairs_mut()
+                .append_pair("messagettl", &time_to_live.as_secs().to_string());
+        }
+
+        let mut request = self.request(url, "POST");
+        request.insert_header(CONTENT_TYPE, "application/xml");
+        request.set_xml(&QueueMessage { message_text: text })?;
+
+        self.pipeline.send(&mut ctx, &mut request).await
+    }
+
+    /// Receives up to [`ReceiveMessagesOptions::max_messages`] from the front of the queue, which
+    /// are hidden from other receivers until they are deleted or their visibility timeout expires.
+    #[instrumented("QueueClient::receive_messages")]
+    pub async fn receive_messages(
+        &self,
+        options: Option<ReceiveMessagesOptions>,
+    ) -> Result<Vec<ReceivedMessage>> {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(false));
+
+        if !(1..=32).contains(&options.max_messages) {
+            return Err(Error::with_message(ErrorKind::InvalidInput, || {
+                format!(
+                    "max_messages must be between 1 and 32 but was {}",
+                    options.max_messages
+                )
+            }));
+        }
+        let mut url = self.messages_url()?;
+        url.query_pairs_mut()
+            .append_pair("numofmessages", &options.max_messages.to_string());
+        if let Some(visibility_timeout) = options.visibility_timeout {
+            url.query_pairs_mut().append_pair(
+                "visibilitytimeout",
+                &visibility_timeout.as_secs().to_string(),
+            );
+        }
+
+        let mut request = self.request(url, "GET");
+        let response = self.pipeline.send(&mut ctx, &mut request).await?;
+        let list: QueueMessagesList = response.xml().await?;
+        Ok(list.messages)
+    }
+
+    /// Deletes a received message so it is not received again.
+    #[instrumented("QueueClient::delete_message")]
+    pub async fn delete_message(
+        &self,
+        message: &ReceivedMessage,
+        options: Option<DeleteMessageOptions>,
+    ) -> Result<Response> {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        let mut url = self.messages_url()?;
+        url.path_segments_mut()
+            .map_err(|_| Error::message(ErrorKind::InvalidInput, "invalid queue URL"))?
+            .push(&message.message_id);
+        url.query_pairs_mut()
+            .append_pair("popreceipt", &message.pop_receipt);
+
+        let mut request = self.request(url, "DELETE");
+        self.pipeline.send(&mut ctx, &mut request).await
+    }
+
+    /// Deletes every message in the queue.
+    #[instrumented("QueueClient::clear_messages")]
+    pub async fn clear_messages(&self, options: Option<ClearMessagesOptions>) -> Result<Response> {
+        let options = options.unwrap_or_default();
+
+        let mut ctx = options.context.unwrap_or_default();
+        ctx.insert(Namespace::from(NAMESPACE));
+        ctx.insert(Idempotent::from(true));
+
+        let mut request = self.request(self.messages_url()?, "DELETE");
+        self.pipeline.send(&mut ctx, &mut request).await
+    }
+
+    fn messages_url(&self) -> Result<Url> {
+        let mut url = self.url.clone();
+        url.path_segments_mut()
+            .map_err(|_| Error::message(ErrorKind::InvalidInput, "invalid queue URL"))?
+            .push("messages");
+        Ok(url)
+    }
+
+    fn request(&self, url: Url, method: &'static str) -> Request {
+        let mut request = Request::new(url, method);
+        request.insert_header(VERSION, self.version.clone());
+        request
+    }
+}
+
+#[derive(Debug, Clone, Options)]
+#[non_exhaustive]
+pub struct QueueClientOptions {
+    /// The `x-ms-version` sent with each request.
+    #[options(default = "2021-12-02".to_string(), into)]
+    pub version: String,
+    pub options: ClientOptions,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct CreateQueueOptions {
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct DeleteQueueOptions {
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct SendMessageOptions {
+    /// How long the message is hidden from receivers after it is sent.
+    pub visibility_timeout: Option<Duration>,
+    /// How long the message is kept, which defaults to 7 days.
+    pub time_to_live: Option<Duration>,
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct ReceiveMessagesOptions {
+    /// The most messages to receive, from 1 to 32.
+    #[options(default = 1)]
+    pub max_messages: u8,
+    /// How long received messages are hidden from other receivers, which defaults to 30 seconds.
+    pub visibility_timeout: Option<Duration>,
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct DeleteMessageOptions {
+    pub context: Option<Context>,
+}
+
+#[derive(Clone, Debug, Options)]
+#[non_exhaustive]
+pub struct ClearMessagesOptions {
+    pub context: Option<Context>,
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// The body of `Put Message`.
+#[derive(Serialize)]
+pub(crate) struct QueueMessage<'a> {
+    #[serde(rename = "MessageText")]
+    pub message_text: &'a str,
+}
+
+/// A message received from a queue, which is hidden from other receivers until its visibility
+/// timeout expires or it is deleted.
+#[derive(Clone, Debug, Deserialize)]
+#[non_exhaustive]
+pub struct ReceivedMessage {
+    #[serde(rename = "MessageId")]
+    pub message_id: String,
+    /// Identifies this receipt of the message to [delete](crate::QueueClient::delete_message) it.
+    #[serde(rename = "PopReceipt")]
+    pub pop_receipt: String,
+    /// How many times the message has been received, including this time.
+    #[serde(rename = "DequeueCount")]
+    pub dequeue_count: u32,
+    #[serde(rename = "MessageText", default)]
+    pub message_text: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct QueueMessagesList {
+    #[serde(rename = "QueueMessage", default)]
+    pub messages: Vec<ReceivedMessage>,
+}